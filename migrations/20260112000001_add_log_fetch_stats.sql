@@ -0,0 +1,4 @@
+-- Track how long each fetch took and how many entries the feed returned
+-- Both are nullable: error logs may have neither, and older rows predate them
+ALTER TABLE logs ADD COLUMN duration_ms INTEGER;
+ALTER TABLE logs ADD COLUMN entry_count INTEGER;
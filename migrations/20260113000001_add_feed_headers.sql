@@ -0,0 +1,10 @@
+-- Custom HTTP request headers sent when fetching a feed
+-- Some feeds require a specific Referer or a token header to serve content
+CREATE TABLE IF NOT EXISTS feed_headers (
+    feed_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    value TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (feed_id, name),
+    FOREIGN KEY (feed_id) REFERENCES feeds(id) ON DELETE CASCADE
+);
@@ -0,0 +1,10 @@
+-- Share tokens grant read-only access to a group's articles via /share/:token
+-- Deleting a token revokes access; deleting the group revokes all of its tokens
+CREATE TABLE IF NOT EXISTS share_tokens (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token TEXT NOT NULL UNIQUE,
+    group_id INTEGER NOT NULL REFERENCES groups(id) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_share_tokens_group_id ON share_tokens(group_id);
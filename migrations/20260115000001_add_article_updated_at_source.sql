@@ -0,0 +1,3 @@
+-- The feed's own `updated` timestamp for each entry, kept apart from published_at
+-- published_at is frozen at first insert so edited entries don't jump to the top
+ALTER TABLE articles ADD COLUMN updated_at_source TIMESTAMP;
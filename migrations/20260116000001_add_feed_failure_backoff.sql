@@ -0,0 +1,4 @@
+-- Exponential backoff for feeds failing with transient errors
+-- next_retry_at holds the feed back from fetch cycles until then; both reset on success
+ALTER TABLE feeds ADD COLUMN consecutive_failures INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE feeds ADD COLUMN next_retry_at TIMESTAMP;
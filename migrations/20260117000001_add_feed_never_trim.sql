@@ -0,0 +1,2 @@
+-- Per-feed opt-out from article trimming: articles of such feeds are never cleaned up
+ALTER TABLE feeds ADD COLUMN never_trim BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+-- Remember whether a group is collapsed in the group tree
+ALTER TABLE groups ADD COLUMN collapsed BOOLEAN NOT NULL DEFAULT 0;
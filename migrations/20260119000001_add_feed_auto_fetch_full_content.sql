@@ -0,0 +1,2 @@
+-- Fetch the full article page for every new article of summary-only feeds
+ALTER TABLE feeds ADD COLUMN auto_fetch_full_content BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+-- Remember titles set by the user so feed updates don't overwrite them
+ALTER TABLE feeds ADD COLUMN title_is_custom BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,46 @@
+-- Index the feed title alongside article fields so search matches it too
+
+DROP TRIGGER IF EXISTS articles_fts_insert;
+DROP TRIGGER IF EXISTS articles_fts_delete;
+DROP TRIGGER IF EXISTS articles_fts_update;
+DROP TABLE IF EXISTS articles_fts;
+
+CREATE VIRTUAL TABLE articles_fts USING fts5(
+    article_id UNINDEXED,
+    title,
+    content,
+    summary,
+    author,
+    feed_title
+);
+
+-- Triggers to keep FTS index in sync
+CREATE TRIGGER articles_fts_insert AFTER INSERT ON articles BEGIN
+    INSERT INTO articles_fts(rowid, article_id, title, content, summary, author, feed_title)
+    VALUES (new.id, new.id, new.title, new.content, new.summary, new.author,
+            (SELECT title FROM feeds WHERE id = new.feed_id));
+END;
+
+CREATE TRIGGER articles_fts_delete AFTER DELETE ON articles BEGIN
+    DELETE FROM articles_fts WHERE rowid = old.id;
+END;
+
+CREATE TRIGGER articles_fts_update AFTER UPDATE ON articles BEGIN
+    DELETE FROM articles_fts WHERE rowid = old.id;
+    INSERT INTO articles_fts(rowid, article_id, title, content, summary, author, feed_title)
+    VALUES (new.id, new.id, new.title, new.content, new.summary, new.author,
+            (SELECT title FROM feeds WHERE id = new.feed_id));
+END;
+
+-- Renaming a feed updates the denormalized title of its articles
+CREATE TRIGGER articles_fts_feed_title AFTER UPDATE OF title ON feeds
+WHEN new.title IS NOT old.title BEGIN
+    UPDATE articles_fts SET feed_title = new.title
+    WHERE rowid IN (SELECT id FROM articles WHERE feed_id = new.id);
+END;
+
+-- Backfill existing articles
+INSERT INTO articles_fts(rowid, article_id, title, content, summary, author, feed_title)
+SELECT a.id, a.id, a.title, a.content, a.summary, a.author, f.title
+FROM articles a
+INNER JOIN feeds f ON f.id = a.feed_id;
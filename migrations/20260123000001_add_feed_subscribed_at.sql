@@ -0,0 +1,3 @@
+-- When the user subscribed to the feed; unlike updated_at, never changed afterwards
+ALTER TABLE feeds ADD COLUMN subscribed_at DATETIME NOT NULL DEFAULT '1970-01-01 00:00:00';
+UPDATE feeds SET subscribed_at = created_at;
@@ -0,0 +1,2 @@
+-- Muted feeds keep fetching but stay out of the unfiltered article list
+ALTER TABLE feeds ADD COLUMN muted BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+-- Language declared by the feed (BCP 47 tag such as en-US), if any
+ALTER TABLE feeds ADD COLUMN language TEXT;
@@ -0,0 +1,4 @@
+-- Fingerprint of the text a feed sent for an article, to notice when it
+-- sends different content under a GUID it already used
+ALTER TABLE articles ADD COLUMN content_hash TEXT;
+ALTER TABLE articles ADD COLUMN content_changed_at TIMESTAMP;
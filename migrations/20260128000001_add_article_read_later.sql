@@ -0,0 +1,2 @@
+-- Articles set aside to read later, separate from starred favorites
+ALTER TABLE articles ADD COLUMN is_read_later BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- Plain-text excerpt of an article for the list views, so they don't have to
+-- render the full content of every article
+ALTER TABLE articles ADD COLUMN content_preview TEXT;
@@ -0,0 +1,2 @@
+-- Words in an article's text, for the estimated reading time
+ALTER TABLE articles ADD COLUMN word_count INTEGER;
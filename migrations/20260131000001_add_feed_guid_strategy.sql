@@ -0,0 +1,2 @@
+-- Which entry field identifies an article: auto, link, id or title_date
+ALTER TABLE feeds ADD COLUMN guid_strategy TEXT NOT NULL DEFAULT 'auto';
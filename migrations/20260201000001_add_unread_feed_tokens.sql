@@ -0,0 +1,7 @@
+-- Unread feed tokens grant read access to all unread articles via
+-- /feed/:token/unread.atom; deleting a token revokes access
+CREATE TABLE IF NOT EXISTS unread_feed_tokens (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
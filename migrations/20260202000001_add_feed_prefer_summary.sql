@@ -0,0 +1,2 @@
+-- Show the summary rather than the content as the article body
+ALTER TABLE feeds ADD COLUMN prefer_summary BOOLEAN NOT NULL DEFAULT 0;
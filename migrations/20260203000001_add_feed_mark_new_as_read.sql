@@ -0,0 +1,2 @@
+-- Store new articles of the feed as already read
+ALTER TABLE feeds ADD COLUMN mark_new_as_read BOOLEAN NOT NULL DEFAULT 0;
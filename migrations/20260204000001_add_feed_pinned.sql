@@ -0,0 +1,2 @@
+-- Keep the feed at the top of its group, whatever its title
+ALTER TABLE feeds ADD COLUMN pinned BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,9 @@
+-- Display preferences of the (single) user, kept server-side so they follow
+-- them across devices. The table holds exactly one row.
+CREATE TABLE IF NOT EXISTS preferences (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    theme TEXT NOT NULL DEFAULT 'auto',
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT OR IGNORE INTO preferences (id) VALUES (1);
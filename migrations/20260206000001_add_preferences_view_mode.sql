@@ -0,0 +1,2 @@
+-- Article list layout used when the URL does not ask for one
+ALTER TABLE preferences ADD COLUMN default_view_mode TEXT NOT NULL DEFAULT 'cards';
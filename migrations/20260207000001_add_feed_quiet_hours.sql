@@ -0,0 +1,3 @@
+-- Daily window in which the feed is not fetched, as HH:MM-HH:MM in the
+-- display time zone; NULL for none
+ALTER TABLE feeds ADD COLUMN quiet_hours TEXT;
@@ -0,0 +1,7 @@
+-- Language of the article as a BCP 47 tag, declared by the entry or else
+-- inherited from its feed; NULL when neither declares one
+ALTER TABLE articles ADD COLUMN language TEXT;
+
+UPDATE articles SET language = (SELECT language FROM feeds WHERE feeds.id = articles.feed_id);
+
+CREATE INDEX IF NOT EXISTS idx_articles_language ON articles(language);
@@ -0,0 +1,18 @@
+-- GUIDs of articles deleted to stay within MAX_ARTICLES. Entries the feed
+-- still carries would otherwise come back as new unread articles on the
+-- next fetch, so inserting a trimmed GUID again is silently skipped.
+CREATE TABLE IF NOT EXISTS trimmed_articles (
+    feed_id INTEGER NOT NULL,
+    guid TEXT NOT NULL,
+    trimmed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (feed_id, guid),
+    FOREIGN KEY (feed_id) REFERENCES feeds(id) ON DELETE CASCADE
+);
+
+CREATE TRIGGER IF NOT EXISTS articles_skip_trimmed BEFORE INSERT ON articles
+WHEN EXISTS (
+    SELECT 1 FROM trimmed_articles WHERE feed_id = NEW.feed_id AND guid = NEW.guid
+)
+BEGIN
+    SELECT RAISE(IGNORE);
+END;
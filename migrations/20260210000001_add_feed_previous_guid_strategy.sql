@@ -0,0 +1,4 @@
+-- GUID strategy the stored articles were identified with before the
+-- strategy was changed; the next fetch moves them to the new GUIDs and
+-- clears it, so that a change doesn't store every entry a second time
+ALTER TABLE feeds ADD COLUMN previous_guid_strategy TEXT;
@@ -0,0 +1,3 @@
+-- Whether the stored TTL came from the caching headers rather than the
+-- feed's <ttl> element, which takes precedence
+ALTER TABLE feeds ADD COLUMN ttl_from_headers BOOLEAN NOT NULL DEFAULT 0;
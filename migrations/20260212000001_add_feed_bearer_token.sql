@@ -0,0 +1,18 @@
+-- Bearer token sent as `Authorization` to the feed's origin. It is only
+-- ever replaced or cleared from the edit form, never shown again.
+ALTER TABLE feeds ADD COLUMN bearer_token TEXT;
+
+-- Tokens configured as custom headers move to the new column
+UPDATE feeds SET bearer_token = (
+    SELECT trim(substr(value, 8)) FROM feed_headers
+    WHERE feed_headers.feed_id = feeds.id
+      AND lower(name) = 'authorization'
+      AND value LIKE 'Bearer %'
+)
+WHERE id IN (
+    SELECT feed_id FROM feed_headers
+    WHERE lower(name) = 'authorization' AND value LIKE 'Bearer %'
+);
+
+DELETE FROM feed_headers
+WHERE lower(name) = 'authorization' AND value LIKE 'Bearer %';
@@ -0,0 +1,56 @@
+use crate::api::feeds::AppState;
+use crate::domain::article_service::{self, ArticleServiceError};
+use crate::infrastructure::error_pages::json_error;
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+/// Share metadata of an article as JSON (GET /articles/:id/share)
+pub async fn share_article(State(state): State<AppState>, Path(article_id): Path<i64>) -> Response {
+    match article_service::get_share_payload(&state.db_pool, article_id).await {
+        Ok(payload) => (StatusCode::OK, Json(payload)).into_response(),
+        Err(err) => {
+            let (status, message) = error_status(err);
+            json_error(status, message)
+        }
+    }
+}
+
+/// Share line of an article as plain text, "Title — URL" (GET /articles/:id/share.txt)
+pub async fn share_article_text(
+    State(state): State<AppState>,
+    Path(article_id): Path<i64>,
+) -> Response {
+    let (status, body) = match article_service::get_share_payload(&state.db_pool, article_id).await
+    {
+        Ok(payload) => (
+            StatusCode::OK,
+            format!("{} — {}", payload.title, payload.url),
+        ),
+        Err(err) => error_status(err),
+    };
+    (
+        status,
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        body,
+    )
+        .into_response()
+}
+
+fn error_status(err: ArticleServiceError) -> (StatusCode, String) {
+    match err {
+        ArticleServiceError::NotFound => (StatusCode::NOT_FOUND, err.to_string()),
+        ArticleServiceError::NotShareable => (StatusCode::UNPROCESSABLE_ENTITY, err.to_string()),
+        ArticleServiceError::InvalidStateImport(_) => (StatusCode::BAD_REQUEST, err.to_string()),
+        ArticleServiceError::DatabaseError(e) => {
+            tracing::error!("Failed to load article for sharing: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load article. Please try again later.".to_string(),
+            )
+        }
+    }
+}
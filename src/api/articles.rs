@@ -1,20 +1,26 @@
 use crate::api::feeds::AppState;
+use crate::api::params::{deserialize_id_list, parse_ids};
+use crate::domain::models::{ArticleListFilter, ViewMode};
 use crate::domain::{article_service, feed_service, group_service};
+use crate::infrastructure::error_pages::render_error;
 use crate::infrastructure::repository;
-use crate::web::url_builders::ArticleFilters;
+use crate::web::redirect::HxRedirect;
 use crate::web::templates::{
-    ArticleCompactRowTemplate, ArticleCompactRowsTemplate, ArticleFullscreenRowTemplate,
-    ArticleFullscreenRowsTemplate, ArticleListFooterTemplate, ArticleRowTemplate,
-    ArticleRowsTemplate, ArticleSearchTemplate, ArticleWithFeed, ArticlesListTemplate,
-    ErrorTemplate,
+    ArticleCompactRowTemplate, ArticleCompactRowsTemplate, ArticleContentTemplate,
+    ArticleDetailCardTemplate, ArticleDetailTemplate, ArticleDigestTemplate,
+    ArticleFullscreenRowTemplate, ArticleFullscreenRowsTemplate, ArticleListFooterTemplate,
+    ArticleRowTemplate, ArticleRowsTemplate, ArticleSearchTemplate, ArticleStateImportFormTemplate,
+    ArticleStateImportResultsTemplate, ArticleWithFeed, ArticlesListTemplate,
 };
+use crate::web::url_builders::{search_url, ArticleFilters};
 use askama::Template;
 use axum::{
     extract::{Path, Query, State},
     http::{HeaderMap, StatusCode},
     response::{Html, IntoResponse, Response},
+    Form, Json,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
 use std::collections::HashSet;
 
@@ -25,6 +31,7 @@ pub struct ArticleListParams {
     pub tag_ids: Option<String>,   // Comma-separated tag IDs
     pub is_read: Option<bool>,
     pub is_starred: Option<bool>,
+    pub is_read_later: Option<bool>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
     pub view: Option<String>,
@@ -33,42 +40,49 @@ pub struct ArticleListParams {
     pub date_to: Option<String>,
     pub show: Option<String>, // "all" to override smart default
     pub loaded: Option<i64>,  // Total articles to load (for restoring pagination state)
+    pub new_since_last_visit: Option<bool>,
+    pub lang: Option<String>, // Language tag, e.g. "en" (also matches "en-US")
 }
 
-#[derive(Deserialize)]
-pub struct MarkAllReadParams {
-    pub feed_ids: Option<String>, // Comma-separated feed IDs
+/// Build the URL filter state from the list query parameters
+fn filters_from_params(params: &ArticleListParams, is_read: Option<bool>) -> ArticleFilters {
+    ArticleFilters {
+        feed_ids: parse_ids(params.feed_ids.as_deref()),
+        group_ids: parse_ids(params.group_ids.as_deref()),
+        tag_ids: parse_ids(params.tag_ids.as_deref()),
+        is_read,
+        is_starred: params.is_starred,
+        is_read_later: params.is_read_later,
+        search_query: params.q.clone(),
+        date_from: params.date_from.clone(),
+        date_to: params.date_to.clone(),
+        new_since_last_visit: params.new_since_last_visit == Some(true),
+        language: language_param(params),
+    }
 }
 
-/// Parse comma-separated IDs from query parameter
-fn parse_ids(ids_str: Option<&str>) -> Vec<i64> {
-    ids_str
-        .map(|s| {
-            s.split(',')
-                .filter_map(|id| id.trim().parse().ok())
-                .collect()
-        })
-        .unwrap_or_default()
-}
-
-/// Extract a cookie value from the Cookie header
-fn get_cookie_value(headers: &HeaderMap, name: &str) -> Option<String> {
-    headers
-        .get(axum::http::header::COOKIE)?
-        .to_str()
-        .ok()?
-        .split(';')
-        .find_map(|cookie| {
-            let cookie = cookie.trim();
-            if cookie.starts_with(name) && cookie.chars().nth(name.len()) == Some('=') {
-                Some(cookie[name.len() + 1..].to_string())
-            } else {
-                None
-            }
+/// The `lang` filter, if it looks like a language tag
+fn language_param(params: &ArticleListParams) -> Option<String> {
+    params
+        .lang
+        .as_deref()
+        .map(str::trim)
+        .filter(|lang| {
+            !lang.is_empty() && lang.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
         })
+        .map(str::to_string)
+}
+
+/// The view requested by the `view` parameter, else the stored default
+async fn view_mode(pool: &SqlitePool, params: &ArticleListParams) -> Result<ViewMode, AppError> {
+    match params.view.as_deref().and_then(ViewMode::parse) {
+        Some(view_mode) => Ok(view_mode),
+        None => Ok(repository::get_preferences(pool).await?.default_view_mode),
+    }
 }
 
-/// Fetch tags for all unique feeds and attach to articles
+/// Fetch the tags of all unique feeds plus the articles' own tags and attach
+/// them to the articles
 async fn attach_tags_to_articles(
     pool: &SqlitePool,
     mut articles: Vec<ArticleWithFeed>,
@@ -87,13 +101,25 @@ async fn attach_tags_to_articles(
 
     // Batch-fetch tags for all feeds
     let tags_map = repository::get_tags_for_feeds(pool, &feed_ids).await?;
+    let article_ids: Vec<i64> = articles.iter().map(|a| a.article.id).collect();
+    let mut article_tags_map = repository::get_tags_for_articles(pool, &article_ids).await?;
 
     // Attach tags to each article
     for article in &mut articles {
-        article.tags = tags_map
+        let mut tags = tags_map
             .get(&article.article.feed_id)
             .cloned()
             .unwrap_or_default();
+        for tag in article_tags_map
+            .remove(&article.article.id)
+            .unwrap_or_default()
+        {
+            if !tags.iter().any(|t| t.id == tag.id) {
+                tags.push(tag);
+            }
+        }
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+        article.tags = tags;
     }
 
     Ok(articles)
@@ -106,66 +132,9 @@ pub async fn list_articles(
 ) -> Result<Html<String>, AppError> {
     // If `loaded` param is set (from returning to page), use it as the initial limit
     // This allows restoring pagination state when navigating back
-    let default_limit = 20;
-    let limit = params.loaded.unwrap_or_else(|| params.limit.unwrap_or(default_limit));
+    let limit = state.page_size.initial_limit(params.loaded, params.limit);
     let offset = params.offset.unwrap_or(0);
 
-    // Parse date parameters
-    let date_from = parse_date_param(params.date_from.as_deref(), true);
-    let date_to = parse_date_param(params.date_to.as_deref(), false);
-
-    // Parse comma-separated IDs
-    let selected_feed_ids = parse_ids(params.feed_ids.as_deref());
-    let selected_group_ids = parse_ids(params.group_ids.as_deref());
-    let selected_tag_ids = parse_ids(params.tag_ids.as_deref());
-
-    // Resolve groups to feed IDs
-    let mut feed_ids_from_groups = if selected_feed_ids.is_empty() && selected_group_ids.is_empty()
-    {
-        Vec::new()
-    } else {
-        group_service::resolve_selection_to_feed_ids(
-            &state.db_pool,
-            &selected_group_ids,
-            &selected_feed_ids,
-        )
-        .await?
-    };
-
-    // Resolve tags to feed IDs
-    let feed_ids_from_tags = if !selected_tag_ids.is_empty() {
-        repository::get_feed_ids_by_tags(&state.db_pool, &selected_tag_ids).await?
-    } else {
-        Vec::new()
-    };
-
-    // Combine feed filters
-    let feed_ids = if feed_ids_from_groups.is_empty()
-        && feed_ids_from_tags.is_empty()
-        && selected_feed_ids.is_empty()
-        && selected_group_ids.is_empty()
-        && selected_tag_ids.is_empty()
-    {
-        None // No filter = all feeds
-    } else if feed_ids_from_groups.is_empty() && !feed_ids_from_tags.is_empty() {
-        // Only tag filter active
-        Some(feed_ids_from_tags)
-    } else if !feed_ids_from_groups.is_empty() && feed_ids_from_tags.is_empty() {
-        // Only feed/group filter active
-        Some(feed_ids_from_groups)
-    } else if !feed_ids_from_groups.is_empty() && !feed_ids_from_tags.is_empty() {
-        // Both filters active - intersect them
-        feed_ids_from_groups.retain(|id| feed_ids_from_tags.contains(id));
-        if feed_ids_from_groups.is_empty() {
-            // No matching feeds - use impossible ID to get empty results
-            Some(vec![-1])
-        } else {
-            Some(feed_ids_from_groups)
-        }
-    } else {
-        None
-    };
-
     // Get article counts for sidebar and smart default
     let counts = repository::get_article_counts(&state.db_pool).await?;
 
@@ -174,6 +143,12 @@ pub async fn list_articles(
     let (effective_is_read, active_filter) = if params.is_starred == Some(true) {
         // Starred filter takes precedence
         (None, "starred".to_string())
+    } else if params.is_read_later == Some(true) {
+        // As is the read-later list
+        (None, "read_later".to_string())
+    } else if params.new_since_last_visit == Some(true) && params.is_read.is_none() {
+        // Arrivals since the last visit, whether read or not
+        (None, "new".to_string())
     } else if let Some(is_read) = params.is_read {
         // Explicit is_read param
         if is_read {
@@ -195,12 +170,7 @@ pub async fn list_articles(
     // Get articles with feed data in a single JOIN query (no N+1 problem)
     let articles_with_feed = repository::list_articles_with_feeds(
         &state.db_pool,
-        feed_ids,
-        effective_is_read,
-        params.is_starred,
-        params.q.clone(),
-        date_from,
-        date_to,
+        &list_filter(&state, &params, effective_is_read).await?,
         limit + 1, // Fetch one extra to check if there are more
         offset,
     )
@@ -215,12 +185,26 @@ pub async fn list_articles(
     // Fetch and attach tags to articles
     let articles_to_show = attach_tags_to_articles(&state.db_pool, articles_to_show).await?;
 
+    let view_mode = view_mode(&state.db_pool, &params).await?;
+
     // Check if this is an HTMX pagination request
     let is_htmx = headers.get("HX-Request").is_some();
 
     // If HTMX request with offset > 0, return just the article rows for pagination
     if is_htmx && offset > 0 {
-        return render_htmx_pagination(articles_to_show, has_more, offset, limit, &params);
+        return render_htmx_pagination(
+            articles_to_show,
+            has_more,
+            offset,
+            limit,
+            &params,
+            view_mode,
+            &state,
+        );
+    }
+
+    if offset == 0 && is_unfiltered(&params) {
+        article_service::record_visit(&state.db_pool, chrono::Utc::now()).await?;
     }
 
     let effective_filter = EffectiveFilter {
@@ -232,17 +216,91 @@ pub async fn list_articles(
     // Render full page
     render_full_articles_page(
         &state,
-        &headers,
         articles_to_show,
         has_more,
         offset,
         limit,
         &params,
         effective_filter,
+        view_mode,
     )
     .await
 }
 
+/// The articles the list shows for `params`, with the read state already
+/// resolved to `is_read`
+async fn list_filter(
+    state: &AppState,
+    params: &ArticleListParams,
+    is_read: Option<bool>,
+) -> Result<ArticleListFilter, AppError> {
+    // Parse comma-separated IDs
+    let selected_feed_ids = parse_ids(params.feed_ids.as_deref());
+    let selected_group_ids = parse_ids(params.group_ids.as_deref());
+
+    // Resolve groups to feed IDs; no selection means all feeds. Tags are
+    // matched by the query itself, as they may sit on a feed or an article.
+    let feed_ids = if selected_feed_ids.is_empty() && selected_group_ids.is_empty() {
+        None
+    } else {
+        Some(
+            group_service::resolve_selection_to_feed_ids(
+                &state.db_pool,
+                &selected_group_ids,
+                &selected_feed_ids,
+            )
+            .await?,
+        )
+    };
+
+    // Before the first recorded visit every article counts as new
+    let created_after = if params.new_since_last_visit == Some(true) {
+        article_service::last_visit(&state.db_pool).await?
+    } else {
+        None
+    };
+
+    Ok(ArticleListFilter {
+        feed_ids,
+        tag_ids: parse_ids(params.tag_ids.as_deref()),
+        is_read,
+        is_starred: params.is_starred,
+        is_read_later: params.is_read_later,
+        search_query: params.q.clone(),
+        date_from: parse_date_param(params.date_from.as_deref(), true),
+        date_to: parse_date_param(params.date_to.as_deref(), false),
+        created_after,
+        language: language_param(params),
+    })
+}
+
+/// The articles set aside to read later (GET /articles/read-later)
+pub async fn list_read_later(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Query(mut params): Query<ArticleListParams>,
+) -> Result<Html<String>, AppError> {
+    params.is_read_later = Some(true);
+    list_articles(state, headers, Query(params)).await
+}
+
+/// Whether this is the plain article list, which counts as a visit. The
+/// read-state tabs still count; feed, tag, search and date filters don't.
+fn is_unfiltered(params: &ArticleListParams) -> bool {
+    let blank = |value: &Option<String>| value.as_deref().unwrap_or("").trim().is_empty();
+
+    blank(&params.feed_ids)
+        && blank(&params.group_ids)
+        && blank(&params.tag_ids)
+        && blank(&params.q)
+        && blank(&params.date_from)
+        && blank(&params.date_to)
+        && blank(&params.lang)
+        && params.is_starred != Some(true)
+        && params.is_read_later != Some(true)
+        && params.new_since_last_visit != Some(true)
+}
+
 /// Parse date parameter to DateTime (start of day or end of day)
 fn parse_date_param(
     date_str: Option<&str>,
@@ -267,27 +325,22 @@ fn render_htmx_pagination(
     offset: i64,
     limit: i64,
     params: &ArticleListParams,
+    view_mode: ViewMode,
+    state: &AppState,
 ) -> Result<Html<String>, AppError> {
+    let image_proxy = state.image_proxy.signer();
     let mut html = String::new();
 
-    let filters = ArticleFilters {
-        feed_ids: parse_ids(params.feed_ids.as_deref()),
-        group_ids: parse_ids(params.group_ids.as_deref()),
-        tag_ids: parse_ids(params.tag_ids.as_deref()),
-        is_read: params.is_read,
-        is_starred: params.is_starred,
-        search_query: params.q.clone(),
-        date_from: params.date_from.clone(),
-        date_to: params.date_to.clone(),
-    };
+    let filters = filters_from_params(params, params.is_read);
     let next_url = filters.articles_fullscreen_url(&(offset + limit));
 
     // Render article rows using the appropriate template based on view mode
-    let view_mode = params.view.as_deref().unwrap_or("cards");
-    if view_mode == "fullscreen" {
+    if view_mode == ViewMode::Fullscreen {
         // Fullscreen mode: render fullscreen rows
         let rows_template = ArticleFullscreenRowsTemplate {
             articles: articles.clone(),
+            image_proxy,
+            timezone: state.display_timezone,
         };
         html.push_str(&rows_template.render()?);
 
@@ -296,14 +349,18 @@ fn render_htmx_pagination(
             r#"<template id="fullscreen-load-more-meta" data-has-more="{}" data-next-url="{}"></template>"#,
             has_more, next_url
         ));
-    } else if view_mode == "compact" {
+    } else if view_mode == ViewMode::Compact {
         let rows_template = ArticleCompactRowsTemplate {
             articles: articles.clone(),
+            image_proxy,
+            timezone: state.display_timezone,
         };
         html.push_str(&rows_template.render()?);
     } else {
         let rows_template = ArticleRowsTemplate {
             articles: articles.clone(),
+            image_proxy,
+            timezone: state.display_timezone,
         };
         html.push_str(&rows_template.render()?);
     }
@@ -331,41 +388,25 @@ fn render_htmx_pagination(
 #[allow(clippy::too_many_arguments)]
 async fn render_full_articles_page(
     state: &AppState,
-    headers: &HeaderMap,
     articles: Vec<ArticleWithFeed>,
     has_more: bool,
     offset: i64,
     limit: i64,
     params: &ArticleListParams,
     effective_filter: EffectiveFilter,
+    view_mode: ViewMode,
 ) -> Result<Html<String>, AppError> {
-    // Get all feeds and groups for the filter modal
-    let feeds = feed_service::list_all_feeds(&state.db_pool).await?;
-    let groups = repository::list_groups(&state.db_pool).await?;
+    // Feeds and group tree for the filter modal, cached across page loads
+    let tree = state.group_tree_cache.get_or_load(&state.db_pool).await?;
     let all_tags = repository::list_tags(&state.db_pool).await?;
 
-    // Build group tree for the filter modal
-    let (group_tree, ungrouped_feeds) = group_service::build_group_tree(groups, feeds.clone());
-
-    // Get view mode from cookie (default to "cards")
-    let view_mode = get_cookie_value(headers, "articleView").unwrap_or_else(|| "cards".to_string());
-
-    let filters = ArticleFilters {
-        feed_ids: parse_ids(params.feed_ids.as_deref()),
-        group_ids: parse_ids(params.group_ids.as_deref()),
-        tag_ids: parse_ids(params.tag_ids.as_deref()),
-        is_read: effective_filter.is_read,
-        is_starred: params.is_starred,
-        search_query: params.q.clone(),
-        date_from: params.date_from.clone(),
-        date_to: params.date_to.clone(),
-    };
+    let filters = filters_from_params(params, effective_filter.is_read);
 
     let template = ArticlesListTemplate {
         articles,
-        feeds,
-        group_tree,
-        ungrouped_feeds,
+        feeds: tree.feeds,
+        group_tree: tree.group_tree,
+        ungrouped_feeds: tree.ungrouped_feeds,
         offset,
         limit,
         has_more,
@@ -373,23 +414,31 @@ async fn render_full_articles_page(
         article_counts: effective_filter.counts,
         active_filter: effective_filter.active_filter,
         all_tags,
-        view_mode,
+        view_mode: view_mode.as_str().to_string(),
+        theme: repository::get_preferences(&state.db_pool).await?.theme,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
     };
 
     Ok(Html(template.render()?))
 }
 
+/// Attach the `articlesRead` HX-Trigger so the navigation's unread badges refresh
+fn read_status_changed(html: Html<String>) -> Response {
+    ([("HX-Trigger", "articlesRead")], html).into_response()
+}
+
 /// Represents the effective filter after applying smart defaults
 struct EffectiveFilter {
     is_read: Option<bool>,
     counts: repository::ArticleCounts,
-    active_filter: String, // "all", "unread", "read", "starred"
+    active_filter: String, // "all", "unread", "read", "starred", "read_later"
 }
 
 pub async fn toggle_read_status(
     State(state): State<AppState>,
     Path(article_id): Path<i64>,
-) -> Result<Html<String>, AppError> {
+) -> Result<Response, AppError> {
     article_service::toggle_read_status(&state.db_pool, article_id).await?;
 
     // Get article with feed info in a single JOIN query
@@ -399,15 +448,17 @@ pub async fn toggle_read_status(
 
     let template = ArticleRowTemplate {
         item: article_with_feed,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
     };
 
-    Ok(Html(template.render()?))
+    Ok(read_status_changed(Html(template.render()?)))
 }
 
 pub async fn toggle_read_status_compact(
     State(state): State<AppState>,
     Path(article_id): Path<i64>,
-) -> Result<Html<String>, AppError> {
+) -> Result<Response, AppError> {
     article_service::toggle_read_status(&state.db_pool, article_id).await?;
 
     // Get article with feed info in a single JOIN query
@@ -417,9 +468,11 @@ pub async fn toggle_read_status_compact(
 
     let template = ArticleCompactRowTemplate {
         item: article_with_feed,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
     };
 
-    Ok(Html(template.render()?))
+    Ok(read_status_changed(Html(template.render()?)))
 }
 
 pub async fn toggle_starred_status(
@@ -435,6 +488,8 @@ pub async fn toggle_starred_status(
 
     let template = ArticleRowTemplate {
         item: article_with_feed,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
     };
 
     Ok(Html(template.render()?))
@@ -453,49 +508,191 @@ pub async fn toggle_starred_status_compact(
 
     let template = ArticleCompactRowTemplate {
         item: article_with_feed,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
     };
 
     Ok(Html(template.render()?))
 }
 
-pub async fn mark_all_read(
+pub async fn toggle_read_later_status(
+    State(state): State<AppState>,
+    Path(article_id): Path<i64>,
+) -> Result<Html<String>, AppError> {
+    article_service::toggle_read_later_status(&state.db_pool, article_id).await?;
+
+    let article_with_feed = repository::get_article_with_feed_by_id(&state.db_pool, article_id)
+        .await?
+        .ok_or(article_service::ArticleServiceError::NotFound)?;
+
+    let template = ArticleRowTemplate {
+        item: article_with_feed,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
+    };
+
+    Ok(Html(template.render()?))
+}
+
+pub async fn toggle_read_later_status_compact(
+    State(state): State<AppState>,
+    Path(article_id): Path<i64>,
+) -> Result<Html<String>, AppError> {
+    article_service::toggle_read_later_status(&state.db_pool, article_id).await?;
+
+    let article_with_feed = repository::get_article_with_feed_by_id(&state.db_pool, article_id)
+        .await?
+        .ok_or(article_service::ArticleServiceError::NotFound)?;
+
+    let template = ArticleCompactRowTemplate {
+        item: article_with_feed,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
+    };
+
+    Ok(Html(template.render()?))
+}
+
+/// Mute or unmute the article's feed. Counts change, so the sidebar is told
+/// to refresh as it is for read-state changes.
+pub async fn toggle_feed_muted(
     State(state): State<AppState>,
-    Query(params): Query<MarkAllReadParams>,
+    Path(article_id): Path<i64>,
 ) -> Result<Response, AppError> {
-    // Parse feed_ids and mark all as read
-    // For simplicity, we mark each feed individually if multiple are specified
-    let feed_ids = parse_ids(params.feed_ids.as_deref());
-
-    let count = if feed_ids.is_empty() {
-        // Mark all articles as read
-        article_service::mark_all_read(&state.db_pool, None).await?
-    } else if feed_ids.len() == 1 {
-        // Single feed
-        article_service::mark_all_read(&state.db_pool, Some(feed_ids[0])).await?
-    } else {
-        // Multiple feeds - mark each one
-        let mut total = 0u64;
-        for feed_id in feed_ids {
-            total += article_service::mark_all_read(&state.db_pool, Some(feed_id)).await?;
-        }
-        total
+    let item = toggle_feed_muted_of_article(&state.db_pool, article_id).await?;
+    let template = ArticleRowTemplate {
+        item,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
     };
 
+    Ok(read_status_changed(Html(template.render()?)))
+}
+
+pub async fn toggle_feed_muted_compact(
+    State(state): State<AppState>,
+    Path(article_id): Path<i64>,
+) -> Result<Response, AppError> {
+    let item = toggle_feed_muted_of_article(&state.db_pool, article_id).await?;
+    let template = ArticleCompactRowTemplate {
+        item,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
+    };
+
+    Ok(read_status_changed(Html(template.render()?)))
+}
+
+/// Flip the mute flag of the article's feed and reload the article
+async fn toggle_feed_muted_of_article(
+    pool: &SqlitePool,
+    article_id: i64,
+) -> Result<ArticleWithFeed, AppError> {
+    let article = repository::get_article_by_id(pool, article_id)
+        .await?
+        .ok_or(article_service::ArticleServiceError::NotFound)?;
+    repository::toggle_feed_muted(pool, article.feed_id).await?;
+
+    let article_with_feed = repository::get_article_with_feed_by_id(pool, article_id)
+        .await?
+        .ok_or(article_service::ArticleServiceError::NotFound)?;
+
+    Ok(article_with_feed)
+}
+
+/// Mark read the articles of the current view, with all its filters
+pub async fn mark_all_read(
+    State(state): State<AppState>,
+    Query(params): Query<ArticleListParams>,
+) -> Result<Response, AppError> {
+    // Read articles stay read whichever read-state tab is open
+    let filter = list_filter(&state, &params, None).await?;
+    let count = article_service::mark_search_read(&state.db_pool, &filter).await?;
+
     tracing::info!("Marked {} articles as read", count);
 
-    // Return HX-Refresh header to reload the page
+    // Redirect back to the article list, keeping the user's current filters
+    let redirect_url = filters_from_params(&params, params.is_read).list_url();
+    Ok((
+        StatusCode::OK,
+        HxRedirect(redirect_url),
+        format!("Marked {} articles as read", count),
+    )
+        .into_response())
+}
+
+/// Mark read exactly the articles a search returns (POST /articles/search/mark-read)
+pub async fn mark_search_read(
+    State(state): State<AppState>,
+    Query(params): Query<ArticleListParams>,
+) -> Result<Response, AppError> {
+    let search = search_filters(&state, &params).await?;
+    // A search that matches everything would mark every article read
+    if !search_narrows(&search) {
+        return Err(AppError::BadRequest(
+            "Enter a search term, feed or date range to mark its results as read.".to_string(),
+        ));
+    }
+
+    let count = article_service::mark_search_read(&state.db_pool, &search).await?;
+    tracing::info!("Marked {} search results as read", count);
+
+    let redirect_url = search_url(
+        params.q.as_deref(),
+        params.date_from.as_deref(),
+        params.date_to.as_deref(),
+    );
     Ok((
         StatusCode::OK,
-        [("HX-Refresh", "true")],
+        HxRedirect(redirect_url),
         format!("Marked {} articles as read", count),
     )
         .into_response())
 }
 
+#[derive(Deserialize)]
+pub struct BulkUpdateForm {
+    #[serde(default, deserialize_with = "deserialize_id_list")]
+    pub article_ids: Vec<i64>,
+    pub action: String,
+}
+
+#[derive(Serialize)]
+pub struct BulkUpdateResponse {
+    pub affected: u64,
+}
+
+/// Mark a selection of articles as read/unread or star/unstar them (POST /articles/bulk)
+pub async fn bulk_update(
+    State(state): State<AppState>,
+    Form(form): Form<BulkUpdateForm>,
+) -> Result<Response, AppError> {
+    let action = article_service::BulkAction::parse(&form.action).ok_or_else(|| {
+        AppError::BadRequest(format!(
+            "Unknown action \"{}\", expected read, unread, star or unstar.",
+            form.action
+        ))
+    })?;
+
+    let affected = article_service::bulk_update(&state.db_pool, &form.article_ids, action).await?;
+    tracing::info!(
+        "Applied {:?} to {} of {} articles",
+        action,
+        affected,
+        form.article_ids.len()
+    );
+
+    Ok((
+        [("HX-Trigger", "articlesRead")],
+        Json(BulkUpdateResponse { affected }),
+    )
+        .into_response())
+}
+
 pub async fn mark_read_status(
     State(state): State<AppState>,
     Path(article_id): Path<i64>,
-) -> Result<Html<String>, AppError> {
+) -> Result<Response, AppError> {
     article_service::mark_as_read(&state.db_pool, article_id).await?;
 
     // Get article with feed info in a single JOIN query
@@ -505,15 +702,17 @@ pub async fn mark_read_status(
 
     let template = ArticleRowTemplate {
         item: article_with_feed,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
     };
 
-    Ok(Html(template.render()?))
+    Ok(read_status_changed(Html(template.render()?)))
 }
 
 pub async fn mark_read_status_compact(
     State(state): State<AppState>,
     Path(article_id): Path<i64>,
-) -> Result<Html<String>, AppError> {
+) -> Result<Response, AppError> {
     article_service::mark_as_read(&state.db_pool, article_id).await?;
 
     // Get article with feed info in a single JOIN query
@@ -523,15 +722,17 @@ pub async fn mark_read_status_compact(
 
     let template = ArticleCompactRowTemplate {
         item: article_with_feed,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
     };
 
-    Ok(Html(template.render()?))
+    Ok(read_status_changed(Html(template.render()?)))
 }
 
 pub async fn toggle_read_status_fullscreen(
     State(state): State<AppState>,
     Path(article_id): Path<i64>,
-) -> Result<Html<String>, AppError> {
+) -> Result<Response, AppError> {
     article_service::toggle_read_status(&state.db_pool, article_id).await?;
 
     let article_with_feed = repository::get_article_with_feed_by_id(&state.db_pool, article_id)
@@ -540,9 +741,11 @@ pub async fn toggle_read_status_fullscreen(
 
     let template = ArticleFullscreenRowTemplate {
         item: article_with_feed,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
     };
 
-    Ok(Html(template.render()?))
+    Ok(read_status_changed(Html(template.render()?)))
 }
 
 pub async fn toggle_starred_status_fullscreen(
@@ -557,15 +760,36 @@ pub async fn toggle_starred_status_fullscreen(
 
     let template = ArticleFullscreenRowTemplate {
         item: article_with_feed,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
     };
 
     Ok(Html(template.render()?))
 }
 
-pub async fn mark_read_status_fullscreen(
+pub async fn toggle_read_later_status_fullscreen(
     State(state): State<AppState>,
     Path(article_id): Path<i64>,
 ) -> Result<Html<String>, AppError> {
+    article_service::toggle_read_later_status(&state.db_pool, article_id).await?;
+
+    let article_with_feed = repository::get_article_with_feed_by_id(&state.db_pool, article_id)
+        .await?
+        .ok_or(article_service::ArticleServiceError::NotFound)?;
+
+    let template = ArticleFullscreenRowTemplate {
+        item: article_with_feed,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
+    };
+
+    Ok(Html(template.render()?))
+}
+
+pub async fn mark_read_status_fullscreen(
+    State(state): State<AppState>,
+    Path(article_id): Path<i64>,
+) -> Result<Response, AppError> {
     article_service::mark_as_read(&state.db_pool, article_id).await?;
 
     let article_with_feed = repository::get_article_with_feed_by_id(&state.db_pool, article_id)
@@ -574,6 +798,153 @@ pub async fn mark_read_status_fullscreen(
 
     let template = ArticleFullscreenRowTemplate {
         item: article_with_feed,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
+    };
+
+    Ok(read_status_changed(Html(template.render()?)))
+}
+
+/// Article with its feed and tags for the permalink page
+async fn load_article_detail(
+    pool: &SqlitePool,
+    article_id: i64,
+) -> Result<ArticleWithFeed, AppError> {
+    let article_with_feed = repository::get_article_with_feed_by_id(pool, article_id)
+        .await?
+        .ok_or(article_service::ArticleServiceError::NotFound)?;
+
+    let mut tagged = attach_tags_to_articles(pool, vec![article_with_feed]).await?;
+    Ok(tagged.remove(0))
+}
+
+/// Permalink page of a single article (GET /articles/:id)
+pub async fn show_article(
+    State(state): State<AppState>,
+    Path(article_id): Path<i64>,
+) -> Result<Html<String>, AppError> {
+    let template = ArticleDetailTemplate {
+        item: load_article_detail(&state.db_pool, article_id).await?,
+        theme: repository::get_preferences(&state.db_pool).await?.theme,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
+    };
+
+    Ok(Html(template.render()?))
+}
+
+/// Form for importing read/starred states from another reader (GET /articles/import-state/form)
+pub async fn show_state_import_form() -> Result<Html<String>, AppError> {
+    Ok(Html(ArticleStateImportFormTemplate.render()?))
+}
+
+#[derive(Deserialize)]
+pub struct ArticleStateImportForm {
+    states: String,
+}
+
+/// Apply imported read/starred states to matching articles (POST /articles/import-state)
+pub async fn import_article_states(
+    State(state): State<AppState>,
+    Form(form): Form<ArticleStateImportForm>,
+) -> Result<Html<String>, AppError> {
+    let result = article_service::import_article_states(&state.db_pool, &form.states).await?;
+
+    let template = ArticleStateImportResultsTemplate {
+        applied: result.applied,
+        unmatched: result.unmatched,
+    };
+    Ok(Html(template.render()?))
+}
+
+/// Full text of an article replacing its preview in a list view (HTMX)
+pub async fn show_article_content(
+    State(state): State<AppState>,
+    Path(article_id): Path<i64>,
+) -> Result<Html<String>, AppError> {
+    let template = ArticleContentTemplate {
+        item: load_article_detail(&state.db_pool, article_id).await?,
+        image_proxy: state.image_proxy.signer(),
+    };
+
+    Ok(Html(template.render()?))
+}
+
+pub async fn toggle_read_status_detail(
+    State(state): State<AppState>,
+    Path(article_id): Path<i64>,
+) -> Result<Response, AppError> {
+    article_service::toggle_read_status(&state.db_pool, article_id).await?;
+
+    let template = ArticleDetailCardTemplate {
+        item: load_article_detail(&state.db_pool, article_id).await?,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
+    };
+
+    Ok(read_status_changed(Html(template.render()?)))
+}
+
+pub async fn toggle_starred_status_detail(
+    State(state): State<AppState>,
+    Path(article_id): Path<i64>,
+) -> Result<Html<String>, AppError> {
+    article_service::toggle_starred_status(&state.db_pool, article_id).await?;
+
+    let template = ArticleDetailCardTemplate {
+        item: load_article_detail(&state.db_pool, article_id).await?,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
+    };
+
+    Ok(Html(template.render()?))
+}
+
+pub async fn toggle_read_later_status_detail(
+    State(state): State<AppState>,
+    Path(article_id): Path<i64>,
+) -> Result<Html<String>, AppError> {
+    article_service::toggle_read_later_status(&state.db_pool, article_id).await?;
+
+    let template = ArticleDetailCardTemplate {
+        item: load_article_detail(&state.db_pool, article_id).await?,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
+    };
+
+    Ok(Html(template.render()?))
+}
+
+#[derive(Deserialize)]
+pub struct DigestParams {
+    pub date: Option<String>,
+    pub unread: Option<bool>,
+}
+
+/// Print-friendly digest of one day's articles (GET /articles/digest)
+///
+/// Defaults to today's unread articles; pass `unread=false` to include read ones.
+pub async fn show_digest(
+    State(state): State<AppState>,
+    Query(params): Query<DigestParams>,
+) -> Result<Html<String>, AppError> {
+    // Days begin and end at midnight where the reader is
+    let tz = state.display_timezone;
+    let date = match params.date.as_deref().filter(|d| !d.is_empty()) {
+        Some(date) => chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| AppError::BadRequest("Expected a date as YYYY-MM-DD.".to_string()))?,
+        None => chrono::Utc::now().with_timezone(&tz).date_naive(),
+    };
+    let unread_only = params.unread.unwrap_or(true);
+
+    let feeds = article_service::daily_digest(&state.db_pool, date, tz, unread_only).await?;
+
+    let template = ArticleDigestTemplate {
+        date,
+        unread_only,
+        article_count: feeds.iter().map(|f| f.entries.len()).sum(),
+        feeds,
+        timezone: tz,
     };
 
     Ok(Html(template.render()?))
@@ -587,6 +958,7 @@ pub enum AppError {
     FeedServiceError(feed_service::FeedServiceError),
     DatabaseError(sqlx::Error),
     NotFound(String),
+    BadRequest(String),
 }
 
 impl From<askama::Error> for AppError {
@@ -630,6 +1002,14 @@ impl IntoResponse for AppError {
                 "Not Found".to_string(),
                 "The article you're looking for doesn't exist.".to_string(),
             ),
+            AppError::ServiceError(article_service::ArticleServiceError::NotShareable) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Not Shareable".to_string(),
+                "This article has no web link that could be shared.".to_string(),
+            ),
+            AppError::ServiceError(article_service::ArticleServiceError::InvalidStateImport(
+                msg,
+            )) => (StatusCode::BAD_REQUEST, "Bad Request".to_string(), msg),
             AppError::ServiceError(article_service::ArticleServiceError::DatabaseError(err)) => {
                 tracing::error!("Database error: {}", err);
                 (
@@ -652,22 +1032,70 @@ impl IntoResponse for AppError {
                 )
             }
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, "Not Found".to_string(), msg),
+            AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "Bad Request".to_string(), msg),
         };
 
-        let template = ErrorTemplate {
-            status_code: status_code.as_u16(),
-            status_text,
-            message,
-        };
+        render_error(status_code, status_text, message)
+    }
+}
 
-        match template.render() {
-            Ok(html) => (status_code, Html(html)).into_response(),
-            Err(err) => {
-                tracing::error!("Error rendering error template: {}", err);
-                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response()
-            }
+/// Whether a search restricts the articles at all; `is:` read state alone
+/// does not count
+fn search_narrows(search: &ArticleListFilter) -> bool {
+    search.search_query.is_some()
+        || search.feed_ids.is_some()
+        || search.is_starred.is_some()
+        || search.date_from.is_some()
+        || search.date_to.is_some()
+}
+
+/// Filters of an article search, resolved from its query and date parameters
+async fn search_filters(
+    state: &AppState,
+    params: &ArticleListParams,
+) -> Result<ArticleListFilter, AppError> {
+    // Operators in the query (feed:, author:, is:, before:, after:) become filters
+    let search = params
+        .q
+        .as_deref()
+        .map(article_service::parse_search_query)
+        .unwrap_or_default();
+
+    // Parse date parameters; before:/after: in the query take precedence
+    let date_from = search.date_from().or_else(|| {
+        params
+            .date_from
+            .as_ref()
+            .and_then(|s| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+            .map(|d| d.and_hms_opt(0, 0, 0).unwrap().and_utc())
+    });
+
+    let date_to = search.date_to().or_else(|| {
+        params
+            .date_to
+            .as_ref()
+            .and_then(|s| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+            .map(|d| d.and_hms_opt(23, 59, 59).unwrap().and_utc())
+    });
+
+    let feed_ids = match &search.feed {
+        Some(name) => {
+            let ids = article_service::feed_ids_by_title(&state.db_pool, name).await?;
+            // No matching feed must not fall back to searching every feed
+            Some(if ids.is_empty() { vec![-1] } else { ids })
         }
-    }
+        None => None,
+    };
+
+    Ok(ArticleListFilter {
+        feed_ids,
+        is_read: search.is_read,
+        is_starred: search.is_starred,
+        search_query: search.fts_query(),
+        date_from,
+        date_to,
+        ..Default::default()
+    })
 }
 
 pub async fn search_articles(
@@ -676,39 +1104,18 @@ pub async fn search_articles(
     Query(params): Query<ArticleListParams>,
 ) -> Result<Html<String>, AppError> {
     // If `loaded` param is set (from returning to page), use it as the initial limit
-    let default_limit = 20;
-    let limit = params.loaded.unwrap_or_else(|| params.limit.unwrap_or(default_limit));
+    let limit = state.page_size.initial_limit(params.loaded, params.limit);
     let offset = params.offset.unwrap_or(0);
 
-    // Parse date parameters
-    let date_from = params
-        .date_from
-        .as_ref()
-        .and_then(|s| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
-        .map(|d| d.and_hms_opt(0, 0, 0).unwrap().and_utc());
-
-    let date_to = params
-        .date_to
-        .as_ref()
-        .and_then(|s| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
-        .map(|d| d.and_hms_opt(23, 59, 59).unwrap().and_utc());
+    let search = search_filters(&state, &params).await?;
 
     // Only search if we have a query or date filter
     let (articles_with_feed, has_more) =
-        if params.q.is_some() || date_from.is_some() || date_to.is_some() {
+        if params.q.is_some() || search.date_from.is_some() || search.date_to.is_some() {
             // Get articles with feed data in a single JOIN query (no N+1 problem)
-            let articles_with_feed = repository::list_articles_with_feeds(
-                &state.db_pool,
-                None, // No feed filter on search page
-                None, // No read filter on search page
-                None, // No starred filter on search page
-                params.q.clone(),
-                date_from,
-                date_to,
-                limit + 1,
-                offset,
-            )
-            .await?;
+            let articles_with_feed =
+                repository::list_articles_with_feeds(&state.db_pool, &search, limit + 1, offset)
+                    .await?;
 
             let has_more_results = articles_with_feed.len() > limit as usize;
             let articles_to_show: Vec<_> = articles_with_feed
@@ -727,6 +1134,8 @@ pub async fn search_articles(
                 // Return just the article rows for pagination
                 let rows_template = ArticleRowsTemplate {
                     articles: articles_to_show,
+                    image_proxy: state.image_proxy.signer(),
+                    timezone: state.display_timezone,
                 };
                 return Ok(Html(rows_template.render()?));
             }
@@ -744,6 +1153,9 @@ pub async fn search_articles(
         search_query: params.q.clone(),
         date_from: params.date_from.clone(),
         date_to: params.date_to.clone(),
+        theme: repository::get_preferences(&state.db_pool).await?.theme,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
     };
 
     Ok(Html(template.render()?))
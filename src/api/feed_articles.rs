@@ -0,0 +1,88 @@
+use crate::api::feeds::AppState;
+use crate::domain::models::{Article, ArticleListFilter};
+use crate::infrastructure::error_pages::json_error;
+use crate::infrastructure::repository;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct FeedArticlesQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    /// `true` for unread articles only, `false` for read ones only
+    pub unread: Option<bool>,
+    pub starred: Option<bool>,
+    pub read_later: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct FeedArticle {
+    #[serde(flatten)]
+    pub article: Article,
+    pub feed_title: String,
+}
+
+/// List the articles of one feed, newest first (GET /api/feeds/:id/articles)
+pub async fn list_feed_articles(
+    State(state): State<AppState>,
+    Path(feed_id): Path<i64>,
+    Query(query): Query<FeedArticlesQuery>,
+) -> Response {
+    match load_feed_articles(&state, feed_id, &query).await {
+        Ok(Some(articles)) => (StatusCode::OK, Json(articles)).into_response(),
+        Ok(None) => json_error(
+            StatusCode::NOT_FOUND,
+            "The feed you're looking for doesn't exist.".to_string(),
+        ),
+        Err(e) => {
+            tracing::error!("Failed to list articles of feed {}: {}", feed_id, e);
+            json_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load articles. Please try again later.".to_string(),
+            )
+        }
+    }
+}
+
+/// The requested page of a feed's articles, or `None` if there is no such feed
+async fn load_feed_articles(
+    state: &AppState,
+    feed_id: i64,
+    query: &FeedArticlesQuery,
+) -> Result<Option<Vec<FeedArticle>>, sqlx::Error> {
+    if repository::get_feed_by_id(&state.db_pool, feed_id)
+        .await?
+        .is_none()
+    {
+        return Ok(None);
+    }
+
+    let articles = repository::list_articles_with_feeds(
+        &state.db_pool,
+        &ArticleListFilter {
+            feed_ids: Some(vec![feed_id]),
+            is_read: query.unread.map(|unread| !unread),
+            is_starred: query.starred,
+            is_read_later: query.read_later,
+            ..Default::default()
+        },
+        state.page_size.limit(query.limit),
+        query.offset.unwrap_or(0).max(0),
+    )
+    .await?;
+
+    Ok(Some(
+        articles
+            .into_iter()
+            .map(|item| FeedArticle {
+                article: item.article,
+                feed_title: item.feed_title,
+            })
+            .collect(),
+    ))
+}
@@ -0,0 +1,18 @@
+use crate::api::feeds::AppState;
+use crate::infrastructure::error_pages::json_error;
+use crate::infrastructure::repository;
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+
+/// List all feeds with article counts and last fetch status (GET /api/feeds)
+pub async fn list_feed_stats(State(state): State<AppState>) -> impl IntoResponse {
+    match repository::list_feed_stats(&state.db_pool).await {
+        Ok(stats) => (StatusCode::OK, Json(stats)).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to list feed stats: {}", e);
+            json_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load feeds. Please try again later.".to_string(),
+            )
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use crate::api::feeds::AppState;
+use crate::domain::feed_service;
+use crate::domain::models::FeedValidation;
+use axum::{extract::State, Json};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct ValidateFeedRequest {
+    pub url: String,
+}
+
+/// Dry-run a subscription: fetch and parse the URL without storing anything
+/// (POST /api/feeds/validate)
+pub async fn validate_feed(
+    State(state): State<AppState>,
+    Json(request): Json<ValidateFeedRequest>,
+) -> Json<FeedValidation> {
+    Json(feed_service::validate_feed_url(request.url.trim(), &state.fetch_host_policy).await)
+}
@@ -1,20 +1,40 @@
-use crate::domain::feed_service;
-use crate::infrastructure::{repository, scheduler};
+use crate::api::params::deserialize_id_list;
+use crate::config::{
+    AdaptiveInterval, FeedSort, FetchDelays, HostPolicy, NewFeedFetch, PageSize, QuietHours,
+    ReaderSanitization, StorageLimits,
+};
+use crate::domain::feed_service::{self, FeedServiceError};
+use crate::domain::models::{
+    ArticleListFilter, Feed, FeedHealth, FeedListFilter, FeedSettings, GuidStrategy,
+};
+use crate::infrastructure::error_pages::{render_error, render_error_with_link};
+use crate::infrastructure::group_tree_cache::GroupTreeCache;
+use crate::infrastructure::image_proxy::ImageProxy;
+use crate::infrastructure::insert_progress::InsertProgressStore;
+use crate::infrastructure::reader_limiter::ReaderLimiter;
+use crate::infrastructure::scheduler_status::SchedulerStatus;
+use crate::infrastructure::secrets::SecretKey;
+use crate::infrastructure::{repository, rss_fetcher, scheduler};
+use crate::web::atom;
+use crate::web::opml::{self, OpmlFeed};
+use crate::web::redirect::HxRedirect;
 use crate::web::templates::{
-    ErrorTemplate, FeedDetailTemplate, FeedFormTemplate, FeedImportFormTemplate,
-    FeedImportProgressTemplate, FeedImportResultsTemplate, FeedRowTemplate, FeedsListTemplate,
-    ImportResult,
+    ErrorLink, FeedDetailTemplate, FeedDiagnosticsTemplate, FeedFormTemplate,
+    FeedImportFormTemplate, FeedImportProgressTemplate, FeedImportResultsTemplate,
+    FeedRowFetchingTemplate, FeedRowTemplate, FeedRowsTemplate, FeedsListTemplate, ImportResult,
+    LoadMoreButtonFeedsTemplate,
 };
 use askama::Template;
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
     response::{Html, IntoResponse, Response},
-    Form,
+    Form, Json,
 };
-use serde::Deserialize;
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -63,6 +83,43 @@ pub type ImportJobStore = Arc<RwLock<HashMap<String, ImportJob>>>;
 pub struct AppState {
     pub db_pool: SqlitePool,
     pub import_jobs: ImportJobStore,
+    pub fetch_delays: FetchDelays,
+    pub adaptive_interval: AdaptiveInterval,
+    pub archive_backfill_pages: usize,
+    pub max_feeds_per_cycle: i64,
+    /// No feed is fetched in this window
+    pub quiet_hours: Option<QuietHours>,
+    pub storage_limits: StorageLimits,
+    pub api_token: Option<String>,
+    pub page_size: PageSize,
+    pub feed_sort: FeedSort,
+    pub group_tree_cache: GroupTreeCache,
+    pub reader_limiter: ReaderLimiter,
+    pub scheduler_status: SchedulerStatus,
+    pub insert_progress: InsertProgressStore,
+    pub image_proxy: ImageProxy,
+    /// Zone of absolute timestamps and of quiet hours
+    pub display_timezone: Tz,
+    pub reader_sanitization: ReaderSanitization,
+    /// Hosts feeds may be fetched from
+    pub fetch_host_policy: Arc<HostPolicy>,
+    /// Key of credentials stored in the database
+    pub secret_key: SecretKey,
+}
+
+impl AppState {
+    /// Settings for the first fetch of a feed subscribed to now
+    pub fn new_feed_fetch(&self) -> NewFeedFetch {
+        NewFeedFetch {
+            delays: self.fetch_delays,
+            adaptive: self.adaptive_interval,
+            archive_backfill_pages: self.archive_backfill_pages,
+            limits: self.storage_limits,
+            reader_sanitization: self.reader_sanitization,
+            host_policy: self.fetch_host_policy.clone(),
+            secret_key: self.secret_key.clone(),
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -71,62 +128,6 @@ pub struct CreateFeedForm {
     title: Option<String>,
 }
 
-/// Deserialize a form field that can be either a single value or multiple values into a Vec
-fn deserialize_id_list<'de, D>(deserializer: D) -> Result<Vec<i64>, D::Error>
-where
-    D: serde::Deserializer<'de>,
-{
-    use serde::de::{self, Visitor};
-    use std::fmt;
-
-    struct IdListVisitor;
-
-    impl<'de> Visitor<'de> for IdListVisitor {
-        type Value = Vec<i64>;
-
-        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-            formatter.write_str("a string, integer, or sequence of integers")
-        }
-
-        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
-        where
-            E: de::Error,
-        {
-            value
-                .parse::<i64>()
-                .map(|id| vec![id])
-                .map_err(de::Error::custom)
-        }
-
-        fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
-        where
-            E: de::Error,
-        {
-            Ok(vec![value])
-        }
-
-        fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
-        where
-            E: de::Error,
-        {
-            Ok(vec![value as i64])
-        }
-
-        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
-        where
-            A: de::SeqAccess<'de>,
-        {
-            let mut ids = Vec::new();
-            while let Some(value) = seq.next_element::<String>()? {
-                ids.push(value.parse::<i64>().map_err(de::Error::custom)?);
-            }
-            Ok(ids)
-        }
-    }
-
-    deserializer.deserialize_any(IdListVisitor)
-}
-
 #[derive(Deserialize)]
 pub struct UpdateFeedForm {
     pub title: String,
@@ -135,18 +136,128 @@ pub struct UpdateFeedForm {
     pub fetch_frequency: String,
     pub color: String,
     pub ignore_pattern: Option<String>,
+    #[serde(default)]
+    pub never_trim: bool,
+    #[serde(default)]
+    pub auto_fetch_full_content: bool,
+    #[serde(default)]
+    pub prefer_summary: bool,
+    #[serde(default)]
+    pub mark_new_as_read: bool,
+    /// One of the [`GuidStrategy`] names; left as is if absent
+    #[serde(default)]
+    pub guid_strategy: Option<String>,
+    /// `HH:MM-HH:MM`, empty for none
+    #[serde(default)]
+    pub quiet_hours: Option<String>,
+    #[serde(default)]
+    pub custom_headers: Option<String>,
+    /// New bearer token; empty keeps the stored one
+    #[serde(default)]
+    pub bearer_token: Option<String>,
+    #[serde(default)]
+    pub clear_bearer_token: bool,
     #[serde(default, deserialize_with = "deserialize_id_list")]
     pub tag_ids: Vec<i64>,
+    /// Group to move the feed into, empty for ungrouped; left as is if absent
+    #[serde(default)]
+    pub group_id: Option<String>,
     /// Ignored field sent by the form for custom frequency input
     #[allow(dead_code)]
     #[serde(default)]
     pub custom_hours: Option<String>,
 }
 
-pub async fn list_feeds(State(state): State<AppState>) -> Result<Html<String>, AppError> {
-    let feeds = feed_service::list_all_feeds(&state.db_pool).await?;
+#[derive(Deserialize)]
+pub struct FeedListParams {
+    pub q: Option<String>,
+    pub group_id: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+pub async fn list_feeds(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<FeedListParams>,
+) -> Result<Html<String>, AppError> {
+    let limit = state.page_size.limit(params.limit);
+    let offset = params.offset.unwrap_or(0);
+
+    let filter = FeedListFilter {
+        search: params
+            .q
+            .map(|q| q.trim().to_string())
+            .filter(|q| !q.is_empty()),
+        group_id: params.group_id.and_then(|id| id.parse().ok()),
+    };
+
+    let feeds = repository::list_feeds_paged(
+        &state.db_pool,
+        &filter,
+        limit + 1, // Fetch one extra to check if there are more
+        offset,
+    )
+    .await?;
+
+    let has_more = feeds.len() > limit as usize;
+    let feeds: Vec<_> = feeds.into_iter().take(limit as usize).collect();
 
-    let template = FeedsListTemplate { feeds };
+    // If HTMX request with offset > 0, return just the feed rows for pagination
+    if headers.get("HX-Request").is_some() && offset > 0 {
+        let mut html = FeedRowsTemplate { feeds }.render()?;
+
+        // Update the Load More button using out-of-band swap
+        if has_more {
+            let button_template = LoadMoreButtonFeedsTemplate {
+                next_offset: offset + limit,
+                filter,
+            };
+            html.push_str(
+                r#"<div id="load-more-container" hx-swap-oob="true" class="mt-8 text-center">"#,
+            );
+            html.push_str(&button_template.render()?);
+            html.push_str("</div>");
+        } else {
+            html.push_str(r#"<div id="load-more-container" hx-swap-oob="true"></div>"#);
+        }
+
+        return Ok(Html(html));
+    }
+
+    // Groups for the filter dropdown
+    let groups = repository::list_groups(&state.db_pool).await?;
+
+    let template = FeedsListTemplate {
+        feeds,
+        groups,
+        offset,
+        limit,
+        has_more,
+        filter,
+        theme: repository::get_preferences(&state.db_pool).await?.theme,
+    };
+    Ok(Html(template.render()?))
+}
+
+/// Feeds grouped by fetch health (GET /feeds/diagnostics)
+pub async fn show_feed_diagnostics(
+    State(state): State<AppState>,
+) -> Result<Html<String>, AppError> {
+    let mut template = FeedDiagnosticsTemplate {
+        failing: Vec::new(),
+        pending: Vec::new(),
+        healthy: Vec::new(),
+        timezone: state.display_timezone,
+        theme: repository::get_preferences(&state.db_pool).await?.theme,
+    };
+    for diagnostic in repository::list_feed_diagnostics(&state.db_pool).await? {
+        match diagnostic.health() {
+            FeedHealth::Failing => template.failing.push(diagnostic),
+            FeedHealth::Pending => template.pending.push(diagnostic),
+            FeedHealth::Healthy => template.healthy.push(diagnostic),
+        }
+    }
     Ok(Html(template.render()?))
 }
 
@@ -155,14 +266,40 @@ pub async fn show_feed_form() -> Result<Html<String>, AppError> {
     Ok(Html(template.render()?))
 }
 
+#[derive(Deserialize)]
+pub struct CreateFeedParams {
+    /// Respond at once and fetch the new feed in the background
+    #[serde(default)]
+    pub defer: bool,
+}
+
 pub async fn create_feed(
     State(state): State<AppState>,
+    Query(params): Query<CreateFeedParams>,
     Form(form): Form<CreateFeedForm>,
 ) -> Result<Html<String>, AppError> {
+    if params.defer {
+        let (feed, _) = feed_service::create_feed_in_background(
+            &state.db_pool,
+            form.url,
+            form.title.filter(|s| !s.is_empty()),
+            state.new_feed_fetch(),
+            state.insert_progress.clone(),
+        )
+        .await?;
+
+        let template = FeedRowFetchingTemplate {
+            feed,
+            progress: None,
+        };
+        return Ok(Html(template.render()?));
+    }
+
     let feed = feed_service::create_feed(
         &state.db_pool,
         form.url,
         form.title.filter(|s| !s.is_empty()),
+        &state.new_feed_fetch(),
     )
     .await?;
 
@@ -170,39 +307,248 @@ pub async fn create_feed(
     Ok(Html(template.render()?))
 }
 
-pub async fn show_feed(
+/// Row of a feed, or its placeholder while the first fetch is still running
+/// (GET /feeds/:id/row)
+pub async fn show_feed_row(
     State(state): State<AppState>,
-    Path(feed_id): Path<i64>,
+    Path(id): Path<i64>,
 ) -> Result<Html<String>, AppError> {
-    let feed = repository::get_feed_by_id(&state.db_pool, feed_id)
+    let feed = repository::get_feed_by_id(&state.db_pool, id)
         .await?
         .ok_or(feed_service::FeedServiceError::NotFound)?;
 
-    // Get tags for this feed
-    let tags = repository::get_feed_tags(&state.db_pool, feed_id).await?;
+    if feed.last_fetched_at.is_none() && !repository::feed_has_logs(&state.db_pool, id).await? {
+        let template = FeedRowFetchingTemplate {
+            progress: state.insert_progress.get(id),
+            feed,
+        };
+        return Ok(Html(template.render()?));
+    }
 
-    let template = FeedDetailTemplate { feed, tags };
+    let template = FeedRowTemplate { feed };
     Ok(Html(template.render()?))
 }
 
+/// Maximum number of stored articles included in a feed's Atom export
+const ATOM_ENTRY_LIMIT: i64 = 100;
+
+pub async fn show_feed(
+    State(state): State<AppState>,
+    Path(feed_path): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    // `/feeds/:id.atom` and `Accept: application/atom+xml` both select the Atom export
+    let (id_part, negotiated) = match feed_path.strip_suffix(".atom") {
+        Some(id_part) => (id_part, false),
+        None => (feed_path.as_str(), true),
+    };
+    let wants_atom = !negotiated || accepts_atom(&headers);
+    let feed_id: i64 = id_part
+        .parse()
+        .map_err(|_| feed_service::FeedServiceError::NotFound)?;
+
+    let feed = repository::get_feed_by_id(&state.db_pool, feed_id)
+        .await?
+        .ok_or(feed_service::FeedServiceError::NotFound)?;
+
+    let mut response = if wants_atom {
+        render_feed_atom(&state.db_pool, feed, &headers).await?
+    } else {
+        // Get tags for this feed
+        let tags = repository::get_feed_tags(&state.db_pool, feed_id).await?;
+
+        let template = FeedDetailTemplate {
+            feed,
+            tags,
+            timezone: state.display_timezone,
+            theme: repository::get_preferences(&state.db_pool).await?.theme,
+        };
+        Html(template.render()?).into_response()
+    };
+
+    // Caches must not hand the HTML page to a feed reader or vice versa
+    if negotiated {
+        response
+            .headers_mut()
+            .insert(header::VARY, HeaderValue::from_static("Accept"));
+    }
+    Ok(response)
+}
+
+/// Whether the client ranks Atom above HTML. A client that likes both
+/// equally, such as one sending only `*/*`, gets the page.
+fn accepts_atom(headers: &HeaderMap) -> bool {
+    let accept: Vec<_> = headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect();
+    let accept = accept.join(",");
+
+    media_quality(&accept, "application/atom+xml") > media_quality(&accept, "text/html")
+}
+
+/// The q-value an Accept header gives a media type, taken from the most
+/// specific range that matches it; 0 (not acceptable) if none does
+fn media_quality(accept: &str, media_type: &str) -> f32 {
+    let (kind, _) = media_type.split_once('/').unwrap_or((media_type, ""));
+    accept
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let range = parts.next()?.trim();
+            let specificity = if range.eq_ignore_ascii_case(media_type) {
+                3
+            } else if range
+                .strip_suffix("/*")
+                .is_some_and(|range_kind| range_kind.eq_ignore_ascii_case(kind))
+            {
+                2
+            } else if range == "*/*" {
+                1
+            } else {
+                return None;
+            };
+            let quality = parts
+                .find_map(|param| {
+                    let (name, value) = param.split_once('=')?;
+                    name.trim()
+                        .eq_ignore_ascii_case("q")
+                        .then(|| value.trim().parse::<f32>().ok())
+                        .flatten()
+                })
+                .unwrap_or(1.0)
+                .clamp(0.0, 1.0);
+            Some((specificity, quality))
+        })
+        .max_by_key(|(specificity, _)| *specificity)
+        .map_or(0.0, |(_, quality)| quality)
+}
+
+async fn render_feed_atom(
+    pool: &SqlitePool,
+    feed: Feed,
+    headers: &HeaderMap,
+) -> Result<Response, AppError> {
+    let articles: Vec<_> = repository::list_articles_with_feeds(
+        pool,
+        &ArticleListFilter {
+            feed_ids: Some(vec![feed.id]),
+            ..Default::default()
+        },
+        ATOM_ENTRY_LIMIT,
+        0,
+    )
+    .await?
+    .into_iter()
+    .map(|a| a.article)
+    .collect();
+
+    // Conditional GET, so feed readers polling the export skip unchanged documents
+    let etag = atom::entity_tag(&feed, &articles);
+    let last_modified = httpdate::fmt_http_date(atom::feed_updated(&feed, &articles).into());
+    if if_none_match(headers, &etag) {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            [(header::ETAG, etag), (header::LAST_MODIFIED, last_modified)],
+        )
+            .into_response());
+    }
+
+    let xml = atom::render_feed(&feed, &articles).map_err(AppError::AtomError)?;
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                "application/atom+xml; charset=utf-8".to_string(),
+            ),
+            (header::ETAG, etag),
+            (header::LAST_MODIFIED, last_modified),
+        ],
+        xml,
+    )
+        .into_response())
+}
+
+/// Whether the client's `If-None-Match` lists the given entity tag
+pub(crate) fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
 pub async fn delete_feed(
     State(state): State<AppState>,
     Path(feed_id): Path<i64>,
 ) -> Result<impl IntoResponse, AppError> {
     feed_service::delete_feed(&state.db_pool, feed_id).await?;
-    Ok((StatusCode::OK, [("HX-Redirect", "/feeds")]))
+    Ok(HxRedirect("/feeds".to_string()))
+}
+
+/// Mute or unmute a feed; muted feeds keep fetching (POST /feeds/:id/toggle-muted)
+pub async fn toggle_feed_muted(
+    State(state): State<AppState>,
+    Path(feed_id): Path<i64>,
+) -> Result<Html<String>, AppError> {
+    if !repository::toggle_feed_muted(&state.db_pool, feed_id).await? {
+        return Err(feed_service::FeedServiceError::NotFound.into());
+    }
+
+    let feed = repository::get_feed_by_id(&state.db_pool, feed_id)
+        .await?
+        .ok_or(feed_service::FeedServiceError::NotFound)?;
+
+    let template = FeedRowTemplate { feed };
+    Ok(Html(template.render()?))
+}
+
+/// Pin or unpin a feed; pinned feeds are listed first in their group
+/// (POST /feeds/:id/toggle-pinned)
+pub async fn toggle_feed_pinned(
+    State(state): State<AppState>,
+    Path(feed_id): Path<i64>,
+) -> Result<Html<String>, AppError> {
+    if !repository::toggle_feed_pinned(&state.db_pool, feed_id).await? {
+        return Err(feed_service::FeedServiceError::NotFound.into());
+    }
+
+    let feed = repository::get_feed_by_id(&state.db_pool, feed_id)
+        .await?
+        .ok_or(feed_service::FeedServiceError::NotFound)?;
+
+    let template = FeedRowTemplate { feed };
+    Ok(Html(template.render()?))
 }
 
 pub async fn fetch_feed(
     State(state): State<AppState>,
     Path(feed_id): Path<i64>,
 ) -> Result<StatusCode, AppError> {
+    // The feed of saved pages has nothing to fetch
     let feed = repository::get_feed_by_id(&state.db_pool, feed_id)
         .await?
+        .filter(|feed| feed.url != repository::SAVED_FEED_URL)
         .ok_or(feed_service::FeedServiceError::NotFound)?;
 
-    match scheduler::fetch_single_feed(&state.db_pool, &feed).await {
-        Ok(scheduler::FetchSingleFeedResult::Updated { new_articles_count }) => {
+    match scheduler::fetch_single_feed(
+        &state.db_pool,
+        &feed,
+        &state.fetch_delays,
+        &state.adaptive_interval,
+        state.reader_sanitization,
+        &state.fetch_host_policy,
+        &state.secret_key,
+        Some(&state.insert_progress),
+    )
+    .await
+    {
+        Ok(scheduler::FetchSingleFeedResult::Updated {
+            new_articles_count, ..
+        }) => {
             tracing::info!(
                 "Fetched feed {} with {} new articles",
                 feed_id,
@@ -214,13 +560,66 @@ pub async fn fetch_feed(
         }
         Err(e) => {
             tracing::warn!("Failed to fetch feed {}: {}", feed_id, e);
-            return Err(AppError::FetchError(e.to_string()));
+            return Err(fetch_failure(e));
         }
     }
 
     Ok(StatusCode::OK)
 }
 
+/// Error for a failed interactive fetch; an upstream 429 is passed on as such
+fn fetch_failure(error: Box<dyn std::error::Error>) -> AppError {
+    match error.downcast_ref::<rss_fetcher::FetchError>() {
+        Some(rss_fetcher::FetchError::RequestFailed {
+            status,
+            retry_after,
+            ..
+        }) if status.as_u16() == 429 => AppError::RateLimited {
+            retry_after: retry_after.clone(),
+        },
+        _ => AppError::FetchError(error.to_string()),
+    }
+}
+
+/// When to try again, from an upstream `Retry-After` (seconds or an HTTP date)
+fn retry_hint(retry_after: Option<&str>) -> String {
+    match retry_after.map(str::trim) {
+        Some(value) => match value.parse::<u64>() {
+            Ok(1) => "Try again in 1 second.".to_string(),
+            Ok(seconds) if seconds < 120 => format!("Try again in {} seconds.", seconds),
+            Ok(seconds) => format!("Try again in {} minutes.", seconds.div_ceil(60)),
+            Err(_) => format!("Try again after {}.", value),
+        },
+        None => "Try again later.".to_string(),
+    }
+}
+
+#[derive(Serialize)]
+pub struct OpenGraphRefreshResponse {
+    pub queued: usize,
+}
+
+/// Re-run OpenGraph enrichment in the background for the feed's articles
+/// without an image (POST /feeds/:id/refresh-opengraph)
+pub async fn refresh_opengraph(
+    State(state): State<AppState>,
+    Path(feed_id): Path<i64>,
+) -> Result<Json<OpenGraphRefreshResponse>, AppError> {
+    repository::get_feed_by_id(&state.db_pool, feed_id)
+        .await?
+        .ok_or(feed_service::FeedServiceError::NotFound)?;
+
+    let queued =
+        scheduler::refresh_feed_opengraph(&state.db_pool, feed_id, &state.fetch_delays).await?;
+    tracing::info!(
+        "Queued {} articles of feed {} for OpenGraph refresh",
+        queued,
+        feed_id
+    );
+
+    Ok(Json(OpenGraphRefreshResponse { queued }))
+}
+
 pub async fn show_edit_feed_form(
     State(state): State<AppState>,
     Path(feed_id): Path<i64>,
@@ -233,10 +632,28 @@ pub async fn show_edit_feed_form(
     let feed_tags = repository::get_feed_tags(&state.db_pool, feed_id).await?;
     let feed_tag_ids: Vec<i64> = feed_tags.iter().map(|t| t.id).collect();
 
+    let custom_headers = repository::get_feed_headers(&state.db_pool, feed_id, &state.secret_key)
+        .await?
+        .iter()
+        .map(|h| format!("{}: {}", h.name, h.value))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let has_bearer_token =
+        repository::get_feed_bearer_token(&state.db_pool, feed_id, &state.secret_key)
+            .await?
+            .is_some();
+
+    let groups = repository::list_groups(&state.db_pool).await?;
+
     let template = crate::web::templates::FeedEditFormTemplate {
         feed,
         all_tags,
         feed_tag_ids,
+        custom_headers,
+        has_bearer_token,
+        groups,
+        theme: repository::get_preferences(&state.db_pool).await?.theme,
     };
     Ok(Html(template.render()?))
 }
@@ -256,7 +673,7 @@ pub async fn update_feed(
     }
 
     // Validate color format
-    if !form.color.starts_with('#') || form.color.len() != 7 {
+    if !is_hex_color(&form.color) {
         return Err(AppError::ServiceError(
             feed_service::FeedServiceError::InvalidUrl(
                 "Color must be in hex format (#RRGGBB)".to_string(),
@@ -281,9 +698,72 @@ pub async fn update_feed(
         })?;
     }
 
-    // Update in database
+    // Validate the GUID strategy
+    let guid_strategy = form
+        .guid_strategy
+        .as_deref()
+        .map(|value| {
+            GuidStrategy::parse(value).ok_or_else(|| {
+                AppError::ServiceError(feed_service::FeedServiceError::Validation(format!(
+                    "Unknown GUID strategy: {}",
+                    value
+                )))
+            })
+        })
+        .transpose()?;
+
+    // Validate the quiet hours and store them in their canonical form
+    let quiet_hours = form
+        .quiet_hours
+        .as_deref()
+        .filter(|value| !value.trim().is_empty())
+        .map(|value| {
+            QuietHours::parse(value)
+                .map(|quiet_hours| quiet_hours.to_string())
+                .map_err(|e| AppError::ServiceError(feed_service::FeedServiceError::Validation(e)))
+        })
+        .transpose()?;
+
+    // Validate custom request headers
+    let custom_headers =
+        feed_service::parse_custom_headers(form.custom_headers.as_deref().unwrap_or(""))?;
+
+    // A new token replaces the stored one. The stored token was set for the
+    // feed's origin, so it is dropped when the URL moves to another one.
+    let feed = repository::get_feed_by_id(&state.db_pool, feed_id)
+        .await?
+        .ok_or(feed_service::FeedServiceError::NotFound)?;
+    let bearer_token =
+        match feed_service::parse_bearer_token(form.bearer_token.as_deref().unwrap_or(""))? {
+            Some(token) => Some(Some(token)),
+            None if form.clear_bearer_token || !rss_fetcher::same_origin(&feed.url, &form.url) => {
+                Some(None)
+            }
+            None => None,
+        };
+
+    // Resolve the selected group; an empty selection ungroups the feed
+    let group_id = match form.group_id.as_deref().map(str::trim) {
+        None => None,
+        Some("") => Some(None),
+        Some(id) => {
+            let group = match id.parse() {
+                Ok(id) => repository::get_group(&state.db_pool, id).await?,
+                Err(_) => None,
+            };
+            let group = group.ok_or_else(|| {
+                AppError::ServiceError(feed_service::FeedServiceError::Validation(
+                    "Selected group does not exist".to_string(),
+                ))
+            })?;
+            Some(Some(group.id))
+        }
+    };
+
+    // Update in database, all settings or none
+    let mut tx = state.db_pool.begin().await?;
     repository::update_feed_properties(
-        &state.db_pool,
+        &mut *tx,
         feed_id,
         &form.title,
         &form.url,
@@ -292,17 +772,78 @@ pub async fn update_feed(
         fetch_interval_minutes,
         &form.color,
         ignore_pattern.as_deref(),
+        form.never_trim,
+        form.auto_fetch_full_content,
+        form.prefer_summary,
+        form.mark_new_as_read,
+        guid_strategy.map(|strategy| strategy.as_str()),
+        quiet_hours.as_deref(),
     )
     .await?;
 
+    if let Some(group_id) = group_id {
+        repository::update_feed_group(&mut *tx, feed_id, group_id).await?;
+    }
+
     // Update feed tags
-    repository::set_feed_tags(&state.db_pool, feed_id, &form.tag_ids).await?;
+    repository::set_feed_tags(&mut tx, feed_id, &form.tag_ids).await?;
+
+    // Update custom request headers
+    repository::set_feed_headers(&mut tx, feed_id, &custom_headers, &state.secret_key).await?;
+    if let Some(token) = bearer_token {
+        repository::set_feed_bearer_token(&mut *tx, feed_id, token.as_deref(), &state.secret_key)
+            .await?;
+    }
+    tx.commit().await?;
 
     // Redirect to feed detail page
+    Ok(HxRedirect(format!("/feeds/{}", feed_id)))
+}
+
+/// Export all feeds as OPML, with their FluxFeed settings (GET /feeds/export.opml)
+pub async fn export_opml(State(state): State<AppState>) -> Result<Response, AppError> {
+    // Leaves out the feed of saved pages, whose URL no reader could fetch
+    let feeds = repository::list_feeds(&state.db_pool).await?;
+    let xml = opml::render_opml(&feeds).map_err(AppError::OpmlError)?;
+
     Ok((
-        StatusCode::OK,
-        [("HX-Redirect", format!("/feeds/{}", feed_id))],
-    ))
+        [
+            (header::CONTENT_TYPE, "text/x-opml; charset=utf-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"fluxfeed-feeds.opml\"",
+            ),
+        ],
+        xml,
+    )
+        .into_response())
+}
+
+/// Export one feed as OPML for sharing, without its settings (GET /feeds/:id/opml)
+pub async fn export_feed_opml(
+    State(state): State<AppState>,
+    Path(feed_id): Path<i64>,
+) -> Result<Response, AppError> {
+    let feed = repository::get_feed_by_id(&state.db_pool, feed_id)
+        .await?
+        .filter(|feed| feed.url != repository::SAVED_FEED_URL)
+        .ok_or(feed_service::FeedServiceError::NotFound)?;
+    let xml = opml::render_feed_opml(&feed).map_err(AppError::OpmlError)?;
+
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                "text/x-opml; charset=utf-8".to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"fluxfeed-feed-{}.opml\"", feed.id),
+            ),
+        ],
+        xml,
+    )
+        .into_response())
 }
 
 pub async fn show_import_form() -> Result<Html<String>, AppError> {
@@ -319,9 +860,34 @@ pub struct ImportFeedsForm {
 struct ParsedFeedEntry {
     url: String,
     title: Option<String>,
+    settings: FeedSettings,
+}
+
+/// Feed settings restored from a FluxFeed OPML export. Values that would not
+/// pass the edit form's validation are dropped, leaving the default.
+fn imported_settings(feed: &OpmlFeed) -> FeedSettings {
+    FeedSettings {
+        fetch_frequency: feed.fetch_frequency.as_ref().and_then(|frequency| {
+            let minutes = feed_service::parse_fetch_frequency(frequency).ok()?;
+            Some((frequency.trim().to_string(), minutes))
+        }),
+        color: feed.color.clone().filter(|color| is_hex_color(color)),
+        ignore_pattern: feed
+            .ignore_pattern
+            .clone()
+            .filter(|pattern| regex::Regex::new(pattern).is_ok()),
+    }
+}
+
+fn is_hex_color(color: &str) -> bool {
+    color.starts_with('#') && color.len() == 7
 }
 
 /// Parse the import form input into individual feed entries
+///
+/// Each line is a URL, optionally followed by a title. The URL ends at the
+/// first space, tab or comma, so pasted CSV and TSV exports work too, and the
+/// title may be wrapped in double quotes.
 fn parse_import_input(input: &str) -> Vec<ParsedFeedEntry> {
     input
         .lines()
@@ -330,23 +896,57 @@ fn parse_import_input(input: &str) -> Vec<ParsedFeedEntry> {
             if line.is_empty() {
                 return None;
             }
-            // Split by whitespace - first part is URL, rest is optional title
-            let parts: Vec<&str> = line.splitn(2, ' ').collect();
-            let url = parts[0].to_string();
-            let title = parts
-                .get(1)
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty());
-            Some(ParsedFeedEntry { url, title })
+            let (url, rest) = line.split_once([' ', '\t', ',']).unwrap_or((line, ""));
+            let title = unquote_title(rest.trim());
+            Some(ParsedFeedEntry {
+                url: url.to_string(),
+                title: (!title.is_empty()).then_some(title),
+                settings: FeedSettings::default(),
+            })
         })
         .collect()
 }
 
+/// Feed entries of a pasted OPML document, with any FluxFeed settings
+fn parse_opml_import(input: &str) -> Result<Vec<ParsedFeedEntry>, AppError> {
+    let feeds = opml::parse_opml(input).map_err(|e| {
+        AppError::ServiceError(feed_service::FeedServiceError::InvalidUrl(format!(
+            "Invalid OPML: {}",
+            e
+        )))
+    })?;
+
+    Ok(feeds
+        .into_iter()
+        .map(|feed| ParsedFeedEntry {
+            settings: imported_settings(&feed),
+            url: feed.url,
+            title: feed.title,
+        })
+        .collect())
+}
+
+/// Strip surrounding double quotes from a title, unescaping CSV-style `""`
+fn unquote_title(title: &str) -> String {
+    match title
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    {
+        Some(inner) => inner.replace("\"\"", "\"").trim().to_string(),
+        None => title.to_string(),
+    }
+}
+
 pub async fn import_feeds(
     State(state): State<AppState>,
     Form(form): Form<ImportFeedsForm>,
 ) -> Result<Html<String>, AppError> {
-    let entries = parse_import_input(&form.feeds);
+    // An OPML export is pasted as is; anything else is one feed per line
+    let entries = if form.feeds.trim_start().starts_with('<') {
+        parse_opml_import(&form.feeds)?
+    } else {
+        parse_import_input(&form.feeds)
+    };
 
     if entries.is_empty() {
         // No feeds to import, return empty results
@@ -374,9 +974,19 @@ pub async fn import_feeds(
     let job_id_clone = job_id.clone();
     let pool = state.db_pool.clone();
     let import_jobs = state.import_jobs.clone();
+    let max_feeds = state.storage_limits.max_feeds;
+    let host_policy = state.fetch_host_policy.clone();
 
     tokio::spawn(async move {
-        process_import_job(job_id_clone, entries, pool, import_jobs).await;
+        process_import_job(
+            job_id_clone,
+            entries,
+            pool,
+            import_jobs,
+            max_feeds,
+            &host_policy,
+        )
+        .await;
     });
 
     // Return immediately with progress UI that will poll for updates
@@ -394,6 +1004,8 @@ async fn process_import_job(
     entries: Vec<ParsedFeedEntry>,
     pool: SqlitePool,
     import_jobs: ImportJobStore,
+    max_feeds: Option<i64>,
+    host_policy: &HostPolicy,
 ) {
     tracing::info!(
         "Starting background import job {} with {} feeds",
@@ -401,10 +1013,38 @@ async fn process_import_job(
         entries.len()
     );
 
+    // Compare by URL identity so http/https or trailing-slash variants count as the same feed
+    let subscribed: HashSet<String> = match repository::list_feeds(&pool).await {
+        Ok(feeds) => feeds
+            .iter()
+            .map(|f| feed_service::url_identity(&f.url))
+            .collect(),
+        Err(e) => {
+            tracing::warn!("Failed to load existing feeds for import: {}", e);
+            HashSet::new()
+        }
+    };
+    let mut seen_in_import = HashSet::new();
+
     for entry in entries {
-        let result =
-            match feed_service::create_feed_deferred(&pool, entry.url.clone(), entry.title.clone())
-                .await
+        let identity = feed_service::url_identity(&entry.url);
+        let result = if subscribed.contains(&identity) {
+            failed_import(
+                entry,
+                import_error_message(FeedServiceError::DuplicateUrl(None)),
+            )
+        } else if !seen_in_import.insert(identity) {
+            failed_import(entry, "Duplicate in import list".to_string())
+        } else {
+            match feed_service::create_feed_deferred(
+                &pool,
+                entry.url.clone(),
+                entry.title.clone(),
+                &entry.settings,
+                max_feeds,
+                host_policy,
+            )
+            .await
             {
                 Ok(feed) => ImportJobResult {
                     url: feed.url,
@@ -412,32 +1052,9 @@ async fn process_import_job(
                     success: true,
                     error: None,
                 },
-                Err(e) => {
-                    let error_msg = match e {
-                        feed_service::FeedServiceError::DuplicateUrl => {
-                            "Feed URL already exists".to_string()
-                        }
-                        feed_service::FeedServiceError::InvalidUrl(msg) => msg,
-                        feed_service::FeedServiceError::FetchError(msg) => {
-                            format!("Failed to fetch feed: {}", msg)
-                        }
-                        feed_service::FeedServiceError::DatabaseError(err) => {
-                            format!("Database error: {}", err)
-                        }
-                        feed_service::FeedServiceError::SsrfBlocked => {
-                            "URL points to internal/private network (blocked for security)"
-                                .to_string()
-                        }
-                        _ => "Unknown error".to_string(),
-                    };
-                    ImportJobResult {
-                        url: entry.url,
-                        title: entry.title,
-                        success: false,
-                        error: Some(error_msg),
-                    }
-                }
-            };
+                Err(e) => failed_import(entry, import_error_message(e)),
+            }
+        };
 
         // Update job state
         {
@@ -463,6 +1080,32 @@ async fn process_import_job(
     tracing::info!("Completed background import job {}", job_id);
 }
 
+fn failed_import(entry: ParsedFeedEntry, error: String) -> ImportJobResult {
+    ImportJobResult {
+        url: entry.url,
+        title: entry.title,
+        success: false,
+        error: Some(error),
+    }
+}
+
+fn import_error_message(err: FeedServiceError) -> String {
+    match err {
+        FeedServiceError::DuplicateUrl(_) => "Feed URL already exists".to_string(),
+        FeedServiceError::InvalidUrl(msg) => msg,
+        FeedServiceError::FetchError(msg) => format!("Failed to fetch feed: {}", msg),
+        FeedServiceError::DatabaseError(err) => format!("Database error: {}", err),
+        FeedServiceError::SsrfBlocked => {
+            "URL points to internal/private network (blocked for security)".to_string()
+        }
+        FeedServiceError::InvalidHeader(msg) => msg,
+        err @ (FeedServiceError::FeedLimitReached(_) | FeedServiceError::HostNotAllowed(_)) => {
+            err.to_string()
+        }
+        _ => "Unknown error".to_string(),
+    }
+}
+
 /// Get the status of an import job (used for polling)
 pub async fn get_import_job_status(
     State(state): State<AppState>,
@@ -521,6 +1164,12 @@ pub enum AppError {
     ServiceError(feed_service::FeedServiceError),
     DatabaseError(sqlx::Error),
     FetchError(String),
+    /// The feed's server answered 429; its `Retry-After` is passed on
+    RateLimited {
+        retry_after: Option<String>,
+    },
+    AtomError(std::io::Error),
+    OpmlError(std::io::Error),
 }
 
 impl From<askama::Error> for AppError {
@@ -543,6 +1192,21 @@ impl From<sqlx::Error> for AppError {
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        let retry_after = match &self {
+            AppError::RateLimited { retry_after } => retry_after.clone(),
+            _ => None,
+        };
+        // A duplicate links to the feed already subscribed
+        let link = match &self {
+            AppError::ServiceError(feed_service::FeedServiceError::DuplicateUrl(Some(id))) => {
+                Some(ErrorLink {
+                    href: format!("/feeds/{}", id),
+                    label: "Go to Feed".to_string(),
+                })
+            }
+            _ => None,
+        };
+
         let (status_code, status_text, message) = match self {
             AppError::TemplateError(err) => {
                 tracing::error!("Template error: {}", err);
@@ -558,7 +1222,7 @@ impl IntoResponse for AppError {
                 "Not Found".to_string(),
                 "The feed you're looking for doesn't exist.".to_string(),
             ),
-            AppError::ServiceError(feed_service::FeedServiceError::DuplicateUrl) => (
+            AppError::ServiceError(feed_service::FeedServiceError::DuplicateUrl(_)) => (
                 StatusCode::CONFLICT,
                 "Duplicate Feed".to_string(),
                 "This feed URL is already in your collection.".to_string(),
@@ -595,6 +1259,25 @@ impl IntoResponse for AppError {
                 "This URL points to an internal or private network address and cannot be used."
                     .to_string(),
             ),
+            AppError::ServiceError(feed_service::FeedServiceError::HostNotAllowed(host)) => (
+                StatusCode::FORBIDDEN,
+                "Host Not Allowed".to_string(),
+                format!("This server does not fetch feeds from {}.", host),
+            ),
+            AppError::ServiceError(feed_service::FeedServiceError::InvalidHeader(msg)) => {
+                (StatusCode::BAD_REQUEST, "Invalid Header".to_string(), msg)
+            }
+            AppError::ServiceError(feed_service::FeedServiceError::Validation(msg)) => {
+                (StatusCode::BAD_REQUEST, "Invalid Setting".to_string(), msg)
+            }
+            AppError::ServiceError(feed_service::FeedServiceError::FeedLimitReached(max)) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Feed Limit Reached".to_string(),
+                format!(
+                    "This instance allows at most {} feeds. Remove a feed before adding another.",
+                    max
+                ),
+            ),
             AppError::DatabaseError(err) => {
                 tracing::error!("Database error: {}", err);
                 (
@@ -603,6 +1286,24 @@ impl IntoResponse for AppError {
                     "A database error occurred. Please try again later.".to_string(),
                 )
             }
+            AppError::AtomError(err) => {
+                tracing::error!("Atom serialization error: {}", err);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal Server Error".to_string(),
+                    "An error occurred while generating the feed. Please try again later."
+                        .to_string(),
+                )
+            }
+            AppError::OpmlError(err) => {
+                tracing::error!("OPML serialization error: {}", err);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal Server Error".to_string(),
+                    "An error occurred while exporting the feeds. Please try again later."
+                        .to_string(),
+                )
+            }
             AppError::FetchError(msg) => {
                 // Log the actual error for debugging
                 tracing::warn!("Feed fetch error: {}", msg);
@@ -612,20 +1313,149 @@ impl IntoResponse for AppError {
                     "Unable to fetch the feed. Please check the URL and try again.".to_string(),
                 )
             }
+            AppError::RateLimited { retry_after } => {
+                tracing::warn!("Feed fetch rate limited, retry after {:?}", retry_after);
+                (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    "Rate Limited".to_string(),
+                    format!(
+                        "The feed's server is limiting requests. {}",
+                        retry_hint(retry_after.as_deref())
+                    ),
+                )
+            }
         };
 
-        let template = ErrorTemplate {
-            status_code: status_code.as_u16(),
-            status_text,
-            message,
+        let mut response = match link {
+            Some(link) => render_error_with_link(status_code, status_text, message, link),
+            None => render_error(status_code, status_text, message),
         };
-
-        match template.render() {
-            Ok(html) => (status_code, Html(html)).into_response(),
-            Err(err) => {
-                tracing::error!("Error rendering error template: {}", err);
-                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response()
-            }
+        if let Some(value) = retry_after.and_then(|v| HeaderValue::from_str(&v).ok()) {
+            response.headers_mut().insert(header::RETRY_AFTER, value);
         }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_line(line: &str) -> (String, Option<String>) {
+        let entries = parse_import_input(line);
+        assert_eq!(entries.len(), 1);
+        (entries[0].url.clone(), entries[0].title.clone())
+    }
+
+    #[test]
+    fn test_parse_import_space_separated() {
+        assert_eq!(
+            parse_line("https://example.com/feed.xml  My Feed "),
+            (
+                "https://example.com/feed.xml".to_string(),
+                Some("My Feed".to_string())
+            )
+        );
+        assert_eq!(
+            parse_line("https://example.com/feed.xml"),
+            ("https://example.com/feed.xml".to_string(), None)
+        );
+    }
+
+    #[test]
+    fn test_parse_import_csv() {
+        assert_eq!(
+            parse_line("https://example.com/feed.xml,My Feed"),
+            (
+                "https://example.com/feed.xml".to_string(),
+                Some("My Feed".to_string())
+            )
+        );
+        assert_eq!(
+            parse_line(r#"https://example.com/feed.xml,"Rust, ""weekly"" edition""#),
+            (
+                "https://example.com/feed.xml".to_string(),
+                Some(r#"Rust, "weekly" edition"#.to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_import_tsv() {
+        assert_eq!(
+            parse_line("https://example.com/feed.xml\tMy Feed\t"),
+            (
+                "https://example.com/feed.xml".to_string(),
+                Some("My Feed".to_string())
+            )
+        );
+    }
+
+    fn atom_for(accept: &str) -> bool {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, accept.parse().unwrap());
+        accepts_atom(&headers)
+    }
+
+    #[test]
+    fn test_accepts_atom_ranks_against_html() {
+        assert!(atom_for("application/atom+xml"));
+        assert!(atom_for("application/atom+xml, text/html;q=0.5"));
+        assert!(atom_for("application/*, */*;q=0.1"));
+
+        // Browsers list HTML first and Atom at most through a wildcard
+        assert!(!atom_for(
+            "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8"
+        ));
+        assert!(!atom_for("text/html, application/atom+xml"));
+        assert!(!atom_for("*/*"));
+        assert!(!atom_for("application/atom+xml;q=0, */*"));
+        assert!(!atom_for("application/atom+xml;q=0.5, text/html"));
+    }
+
+    #[test]
+    fn test_upstream_429_becomes_rate_limited() {
+        let error: Box<dyn std::error::Error> = Box::new(rss_fetcher::FetchError::RequestFailed {
+            status: reqwest::StatusCode::TOO_MANY_REQUESTS,
+            message: "Too Many Requests".to_string(),
+            retry_after: Some("120".to_string()),
+        });
+
+        let response = fetch_failure(error).into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "120");
+
+        let error: Box<dyn std::error::Error> = Box::new(rss_fetcher::FetchError::RequestFailed {
+            status: reqwest::StatusCode::SERVICE_UNAVAILABLE,
+            message: "Service Unavailable".to_string(),
+            retry_after: Some("120".to_string()),
+        });
+        assert!(matches!(fetch_failure(error), AppError::FetchError(_)));
+    }
+
+    #[test]
+    fn test_retry_hint() {
+        assert_eq!(retry_hint(Some("30")), "Try again in 30 seconds.");
+        assert_eq!(retry_hint(Some("3600")), "Try again in 60 minutes.");
+        assert_eq!(
+            retry_hint(Some("Wed, 21 Oct 2026 07:28:00 GMT")),
+            "Try again after Wed, 21 Oct 2026 07:28:00 GMT."
+        );
+        assert_eq!(retry_hint(None), "Try again later.");
+    }
+
+    #[test]
+    fn test_parse_import_quoted_title() {
+        assert_eq!(
+            parse_line(r#"https://example.com/feed.xml "My Feed Title""#),
+            (
+                "https://example.com/feed.xml".to_string(),
+                Some("My Feed Title".to_string())
+            )
+        );
+        assert_eq!(
+            parse_line(r#"https://example.com/feed.xml """#),
+            ("https://example.com/feed.xml".to_string(), None)
+        );
     }
 }
@@ -1,12 +1,15 @@
 use crate::api::articles::AppError;
 use crate::api::feeds::AppState;
+use crate::api::params::{deserialize_id_list, parse_ids};
+use crate::config::FeedSort;
 use crate::domain::group_service;
+use crate::domain::models::{FeedWithUnread, GroupNodeWithUnread};
 use crate::infrastructure::repository;
-use crate::web::url_builders::ArticleFilters;
 use crate::web::templates::{
     AssignFeedTemplate, FeedFilterModalTemplate, GroupFormTemplate, GroupListContentTemplate,
-    GroupsListTemplate,
+    GroupMemberFeedsTemplate, GroupShareLinksTemplate, GroupUnreadNavTemplate, GroupsListTemplate,
 };
+use crate::web::url_builders::ArticleFilters;
 use askama::Template;
 use axum::{
     extract::{Path, Query, State},
@@ -14,6 +17,7 @@ use axum::{
     Form,
 };
 use serde::Deserialize;
+use sqlx::SqlitePool;
 
 #[derive(Deserialize)]
 pub struct FilterModalParams {
@@ -21,17 +25,40 @@ pub struct FilterModalParams {
     pub group_ids: Option<String>,
     pub is_read: Option<bool>,
     pub is_starred: Option<bool>,
+    pub is_read_later: Option<bool>,
 }
 
-/// Parse comma-separated IDs from query parameter
-fn parse_ids(ids_str: Option<&str>) -> Vec<i64> {
-    ids_str
-        .map(|s| {
-            s.split(',')
-                .filter_map(|id| id.trim().parse().ok())
-                .collect()
-        })
-        .unwrap_or_default()
+/// Load the group tree and ungrouped feeds, annotated with unread counts
+async fn load_group_tree_with_unread(
+    pool: &SqlitePool,
+) -> Result<(Vec<GroupNodeWithUnread>, Vec<FeedWithUnread>), sqlx::Error> {
+    let groups = repository::list_groups(pool).await?;
+    let feeds = repository::list_feeds(pool).await?;
+    let unread_counts = repository::get_feed_unread_counts(pool).await?;
+
+    let (group_tree, ungrouped_feeds) = group_service::build_group_tree(groups, feeds);
+    let group_tree = group_service::add_unread_counts_to_tree(group_tree, &unread_counts);
+    let ungrouped_feeds =
+        group_service::add_unread_counts_to_feeds(ungrouped_feeds, &unread_counts);
+
+    Ok((group_tree, ungrouped_feeds))
+}
+
+/// Unread badges of top-level groups for the navigation (GET /groups/unread-nav)
+/// Polled by the layout and refreshed on the `articlesRead` event
+pub async fn show_group_unread_nav(
+    State(state): State<AppState>,
+) -> Result<Html<String>, AppError> {
+    let (group_tree, _) = load_group_tree_with_unread(&state.db_pool).await?;
+
+    let template = GroupUnreadNavTemplate {
+        groups: group_tree
+            .into_iter()
+            .filter(|node| node.total_unread > 0)
+            .collect(),
+    };
+
+    Ok(Html(template.render()?))
 }
 
 /// Render the feed filter modal with hierarchical tree
@@ -39,16 +66,10 @@ pub async fn show_feed_filter_modal(
     State(state): State<AppState>,
     Query(params): Query<FilterModalParams>,
 ) -> Result<Html<String>, AppError> {
-    // Get all groups, feeds, and unread counts
-    let groups = repository::list_groups(&state.db_pool).await?;
-    let feeds = repository::list_feeds(&state.db_pool).await?;
-    let unread_counts = repository::get_feed_unread_counts(&state.db_pool).await?;
-
-    // Build group tree with unread counts
-    let (group_tree, ungrouped_feeds) = group_service::build_group_tree(groups, feeds);
-    let group_tree = group_service::add_unread_counts_to_tree(group_tree, &unread_counts);
-    let ungrouped_feeds =
-        group_service::add_unread_counts_to_feeds(ungrouped_feeds, &unread_counts);
+    let (mut group_tree, mut ungrouped_feeds) = load_group_tree_with_unread(&state.db_pool).await?;
+    if state.feed_sort == FeedSort::Unread {
+        group_service::sort_feeds_by_unread(&mut group_tree, &mut ungrouped_feeds);
+    }
 
     // Parse currently selected IDs from query params
     let selected_feed_ids = parse_ids(params.feed_ids.as_deref());
@@ -63,15 +84,41 @@ pub async fn show_feed_filter_modal(
             tag_ids: vec![],
             is_read: params.is_read,
             is_starred: params.is_starred,
+            is_read_later: params.is_read_later,
             search_query: None,
             date_from: None,
             date_to: None,
+            new_since_last_visit: false,
+            language: None,
         },
     };
 
     Ok(Html(template.render()?))
 }
 
+/// Feeds a group rolls up, including those of nested groups, so the filter
+/// modal can show what selecting it covers (GET /groups/:id/feeds)
+pub async fn show_group_feeds(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Html<String>, AppError> {
+    repository::get_group(&state.db_pool, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Group not found".to_string()))?;
+
+    let feed_ids = group_service::resolve_selection_to_feed_ids(&state.db_pool, &[id], &[]).await?;
+    let mut feeds: Vec<_> = repository::list_feeds(&state.db_pool)
+        .await?
+        .into_iter()
+        .filter(|feed| feed_ids.binary_search(&feed.id).is_ok())
+        .collect();
+    feeds.sort_by_key(|feed| feed.title.to_lowercase());
+
+    let template = GroupMemberFeedsTemplate { feeds };
+
+    Ok(Html(template.render()?))
+}
+
 // ============ Group CRUD Handlers ============
 
 /// List all groups (GET /groups)
@@ -85,6 +132,7 @@ pub async fn list_groups(State(state): State<AppState>) -> Result<Html<String>,
     let template = GroupsListTemplate {
         tree_items,
         ungrouped_feeds,
+        theme: repository::get_preferences(&state.db_pool).await?.theme,
     };
 
     Ok(Html(template.render()?))
@@ -113,11 +161,45 @@ pub async fn show_new_group_form(State(state): State<AppState>) -> Result<Html<S
     let template = GroupFormTemplate {
         group: None,
         available_groups,
+        share_tokens: vec![],
     };
 
     Ok(Html(template.render()?))
 }
 
+/// Check that a group's new parent exists and, for an existing group, is
+/// neither the group itself nor one of its descendants
+async fn validate_parent(
+    pool: &SqlitePool,
+    group_id: Option<i64>,
+    parent_id: Option<i64>,
+) -> Result<(), AppError> {
+    let Some(parent_id) = parent_id else {
+        return Ok(());
+    };
+
+    if repository::get_group(pool, parent_id).await?.is_none() {
+        return Err(AppError::BadRequest(
+            "Parent group does not exist".to_string(),
+        ));
+    }
+    if let Some(id) = group_id {
+        if parent_id == id {
+            return Err(AppError::BadRequest(
+                "Cannot move a group into itself".to_string(),
+            ));
+        }
+        let descendants = repository::get_descendant_group_ids(pool, id).await?;
+        if descendants.contains(&parent_id) {
+            return Err(AppError::BadRequest(
+                "Cannot move a group into its own descendant".to_string(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(Deserialize)]
 pub struct CreateGroupForm {
     pub name: String,
@@ -134,6 +216,7 @@ pub async fn create_group(
             .as_ref()
             .and_then(|s| if s.is_empty() { None } else { s.parse().ok() });
 
+    validate_parent(&state.db_pool, None, parent_id).await?;
     repository::create_group(&state.db_pool, &form.name, parent_id).await?;
 
     // Return the updated group list content (partial for HTMX)
@@ -150,10 +233,12 @@ pub async fn show_edit_group_form(
         .ok_or_else(|| AppError::NotFound("Group not found".to_string()))?;
 
     let available_groups = repository::list_groups(&state.db_pool).await?;
+    let share_tokens = repository::list_share_tokens(&state.db_pool, id).await?;
 
     let template = GroupFormTemplate {
         group: Some(group),
         available_groups,
+        share_tokens,
     };
 
     Ok(Html(template.render()?))
@@ -176,6 +261,7 @@ pub async fn update_group(
             .as_ref()
             .and_then(|s| if s.is_empty() { None } else { s.parse().ok() });
 
+    validate_parent(&state.db_pool, Some(id), parent_id).await?;
     repository::update_group(&state.db_pool, id, &form.name, parent_id).await?;
 
     // Return the updated group list content (partial for HTMX)
@@ -231,6 +317,39 @@ pub async fn assign_feed_to_group(
     render_group_list_content(&state).await
 }
 
+#[derive(Deserialize)]
+pub struct AssignFeedsForm {
+    #[serde(default, deserialize_with = "deserialize_id_list")]
+    pub feed_ids: Vec<i64>,
+}
+
+/// Assign several feeds to a group at once (PUT /groups/:id/assign-feeds)
+pub async fn assign_feeds_to_group(
+    State(state): State<AppState>,
+    Path(group_id): Path<i64>,
+    Form(form): Form<AssignFeedsForm>,
+) -> Result<Html<String>, AppError> {
+    repository::get_group(&state.db_pool, group_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Group not found".to_string()))?;
+
+    repository::update_feeds_group(&state.db_pool, &form.feed_ids, Some(group_id)).await?;
+
+    // Return the updated group list content (partial for HTMX)
+    render_group_list_content(&state).await
+}
+
+/// Remove several feeds from their groups at once (PUT /groups/ungrouped/assign-feeds)
+pub async fn unassign_feeds(
+    State(state): State<AppState>,
+    Form(form): Form<AssignFeedsForm>,
+) -> Result<Html<String>, AppError> {
+    repository::update_feeds_group(&state.db_pool, &form.feed_ids, None).await?;
+
+    // Return the updated group list content (partial for HTMX)
+    render_group_list_content(&state).await
+}
+
 #[derive(Deserialize)]
 pub struct MoveGroupForm {
     pub parent_id: Option<String>,
@@ -247,24 +366,60 @@ pub async fn move_group(
             .as_ref()
             .and_then(|s| if s.is_empty() { None } else { s.parse().ok() });
 
-    // Prevent moving a group into itself or its descendants
-    if let Some(new_parent_id) = parent_id {
-        if new_parent_id == id {
-            return Err(AppError::NotFound(
-                "Cannot move a group into itself".to_string(),
-            ));
-        }
-        // Check if new_parent_id is a descendant of id
-        let descendants = repository::get_descendant_group_ids(&state.db_pool, id).await?;
-        if descendants.contains(&new_parent_id) {
-            return Err(AppError::NotFound(
-                "Cannot move a group into its own descendant".to_string(),
-            ));
-        }
-    }
-
+    validate_parent(&state.db_pool, Some(id), parent_id).await?;
     repository::update_group_parent(&state.db_pool, id, parent_id).await?;
 
     // Return the updated group list content (partial for HTMX)
     render_group_list_content(&state).await
 }
+
+/// Collapse or expand a group in the tree (PUT /groups/:id/collapse)
+pub async fn toggle_group_collapse(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Html<String>, AppError> {
+    if !repository::toggle_group_collapsed(&state.db_pool, id).await? {
+        return Err(AppError::NotFound("Group not found".to_string()));
+    }
+
+    render_group_list_content(&state).await
+}
+
+// ============ Group Share Link Handlers ============
+
+/// Returns just the share link list of a group (for HTMX partial updates)
+async fn render_share_links(state: &AppState, group_id: i64) -> Result<Html<String>, AppError> {
+    let share_tokens = repository::list_share_tokens(&state.db_pool, group_id).await?;
+
+    let template = GroupShareLinksTemplate {
+        group_id,
+        share_tokens,
+    };
+
+    Ok(Html(template.render()?))
+}
+
+/// Create a read-only share link for a group (POST /groups/:id/shares)
+pub async fn create_share_link(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Html<String>, AppError> {
+    repository::get_group(&state.db_pool, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Group not found".to_string()))?;
+
+    let token = group_service::generate_share_token();
+    repository::create_share_token(&state.db_pool, id, &token).await?;
+
+    render_share_links(&state, id).await
+}
+
+/// Revoke a share link of a group (DELETE /groups/:id/shares/:share_id)
+pub async fn revoke_share_link(
+    State(state): State<AppState>,
+    Path((id, share_id)): Path<(i64, i64)>,
+) -> Result<Html<String>, AppError> {
+    repository::delete_share_token(&state.db_pool, id, share_id).await?;
+
+    render_share_links(&state, id).await
+}
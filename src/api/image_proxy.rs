@@ -0,0 +1,109 @@
+use crate::api::feeds::AppState;
+use crate::infrastructure::error_pages::render_error;
+use crate::infrastructure::image_proxy::ImageProxyError;
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+
+/// How long browsers may reuse a proxied image
+const IMAGE_MAX_AGE_SECS: u64 = 86400;
+
+#[derive(Deserialize)]
+pub struct ImageProxyParams {
+    url: String,
+    /// Signature of `url`, from the page that links the image
+    sig: Option<String>,
+}
+
+/// An image of article content, fetched server-side (GET /img?url=&sig=)
+pub async fn proxy_image(
+    State(state): State<AppState>,
+    Query(params): Query<ImageProxyParams>,
+) -> Result<Response, AppError> {
+    if !state.image_proxy.enabled() {
+        return Err(AppError::Disabled);
+    }
+    let signed = params
+        .sig
+        .as_deref()
+        .is_some_and(|sig| state.image_proxy.signature_matches(&params.url, sig));
+    if !signed {
+        return Err(AppError::Unsigned);
+    }
+
+    let image = state.image_proxy.fetch(&params.url).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, image.content_type),
+            (
+                header::CACHE_CONTROL,
+                format!("private, max-age={}", IMAGE_MAX_AGE_SECS),
+            ),
+        ],
+        image.body,
+    )
+        .into_response())
+}
+
+// Error handling
+pub enum AppError {
+    /// `IMAGE_PROXY` is off, so there is nothing to serve
+    Disabled,
+    /// The URL wasn't signed by this instance
+    Unsigned,
+    ImageProxyError(ImageProxyError),
+}
+
+impl From<ImageProxyError> for AppError {
+    fn from(err: ImageProxyError) -> Self {
+        AppError::ImageProxyError(err)
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let (status_code, status_text, message) = match self {
+            AppError::Disabled => (
+                StatusCode::NOT_FOUND,
+                "Not Found".to_string(),
+                "The image proxy is not enabled.".to_string(),
+            ),
+            AppError::Unsigned => (
+                StatusCode::FORBIDDEN,
+                "Forbidden".to_string(),
+                "Only images of pages shown by this instance are proxied.".to_string(),
+            ),
+            AppError::ImageProxyError(err) => {
+                tracing::warn!("Image proxy error: {}", err);
+                match err {
+                    ImageProxyError::SsrfBlocked => (
+                        StatusCode::FORBIDDEN,
+                        "Forbidden".to_string(),
+                        "Images from internal or private networks are not proxied.".to_string(),
+                    ),
+                    ImageProxyError::NotAnImage(content_type) => (
+                        StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                        "Unsupported Media Type".to_string(),
+                        format!("Content of type {} is not proxied.", content_type),
+                    ),
+                    ImageProxyError::TooLarge => (
+                        StatusCode::PAYLOAD_TOO_LARGE,
+                        "Image Too Large".to_string(),
+                        err.to_string(),
+                    ),
+                    ImageProxyError::UpstreamStatus(_) | ImageProxyError::RequestFailed(_) => (
+                        StatusCode::BAD_GATEWAY,
+                        "Bad Gateway".to_string(),
+                        "The image could not be fetched.".to_string(),
+                    ),
+                }
+            }
+        };
+
+        render_error(status_code, status_text, message)
+    }
+}
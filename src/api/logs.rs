@@ -1,16 +1,20 @@
 use crate::api::feeds::AppState;
+use crate::domain::models::LogWithFeed;
+use crate::infrastructure::error_pages::render_error;
 use crate::infrastructure::repository;
+use crate::web::csv;
 use crate::web::url_builders::LogFilters;
-use crate::web::templates::{
-    ErrorTemplate, LoadMoreButtonLogsTemplate, LogRowsTemplate, LogsListTemplate,
-};
+use crate::web::templates::{LoadMoreButtonLogsTemplate, LogRowsTemplate, LogsListTemplate};
 use askama::Template;
 use axum::{
+    body::Body,
     extract::{Query, State},
-    http::{HeaderMap, StatusCode},
+    http::{header, HeaderMap, StatusCode},
     response::{Html, IntoResponse, Response},
 };
 use serde::Deserialize;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
 
 #[derive(Deserialize)]
 pub struct LogListParams {
@@ -26,7 +30,7 @@ pub async fn list_logs(
     headers: HeaderMap,
     Query(params): Query<LogListParams>,
 ) -> Result<Html<String>, AppError> {
-    let limit = params.limit.unwrap_or(50);
+    let limit = state.page_size.clamp(params.limit.unwrap_or(50));
     let offset = params.offset.unwrap_or(0);
 
     let filters = LogFilters {
@@ -57,7 +61,10 @@ pub async fn list_logs(
         let mut html = String::new();
 
         // Render log rows using template
-        let rows_template = LogRowsTemplate { logs: logs_to_show };
+        let rows_template = LogRowsTemplate {
+            logs: logs_to_show,
+            timezone: state.display_timezone,
+        };
         html.push_str(&rows_template.render()?);
 
         // Update the Load More button using out-of-band swap
@@ -89,11 +96,86 @@ pub async fn list_logs(
         limit,
         has_more,
         filters,
+        timezone: state.display_timezone,
+        theme: repository::get_preferences(&state.db_pool).await?.theme,
     };
 
     Ok(Html(template.render()?))
 }
 
+#[derive(Deserialize)]
+pub struct LogExportParams {
+    pub feed_id: Option<i64>,
+    pub feed_name: Option<String>,
+    pub log_type: Option<String>,
+}
+
+const LOG_CSV_COLUMNS: [&str; 7] = [
+    "fetched_at",
+    "feed_title",
+    "feed_url",
+    "log_type",
+    "status_code",
+    "error_message",
+    "retry_after",
+];
+
+/// Export logs matching the list filters as CSV (GET /logs/export.csv)
+/// Rows are streamed from the database as they are read.
+pub async fn export_logs_csv(
+    State(state): State<AppState>,
+    Query(params): Query<LogExportParams>,
+) -> Response {
+    let (tx, rx) = mpsc::channel::<Result<String, std::io::Error>>(64);
+
+    tokio::spawn(async move {
+        if tx.send(Ok(csv::record(&LOG_CSV_COLUMNS))).await.is_err() {
+            return;
+        }
+        let result = repository::for_each_log_with_feed(
+            &state.db_pool,
+            params.feed_id,
+            params.feed_name.as_deref(),
+            params.log_type.as_deref(),
+            |log| {
+                let tx = tx.clone();
+                async move { tx.send(Ok(log_csv_record(&log))).await.is_ok() }
+            },
+        )
+        .await;
+
+        if let Err(e) = result {
+            tracing::error!("Failed to export logs: {}", e);
+            let _ = tx.send(Err(std::io::Error::other(e))).await;
+        }
+    });
+
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"fluxfeed-logs.csv\"",
+            ),
+        ],
+        Body::from_stream(ReceiverStream::new(rx)),
+    )
+        .into_response()
+}
+
+fn log_csv_record(entry: &LogWithFeed) -> String {
+    let log = &entry.log;
+    csv::record(&[
+        &log.fetched_at.to_rfc3339(),
+        &entry.feed_title,
+        &entry.feed_url,
+        &log.log_type,
+        &log.status_code.map(|c| c.to_string()).unwrap_or_default(),
+        log.error_message.as_deref().unwrap_or_default(),
+        log.retry_after.as_deref().unwrap_or_default(),
+    ])
+}
+
 // Error handling
 pub enum AppError {
     TemplateError(askama::Error),
@@ -134,18 +216,6 @@ impl IntoResponse for AppError {
             }
         };
 
-        let template = ErrorTemplate {
-            status_code: status_code.as_u16(),
-            status_text,
-            message,
-        };
-
-        match template.render() {
-            Ok(html) => (status_code, Html(html)).into_response(),
-            Err(err) => {
-                tracing::error!("Error rendering error template: {}", err);
-                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response()
-            }
-        }
+        render_error(status_code, status_text, message)
     }
 }
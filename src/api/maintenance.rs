@@ -0,0 +1,51 @@
+use crate::api::feeds::AppState;
+use crate::infrastructure::database;
+use crate::infrastructure::error_pages::json_error;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct VacuumResponse {
+    message: String,
+    /// Bytes of the database file and its write-ahead log; absent for an
+    /// in-memory database
+    size_before: Option<u64>,
+    size_after: Option<u64>,
+}
+
+/// Compact the database after large deletes (POST /api/maintenance/vacuum).
+/// The database is locked while this runs, so requests and fetches wait.
+pub async fn vacuum_database(State(state): State<AppState>) -> Response {
+    tracing::warn!("Vacuuming the database; it is locked until this completes");
+
+    match database::vacuum(&state.db_pool).await {
+        Ok(report) => {
+            tracing::info!(
+                "Database vacuumed: {:?} bytes before, {:?} after",
+                report.size_before,
+                report.size_after
+            );
+            (
+                StatusCode::OK,
+                Json(VacuumResponse {
+                    message: "Database vacuumed".to_string(),
+                    size_before: report.size_before,
+                    size_after: report.size_after,
+                }),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            tracing::error!("Database vacuum failed: {}", e);
+            json_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database vacuum failed. Please try again later.".to_string(),
+            )
+        }
+    }
+}
@@ -1,8 +1,19 @@
+pub mod article_share;
 pub mod articles;
+pub mod feed_articles;
+pub mod feed_stats;
+pub mod feed_validation;
 pub mod feeds;
 pub mod groups;
 pub mod health;
+pub mod image_proxy;
 pub mod logs;
+pub mod maintenance;
 pub mod manual_fetch;
+pub mod params;
+pub mod preferences;
 pub mod reader;
+pub mod share;
+pub mod status;
 pub mod tags;
+pub mod unread_feed;
@@ -0,0 +1,130 @@
+//! Parsing of ID lists in query strings and form fields
+
+/// The IDs in a comma-separated list, which may also be written as a JSON
+/// array (`[1, 2]` or `["1", "2"]`); blank entries are skipped
+fn split_ids(value: &str) -> impl Iterator<Item = &str> {
+    let value = value.trim();
+    let value = value
+        .strip_prefix('[')
+        .and_then(|v| v.strip_suffix(']'))
+        .unwrap_or(value);
+
+    value
+        .split(',')
+        .map(|id| id.trim().trim_matches('"').trim())
+        .filter(|id| !id.is_empty())
+}
+
+/// Parse comma-separated IDs from a query parameter, skipping invalid ones
+pub fn parse_ids(ids_str: Option<&str>) -> Vec<i64> {
+    ids_str
+        .map(|s| split_ids(s).filter_map(|id| id.parse().ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Deserialize a form field that can be a single value, a comma-separated or
+/// bracketed list or multiple values into a Vec
+pub(crate) fn deserialize_id_list<'de, D>(deserializer: D) -> Result<Vec<i64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::{self, Visitor};
+    use std::fmt;
+
+    struct IdListVisitor;
+
+    impl<'de> Visitor<'de> for IdListVisitor {
+        type Value = Vec<i64>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a string, integer, or sequence of integers")
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            split_ids(value)
+                .map(|id| id.parse::<i64>().map_err(de::Error::custom))
+                .collect()
+        }
+
+        fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(vec![value])
+        }
+
+        fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(vec![value as i64])
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: de::SeqAccess<'de>,
+        {
+            let mut ids = Vec::new();
+            while let Some(value) = seq.next_element::<String>()? {
+                for id in split_ids(&value) {
+                    ids.push(id.parse::<i64>().map_err(de::Error::custom)?);
+                }
+            }
+            Ok(ids)
+        }
+    }
+
+    deserializer.deserialize_any(IdListVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[test]
+    fn test_parse_ids_empty() {
+        assert!(parse_ids(None).is_empty());
+        assert!(parse_ids(Some("")).is_empty());
+        assert!(parse_ids(Some(" , ")).is_empty());
+        assert!(parse_ids(Some("[]")).is_empty());
+    }
+
+    #[test]
+    fn test_parse_ids_single_and_multiple() {
+        assert_eq!(parse_ids(Some("7")), vec![7]);
+        assert_eq!(parse_ids(Some("1,2,3")), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_parse_ids_whitespace_and_invalid() {
+        assert_eq!(parse_ids(Some(" 1 ,  2,3 ")), vec![1, 2, 3]);
+        assert_eq!(parse_ids(Some("1,x,3")), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_parse_ids_bracketed() {
+        assert_eq!(parse_ids(Some("[1, 2]")), vec![1, 2]);
+        assert_eq!(parse_ids(Some(r#" ["4","5"] "#)), vec![4, 5]);
+    }
+
+    #[derive(Deserialize)]
+    struct IdForm {
+        #[serde(default, deserialize_with = "deserialize_id_list")]
+        ids: Vec<i64>,
+    }
+
+    #[test]
+    fn test_deserialize_id_list_forms() {
+        let parse = |json: &str| serde_json::from_str::<IdForm>(json).map(|f| f.ids);
+        assert_eq!(parse(r#"{"ids": "1, 2"}"#).unwrap(), vec![1, 2]);
+        assert_eq!(parse(r#"{"ids": "[3,4]"}"#).unwrap(), vec![3, 4]);
+        assert_eq!(parse(r#"{"ids": ["5", "6,7"]}"#).unwrap(), vec![5, 6, 7]);
+        assert_eq!(parse(r#"{"ids": 8}"#).unwrap(), vec![8]);
+        assert_eq!(parse("{}").unwrap(), Vec::<i64>::new());
+        assert!(parse(r#"{"ids": "1,x"}"#).is_err());
+    }
+}
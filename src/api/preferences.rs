@@ -0,0 +1,79 @@
+use crate::api::feeds::AppState;
+use crate::domain::models::{Theme, ViewMode};
+use crate::infrastructure::error_pages::render_error;
+use crate::infrastructure::repository;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Form,
+};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct ThemeForm {
+    theme: String,
+}
+
+/// Store the theme preference (POST /preferences/theme). The page applies
+/// it itself, so there is nothing to swap in.
+pub async fn set_theme(
+    State(state): State<AppState>,
+    Form(form): Form<ThemeForm>,
+) -> Result<StatusCode, AppError> {
+    let theme = Theme::parse(&form.theme)
+        .ok_or_else(|| AppError::BadRequest(format!("Unknown theme: {}", form.theme)))?;
+
+    repository::set_theme(&state.db_pool, theme).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+pub struct ViewModeForm {
+    view_mode: String,
+}
+
+/// Store the article list layout used when the URL asks for none
+/// (POST /preferences/view-mode)
+pub async fn set_view_mode(
+    State(state): State<AppState>,
+    Form(form): Form<ViewModeForm>,
+) -> Result<StatusCode, AppError> {
+    let view_mode = ViewMode::parse(&form.view_mode)
+        .ok_or_else(|| AppError::BadRequest(format!("Unknown view mode: {}", form.view_mode)))?;
+
+    repository::set_default_view_mode(&state.db_pool, view_mode).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Error handling
+pub enum AppError {
+    DatabaseError(sqlx::Error),
+    BadRequest(String),
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(err: sqlx::Error) -> Self {
+        AppError::DatabaseError(err)
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let (status_code, status_text, message) = match self {
+            AppError::DatabaseError(err) => {
+                tracing::error!("Database error: {}", err);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal Server Error".to_string(),
+                    "A database error occurred. Please try again later.".to_string(),
+                )
+            }
+            AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "Bad Request".to_string(), msg),
+        };
+
+        render_error(status_code, status_text, message)
+    }
+}
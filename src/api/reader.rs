@@ -1,18 +1,26 @@
 use crate::api::feeds::AppState;
 use crate::domain::reader_service;
-use crate::web::templates::{ErrorTemplate, ReaderContentTemplate, ReaderModeTemplate};
+use crate::infrastructure::error_pages::render_error;
+use crate::infrastructure::repository;
+use crate::web::redirect::HxRedirect;
+use crate::web::templates::{ReaderContentTemplate, ReaderModeTemplate};
 use askama::Template;
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{Html, IntoResponse, Response},
+    Form,
 };
+use serde::Deserialize;
 
 pub async fn show_reader_mode(
     State(state): State<AppState>,
     Path(article_id): Path<i64>,
 ) -> Result<Html<String>, AppError> {
-    let reader_content = reader_service::get_reader_content(&state.db_pool, article_id).await?;
+    let reader_content = load_reader_content(&state, article_id).await?;
+    let preferences = repository::get_preferences(&state.db_pool)
+        .await
+        .map_err(reader_service::ReaderServiceError::from)?;
 
     let template = ReaderModeTemplate {
         article_url: reader_content
@@ -23,6 +31,10 @@ pub async fn show_reader_mode(
         content: reader_content.content,
         byline: reader_content.byline,
         excerpt: reader_content.excerpt,
+        degraded: reader_content.degraded,
+        language: reader_content.language,
+        theme: preferences.theme,
+        image_proxy: state.image_proxy.signer(),
     };
 
     Ok(Html(template.render()?))
@@ -33,7 +45,7 @@ pub async fn get_reader_content(
     State(state): State<AppState>,
     Path(article_id): Path<i64>,
 ) -> Result<Html<String>, AppError> {
-    let reader_content = reader_service::get_reader_content(&state.db_pool, article_id).await?;
+    let reader_content = load_reader_content(&state, article_id).await?;
 
     let template = ReaderContentTemplate {
         article_url: reader_content
@@ -44,15 +56,68 @@ pub async fn get_reader_content(
         content: reader_content.content,
         byline: reader_content.byline,
         excerpt: reader_content.excerpt,
+        degraded: reader_content.degraded,
+        language: reader_content.language,
+        image_proxy: state.image_proxy.signer(),
     };
 
     Ok(Html(template.render()?))
 }
 
+/// Seconds a client is asked to wait while all reader extractions are busy
+const READER_RETRY_AFTER_SECS: u64 = 5;
+
+/// Reader content from the cache, or extracted if a slot is free; each
+/// extraction fetches the article's page, so their number is capped
+async fn load_reader_content(
+    state: &AppState,
+    article_id: i64,
+) -> Result<reader_service::ReaderContent, AppError> {
+    if let Some(content) = state.reader_limiter.cached(article_id).await {
+        return Ok(content);
+    }
+
+    let Some(_permit) = state.reader_limiter.try_acquire() else {
+        tracing::warn!("Reader mode busy, refusing article {}", article_id);
+        return Err(AppError::Busy);
+    };
+    let content =
+        reader_service::get_reader_content(&state.db_pool, article_id, state.reader_sanitization)
+            .await?;
+    state
+        .reader_limiter
+        .store(article_id, content.clone())
+        .await;
+    Ok(content)
+}
+
+#[derive(Deserialize)]
+pub struct SavePageForm {
+    url: String,
+}
+
+/// Save a web page that isn't from a subscribed feed (POST /articles/manual)
+pub async fn save_page(
+    State(state): State<AppState>,
+    Form(form): Form<SavePageForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let article =
+        reader_service::save_page(&state.db_pool, form.url.trim(), state.reader_sanitization)
+            .await?;
+    tracing::info!("Saved page {} as article {}", form.url.trim(), article.id);
+
+    Ok(HxRedirect(format!(
+        "/articles?feed_ids={}",
+        article.feed_id
+    )))
+}
+
 // Error handling
 pub enum AppError {
     TemplateError(askama::Error),
     ReaderServiceError(reader_service::ReaderServiceError),
+    /// All reader extraction slots are taken
+    Busy,
 }
 
 impl From<askama::Error> for AppError {
@@ -79,6 +144,19 @@ impl IntoResponse for AppError {
                         .to_string(),
                 )
             }
+            AppError::Busy => {
+                let mut response = render_error(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    "Please Wait".to_string(),
+                    "Reader mode is busy with other articles. Please try again in a few seconds."
+                        .to_string(),
+                );
+                response.headers_mut().insert(
+                    header::RETRY_AFTER,
+                    HeaderValue::from(READER_RETRY_AFTER_SECS),
+                );
+                return response;
+            }
             AppError::ReaderServiceError(reader_service::ReaderServiceError::NotFound) => (
                 StatusCode::NOT_FOUND,
                 "Not Found".to_string(),
@@ -117,20 +195,18 @@ impl IntoResponse for AppError {
                     "Unable to extract readable content from this article. The page structure may not be compatible with reader mode.".to_string(),
                 )
             }
+            AppError::ReaderServiceError(reader_service::ReaderServiceError::SsrfBlocked) => (
+                StatusCode::BAD_REQUEST,
+                "URL Blocked".to_string(),
+                "This article's URL points to an internal or private network.".to_string(),
+            ),
+            AppError::ReaderServiceError(reader_service::ReaderServiceError::PageTooLarge) => (
+                StatusCode::BAD_GATEWAY,
+                "Page Too Large".to_string(),
+                "The article page is too large to show in reader mode.".to_string(),
+            ),
         };
 
-        let template = ErrorTemplate {
-            status_code: status_code.as_u16(),
-            status_text,
-            message,
-        };
-
-        match template.render() {
-            Ok(html) => (status_code, Html(html)).into_response(),
-            Err(err) => {
-                tracing::error!("Error rendering error template: {}", err);
-                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response()
-            }
-        }
+        render_error(status_code, status_text, message)
     }
 }
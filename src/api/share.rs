@@ -0,0 +1,134 @@
+use crate::api::feeds::AppState;
+use crate::domain::group_service;
+use crate::domain::models::ArticleListFilter;
+use crate::infrastructure::repository;
+use crate::web::templates::{SharedErrorTemplate, SharedGroupTemplate};
+use askama::Template;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{Html, IntoResponse, Response},
+};
+use serde::Deserialize;
+
+/// Number of articles shown per page of a shared group
+const SHARED_PAGE_SIZE: i64 = 30;
+
+#[derive(Deserialize)]
+pub struct SharedGroupParams {
+    pub offset: Option<i64>,
+}
+
+/// Read-only article list of a shared group (GET /share/:token)
+///
+/// Only the group's articles are exposed: no read/starred state, no tags and
+/// no links or actions into the rest of the app.
+pub async fn show_shared_group(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+    Query(params): Query<SharedGroupParams>,
+) -> Result<Html<String>, AppError> {
+    let share_token = repository::get_share_token(&state.db_pool, &token)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    let group = repository::get_group(&state.db_pool, share_token.group_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let feed_ids =
+        group_service::resolve_selection_to_feed_ids(&state.db_pool, &[group.id], &[]).await?;
+    // An empty group must not fall back to listing every feed
+    let feed_ids = if feed_ids.is_empty() {
+        vec![-1]
+    } else {
+        feed_ids
+    };
+
+    let offset = params.offset.unwrap_or(0).max(0);
+    let mut articles = repository::list_articles_with_feeds(
+        &state.db_pool,
+        &ArticleListFilter {
+            feed_ids: Some(feed_ids),
+            ..Default::default()
+        },
+        SHARED_PAGE_SIZE + 1, // Fetch one extra to check if there are more
+        offset,
+    )
+    .await?;
+
+    let has_more = articles.len() > SHARED_PAGE_SIZE as usize;
+    articles.truncate(SHARED_PAGE_SIZE as usize);
+
+    let template = SharedGroupTemplate {
+        group_name: group.name,
+        articles,
+        has_more,
+        next_offset: offset + SHARED_PAGE_SIZE,
+        image_proxy: state.image_proxy.signer(),
+        timezone: state.display_timezone,
+    };
+
+    Ok(Html(template.render()?))
+}
+
+#[derive(Debug)]
+pub enum AppError {
+    TemplateError(askama::Error),
+    DatabaseError(sqlx::Error),
+    NotFound,
+}
+
+impl From<askama::Error> for AppError {
+    fn from(err: askama::Error) -> Self {
+        AppError::TemplateError(err)
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(err: sqlx::Error) -> Self {
+        AppError::DatabaseError(err)
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let (status_code, status_text, message) = match self {
+            AppError::TemplateError(err) => {
+                tracing::error!("Template error: {}", err);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal Server Error",
+                    "An error occurred while rendering the page. Please try again later.",
+                )
+            }
+            AppError::DatabaseError(err) => {
+                tracing::error!("Database error: {}", err);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal Server Error",
+                    "A database error occurred. Please try again later.",
+                )
+            }
+            AppError::NotFound => (
+                StatusCode::NOT_FOUND,
+                "Not Found",
+                "This share link doesn't exist or has been revoked.",
+            ),
+        };
+
+        // Render with the standalone share layout so errors don't link into the app
+        let template = SharedErrorTemplate {
+            status_code: status_code.as_u16(),
+            status_text: status_text.to_string(),
+            message: message.to_string(),
+        };
+
+        match template.render() {
+            Ok(html) => (status_code, Html(html)).into_response(),
+            Err(err) => {
+                tracing::error!("Error rendering error template: {}", err);
+                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response()
+            }
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use crate::api::feeds::AppState;
+use crate::infrastructure::scheduler::FETCH_CYCLE_MINUTES;
+use crate::infrastructure::scheduler_status::SchedulerSnapshot;
+use axum::{extract::State, Json};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct StatusResponse {
+    version: String,
+    scheduler: SchedulerResponse,
+}
+
+#[derive(Serialize)]
+pub struct SchedulerResponse {
+    #[serde(flatten)]
+    snapshot: SchedulerSnapshot,
+    interval_minutes: u32,
+    /// Feeds fetched at most per cycle, 0 for no limit
+    max_feeds_per_cycle: i64,
+}
+
+/// Summary of the background scheduler for operators (GET /api/status)
+pub async fn show_status(State(state): State<AppState>) -> Json<StatusResponse> {
+    Json(StatusResponse {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        scheduler: SchedulerResponse {
+            snapshot: state.scheduler_status.snapshot().await,
+            interval_minutes: FETCH_CYCLE_MINUTES,
+            max_feeds_per_cycle: state.max_feeds_per_cycle,
+        },
+    })
+}
@@ -1,5 +1,6 @@
 use crate::api::articles::AppError;
 use crate::api::feeds::AppState;
+use crate::api::params::parse_ids;
 use crate::infrastructure::repository;
 use crate::web::url_builders::ArticleFilters;
 use crate::web::templates::{
@@ -17,7 +18,10 @@ use serde::Deserialize;
 pub async fn list_tags(State(state): State<AppState>) -> Result<Html<String>, AppError> {
     let tags = repository::list_tags(&state.db_pool).await?;
 
-    let template = TagsListTemplate { tags };
+    let template = TagsListTemplate {
+        tags,
+        theme: repository::get_preferences(&state.db_pool).await?.theme,
+    };
 
     Ok(Html(template.render()?))
 }
@@ -135,17 +139,7 @@ pub struct TagFilterModalParams {
     pub group_ids: Option<String>,
     pub is_read: Option<bool>,
     pub is_starred: Option<bool>,
-}
-
-/// Parse comma-separated IDs from query parameter
-fn parse_ids(ids_str: Option<&str>) -> Vec<i64> {
-    ids_str
-        .map(|s| {
-            s.split(',')
-                .filter_map(|id| id.trim().parse().ok())
-                .collect()
-        })
-        .unwrap_or_default()
+    pub is_read_later: Option<bool>,
 }
 
 /// Show tag filter modal (GET /articles/tag-filter-modal)
@@ -167,9 +161,12 @@ pub async fn show_tag_filter_modal(
             tag_ids: selected_tag_ids,
             is_read: params.is_read,
             is_starred: params.is_starred,
+            is_read_later: params.is_read_later,
             search_query: None,
             date_from: None,
             date_to: None,
+            new_since_last_visit: false,
+            language: None,
         },
     };
 
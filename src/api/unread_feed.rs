@@ -0,0 +1,102 @@
+use crate::api::feeds::{if_none_match, AppError, AppState};
+use crate::domain::group_service;
+use crate::domain::models::ArticleListFilter;
+use crate::infrastructure::repository;
+use crate::web::atom;
+use crate::web::templates::{UnreadFeedLinksModalTemplate, UnreadFeedLinksTemplate};
+use askama::Template;
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Response},
+};
+
+/// Most unread articles served in the unread feed, newest first
+const UNREAD_FEED_LIMIT: i64 = 200;
+
+/// Atom feed of all unread articles (GET /feed/:token/unread.atom)
+///
+/// Lets another feed reader show what is unread here; reading an entry there
+/// does not mark it read.
+pub async fn show_unread_feed(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    // A bare 404, like the other token views, so nothing links into the app
+    if repository::get_unread_feed_token(&state.db_pool, &token)
+        .await?
+        .is_none()
+    {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+
+    let articles = repository::list_articles_with_feeds(
+        &state.db_pool,
+        &ArticleListFilter {
+            is_read: Some(false),
+            ..Default::default()
+        },
+        UNREAD_FEED_LIMIT,
+        0,
+    )
+    .await?;
+
+    let etag = atom::unread_entity_tag(&articles);
+    let last_modified = httpdate::fmt_http_date(atom::unread_updated(&articles).into());
+    if if_none_match(&headers, &etag) {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            [(header::ETAG, etag), (header::LAST_MODIFIED, last_modified)],
+        )
+            .into_response());
+    }
+
+    let xml = atom::render_unread_feed(&articles).map_err(AppError::AtomError)?;
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                "application/atom+xml; charset=utf-8".to_string(),
+            ),
+            (header::ETAG, etag),
+            (header::LAST_MODIFIED, last_modified),
+        ],
+        xml,
+    )
+        .into_response())
+}
+
+/// Modal listing the unread feed links (GET /unread-feed/links)
+pub async fn show_unread_feed_links(
+    State(state): State<AppState>,
+) -> Result<Html<String>, AppError> {
+    let tokens = repository::list_unread_feed_tokens(&state.db_pool).await?;
+    Ok(Html(UnreadFeedLinksModalTemplate { tokens }.render()?))
+}
+
+/// Returns just the list of unread feed links (for HTMX partial updates)
+async fn render_unread_feed_links(state: &AppState) -> Result<Html<String>, AppError> {
+    let tokens = repository::list_unread_feed_tokens(&state.db_pool).await?;
+    Ok(Html(UnreadFeedLinksTemplate { tokens }.render()?))
+}
+
+/// Create a link to the unread feed (POST /unread-feed/links)
+pub async fn create_unread_feed_link(
+    State(state): State<AppState>,
+) -> Result<Html<String>, AppError> {
+    let token = group_service::generate_share_token();
+    repository::create_unread_feed_token(&state.db_pool, &token).await?;
+
+    render_unread_feed_links(&state).await
+}
+
+/// Revoke a link to the unread feed (DELETE /unread-feed/links/:id)
+pub async fn revoke_unread_feed_link(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Html<String>, AppError> {
+    repository::delete_unread_feed_token(&state.db_pool, id).await?;
+
+    render_unread_feed_links(&state).await
+}
@@ -1,10 +1,323 @@
+use crate::infrastructure::secrets::SecretKey;
+use chrono::NaiveTime;
+use chrono_tz::Tz;
+use ipnet::IpNet;
 use std::env;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Default delay between consecutive feed fetches
+const DEFAULT_INTER_FEED_DELAY_MS: u64 = 500;
+
+/// Default spacing between OpenGraph requests to the same host
+const DEFAULT_INTER_OG_DELAY_MS: u64 = 100;
+
+/// Default number of older pages followed when subscribing to a paged feed
+const DEFAULT_ARCHIVE_BACKFILL_PAGES: usize = 3;
+
+/// Default number of due feeds fetched per scheduler cycle
+const DEFAULT_MAX_FEEDS_PER_CYCLE: i64 = 100;
+
+/// Default number of consecutive fetches with new articles before an adaptive
+/// feed's interval is halved
+const DEFAULT_ADAPTIVE_CONSECUTIVE_FETCHES: i64 = 2;
+
+/// Default shortest adaptive fetch interval: 1 hour
+const DEFAULT_ADAPTIVE_MIN_INTERVAL_MINUTES: i64 = 60;
+
+/// Default longest adaptive fetch interval: 1 week
+const DEFAULT_ADAPTIVE_MAX_INTERVAL_MINUTES: i64 = 10080;
+
+/// Default number of articles per page of the article list
+const DEFAULT_PAGE_SIZE: i64 = 20;
+
+/// Default upper bound for client-requested page sizes
+const DEFAULT_MAX_PAGE_SIZE: i64 = 100;
+
+/// Pages of `MAX_PAGE_SIZE` articles a restored list may span
+const MAX_RESTORED_PAGES: i64 = 10;
+
+/// Politeness delays between outgoing requests of the fetch pipelines
+#[derive(Clone, Copy, Debug)]
+pub struct FetchDelays {
+    /// Delay between consecutive feed fetches (`INTER_FEED_DELAY_MS`)
+    pub inter_feed: Duration,
+    /// Minimum spacing between OpenGraph requests to the same host (`INTER_OG_DELAY_MS`)
+    pub inter_og: Duration,
+}
+
+impl Default for FetchDelays {
+    fn default() -> Self {
+        Self {
+            inter_feed: Duration::from_millis(DEFAULT_INTER_FEED_DELAY_MS),
+            inter_og: Duration::from_millis(DEFAULT_INTER_OG_DELAY_MS),
+        }
+    }
+}
+
+/// Settings the first fetch of a newly subscribed feed runs with
+#[derive(Clone, Debug, Default)]
+pub struct NewFeedFetch {
+    pub delays: FetchDelays,
+    pub adaptive: AdaptiveInterval,
+    /// Older pages of paged or archived feeds to backfill (`ARCHIVE_BACKFILL_PAGES`)
+    pub archive_backfill_pages: usize,
+    pub limits: StorageLimits,
+    pub reader_sanitization: ReaderSanitization,
+    pub host_policy: Arc<HostPolicy>,
+    pub secret_key: SecretKey,
+}
+
+/// How feeds with adaptive fetch frequency speed up and slow down
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AdaptiveInterval {
+    /// Fetches in a row with new articles before the interval is halved
+    /// (`ADAPTIVE_CONSECUTIVE_FETCHES`)
+    pub consecutive_fetches: i64,
+    /// Lower bound of the interval (`ADAPTIVE_MIN_INTERVAL_MINUTES`)
+    pub min_interval_minutes: i64,
+    /// Upper bound of the interval (`ADAPTIVE_MAX_INTERVAL_MINUTES`)
+    pub max_interval_minutes: i64,
+}
+
+impl Default for AdaptiveInterval {
+    fn default() -> Self {
+        Self {
+            consecutive_fetches: DEFAULT_ADAPTIVE_CONSECUTIVE_FETCHES,
+            min_interval_minutes: DEFAULT_ADAPTIVE_MIN_INTERVAL_MINUTES,
+            max_interval_minutes: DEFAULT_ADAPTIVE_MAX_INTERVAL_MINUTES,
+        }
+    }
+}
+
+/// Page size of paginated lists and the cap on client-requested limits
+#[derive(Clone, Copy, Debug)]
+pub struct PageSize {
+    /// Articles per page when the client asks for nothing else (`PAGE_SIZE`)
+    pub default: i64,
+    /// Largest limit a client may request (`MAX_PAGE_SIZE`)
+    pub max: i64,
+}
+
+impl PageSize {
+    /// The requested limit, or the default page size, capped at the maximum
+    pub fn limit(&self, requested: Option<i64>) -> i64 {
+        self.clamp(requested.unwrap_or(self.default))
+    }
+
+    /// Cap a limit to `1..=max`
+    pub fn clamp(&self, limit: i64) -> i64 {
+        limit.clamp(1, self.max.max(1))
+    }
+
+    /// Articles on the first page of a list: the `loaded` count when the
+    /// client restores a list it had paged through, else the requested
+    /// limit. A restored list spans pages, up to `MAX_RESTORED_PAGES` of
+    /// the largest size.
+    pub fn initial_limit(&self, loaded: Option<i64>, requested: Option<i64>) -> i64 {
+        let page = self.limit(requested);
+        let max_restored = self.max.max(1) * MAX_RESTORED_PAGES;
+        loaded.map_or(page, |loaded| loaded.min(max_restored).max(page))
+    }
+}
+
+impl Default for PageSize {
+    fn default() -> Self {
+        Self {
+            default: DEFAULT_PAGE_SIZE,
+            max: DEFAULT_MAX_PAGE_SIZE,
+        }
+    }
+}
+
+/// Caps on what is stored, so a runaway import can't fill the disk; `None`
+/// means unlimited
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StorageLimits {
+    /// Subscribed feeds; adding more is refused (`MAX_FEEDS`)
+    pub max_feeds: Option<i64>,
+    /// Stored articles; the oldest are trimmed beyond this (`MAX_ARTICLES`)
+    pub max_articles: Option<i64>,
+}
+
+/// A daily window without fetches, in the display time zone, e.g.
+/// `22:00-07:00`. The end is exclusive; a window may span midnight.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    /// Parse `HH:MM-HH:MM`
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let invalid = || format!("Quiet hours must look like 22:00-07:00, not {}", value);
+        let (start, end) = value.trim().split_once('-').ok_or_else(invalid)?;
+        let time =
+            |part: &str| NaiveTime::parse_from_str(part.trim(), "%H:%M").map_err(|_| invalid());
+        let quiet_hours = QuietHours {
+            start: time(start)?,
+            end: time(end)?,
+        };
+        if quiet_hours.start == quiet_hours.end {
+            return Err("Quiet hours must not start and end at the same time".to_string());
+        }
+        Ok(quiet_hours)
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// `HH:MM-HH:MM`, the form stored for feeds
+impl fmt::Display for QuietHours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+/// Hosts feeds may be fetched from, on top of the SSRF checks. Patterns are
+/// a host name or `*.example.com` for any subdomain of `example.com`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HostPolicy {
+    /// Only these hosts are fetched, unless empty (`FETCH_HOST_ALLOWLIST`)
+    pub allowlist: Vec<String>,
+    /// These hosts are never fetched (`FETCH_HOST_BLOCKLIST`)
+    pub blocklist: Vec<String>,
+}
+
+impl HostPolicy {
+    pub fn allows(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        let matches = |pattern: &String| match pattern.strip_prefix("*.") {
+            Some(domain) => host
+                .strip_suffix(domain)
+                .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.')),
+            None => host == *pattern,
+        };
+        !self.blocklist.iter().any(matches)
+            && (self.allowlist.is_empty() || self.allowlist.iter().any(matches))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.allowlist.is_empty() && self.blocklist.is_empty()
+    }
+}
+
+/// Output format of the log subscriber (`LOG_FORMAT`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Pretty,
+    /// One JSON object per line, for log pipelines
+    Json,
+}
+
+/// Order of feeds within each group of the feed filter (`FEED_SORT`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FeedSort {
+    /// Alphabetically by title
+    #[default]
+    Title,
+    /// Feeds with the most unread articles first, then by title
+    Unread,
+}
+
+/// How much markup reader mode keeps from fetched pages (`READER_SANITIZATION`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReaderSanitization {
+    /// The same allowlist as feed content
+    #[default]
+    Standard,
+    /// Like standard, but without images, so reader pages load nothing
+    /// from third parties
+    Strict,
+}
+
+/// `SameSite` attribute of the CSRF cookie (`CSRF_SAMESITE`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CookieSameSite {
+    /// Never sent on cross-site requests
+    #[default]
+    Strict,
+    /// Also sent when following a link from another site
+    Lax,
+}
+
+/// CSP sources that widen the policy a lot, refused unless `CSP_ALLOW_UNSAFE` is set
+const UNSAFE_CSP_SOURCES: &[&str] = &[
+    "*",
+    "'unsafe-eval'",
+    "'wasm-unsafe-eval'",
+    "'unsafe-hashes'",
+];
+
+/// Extra sources merged into the default Content-Security-Policy directives
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CspSources {
+    /// `CSP_SCRIPT_SRC`
+    pub script_src: Vec<String>,
+    /// `CSP_STYLE_SRC`
+    pub style_src: Vec<String>,
+    /// `CSP_IMG_SRC`
+    pub img_src: Vec<String>,
+    /// `CSP_FONT_SRC`
+    pub font_src: Vec<String>,
+    /// `CSP_CONNECT_SRC`
+    pub connect_src: Vec<String>,
+}
 
 #[derive(Clone)]
 pub struct Config {
     pub database_url: String,
     pub port: u16,
     pub host: String,
+    pub fetch_delays: FetchDelays,
+    pub adaptive_interval: AdaptiveInterval,
+    /// Pages of RFC 5005 archive history fetched for new feeds (`ARCHIVE_BACKFILL_PAGES`)
+    pub archive_backfill_pages: usize,
+    /// Due feeds fetched per cycle, `0` for all (`MAX_FEEDS_PER_CYCLE`)
+    pub max_feeds_per_cycle: i64,
+    /// Window in which no feed is fetched (`QUIET_HOURS`)
+    pub quiet_hours: Option<QuietHours>,
+    pub storage_limits: StorageLimits,
+    /// Bearer token required by the JSON API, if set (`API_TOKEN`)
+    pub api_token: Option<String>,
+    /// Key of credentials stored in the database (`SECRET_KEY`); without it
+    /// a key file next to the database is used
+    pub secret_key: Option<[u8; 32]>,
+    pub page_size: PageSize,
+    /// Time zone for absolute timestamps in the UI (`DISPLAY_TIMEZONE`, else `TZ`)
+    pub display_timezone: Tz,
+    pub log_format: LogFormat,
+    pub feed_sort: FeedSort,
+    /// Repopulate the search index before serving (`REBUILD_FTS_ON_STARTUP`)
+    pub rebuild_fts_on_startup: bool,
+    /// Serve without fetching feeds in the background (`DISABLE_SCHEDULER`)
+    pub disable_scheduler: bool,
+    pub csp_sources: CspSources,
+    pub reader_sanitization: ReaderSanitization,
+    /// Serve content images through `/img` (`IMAGE_PROXY`)
+    pub image_proxy: bool,
+    pub csrf_same_site: CookieSameSite,
+    /// Reverse proxies whose forwarding headers are believed (`TRUSTED_PROXY`)
+    pub trusted_proxies: Vec<IpNet>,
+    pub fetch_host_policy: HostPolicy,
+    /// Settings that were ignored rather than failing startup, to be logged
+    /// once logging is set up
+    pub warnings: Vec<String>,
 }
 
 impl Config {
@@ -20,10 +333,541 @@ impl Config {
 
         let host = env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
 
+        let fetch_delays = FetchDelays {
+            inter_feed: delay_from_env("INTER_FEED_DELAY_MS", DEFAULT_INTER_FEED_DELAY_MS)?,
+            inter_og: delay_from_env("INTER_OG_DELAY_MS", DEFAULT_INTER_OG_DELAY_MS)?,
+        };
+
+        let adaptive_interval = validate_adaptive_interval(AdaptiveInterval {
+            consecutive_fetches: int_from_env(
+                "ADAPTIVE_CONSECUTIVE_FETCHES",
+                DEFAULT_ADAPTIVE_CONSECUTIVE_FETCHES,
+            )?,
+            min_interval_minutes: int_from_env(
+                "ADAPTIVE_MIN_INTERVAL_MINUTES",
+                DEFAULT_ADAPTIVE_MIN_INTERVAL_MINUTES,
+            )?,
+            max_interval_minutes: int_from_env(
+                "ADAPTIVE_MAX_INTERVAL_MINUTES",
+                DEFAULT_ADAPTIVE_MAX_INTERVAL_MINUTES,
+            )?,
+        })?;
+
+        let archive_backfill_pages = match env::var("ARCHIVE_BACKFILL_PAGES") {
+            Ok(value) => value.parse::<usize>()?,
+            Err(_) => DEFAULT_ARCHIVE_BACKFILL_PAGES,
+        };
+
+        let max_feeds_per_cycle = int_from_env("MAX_FEEDS_PER_CYCLE", DEFAULT_MAX_FEEDS_PER_CYCLE)?;
+
+        let quiet_hours = match env::var("QUIET_HOURS") {
+            Ok(value) if !value.trim().is_empty() => Some(QuietHours::parse(&value)?),
+            _ => None,
+        };
+
+        let storage_limits = StorageLimits {
+            max_feeds: limit_from_env("MAX_FEEDS")?,
+            max_articles: limit_from_env("MAX_ARTICLES")?,
+        };
+
+        let api_token = env::var("API_TOKEN").ok().filter(|t| !t.trim().is_empty());
+        let secret_key = match env::var("SECRET_KEY") {
+            Ok(value) if !value.trim().is_empty() => Some(parse_secret_key(&value)?),
+            _ => None,
+        };
+
+        let page_size = PageSize {
+            default: int_from_env("PAGE_SIZE", DEFAULT_PAGE_SIZE)?,
+            max: int_from_env("MAX_PAGE_SIZE", DEFAULT_MAX_PAGE_SIZE)?,
+        };
+
+        let mut warnings = Vec::new();
+        let display_timezone = display_timezone_from_env(&mut warnings)?;
+
+        let log_format = match env::var("LOG_FORMAT") {
+            Ok(value) => parse_log_format(&value)?,
+            Err(_) => LogFormat::default(),
+        };
+
+        let feed_sort = match env::var("FEED_SORT") {
+            Ok(value) => parse_feed_sort(&value)?,
+            Err(_) => FeedSort::default(),
+        };
+
+        let rebuild_fts_on_startup = bool_from_env("REBUILD_FTS_ON_STARTUP");
+
+        let disable_scheduler = bool_from_env("DISABLE_SCHEDULER");
+
+        let allow_unsafe_csp = bool_from_env("CSP_ALLOW_UNSAFE");
+        let csp_sources = CspSources {
+            script_src: csp_sources_from_env("CSP_SCRIPT_SRC", allow_unsafe_csp)?,
+            style_src: csp_sources_from_env("CSP_STYLE_SRC", allow_unsafe_csp)?,
+            img_src: csp_sources_from_env("CSP_IMG_SRC", allow_unsafe_csp)?,
+            font_src: csp_sources_from_env("CSP_FONT_SRC", allow_unsafe_csp)?,
+            connect_src: csp_sources_from_env("CSP_CONNECT_SRC", allow_unsafe_csp)?,
+        };
+
+        let reader_sanitization = match env::var("READER_SANITIZATION") {
+            Ok(value) => parse_reader_sanitization(&value)?,
+            Err(_) => ReaderSanitization::default(),
+        };
+
+        let image_proxy = bool_from_env("IMAGE_PROXY");
+
+        let csrf_same_site = match env::var("CSRF_SAMESITE") {
+            Ok(value) => parse_same_site(&value)?,
+            Err(_) => CookieSameSite::default(),
+        };
+
+        let trusted_proxies = match env::var("TRUSTED_PROXY") {
+            Ok(value) => parse_trusted_proxies(&value)?,
+            Err(_) => Vec::new(),
+        };
+
+        let fetch_host_policy = HostPolicy {
+            allowlist: host_patterns_from_env("FETCH_HOST_ALLOWLIST")?,
+            blocklist: host_patterns_from_env("FETCH_HOST_BLOCKLIST")?,
+        };
+
         Ok(Config {
             database_url,
             port,
             host,
+            fetch_delays,
+            adaptive_interval,
+            archive_backfill_pages,
+            max_feeds_per_cycle,
+            quiet_hours,
+            storage_limits,
+            api_token,
+            secret_key,
+            page_size,
+            display_timezone,
+            log_format,
+            feed_sort,
+            rebuild_fts_on_startup,
+            disable_scheduler,
+            csp_sources,
+            reader_sanitization,
+            image_proxy,
+            csrf_same_site,
+            trusted_proxies,
+            fetch_host_policy,
+            warnings,
         })
     }
 }
+
+/// Read a delay in milliseconds from the environment, falling back to a default
+fn delay_from_env(name: &str, default_ms: u64) -> Result<Duration, std::num::ParseIntError> {
+    let millis = match env::var(name) {
+        Ok(value) => value.parse::<u64>()?,
+        Err(_) => default_ms,
+    };
+
+    Ok(Duration::from_millis(millis))
+}
+
+/// Reject adaptive interval settings that would never change an interval
+fn validate_adaptive_interval(adaptive: AdaptiveInterval) -> Result<AdaptiveInterval, String> {
+    if adaptive.consecutive_fetches < 1 {
+        return Err("ADAPTIVE_CONSECUTIVE_FETCHES must be at least 1".to_string());
+    }
+    if adaptive.min_interval_minutes < 1 {
+        return Err("ADAPTIVE_MIN_INTERVAL_MINUTES must be at least 1".to_string());
+    }
+    if adaptive.max_interval_minutes < adaptive.min_interval_minutes {
+        return Err(
+            "ADAPTIVE_MAX_INTERVAL_MINUTES must not be below ADAPTIVE_MIN_INTERVAL_MINUTES"
+                .to_string(),
+        );
+    }
+    Ok(adaptive)
+}
+
+/// Read the display time zone from `DISPLAY_TIMEZONE` or `TZ`, defaulting to UTC
+fn display_timezone_from_env(warnings: &mut Vec<String>) -> Result<Tz, String> {
+    let non_empty = |name| env::var(name).ok().filter(|value| !value.trim().is_empty());
+    display_timezone(non_empty("DISPLAY_TIMEZONE"), non_empty("TZ"), warnings)
+}
+
+/// An unknown `DISPLAY_TIMEZONE` is an error. `TZ` is set for the whole
+/// host and may be a POSIX rule (`EST5EDT,M3.2.0,M11.1.0`) or a path
+/// (`:/etc/localtime`), so an unknown one only warns and UTC is used.
+fn display_timezone(
+    display_timezone: Option<String>,
+    tz: Option<String>,
+    warnings: &mut Vec<String>,
+) -> Result<Tz, String> {
+    if let Some(name) = display_timezone {
+        return name
+            .trim()
+            .parse::<Tz>()
+            .map_err(|_| format!("Unknown time zone: {}", name));
+    }
+
+    match tz {
+        Some(name) => Ok(name.trim().parse::<Tz>().unwrap_or_else(|_| {
+            warnings.push(format!(
+                "TZ={} is not an IANA time zone, showing dates in UTC; set DISPLAY_TIMEZONE to choose one",
+                name
+            ));
+            Tz::UTC
+        })),
+        None => Ok(Tz::UTC),
+    }
+}
+
+/// Parse a `LOG_FORMAT` value; empty means the default
+fn parse_log_format(value: &str) -> Result<LogFormat, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "" | "pretty" | "text" => Ok(LogFormat::Pretty),
+        "json" => Ok(LogFormat::Json),
+        other => Err(format!("Unknown log format: {}", other)),
+    }
+}
+
+/// Parse a `SECRET_KEY` value: a random 32-byte key in base64, used as is,
+/// so a guessable passphrase can't be brute-forced from a database dump
+fn parse_secret_key(value: &str) -> Result<[u8; 32], String> {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    STANDARD
+        .decode(value.trim())
+        .ok()
+        .and_then(|key| <[u8; 32]>::try_from(key).ok())
+        .ok_or_else(|| {
+            "SECRET_KEY must be 32 random bytes in base64, e.g. from `openssl rand -base64 32`"
+                .to_string()
+        })
+}
+
+/// Parse a `FEED_SORT` value; empty means the default
+fn parse_feed_sort(value: &str) -> Result<FeedSort, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "" | "title" => Ok(FeedSort::Title),
+        "unread" => Ok(FeedSort::Unread),
+        other => Err(format!("Unknown feed sort: {}", other)),
+    }
+}
+
+fn parse_reader_sanitization(value: &str) -> Result<ReaderSanitization, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "" | "standard" => Ok(ReaderSanitization::Standard),
+        "strict" => Ok(ReaderSanitization::Strict),
+        other => Err(format!("Unknown reader sanitization level: {}", other)),
+    }
+}
+
+fn parse_same_site(value: &str) -> Result<CookieSameSite, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "" | "strict" => Ok(CookieSameSite::Strict),
+        "lax" => Ok(CookieSameSite::Lax),
+        other => Err(format!("Unknown SameSite value: {}", other)),
+    }
+}
+
+/// Parse a comma-separated list of proxy addresses or networks, e.g.
+/// `127.0.0.1, 172.16.0.0/12`
+fn parse_trusted_proxies(value: &str) -> Result<Vec<IpNet>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|proxy| !proxy.is_empty())
+        .map(|proxy| {
+            proxy
+                .parse::<IpNet>()
+                .or_else(|_| proxy.parse::<std::net::IpAddr>().map(IpNet::from))
+                .map_err(|_| format!("TRUSTED_PROXY: invalid address or network: {}", proxy))
+        })
+        .collect()
+}
+
+/// Read a comma-separated list of host patterns from the environment
+fn host_patterns_from_env(name: &str) -> Result<Vec<String>, String> {
+    match env::var(name) {
+        Ok(value) => parse_host_patterns(&value).map_err(|e| format!("{}: {}", name, e)),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+/// Parse host patterns such as `example.com, *.example.org`, lowercased
+fn parse_host_patterns(value: &str) -> Result<Vec<String>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(|pattern| {
+            let host = pattern.strip_prefix("*.").unwrap_or(pattern);
+            let valid = !host.is_empty()
+                && host
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '[' | ']' | ':'));
+            if valid {
+                Ok(pattern.trim_end_matches('.').to_ascii_lowercase())
+            } else {
+                Err(format!("invalid host pattern: {}", pattern))
+            }
+        })
+        .collect()
+}
+
+/// Read a space-separated list of CSP sources from the environment
+fn csp_sources_from_env(name: &str, allow_unsafe: bool) -> Result<Vec<String>, String> {
+    match env::var(name) {
+        Ok(value) => {
+            parse_csp_sources(&value, allow_unsafe).map_err(|e| format!("{}: {}", name, e))
+        }
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+/// Split a list of CSP sources, rejecting anything that could start a new
+/// directive and, unless allowed, sources that defeat the policy
+fn parse_csp_sources(value: &str, allow_unsafe: bool) -> Result<Vec<String>, String> {
+    value
+        .split_whitespace()
+        .map(|source| {
+            if source.contains([';', ',']) || !source.chars().all(|c| c.is_ascii_graphic()) {
+                return Err(format!("Invalid CSP source: {}", source));
+            }
+            if !allow_unsafe && UNSAFE_CSP_SOURCES.contains(&source.to_ascii_lowercase().as_str()) {
+                return Err(format!(
+                    "CSP source {} requires CSP_ALLOW_UNSAFE=true",
+                    source
+                ));
+            }
+            Ok(source.to_string())
+        })
+        .collect()
+}
+
+/// Read a boolean flag from the environment; unset means false
+fn bool_from_env(name: &str) -> bool {
+    env::var(name)
+        .map(|value| matches!(value.trim(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// Read an optional limit from the environment; unset, empty or `0` means none
+fn limit_from_env(name: &str) -> Result<Option<i64>, std::num::ParseIntError> {
+    match env::var(name) {
+        Ok(value) if !value.trim().is_empty() => {
+            let limit = value.trim().parse::<i64>()?;
+            Ok((limit > 0).then_some(limit))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Read an integer from the environment, falling back to a default
+fn int_from_env(name: &str, default: i64) -> Result<i64, std::num::ParseIntError> {
+    match env::var(name) {
+        Ok(value) => value.parse::<i64>(),
+        Err(_) => Ok(default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_size_caps_absurd_limits() {
+        let page_size = PageSize::default();
+
+        assert_eq!(page_size.limit(None), DEFAULT_PAGE_SIZE);
+        assert_eq!(page_size.limit(Some(50)), 50);
+        assert_eq!(page_size.limit(Some(100_000)), DEFAULT_MAX_PAGE_SIZE);
+        assert_eq!(page_size.limit(Some(-5)), 1);
+    }
+
+    #[test]
+    fn test_restored_list_spans_pages_up_to_a_cap() {
+        let page_size = PageSize::default();
+
+        assert_eq!(page_size.initial_limit(Some(240), None), 240);
+        assert_eq!(
+            page_size.initial_limit(Some(100_000_000), None),
+            DEFAULT_MAX_PAGE_SIZE * MAX_RESTORED_PAGES
+        );
+        assert_eq!(page_size.initial_limit(Some(5), None), DEFAULT_PAGE_SIZE);
+        assert_eq!(
+            page_size.initial_limit(None, Some(100_000)),
+            DEFAULT_MAX_PAGE_SIZE
+        );
+    }
+
+    #[test]
+    fn test_only_an_explicit_display_timezone_must_parse() {
+        let zone = |display: Option<&str>, tz: Option<&str>| {
+            let mut warnings = Vec::new();
+            let zone = display_timezone(
+                display.map(str::to_string),
+                tz.map(str::to_string),
+                &mut warnings,
+            );
+            (zone, warnings.len())
+        };
+
+        assert_eq!(zone(None, None), (Ok(Tz::UTC), 0));
+        assert_eq!(
+            zone(None, Some("Europe/Berlin")),
+            (Ok(Tz::Europe__Berlin), 0)
+        );
+        assert_eq!(
+            zone(Some("Asia/Tokyo"), Some("Europe/Berlin")),
+            (Ok(Tz::Asia__Tokyo), 0)
+        );
+        for host_tz in ["EST5EDT,M3.2.0,M11.1.0", ":/etc/localtime"] {
+            assert_eq!(zone(None, Some(host_tz)), (Ok(Tz::UTC), 1));
+        }
+        assert!(zone(Some("Mars/Olympus"), None).0.is_err());
+    }
+
+    #[test]
+    fn test_quiet_hours_may_span_midnight() {
+        let at = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+
+        let night = QuietHours::parse(" 22:00 - 07:00 ").unwrap();
+        assert_eq!(night.to_string(), "22:00-07:00");
+        assert!(night.contains(at(23, 30)) && night.contains(at(3, 0)));
+        assert!(!night.contains(at(7, 0)) && !night.contains(at(12, 0)));
+
+        let lunch = QuietHours::parse("12:00-13:30").unwrap();
+        assert!(lunch.contains(at(12, 0)) && !lunch.contains(at(13, 30)));
+
+        for invalid in ["22:00", "25:00-07:00", "noon-night", "08:00-08:00"] {
+            assert!(QuietHours::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_parse_log_format() {
+        assert_eq!(parse_log_format("json"), Ok(LogFormat::Json));
+        assert_eq!(parse_log_format(" JSON "), Ok(LogFormat::Json));
+        assert_eq!(parse_log_format("pretty"), Ok(LogFormat::Pretty));
+        assert_eq!(parse_log_format(""), Ok(LogFormat::Pretty));
+        assert!(parse_log_format("xml").is_err());
+    }
+
+    #[test]
+    fn test_parse_secret_key() {
+        let key = [7u8; 32];
+        let encoded = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, key);
+        assert_eq!(parse_secret_key(&format!(" {} ", encoded)), Ok(key));
+        assert!(parse_secret_key("change-me").is_err());
+        assert!(parse_secret_key("c2hvcnQ=").is_err());
+    }
+
+    #[test]
+    fn test_parse_same_site() {
+        assert_eq!(parse_same_site("Lax"), Ok(CookieSameSite::Lax));
+        assert_eq!(parse_same_site(" strict "), Ok(CookieSameSite::Strict));
+        assert_eq!(parse_same_site(""), Ok(CookieSameSite::Strict));
+        // SameSite=None would need a cross-site use case FluxFeed doesn't have
+        assert!(parse_same_site("none").is_err());
+    }
+
+    #[test]
+    fn test_parse_trusted_proxies() {
+        let proxies = parse_trusted_proxies("127.0.0.1, 172.16.0.0/12,::1").unwrap();
+        assert_eq!(
+            proxies,
+            vec![
+                "127.0.0.1/32".parse::<IpNet>().unwrap(),
+                "172.16.0.0/12".parse().unwrap(),
+                "::1/128".parse().unwrap(),
+            ]
+        );
+        assert_eq!(parse_trusted_proxies(""), Ok(vec![]));
+        assert!(parse_trusted_proxies("proxy.local").is_err());
+    }
+
+    #[test]
+    fn test_host_policy() {
+        let policy = HostPolicy {
+            allowlist: parse_host_patterns("Example.com, *.feeds.example").unwrap(),
+            blocklist: parse_host_patterns("ads.feeds.example").unwrap(),
+        };
+        assert!(policy.allows("example.com"));
+        assert!(policy.allows("blog.feeds.example."));
+        assert!(!policy.allows("feeds.example"));
+        assert!(!policy.allows("ads.feeds.example"));
+        assert!(!policy.allows("notexample.com"));
+
+        // Without an allowlist only the blocklist applies
+        let policy = HostPolicy {
+            blocklist: parse_host_patterns("*.tracker.example").unwrap(),
+            ..HostPolicy::default()
+        };
+        assert!(policy.allows("example.com"));
+        assert!(!policy.allows("cdn.tracker.example"));
+
+        assert_eq!(parse_host_patterns(" ,"), Ok(vec![]));
+        assert!(parse_host_patterns("https://example.com").is_err());
+        assert!(parse_host_patterns("*").is_err());
+    }
+
+    #[test]
+    fn test_parse_feed_sort() {
+        assert_eq!(parse_feed_sort("unread"), Ok(FeedSort::Unread));
+        assert_eq!(parse_feed_sort(" Title "), Ok(FeedSort::Title));
+        assert_eq!(parse_feed_sort(""), Ok(FeedSort::Title));
+        assert!(parse_feed_sort("random").is_err());
+    }
+
+    #[test]
+    fn test_parse_reader_sanitization() {
+        assert_eq!(
+            parse_reader_sanitization("strict"),
+            Ok(ReaderSanitization::Strict)
+        );
+        assert_eq!(
+            parse_reader_sanitization(" Standard "),
+            Ok(ReaderSanitization::Standard)
+        );
+        assert_eq!(
+            parse_reader_sanitization(""),
+            Ok(ReaderSanitization::Standard)
+        );
+        assert!(parse_reader_sanitization("none").is_err());
+    }
+
+    #[test]
+    fn test_validate_adaptive_interval() {
+        let adaptive = AdaptiveInterval::default();
+        assert_eq!(validate_adaptive_interval(adaptive), Ok(adaptive));
+        assert!(validate_adaptive_interval(AdaptiveInterval {
+            consecutive_fetches: 0,
+            ..adaptive
+        })
+        .is_err());
+        assert!(validate_adaptive_interval(AdaptiveInterval {
+            min_interval_minutes: 120,
+            max_interval_minutes: 60,
+            ..adaptive
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_csp_sources() {
+        assert_eq!(
+            parse_csp_sources(" https://cdn.example.com  'self' ", false),
+            Ok(vec![
+                "https://cdn.example.com".to_string(),
+                "'self'".to_string()
+            ])
+        );
+        assert_eq!(parse_csp_sources("", false), Ok(Vec::new()));
+        // No smuggling in extra directives
+        assert!(parse_csp_sources("https://a.example; script-src *", true).is_err());
+        assert!(parse_csp_sources("'unsafe-eval'", false).is_err());
+        assert!(parse_csp_sources("*", false).is_err());
+        assert_eq!(
+            parse_csp_sources("'unsafe-eval'", true),
+            Ok(vec!["'unsafe-eval'".to_string()])
+        );
+    }
+}
@@ -1,5 +1,12 @@
-use crate::domain::models::Article;
+use crate::domain::models::{
+    Article, ArticleListFilter, ArticleStateImport, DigestEntry, DigestFeed, ImportedArticleState,
+    SharePayload,
+};
 use crate::infrastructure::repository;
+use crate::web::templates::ArticleWithFeed;
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use dom_query::Document;
 use sqlx::SqlitePool;
 use thiserror::Error;
 
@@ -10,8 +17,32 @@ pub enum ArticleServiceError {
 
     #[error("Article not found")]
     NotFound,
+
+    #[error("Article has no http(s) URL to share")]
+    NotShareable,
+
+    #[error("Invalid article state import: {0}")]
+    InvalidStateImport(String),
 }
 
+/// Settings key of the previous visit, the boundary of the "new since last visit" filter
+const LAST_VISITED_KEY: &str = "last_visited_at";
+
+/// Settings key of the latest view of the unfiltered article list
+const CURRENT_VISIT_KEY: &str = "current_visit_at";
+
+/// Views of the article list closer together than this belong to one visit
+const VISIT_GAP_MINUTES: i64 = 30;
+
+/// Maximum length of the excerpt in a share payload, in characters
+const SHARE_EXCERPT_CHARS: usize = 280;
+
+/// Maximum length of the plain-text preview shown in article lists, in characters
+pub(crate) const CONTENT_PREVIEW_CHARS: usize = 500;
+
+/// Maximum number of articles listed in one daily digest
+const DIGEST_LIMIT: i64 = 500;
+
 pub async fn toggle_read_status(
     pool: &SqlitePool,
     article_id: i64,
@@ -48,11 +79,73 @@ pub async fn toggle_starred_status(
     Ok(updated)
 }
 
-pub async fn mark_all_read(
+pub async fn toggle_read_later_status(
+    pool: &SqlitePool,
+    article_id: i64,
+) -> Result<Article, ArticleServiceError> {
+    let article = repository::get_article_by_id(pool, article_id)
+        .await?
+        .ok_or(ArticleServiceError::NotFound)?;
+
+    let new_status = !article.is_read_later;
+    repository::update_article_read_later_status(pool, article_id, new_status).await?;
+
+    let updated = repository::get_article_by_id(pool, article_id)
+        .await?
+        .ok_or(ArticleServiceError::NotFound)?;
+
+    Ok(updated)
+}
+
+/// Mark read the unread articles matching a search, returning how many changed
+pub async fn mark_search_read(
     pool: &SqlitePool,
-    feed_id: Option<i64>,
+    search: &ArticleListFilter,
 ) -> Result<u64, ArticleServiceError> {
-    Ok(repository::mark_all_articles_read(pool, feed_id).await?)
+    Ok(repository::mark_search_results_read(pool, search).await?)
+}
+
+/// Action applied to a selection of articles at once
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BulkAction {
+    Read,
+    Unread,
+    Star,
+    Unstar,
+}
+
+impl BulkAction {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "read" => Some(BulkAction::Read),
+            "unread" => Some(BulkAction::Unread),
+            "star" => Some(BulkAction::Star),
+            "unstar" => Some(BulkAction::Unstar),
+            _ => None,
+        }
+    }
+}
+
+/// Apply `action` to the given articles, returning how many changed
+pub async fn bulk_update(
+    pool: &SqlitePool,
+    article_ids: &[i64],
+    action: BulkAction,
+) -> Result<u64, ArticleServiceError> {
+    let affected = match action {
+        BulkAction::Read => repository::set_articles_read_status(pool, article_ids, true).await?,
+        BulkAction::Unread => {
+            repository::set_articles_read_status(pool, article_ids, false).await?
+        }
+        BulkAction::Star => {
+            repository::set_articles_starred_status(pool, article_ids, true).await?
+        }
+        BulkAction::Unstar => {
+            repository::set_articles_starred_status(pool, article_ids, false).await?
+        }
+    };
+
+    Ok(affected)
 }
 
 pub async fn mark_as_read(
@@ -75,6 +168,417 @@ pub async fn mark_as_read(
     Ok(updated)
 }
 
+/// When the previous visit to the article list ended, if there was one
+pub async fn last_visit(pool: &SqlitePool) -> Result<Option<DateTime<Utc>>, ArticleServiceError> {
+    read_timestamp(pool, LAST_VISITED_KEY).await
+}
+
+/// Apply read/starred states exported from another reader to the stored
+/// articles with the same URL
+pub async fn import_article_states(
+    pool: &SqlitePool,
+    input: &str,
+) -> Result<ArticleStateImport, ArticleServiceError> {
+    let states: Vec<(String, bool, bool)> = parse_article_states(input)?
+        .into_iter()
+        .map(|state| (state.url, state.is_read, state.is_starred))
+        .collect();
+
+    let unmatched = repository::apply_article_states(pool, &states).await?;
+
+    Ok(ArticleStateImport {
+        applied: states.len() - unmatched.len(),
+        unmatched,
+    })
+}
+
+/// Parse exported article states: a JSON array of `{url, is_read,
+/// is_starred}` objects, or CSV lines of `url,is_read,is_starred` with an
+/// optional header line
+fn parse_article_states(input: &str) -> Result<Vec<ImportedArticleState>, ArticleServiceError> {
+    let input = input.trim();
+    if input.starts_with('[') {
+        return serde_json::from_str(input)
+            .map_err(|e| ArticleServiceError::InvalidStateImport(e.to_string()));
+    }
+
+    let mut states = Vec::new();
+    for (index, line) in input.lines().map(str::trim).enumerate() {
+        if line.is_empty() || (index == 0 && line.to_lowercase().starts_with("url,")) {
+            continue;
+        }
+        // The flags are the last two fields, so URLs may contain commas
+        let mut fields = line.rsplitn(3, ',');
+        let (Some(is_starred), Some(is_read), Some(url)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            return Err(ArticleServiceError::InvalidStateImport(format!(
+                "Expected 'url,is_read,is_starred' on line {}",
+                index + 1
+            )));
+        };
+        states.push(ImportedArticleState {
+            url: url.trim().to_string(),
+            is_read: parse_flag(is_read, index + 1)?,
+            is_starred: parse_flag(is_starred, index + 1)?,
+        });
+    }
+
+    Ok(states)
+}
+
+fn parse_flag(value: &str, line: usize) -> Result<bool, ArticleServiceError> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "1" | "yes" => Ok(true),
+        "false" | "0" | "no" | "" => Ok(false),
+        other => Err(ArticleServiceError::InvalidStateImport(format!(
+            "Expected true or false on line {}, got '{}'",
+            line, other
+        ))),
+    }
+}
+
+/// Record a view of the unfiltered article list. A view after a pause of more
+/// than `VISIT_GAP_MINUTES` starts a new visit, and the end of the one before
+/// becomes the boundary of the "new since last visit" filter.
+pub async fn record_visit(
+    pool: &SqlitePool,
+    now: DateTime<Utc>,
+) -> Result<(), ArticleServiceError> {
+    if let Some(previous_view) = read_timestamp(pool, CURRENT_VISIT_KEY).await? {
+        if now - previous_view > chrono::Duration::minutes(VISIT_GAP_MINUTES) {
+            repository::set_setting(pool, LAST_VISITED_KEY, &previous_view.to_rfc3339()).await?;
+        }
+    }
+    repository::set_setting(pool, CURRENT_VISIT_KEY, &now.to_rfc3339()).await?;
+
+    Ok(())
+}
+
+async fn read_timestamp(
+    pool: &SqlitePool,
+    key: &str,
+) -> Result<Option<DateTime<Utc>>, ArticleServiceError> {
+    let value = repository::get_setting(pool, key).await?;
+
+    Ok(value
+        .and_then(|v| DateTime::parse_from_rfc3339(&v).ok())
+        .map(|dt| dt.with_timezone(&Utc)))
+}
+
+pub async fn get_share_payload(
+    pool: &SqlitePool,
+    article_id: i64,
+) -> Result<SharePayload, ArticleServiceError> {
+    let item = repository::get_article_with_feed_by_id(pool, article_id)
+        .await?
+        .ok_or(ArticleServiceError::NotFound)?;
+
+    share_payload(&item.article, &item.feed_title)
+}
+
+/// Build a share payload from the article's own fields, preferring OpenGraph
+/// metadata and falling back to the summary and feed title
+fn share_payload(article: &Article, feed_title: &str) -> Result<SharePayload, ArticleServiceError> {
+    let url = article
+        .url
+        .as_deref()
+        .map(str::trim)
+        .filter(|u| u.starts_with("http://") || u.starts_with("https://"))
+        .ok_or(ArticleServiceError::NotShareable)?;
+
+    let excerpt = article
+        .og_description
+        .as_deref()
+        .or(article.summary.as_deref())
+        .map(|html| plain_text_excerpt(html, SHARE_EXCERPT_CHARS))
+        .filter(|e| !e.is_empty());
+
+    Ok(SharePayload {
+        title: article.title.trim().to_string(),
+        url: url.to_string(),
+        excerpt,
+        site_name: article
+            .og_site_name
+            .clone()
+            .or_else(|| Some(feed_title.to_string())),
+    })
+}
+
+/// Articles published on `date` in the time zone `tz`, grouped by feed in
+/// feed title order
+pub async fn daily_digest(
+    pool: &SqlitePool,
+    date: NaiveDate,
+    tz: Tz,
+    unread_only: bool,
+) -> Result<Vec<DigestFeed>, ArticleServiceError> {
+    let next_day = date.succ_opt().unwrap_or(date);
+    let articles = repository::list_articles_with_feeds(
+        pool,
+        &ArticleListFilter {
+            is_read: unread_only.then_some(false),
+            date_from: Some(start_of_day(date, tz)),
+            date_to: Some(start_of_day(next_day, tz) - chrono::Duration::seconds(1)),
+            ..Default::default()
+        },
+        DIGEST_LIMIT,
+        0,
+    )
+    .await?;
+
+    Ok(group_digest(articles))
+}
+
+/// Midnight at the start of `date` in `tz`, as UTC. Where a DST change
+/// skips midnight, the day starts an hour later.
+fn start_of_day(date: NaiveDate, tz: Tz) -> DateTime<Utc> {
+    let midnight = date.and_time(NaiveTime::MIN);
+    [midnight, midnight + chrono::Duration::hours(1)]
+        .into_iter()
+        .find_map(|local| tz.from_local_datetime(&local).earliest())
+        .map_or_else(|| midnight.and_utc(), |start| start.with_timezone(&Utc))
+}
+
+/// Group articles by feed, keeping each feed's articles oldest first
+fn group_digest(articles: Vec<ArticleWithFeed>) -> Vec<DigestFeed> {
+    let mut feeds: Vec<DigestFeed> = Vec::new();
+
+    // Articles arrive newest first; walk them in reading order
+    for item in articles.into_iter().rev() {
+        let feed_id = item.article.feed_id;
+        let entry = DigestEntry {
+            excerpt: item
+                .article
+                .summary
+                .as_deref()
+                .or(item.article.og_description.as_deref())
+                .map(|html| plain_text_excerpt(html, SHARE_EXCERPT_CHARS))
+                .filter(|e| !e.is_empty()),
+            title: item.article.title,
+            url: item.article.url,
+            published_at: item.article.published_at,
+        };
+        match feeds.iter_mut().find(|f| f.feed_id == feed_id) {
+            Some(feed) => feed.entries.push(entry),
+            None => feeds.push(DigestFeed {
+                feed_id,
+                feed_title: item.feed_title,
+                feed_color: item.feed_color,
+                entries: vec![entry],
+            }),
+        }
+    }
+
+    feeds.sort_by_key(|f| f.feed_title.to_lowercase());
+    feeds
+}
+
+/// Columns of the full-text index that `column:term` may address directly
+const FTS_COLUMNS: &[&str] = &["title", "content", "summary", "feed_title"];
+
+/// A search box query split into operators and the free text left for FTS
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    /// Free text terms, passed to the full-text index as typed
+    pub terms: Vec<String>,
+    /// `feed:`, matched against feed titles
+    pub feed: Option<String>,
+    /// `author:`
+    pub author: Option<String>,
+    /// `is:unread` or `is:read`
+    pub is_read: Option<bool>,
+    /// `is:starred`
+    pub is_starred: Option<bool>,
+    /// `before:YYYY-MM-DD`, exclusive
+    pub before: Option<NaiveDate>,
+    /// `after:YYYY-MM-DD`, exclusive
+    pub after: Option<NaiveDate>,
+}
+
+impl SearchQuery {
+    /// FTS5 expression of the free text and the author, if there is any
+    pub fn fts_query(&self) -> Option<String> {
+        let mut parts = self.terms.clone();
+        if let Some(author) = &self.author {
+            parts.push(format!("author:{}", fts_phrase(author)));
+        }
+
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+
+    /// Earliest publication time allowed by `after:`
+    pub fn date_from(&self) -> Option<DateTime<Utc>> {
+        self.after
+            .and_then(|d| d.succ_opt())
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|dt| dt.and_utc())
+    }
+
+    /// Latest publication time allowed by `before:`
+    pub fn date_to(&self) -> Option<DateTime<Utc>> {
+        self.before
+            .and_then(|d| d.pred_opt())
+            .and_then(|d| d.and_hms_opt(23, 59, 59))
+            .map(|dt| dt.and_utc())
+    }
+}
+
+/// Parse a search box query such as `feed:TechCrunch is:unread rust async`.
+///
+/// Operator values may be quoted (`author:"Jane Doe"`). Unknown operators and
+/// operators with an invalid value are searched for as literal text; `column:term`
+/// for a column of the full-text index is passed through.
+pub fn parse_search_query(query: &str) -> SearchQuery {
+    let mut search = SearchQuery::default();
+
+    for token in split_search_tokens(query) {
+        let operator = token.split_once(':').filter(|(key, _)| {
+            !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+        let Some((key, value)) = operator else {
+            search.terms.push(token);
+            continue;
+        };
+        let key = key.to_ascii_lowercase();
+        let value = unquote(value);
+
+        let handled = match key.as_str() {
+            "feed" if !value.is_empty() => {
+                search.feed = Some(value.to_string());
+                true
+            }
+            "author" if !value.is_empty() => {
+                search.author = Some(value.to_string());
+                true
+            }
+            "is" => match value.to_ascii_lowercase().as_str() {
+                "unread" => {
+                    search.is_read = Some(false);
+                    true
+                }
+                "read" => {
+                    search.is_read = Some(true);
+                    true
+                }
+                "starred" => {
+                    search.is_starred = Some(true);
+                    true
+                }
+                _ => false,
+            },
+            "before" | "after" => match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+                Ok(date) if key == "before" => {
+                    search.before = Some(date);
+                    true
+                }
+                Ok(date) => {
+                    search.after = Some(date);
+                    true
+                }
+                Err(_) => false,
+            },
+            column if FTS_COLUMNS.contains(&column) => {
+                search.terms.push(token.clone());
+                true
+            }
+            _ => false,
+        };
+
+        if !handled {
+            search.terms.push(fts_phrase(&token));
+        }
+    }
+
+    search
+}
+
+/// Split on whitespace outside of double quotes, keeping the quotes
+fn split_search_tokens(query: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in query.chars() {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        if c.is_whitespace() && !quoted {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+/// Strip one pair of surrounding double quotes
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Quote text as an FTS5 phrase so it is matched literally
+fn fts_phrase(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
+/// Feeds whose title contains `name`, ignoring case
+pub async fn feed_ids_by_title(
+    pool: &SqlitePool,
+    name: &str,
+) -> Result<Vec<i64>, ArticleServiceError> {
+    let name = name.to_lowercase();
+    let feeds = repository::list_feeds(pool).await?;
+
+    Ok(feeds
+        .into_iter()
+        .filter(|feed| feed.title.to_lowercase().contains(&name))
+        .map(|feed| feed.id)
+        .collect())
+}
+
+/// Number of words in the text of an HTML fragment. Text nodes are counted
+/// separately, so adjacent blocks like `<p>a</p><p>b</p>` don't run together.
+pub(crate) fn word_count(html: &str) -> i64 {
+    Document::fragment(html)
+        .root()
+        .descendants_it()
+        .filter(|node| node.is_text())
+        .map(|node| node.text().split_whitespace().count() as i64)
+        .sum()
+}
+
+/// The first paragraph with any text, as a stand-in summary for articles
+/// whose feed sent none
+pub(crate) fn first_paragraph(html: &str) -> Option<String> {
+    Document::fragment(html)
+        .select("p")
+        .iter()
+        .find(|p| !p.text().trim().is_empty())
+        .map(|p| p.html().to_string())
+}
+
+/// Strip markup, collapse whitespace and cut to `max_chars`
+pub(crate) fn plain_text_excerpt(html: &str, max_chars: usize) -> String {
+    let text = Document::fragment(html).text();
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if text.chars().count() <= max_chars {
+        return text;
+    }
+    let truncated: String = text.chars().take(max_chars - 1).collect();
+    format!("{}…", truncated.trim_end())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -91,4 +595,234 @@ mod tests {
         let article_err: ArticleServiceError = sqlx_err.into();
         assert!(matches!(article_err, ArticleServiceError::DatabaseError(_)));
     }
+
+    #[test]
+    fn test_parse_search_query_extracts_operators() {
+        let search = parse_search_query("feed:TechCrunch rust async is:unread");
+        assert_eq!(search.feed.as_deref(), Some("TechCrunch"));
+        assert_eq!(search.is_read, Some(false));
+        assert_eq!(search.terms, vec!["rust", "async"]);
+        assert_eq!(search.fts_query().as_deref(), Some("rust async"));
+
+        let search = parse_search_query("is:starred IS:READ after:2024-01-01 before:2024-02-01");
+        assert_eq!(search.is_starred, Some(true));
+        assert_eq!(search.is_read, Some(true));
+        assert_eq!(search.fts_query(), None);
+        assert_eq!(
+            search.date_from().unwrap().to_rfc3339(),
+            "2024-01-02T00:00:00+00:00"
+        );
+        assert_eq!(
+            search.date_to().unwrap().to_rfc3339(),
+            "2024-01-31T23:59:59+00:00"
+        );
+    }
+
+    #[test]
+    fn test_parse_search_query_handles_quoting() {
+        let search =
+            parse_search_query(r#"feed:"Hacker News" author:"Jane Doe" "exact phrase" "a:b""#);
+        assert_eq!(search.feed.as_deref(), Some("Hacker News"));
+        assert_eq!(search.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(search.terms, vec![r#""exact phrase""#, r#""a:b""#]);
+        assert_eq!(
+            search.fts_query().as_deref(),
+            Some(r#""exact phrase" "a:b" author:"Jane Doe""#)
+        );
+    }
+
+    #[test]
+    fn test_parse_search_query_keeps_unknown_operators_literal() {
+        let search = parse_search_query("lang:rust is:pinned before:yesterday feed: title:async");
+        assert_eq!(
+            search.terms,
+            vec![
+                r#""lang:rust""#,
+                r#""is:pinned""#,
+                r#""before:yesterday""#,
+                r#""feed:""#,
+                "title:async",
+            ]
+        );
+        assert_eq!(search.feed, None);
+        assert_eq!(search.before, None);
+        assert_eq!(search.is_read, None);
+    }
+
+    fn article_with_url(url: Option<&str>) -> Article {
+        let now = chrono::Utc::now();
+        Article {
+            id: 1,
+            feed_id: 1,
+            guid: "guid".to_string(),
+            title: " A title ".to_string(),
+            url: url.map(str::to_string),
+            content: None,
+            summary: Some("<p>Some <b>bold</b>\n  summary</p>".to_string()),
+            content_preview: None,
+            word_count: None,
+            author: None,
+            published_at: None,
+            is_read: false,
+            is_starred: false,
+            is_read_later: false,
+            og_image: None,
+            og_description: None,
+            og_site_name: None,
+            language: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_share_payload_strips_summary_and_falls_back_to_feed_title() {
+        let article = article_with_url(Some("https://example.com/post"));
+        let payload = share_payload(&article, "Example Feed").unwrap();
+
+        assert_eq!(payload.title, "A title");
+        assert_eq!(payload.url, "https://example.com/post");
+        assert_eq!(payload.excerpt.as_deref(), Some("Some bold summary"));
+        assert_eq!(payload.site_name.as_deref(), Some("Example Feed"));
+    }
+
+    #[test]
+    fn test_share_payload_requires_http_url() {
+        for url in [None, Some("javascript:alert(1)"), Some("/relative")] {
+            let article = article_with_url(url);
+            assert!(matches!(
+                share_payload(&article, "Feed"),
+                Err(ArticleServiceError::NotShareable)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_article_states_from_csv_and_json() {
+        let csv = "url,is_read,is_starred\nhttps://example.com/a?x=1,y=2,true,0\n\nhttps://example.com/b,no,YES\n";
+        let states = parse_article_states(csv).unwrap();
+        assert_eq!(states.len(), 2);
+        assert_eq!(states[0].url, "https://example.com/a?x=1,y=2");
+        assert!(states[0].is_read && !states[0].is_starred);
+        assert!(!states[1].is_read && states[1].is_starred);
+
+        let json = r#"[{"url": "https://example.com/a", "is_starred": true}]"#;
+        let states = parse_article_states(json).unwrap();
+        assert_eq!(
+            states,
+            vec![ImportedArticleState {
+                url: "https://example.com/a".to_string(),
+                is_read: false,
+                is_starred: true,
+            }]
+        );
+
+        assert!(parse_article_states("https://example.com/a").is_err());
+        assert!(parse_article_states("https://example.com/a,maybe,true").is_err());
+        assert!(parse_article_states("[{\"is_read\": true}]").is_err());
+    }
+
+    #[test]
+    fn test_word_count() {
+        let body = "<h1>Title</h1><p>One <em>two</em>\n three,  four.</p>\n<ul><li>five</li></ul>";
+        assert_eq!(word_count(body), 6);
+        assert_eq!(word_count("<p>  </p>"), 0);
+    }
+
+    #[test]
+    fn test_plain_text_excerpt_truncates() {
+        let excerpt = plain_text_excerpt(&"word ".repeat(100), SHARE_EXCERPT_CHARS);
+        assert_eq!(excerpt.chars().count(), SHARE_EXCERPT_CHARS);
+        assert!(excerpt.ends_with('…'));
+    }
+
+    #[test]
+    fn test_first_paragraph_skips_empty_ones() {
+        let html = "<h1>Title</h1><p> </p><p>First <em>real</em> one.</p><p>Second.</p>";
+        assert_eq!(
+            first_paragraph(html).as_deref(),
+            Some("<p>First <em>real</em> one.</p>")
+        );
+        assert_eq!(first_paragraph("<div>No paragraphs</div>"), None);
+    }
+
+    #[test]
+    fn test_start_of_day_is_local_midnight() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        assert_eq!(
+            start_of_day(date, Tz::Europe__Berlin).to_rfc3339(),
+            "2026-03-09T23:00:00+00:00"
+        );
+        assert_eq!(
+            start_of_day(date, Tz::UTC).to_rfc3339(),
+            "2026-03-10T00:00:00+00:00"
+        );
+
+        // Sao Paulo skipped from midnight to 01:00 when DST began in 2018
+        let dst_start = NaiveDate::from_ymd_opt(2018, 11, 4).unwrap();
+        assert_eq!(
+            start_of_day(dst_start, Tz::America__Sao_Paulo).to_rfc3339(),
+            "2018-11-04T03:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_group_digest_groups_by_feed_in_reading_order() {
+        let item = |title: &str, feed_id: i64, feed: &str| {
+            let mut article = article_with_url(Some("https://example.com/post"));
+            article.title = title.to_string();
+            article.feed_id = feed_id;
+            ArticleWithFeed {
+                article,
+                feed_title: feed.to_string(),
+                feed_color: "#000000".to_string(),
+                feed_muted: false,
+                feed_prefer_summary: false,
+                tags: Vec::new(),
+            }
+        };
+
+        // Newest first, as returned by the repository
+        let digest = group_digest(vec![
+            item("Third", 2, "beta"),
+            item("Second", 1, "Alpha"),
+            item("First", 2, "beta"),
+        ]);
+
+        assert_eq!(digest.len(), 2);
+        assert_eq!(digest[0].feed_title, "Alpha");
+        assert_eq!(digest[1].feed_title, "beta");
+        let titles: Vec<&str> = digest[1].entries.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, vec!["First", "Third"]);
+        assert_eq!(
+            digest[0].entries[0].excerpt.as_deref(),
+            Some("Some bold summary")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record_visit_rotates_after_gap() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let start = Utc::now();
+
+        record_visit(&pool, start).await.unwrap();
+        record_visit(&pool, start + chrono::Duration::minutes(10))
+            .await
+            .unwrap();
+        assert_eq!(last_visit(&pool).await.unwrap(), None);
+
+        record_visit(&pool, start + chrono::Duration::hours(2))
+            .await
+            .unwrap();
+        let last = last_visit(&pool).await.unwrap().unwrap();
+        assert_eq!(
+            last.timestamp(),
+            (start + chrono::Duration::minutes(10)).timestamp()
+        );
+    }
 }
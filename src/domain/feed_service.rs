@@ -1,7 +1,13 @@
-use crate::domain::models::{CreateFeed, Feed};
-use crate::infrastructure::{repository, scheduler, ssrf};
+use crate::config::{HostPolicy, NewFeedFetch};
+use crate::domain::models::{CreateFeed, Feed, FeedSettings, FeedValidation};
+use crate::infrastructure::insert_progress::InsertProgressStore;
+use crate::infrastructure::rss_fetcher::{FetchResult, RssFetcher, FORBIDDEN_CUSTOM_HEADERS};
+use crate::infrastructure::ssrf::{self, SsrfError};
+use crate::infrastructure::{repository, scheduler};
+use reqwest::header::{self, HeaderName, HeaderValue};
 use sqlx::SqlitePool;
 use thiserror::Error;
+use tokio::task::JoinHandle;
 
 #[derive(Error, Debug)]
 #[allow(dead_code)]
@@ -15,8 +21,9 @@ pub enum FeedServiceError {
     #[error("Invalid feed URL: {0}")]
     InvalidUrl(String),
 
+    /// Carries the id of the feed already subscribed under the URL, if found
     #[error("Duplicate feed URL")]
-    DuplicateUrl,
+    DuplicateUrl(Option<i64>),
 
     #[error("Feed fetch failed: {0}")]
     FetchError(String),
@@ -26,12 +33,27 @@ pub enum FeedServiceError {
 
     #[error("URL points to internal/private network")]
     SsrfBlocked,
+
+    #[error("Feeds from {0} are not allowed on this server")]
+    HostNotAllowed(String),
+
+    #[error("Invalid custom header: {0}")]
+    InvalidHeader(String),
+
+    #[error("Invalid feed setting: {0}")]
+    Validation(String),
+
+    #[error("Feed limit reached: at most {0} feeds can be subscribed")]
+    FeedLimitReached(i64),
 }
 
+/// Create a feed and fetch it right away. A homepage URL is replaced by the
+/// feed it advertises during that first fetch.
 pub async fn create_feed(
     pool: &SqlitePool,
     url: String,
     title: Option<String>,
+    settings: &NewFeedFetch,
 ) -> Result<Feed, FeedServiceError> {
     // Basic URL validation
     if !url.starts_with("http://") && !url.starts_with("https://") {
@@ -40,43 +62,123 @@ pub async fn create_feed(
         ));
     }
 
+    check_feed_limit(pool, settings.limits.max_feeds).await?;
+
     // SSRF protection: validate URL doesn't point to internal networks
-    if let Err(e) = ssrf::validate_url(&url) {
-        tracing::warn!("SSRF validation failed for URL {}: {}", url, e);
-        return Err(FeedServiceError::SsrfBlocked);
-    }
+    check_feed_url(&url, &settings.host_policy).await?;
 
     // Use provided title or default to URL temporarily
     // It will be updated from RSS feed metadata after fetching
     let feed_title = title.unwrap_or_else(|| url.clone());
 
     let create_feed = CreateFeed {
+        color: Some(derived_feed_color(&url)),
         url,
         title: feed_title,
         description: None,
+        fetch_frequency: None,
+        ignore_pattern: None,
     };
 
-    let feed = match repository::create_feed(pool, create_feed).await {
-        Ok(feed) => feed,
-        Err(sqlx::Error::Database(db_err)) if db_err.message().contains("UNIQUE constraint") => {
-            return Err(FeedServiceError::DuplicateUrl);
-        }
-        Err(e) => return Err(FeedServiceError::DatabaseError(e)),
-    };
+    let feed = insert_feed(pool, create_feed).await?;
 
     // Immediately fetch the feed to populate metadata and articles
     tracing::info!("Fetching new feed immediately: {}", feed.url);
-    match scheduler::fetch_single_feed(pool, &feed).await {
-        Ok(_) => {
+    fetch_new_feed(pool, &feed, settings, None).await
+}
+
+/// Create a feed and fetch it in a background task, so that adding a slow
+/// feed doesn't hold up the response. The first fetch also follows a
+/// homepage URL to the feed it advertises; the returned handle finishes once
+/// it is done. How many of the feed's articles are stored is reported to
+/// `progress`.
+pub async fn create_feed_in_background(
+    pool: &SqlitePool,
+    url: String,
+    title: Option<String>,
+    settings: NewFeedFetch,
+    progress: InsertProgressStore,
+) -> Result<(Feed, JoinHandle<()>), FeedServiceError> {
+    let feed = create_feed_deferred(
+        pool,
+        url,
+        title,
+        &FeedSettings::default(),
+        settings.limits.max_feeds,
+        &settings.host_policy,
+    )
+    .await?;
+
+    let pool = pool.clone();
+    let new_feed = feed.clone();
+    let task = tokio::spawn(async move {
+        tracing::info!("Fetching new feed in the background: {}", new_feed.url);
+        if let Err(e) = fetch_new_feed(&pool, &new_feed, &settings, Some(&progress)).await {
+            tracing::warn!("Failed to load new feed {}: {}", new_feed.url, e);
+        }
+    });
+
+    Ok((feed, task))
+}
+
+/// First fetch of a newly created feed, backfilling the archive of paged
+/// feeds. Returns the feed reloaded with the fetched metadata.
+async fn fetch_new_feed(
+    pool: &SqlitePool,
+    feed: &Feed,
+    settings: &NewFeedFetch,
+    progress: Option<&InsertProgressStore>,
+) -> Result<Feed, FeedServiceError> {
+    let archive_url = match scheduler::fetch_single_feed(
+        pool,
+        feed,
+        &settings.delays,
+        &settings.adaptive,
+        settings.reader_sanitization,
+        &settings.host_policy,
+        &settings.secret_key,
+        progress,
+    )
+    .await
+    {
+        Ok(scheduler::FetchSingleFeedResult::Updated { archive_url, .. }) => {
+            tracing::info!("Successfully fetched new feed: {}", feed.url);
+            archive_url
+        }
+        Ok(scheduler::FetchSingleFeedResult::NotModified) => {
             tracing::info!("Successfully fetched new feed: {}", feed.url);
+            None
         }
         Err(e) => {
             tracing::warn!("Failed to fetch new feed {}: {}", feed.url, e);
             // Don't fail the creation, just log the error
             // The feed is still created, it will be fetched by the scheduler later
+            None
         }
+    };
+
+    // Backfill older entries of paged feeds, only on subscription (not every
+    // fetch cycle), in the background like the first fetch of a deferred feed
+    let max_pages = settings.archive_backfill_pages;
+    if let Some(archive_url) = archive_url.filter(|_| max_pages > 0) {
+        let (pool, feed, settings) = (pool.clone(), feed.clone(), settings.clone());
+        tokio::spawn(async move {
+            scheduler::backfill_archive(
+                &pool,
+                &feed,
+                archive_url,
+                max_pages,
+                &settings.delays,
+                &settings.host_policy,
+                &settings.secret_key,
+            )
+            .await;
+            scheduler::enforce_article_limit(&pool, settings.limits.max_articles).await;
+        });
     }
 
+    scheduler::enforce_article_limit(pool, settings.limits.max_articles).await;
+
     // Reload feed from database to get updated metadata
     let updated_feed = repository::get_feed_by_id(pool, feed.id)
         .await?
@@ -85,6 +187,59 @@ pub async fn create_feed(
     Ok(updated_feed)
 }
 
+/// Check that a URL serves a parseable feed, following autodiscovery like
+/// `create_feed` does, without writing anything to the database
+pub async fn validate_feed_url(url: &str, host_policy: &HostPolicy) -> FeedValidation {
+    match try_validate_feed_url(url, host_policy).await {
+        Ok(validation) => validation,
+        Err(e) => FeedValidation {
+            error: Some(e.to_string()),
+            ..FeedValidation::default()
+        },
+    }
+}
+
+async fn try_validate_feed_url(
+    url: &str,
+    host_policy: &HostPolicy,
+) -> Result<FeedValidation, FeedServiceError> {
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(FeedServiceError::InvalidUrl(
+            "URL must start with http:// or https://".to_string(),
+        ));
+    }
+    check_feed_url(url, host_policy).await?;
+
+    // One request for a feed URL; a page is followed to the feed it advertises
+    let fetcher =
+        RssFetcher::new(host_policy).map_err(|e| FeedServiceError::FetchError(e.to_string()))?;
+    match fetcher.fetch_discovering(url, None, None, &[]).await {
+        Ok((FetchResult::Updated { feed, .. }, discovered_url)) => Ok(FeedValidation {
+            valid: true,
+            title: feed.title.map(|t| t.content),
+            entry_count: Some(feed.entries.len()),
+            discovered_url,
+            error: None,
+        }),
+        Ok((FetchResult::NotModified { .. }, _)) => Err(FeedServiceError::FetchError(
+            "Server answered Not Modified to an unconditional request".to_string(),
+        )),
+        Err(e) => Err(FeedServiceError::FetchError(e.to_string())),
+    }
+}
+
+/// Refuse feed URLs outside the host policy or pointing to internal networks
+async fn check_feed_url(url: &str, host_policy: &HostPolicy) -> Result<(), FeedServiceError> {
+    match ssrf::validate_feed_url(url, host_policy).await {
+        Ok(()) => Ok(()),
+        Err(SsrfError::HostNotAllowed(host)) => Err(FeedServiceError::HostNotAllowed(host)),
+        Err(e) => {
+            tracing::warn!("SSRF validation failed for URL {}: {}", url, e);
+            Err(FeedServiceError::SsrfBlocked)
+        }
+    }
+}
+
 /// Create a feed without immediately fetching it.
 /// The feed will be fetched by the background scheduler.
 /// This is useful for bulk imports where we want instant feedback.
@@ -92,6 +247,9 @@ pub async fn create_feed_deferred(
     pool: &SqlitePool,
     url: String,
     title: Option<String>,
+    settings: &FeedSettings,
+    max_feeds: Option<i64>,
+    host_policy: &HostPolicy,
 ) -> Result<Feed, FeedServiceError> {
     // Basic URL validation
     if !url.starts_with("http://") && !url.starts_with("https://") {
@@ -100,37 +258,94 @@ pub async fn create_feed_deferred(
         ));
     }
 
+    check_feed_limit(pool, max_feeds).await?;
+
     // SSRF protection: validate URL doesn't point to internal networks
-    if let Err(e) = ssrf::validate_url(&url) {
-        tracing::warn!("SSRF validation failed for URL {}: {}", url, e);
-        return Err(FeedServiceError::SsrfBlocked);
-    }
+    check_feed_url(&url, host_policy).await?;
 
     // Use provided title or default to URL temporarily
     // It will be updated from RSS feed metadata after fetching
     let feed_title = title.unwrap_or_else(|| url.clone());
 
     let create_feed = CreateFeed {
+        color: Some(
+            settings
+                .color
+                .clone()
+                .unwrap_or_else(|| derived_feed_color(&url)),
+        ),
         url,
         title: feed_title,
         description: None,
+        fetch_frequency: settings.fetch_frequency.clone(),
+        ignore_pattern: settings.ignore_pattern.clone(),
     };
 
-    let feed = match repository::create_feed(pool, create_feed).await {
-        Ok(feed) => feed,
-        Err(sqlx::Error::Database(db_err)) if db_err.message().contains("UNIQUE constraint") => {
-            return Err(FeedServiceError::DuplicateUrl);
-        }
-        Err(e) => return Err(FeedServiceError::DatabaseError(e)),
-    };
+    let feed = insert_feed(pool, create_feed).await?;
 
     tracing::info!("Created feed {} (deferred fetch): {}", feed.id, feed.url);
 
     Ok(feed)
 }
 
-pub async fn list_all_feeds(pool: &SqlitePool) -> Result<Vec<Feed>, FeedServiceError> {
-    Ok(repository::list_feeds(pool).await?)
+/// Store a new feed. A URL that is already subscribed fails with the
+/// existing feed's id, so the error can link to it.
+async fn insert_feed(pool: &SqlitePool, create_feed: CreateFeed) -> Result<Feed, FeedServiceError> {
+    let url = create_feed.url.clone();
+
+    match repository::create_feed(pool, create_feed).await {
+        Ok(feed) => Ok(feed),
+        Err(sqlx::Error::Database(db_err)) if db_err.message().contains("UNIQUE constraint") => {
+            let existing = repository::get_feed_by_url(pool, &url).await?;
+            Err(FeedServiceError::DuplicateUrl(existing.map(|feed| feed.id)))
+        }
+        Err(e) => Err(FeedServiceError::DatabaseError(e)),
+    }
+}
+
+/// Default color of a new feed, derived from its URL so that feeds are told
+/// apart at a glance without every one starting out the same blue
+pub fn derived_feed_color(url: &str) -> String {
+    // 64-bit FNV-1a, stable across builds unlike `DefaultHasher`
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in url.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+
+    // Vary only the hue; fixed saturation and lightness keep every color
+    // readable on both the light and the dark theme
+    let hue = (hash % 360) as f64;
+    let (saturation, lightness) = (0.65, 0.5);
+
+    let chroma = (1.0 - (2.0 * lightness - 1.0_f64).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 / 60 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let channel = |value: f64| ((value + m) * 255.0).round() as u8;
+
+    format!("#{:02X}{:02X}{:02X}", channel(r), channel(g), channel(b))
+}
+
+/// Refuse another feed once `max_feeds` are subscribed
+async fn check_feed_limit(
+    pool: &SqlitePool,
+    max_feeds: Option<i64>,
+) -> Result<(), FeedServiceError> {
+    if let Some(max_feeds) = max_feeds {
+        if repository::count_feeds(pool).await? >= max_feeds {
+            return Err(FeedServiceError::FeedLimitReached(max_feeds));
+        }
+    }
+
+    Ok(())
 }
 
 pub async fn delete_feed(pool: &SqlitePool, feed_id: i64) -> Result<(), FeedServiceError> {
@@ -162,9 +377,182 @@ pub fn parse_fetch_frequency(frequency: &str) -> Result<i64, FeedServiceError> {
     }
 }
 
+/// Identity of a feed URL for duplicate detection: scheme, default port,
+/// fragment and trailing slash are ignored, so `http://Example.com/feed/` and
+/// `https://example.com/feed` compare equal
+pub fn url_identity(url: &str) -> String {
+    let Ok(parsed) = url::Url::parse(url.trim()) else {
+        return url.trim().to_lowercase();
+    };
+
+    let host = parsed.host_str().unwrap_or_default();
+    let port = parsed.port().map(|p| format!(":{}", p)).unwrap_or_default();
+    let path = parsed.path().trim_end_matches('/');
+    let query = parsed
+        .query()
+        .map(|q| format!("?{}", q))
+        .unwrap_or_default();
+
+    format!("{}{}{}{}", host, port, path, query)
+}
+
+/// Parse and validate custom request headers, one `Name: Value` per line.
+/// Header names are normalized to lowercase; blank lines are ignored.
+pub fn parse_custom_headers(input: &str) -> Result<Vec<(String, String)>, FeedServiceError> {
+    let mut headers: Vec<(String, String)> = Vec::new();
+
+    for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let (name, value) = line.split_once(':').ok_or_else(|| {
+            FeedServiceError::InvalidHeader(format!("Expected 'Name: Value', got '{}'", line))
+        })?;
+
+        let name = HeaderName::from_bytes(name.trim().as_bytes())
+            .map_err(|_| FeedServiceError::InvalidHeader(format!("Invalid name '{}'", name)))?;
+        let value = value.trim();
+        HeaderValue::from_str(value).map_err(|_| {
+            FeedServiceError::InvalidHeader(format!("Invalid value for '{}'", name))
+        })?;
+
+        if FORBIDDEN_CUSTOM_HEADERS.contains(&name.as_str()) {
+            return Err(FeedServiceError::InvalidHeader(format!(
+                "'{}' cannot be overridden",
+                name
+            )));
+        }
+        // Tokens go in the feed's write-only token field, so they are never
+        // shown in the form again
+        if name == header::AUTHORIZATION {
+            return Err(FeedServiceError::InvalidHeader(
+                "Set bearer tokens in the token field instead of 'authorization'".to_string(),
+            ));
+        }
+        if headers.iter().any(|(n, _)| n == name.as_str()) {
+            return Err(FeedServiceError::InvalidHeader(format!(
+                "'{}' is specified more than once",
+                name
+            )));
+        }
+
+        headers.push((name.as_str().to_string(), value.to_string()));
+    }
+
+    Ok(headers)
+}
+
+/// Parse a bearer token from the edit form; `None` if left empty. A pasted
+/// `Bearer ` prefix is dropped.
+pub fn parse_bearer_token(input: &str) -> Result<Option<String>, FeedServiceError> {
+    let input = input.trim();
+    let token = input.strip_prefix("Bearer ").unwrap_or(input).trim();
+    if token.is_empty() {
+        return Ok(None);
+    }
+    if token.contains(char::is_whitespace) || HeaderValue::from_str(token).is_err() {
+        return Err(FeedServiceError::InvalidHeader(
+            "The bearer token contains characters not allowed in a header".to_string(),
+        ));
+    }
+    Ok(Some(token.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::StorageLimits;
+
+    #[tokio::test]
+    async fn test_create_feed_over_max_feeds_is_rejected() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        // The feed of saved pages doesn't count towards the limit
+        repository::get_or_create_saved_feed(&pool).await.unwrap();
+        repository::create_feed(
+            &pool,
+            CreateFeed {
+                url: "https://example.com/a.xml".to_string(),
+                title: "A".to_string(),
+                description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let settings = NewFeedFetch {
+            limits: StorageLimits {
+                max_feeds: Some(1),
+                max_articles: None,
+            },
+            ..NewFeedFetch::default()
+        };
+        let result = create_feed(
+            &pool,
+            "https://example.com/b.xml".to_string(),
+            None,
+            &settings,
+        )
+        .await;
+        assert!(matches!(result, Err(FeedServiceError::FeedLimitReached(1))));
+
+        let result = create_feed_deferred(
+            &pool,
+            "https://example.com/b.xml".to_string(),
+            None,
+            &FeedSettings::default(),
+            Some(1),
+            &HostPolicy::default(),
+        )
+        .await;
+        assert!(matches!(result, Err(FeedServiceError::FeedLimitReached(1))));
+        assert_eq!(repository::count_feeds(&pool).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_background_creation_returns_before_fetch() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let (feed, task) = create_feed_in_background(
+            &pool,
+            "http://93.184.215.14/feed.xml".to_string(),
+            None,
+            NewFeedFetch::default(),
+            InsertProgressStore::default(),
+        )
+        .await
+        .unwrap();
+
+        // The single-threaded test runtime hasn't polled the fetch task yet
+        assert!(!task.is_finished());
+        task.abort();
+        assert!(feed.last_fetched_at.is_none());
+        let stored = repository::get_feed_by_id(&pool, feed.id).await.unwrap();
+        assert_eq!(stored.unwrap().url, "http://93.184.215.14/feed.xml");
+    }
+
+    #[test]
+    fn test_derived_feed_color_is_distinct_and_stable() {
+        let a = derived_feed_color("https://example.com/feed.xml");
+        let b = derived_feed_color("https://blog.example.org/atom.xml");
+
+        assert_ne!(a, b);
+        assert_eq!(a, derived_feed_color("https://example.com/feed.xml"));
+        for color in [&a, &b] {
+            assert_eq!(color.len(), 7);
+            assert!(color.starts_with('#'));
+            assert!(color[1..].chars().all(|c| c.is_ascii_hexdigit()));
+        }
+    }
 
     #[test]
     fn test_parse_fetch_frequency_adaptive() {
@@ -200,6 +588,71 @@ mod tests {
         assert_eq!(parse_fetch_frequency("  12  ").unwrap(), 720);
     }
 
+    #[test]
+    fn test_parse_custom_headers_valid() {
+        let headers =
+            parse_custom_headers("Referer: https://example.com/\n\n  X-Api-Token:  abc123  \n")
+                .unwrap();
+        assert_eq!(
+            headers,
+            vec![
+                ("referer".to_string(), "https://example.com/".to_string()),
+                ("x-api-token".to_string(), "abc123".to_string()),
+            ]
+        );
+        assert!(parse_custom_headers("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_bearer_token() {
+        assert_eq!(parse_bearer_token("  ").unwrap(), None);
+        assert_eq!(
+            parse_bearer_token(" abc.def ").unwrap(),
+            Some("abc.def".to_string())
+        );
+        assert_eq!(
+            parse_bearer_token("Bearer abc").unwrap(),
+            Some("abc".to_string())
+        );
+        assert!(parse_bearer_token("abc def").is_err());
+        assert!(parse_bearer_token("abc\u{7f}").is_err());
+    }
+
+    #[test]
+    fn test_parse_custom_headers_invalid() {
+        assert!(parse_custom_headers("no colon here").is_err());
+        assert!(parse_custom_headers("Bad Name: value").is_err());
+        assert!(parse_custom_headers("X-Token: line\u{7f}break").is_err());
+        assert!(parse_custom_headers("Host: evil.example").is_err());
+        assert!(parse_custom_headers("Transfer-Encoding: chunked").is_err());
+        assert!(parse_custom_headers("X-A: 1\nx-a: 2").is_err());
+        assert!(parse_custom_headers("Authorization: Bearer abc").is_err());
+    }
+
+    #[test]
+    fn test_url_identity_ignores_scheme_and_trailing_slash() {
+        assert_eq!(
+            url_identity("http://Example.com/feed/"),
+            url_identity("https://example.com/feed")
+        );
+        assert_eq!(
+            url_identity("https://example.com:443/feed#top"),
+            url_identity("https://example.com/feed")
+        );
+    }
+
+    #[test]
+    fn test_url_identity_keeps_distinct_feeds_apart() {
+        assert_ne!(
+            url_identity("https://example.com/feed?lang=en"),
+            url_identity("https://example.com/feed?lang=de")
+        );
+        assert_ne!(
+            url_identity("https://example.com:8080/feed"),
+            url_identity("https://example.com/feed")
+        );
+    }
+
     #[test]
     fn test_feed_service_error_display() {
         let err = FeedServiceError::InvalidUrl("bad url".to_string());
@@ -208,7 +661,7 @@ mod tests {
         let err = FeedServiceError::NotFound;
         assert_eq!(err.to_string(), "Feed not found");
 
-        let err = FeedServiceError::DuplicateUrl;
+        let err = FeedServiceError::DuplicateUrl(Some(1));
         assert_eq!(err.to_string(), "Duplicate feed URL");
 
         let err = FeedServiceError::InvalidFrequency;
@@ -2,9 +2,19 @@ use crate::domain::models::{
     Feed, FeedWithUnread, FlatTreeItem, Group, GroupNode, GroupNodeWithUnread,
 };
 use crate::infrastructure::repository;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use rand::RngCore;
 use sqlx::SqlitePool;
 use std::collections::HashMap;
 
+/// Number of random bytes in a group share token
+const SHARE_TOKEN_LENGTH: usize = 32;
+
+/// Pinned feeds first, then by title
+fn feed_order(a: &Feed, b: &Feed) -> std::cmp::Ordering {
+    b.pinned.cmp(&a.pinned).then_with(|| a.title.cmp(&b.title))
+}
+
 /// Build a hierarchical tree from flat lists of groups and feeds
 pub fn build_group_tree(groups: Vec<Group>, feeds: Vec<Feed>) -> (Vec<GroupNode>, Vec<Feed>) {
     // Group feeds by group_id
@@ -63,7 +73,7 @@ pub fn build_group_tree(groups: Vec<Group>, feeds: Vec<Feed>) -> (Vec<GroupNode>
                 .cmp(&b.group.position)
                 .then_with(|| a.group.name.cmp(&b.group.name))
         });
-        node.feeds.sort_by(|a, b| a.title.cmp(&b.title));
+        node.feeds.sort_by(feed_order);
         for child in &mut node.children {
             sort_children(child);
         }
@@ -75,7 +85,7 @@ pub fn build_group_tree(groups: Vec<Group>, feeds: Vec<Feed>) -> (Vec<GroupNode>
 
     // Get ungrouped feeds (those with group_id = None)
     let mut ungrouped = feeds_by_group.remove(&None).unwrap_or_default();
-    ungrouped.sort_by(|a, b| a.title.cmp(&b.title));
+    ungrouped.sort_by(feed_order);
 
     (root_groups, ungrouped)
 }
@@ -91,6 +101,11 @@ pub fn flatten_group_tree(tree: &[GroupNode]) -> Vec<FlatTreeItem> {
             depth,
         });
 
+        // Descendants of a collapsed group stay hidden
+        if node.group.collapsed {
+            return;
+        }
+
         // Add feeds in this group
         for feed in &node.feeds {
             items.push(FlatTreeItem::Feed {
@@ -169,6 +184,32 @@ pub fn add_unread_counts_to_feeds(
         .collect()
 }
 
+/// Reorder feeds so those with the most unread articles come first, then by
+/// title; pinned feeds stay ahead of the others
+pub fn sort_feeds_by_unread(tree: &mut [GroupNodeWithUnread], ungrouped: &mut [FeedWithUnread]) {
+    fn sort_feeds(feeds: &mut [FeedWithUnread]) {
+        feeds.sort_by(|a, b| {
+            b.feed
+                .pinned
+                .cmp(&a.feed.pinned)
+                .then_with(|| b.unread_count.cmp(&a.unread_count))
+                .then_with(|| a.feed.title.cmp(&b.feed.title))
+        });
+    }
+
+    fn sort_node(node: &mut GroupNodeWithUnread) {
+        sort_feeds(&mut node.feeds);
+        for child in &mut node.children {
+            sort_node(child);
+        }
+    }
+
+    for node in tree {
+        sort_node(node);
+    }
+    sort_feeds(ungrouped);
+}
+
 /// Resolve selected groups and feeds to a list of feed IDs
 /// Groups are expanded recursively to include all descendant feeds
 pub async fn resolve_selection_to_feed_ids(
@@ -191,6 +232,13 @@ pub async fn resolve_selection_to_feed_ids(
     Ok(all_feed_ids)
 }
 
+/// Generate an unguessable, URL-safe token for a shared group link
+pub fn generate_share_token() -> String {
+    let mut bytes = [0u8; SHARE_TOKEN_LENGTH];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -202,6 +250,7 @@ mod tests {
             name: name.to_string(),
             parent_id,
             position,
+            collapsed: false,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
@@ -222,10 +271,25 @@ mod tests {
             color: "#3B82F6".to_string(),
             fetch_frequency: "adaptive".to_string(),
             ttl_minutes: None,
+            ttl_from_headers: false,
             consecutive_new_articles: 0,
             ignore_pattern: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            consecutive_failures: 0,
+            next_retry_at: None,
+            never_trim: false,
+            auto_fetch_full_content: false,
+            title_is_custom: false,
+            subscribed_at: Utc::now(),
+            muted: false,
+            language: None,
+            guid_strategy: "auto".to_string(),
+            previous_guid_strategy: None,
+            prefer_summary: false,
+            mark_new_as_read: false,
+            pinned: false,
+            quiet_hours: None,
         }
     }
 
@@ -299,4 +363,72 @@ mod tests {
         assert_eq!(ungrouped[0].title, "Another Blog");
         assert_eq!(ungrouped[1].title, "Random Blog");
     }
+
+    #[test]
+    fn test_flatten_group_tree_skips_collapsed_children() {
+        let mut tech = make_group(1, "Tech", None, 0);
+        tech.collapsed = true;
+        let groups = vec![
+            tech,
+            make_group(2, "Tech News", Some(1), 0),
+            make_group(3, "Science", None, 1),
+        ];
+        let feeds = vec![
+            make_feed(1, "TechCrunch", Some(1)),
+            make_feed(2, "Ars Technica", Some(2)),
+            make_feed(3, "Nature", Some(3)),
+        ];
+
+        let (tree, _) = build_group_tree(groups, feeds);
+        let items = flatten_group_tree(&tree);
+
+        let names: Vec<&str> = items
+            .iter()
+            .map(|item| match item {
+                FlatTreeItem::Group { group, .. } => group.name.as_str(),
+                FlatTreeItem::Feed { feed, .. } => feed.title.as_str(),
+            })
+            .collect();
+        assert_eq!(names, vec!["Tech", "Science", "Nature"]);
+    }
+
+    #[test]
+    fn test_sort_feeds_by_unread() {
+        let groups = vec![make_group(1, "Tech", None, 0)];
+        let feeds = vec![
+            make_feed(1, "Ars Technica", Some(1)),
+            make_feed(2, "TechCrunch", Some(1)),
+            make_feed(3, "Another Blog", None),
+            make_feed(4, "Random Blog", None),
+        ];
+        let unread_counts = HashMap::from([(2, 5), (4, 1)]);
+
+        let (tree, ungrouped) = build_group_tree(groups, feeds);
+        let mut tree = add_unread_counts_to_tree(tree, &unread_counts);
+        let mut ungrouped = add_unread_counts_to_feeds(ungrouped, &unread_counts);
+        sort_feeds_by_unread(&mut tree, &mut ungrouped);
+
+        assert_eq!(tree[0].feeds[0].feed.title, "TechCrunch");
+        assert_eq!(tree[0].feeds[1].feed.title, "Ars Technica");
+        assert_eq!(ungrouped[0].feed.title, "Random Blog");
+        assert_eq!(ungrouped[1].feed.title, "Another Blog");
+    }
+
+    #[test]
+    fn test_pinned_feed_sorts_first_in_its_group() {
+        let groups = vec![make_group(1, "Tech", None, 0)];
+        let mut pinned = make_feed(2, "Zed's Blog", Some(1));
+        pinned.pinned = true;
+        let feeds = vec![make_feed(1, "Ars Technica", Some(1)), pinned];
+        let unread_counts = HashMap::from([(1, 3)]);
+
+        let (tree, _) = build_group_tree(groups, feeds);
+        let titles: Vec<_> = tree[0].feeds.iter().map(|f| f.title.as_str()).collect();
+        assert_eq!(titles, vec!["Zed's Blog", "Ars Technica"]);
+
+        // Also ahead of feeds with more unread articles
+        let mut tree = add_unread_counts_to_tree(tree, &unread_counts);
+        sort_feeds_by_unread(&mut tree, &mut []);
+        assert_eq!(tree[0].feeds[0].feed.title, "Zed's Blog");
+    }
 }
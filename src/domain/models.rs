@@ -16,11 +16,261 @@ pub struct Feed {
     pub fetch_interval_minutes: i64,
     pub color: String,
     pub fetch_frequency: String,
+    /// Refresh interval the feed announces. A `<ttl>` is shown for
+    /// information only: the fetch interval is adaptive or chosen by the user
     pub ttl_minutes: Option<i64>,
+    /// `ttl_minutes` came from the caching headers, as the feed has no
+    /// `<ttl>`; such a hint also defers the next fetch (`next_retry_at`)
+    pub ttl_from_headers: bool,
     pub consecutive_new_articles: i64,
     pub ignore_pattern: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Failed fetches in a row, reset by the next successful fetch
+    pub consecutive_failures: i64,
+    /// Earliest time of the next fetch while backing off from transient
+    /// errors, or while the server's cached copy is still fresh
+    pub next_retry_at: Option<DateTime<Utc>>,
+    /// Exempt the feed's articles from trimming, whatever the global policy
+    pub never_trim: bool,
+    /// Replace the content of new articles with the readable text of their page
+    pub auto_fetch_full_content: bool,
+    /// The title was set by the user and is no longer taken from the feed
+    pub title_is_custom: bool,
+    /// Set once when the feed is added; fetches and edits leave it alone
+    pub subscribed_at: DateTime<Utc>,
+    /// Hidden from the unfiltered article list and the unread count, but
+    /// still fetched
+    pub muted: bool,
+    /// Language tag declared by the feed, e.g. `en-US`
+    pub language: Option<String>,
+    /// Entry field that identifies an article, see [`GuidStrategy`]
+    pub guid_strategy: String,
+    /// Strategy the stored GUIDs were made with, until the next fetch has
+    /// moved them over to `guid_strategy`
+    pub previous_guid_strategy: Option<String>,
+    /// Show the summary rather than the content as the article body
+    pub prefer_summary: bool,
+    /// Store new articles as already read
+    pub mark_new_as_read: bool,
+    /// Listed before the other feeds of its group
+    pub pinned: bool,
+    /// Daily window without fetches, `HH:MM-HH:MM` in the display time zone
+    pub quiet_hours: Option<String>,
+}
+
+/// Refresh interval a feed announces in minutes, from its `<ttl>` element or
+/// else from the caching headers of the response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeedTtl {
+    pub minutes: i64,
+    pub from_headers: bool,
+}
+
+/// Which field of a feed entry becomes the article's GUID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GuidStrategy {
+    /// Entry id, else link and title, else title and date
+    #[default]
+    Auto,
+    /// Entry link, for feeds whose ids change between fetches
+    Link,
+    /// Entry id
+    Id,
+    /// Title and publication date
+    TitleDate,
+}
+
+impl GuidStrategy {
+    pub const ALL: [GuidStrategy; 4] = [
+        GuidStrategy::Auto,
+        GuidStrategy::Link,
+        GuidStrategy::Id,
+        GuidStrategy::TitleDate,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|strategy| strategy.as_str() == value.trim())
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GuidStrategy::Auto => "auto",
+            GuidStrategy::Link => "link",
+            GuidStrategy::Id => "id",
+            GuidStrategy::TitleDate => "title_date",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GuidStrategy::Auto => "Automatic (id, then link, then title and date)",
+            GuidStrategy::Link => "Link",
+            GuidStrategy::Id => "Entry id",
+            GuidStrategy::TitleDate => "Title and date",
+        }
+    }
+}
+
+impl Feed {
+    /// The feed's GUID strategy, `auto` if the stored value is unknown
+    pub fn guid_strategy(&self) -> GuidStrategy {
+        GuidStrategy::parse(&self.guid_strategy).unwrap_or_default()
+    }
+
+    /// The stored TTL with its source
+    pub fn ttl(&self) -> Option<FeedTtl> {
+        self.ttl_minutes.map(|minutes| FeedTtl {
+            minutes,
+            from_headers: self.ttl_from_headers,
+        })
+    }
+
+    /// The strategy stored GUIDs still have to be moved away from, if the
+    /// strategy changed since the last fetch
+    pub fn pending_guid_remap(&self) -> Option<GuidStrategy> {
+        self.previous_guid_strategy
+            .as_deref()
+            .and_then(GuidStrategy::parse)
+            .filter(|previous| *previous != self.guid_strategy())
+    }
+}
+
+/// Filters of the paginated feed list
+#[derive(Debug, Clone, Default)]
+pub struct FeedListFilter {
+    /// Substring of the feed's title or URL
+    pub search: Option<String>,
+    /// Only feeds placed directly in this group
+    pub group_id: Option<i64>,
+}
+
+/// Filters of an article listing, with groups already resolved to feeds.
+/// `None` leaves the corresponding condition out.
+#[derive(Debug, Clone, Default)]
+pub struct ArticleListFilter {
+    pub feed_ids: Option<Vec<i64>>,
+    /// Articles tagged with any of these, directly or through their feed
+    pub tag_ids: Vec<i64>,
+    pub is_read: Option<bool>,
+    pub is_starred: Option<bool>,
+    pub is_read_later: Option<bool>,
+    /// FTS5 match expression
+    pub search_query: Option<String>,
+    pub date_from: Option<DateTime<Utc>>,
+    pub date_to: Option<DateTime<Utc>>,
+    /// Only articles stored after this instant
+    pub created_after: Option<DateTime<Utc>>,
+    /// Language tag; a primary language also matches its regional variants
+    pub language: Option<String>,
+}
+
+/// Feed summary with article counts and last fetch status, served by the JSON API
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct FeedStats {
+    pub id: i64,
+    pub title: String,
+    pub url: String,
+    pub site_url: Option<String>,
+    pub group_id: Option<i64>,
+    pub language: Option<String>,
+    pub total_articles: i64,
+    pub unread: i64,
+    pub last_fetched_at: Option<DateTime<Utc>>,
+    /// Log type of the most recent fetch (e.g. "success", "not_modified", "error",
+    /// "auth_failed")
+    pub last_status: Option<String>,
+}
+
+/// Feed with its most recent fetch log, for the diagnostics page
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct FeedDiagnostic {
+    pub id: i64,
+    pub title: String,
+    pub url: String,
+    pub last_fetched_at: Option<DateTime<Utc>>,
+    /// Log type of the most recent fetch
+    pub last_status: Option<String>,
+    pub last_error: Option<String>,
+    pub last_logged_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedHealth {
+    /// Never fetched successfully and no error logged yet
+    Pending,
+    Healthy,
+    /// The most recent fetch failed
+    Failing,
+}
+
+impl FeedDiagnostic {
+    pub fn health(&self) -> FeedHealth {
+        match self.last_status.as_deref() {
+            Some("error") | Some("rate_limited") | Some("auth_failed") => FeedHealth::Failing,
+            _ if self.last_fetched_at.is_none() => FeedHealth::Pending,
+            _ => FeedHealth::Healthy,
+        }
+    }
+}
+
+/// Title, URL and excerpt of an article for share buttons and bookmarklets
+#[derive(Debug, Clone, Serialize)]
+pub struct SharePayload {
+    pub title: String,
+    pub url: String,
+    pub excerpt: Option<String>,
+    pub site_name: Option<String>,
+}
+
+/// Outcome of a dry-run subscription, served by the JSON API
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FeedValidation {
+    pub valid: bool,
+    pub title: Option<String>,
+    pub entry_count: Option<usize>,
+    /// Feed URL advertised by the given HTML page, if it was one
+    pub discovered_url: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Read and starred flags of an article as exported by another reader
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ImportedArticleState {
+    pub url: String,
+    #[serde(default)]
+    pub is_read: bool,
+    #[serde(default)]
+    pub is_starred: bool,
+}
+
+/// Outcome of importing read/starred states from another reader
+#[derive(Debug, Clone, Default)]
+pub struct ArticleStateImport {
+    /// Number of imported states that matched a stored article
+    pub applied: usize,
+    /// URLs of imported states that matched no stored article
+    pub unmatched: Vec<String>,
+}
+
+/// One feed's section of the daily digest
+#[derive(Debug, Clone)]
+pub struct DigestFeed {
+    pub feed_id: i64,
+    pub feed_title: String,
+    pub feed_color: String,
+    pub entries: Vec<DigestEntry>,
+}
+
+/// An article as listed in the daily digest
+#[derive(Debug, Clone)]
+pub struct DigestEntry {
+    pub title: String,
+    pub url: Option<String>,
+    pub excerpt: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +278,21 @@ pub struct CreateFeed {
     pub url: String,
     pub title: String,
     pub description: Option<String>,
+    /// `None` keeps the column's default color
+    pub color: Option<String>,
+    /// Fetch frequency and its interval in minutes; `None` fetches adaptively
+    pub fetch_frequency: Option<(String, i64)>,
+    pub ignore_pattern: Option<String>,
+}
+
+/// Settings a new feed starts out with, e.g. those restored from an OPML
+/// export; `None` leaves a setting at its default
+#[derive(Debug, Clone, Default)]
+pub struct FeedSettings {
+    /// The stored frequency and its interval in minutes
+    pub fetch_frequency: Option<(String, i64)>,
+    pub color: Option<String>,
+    pub ignore_pattern: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -40,6 +305,62 @@ pub struct NewArticle {
     pub summary: Option<String>,
     pub author: Option<String>,
     pub published_at: Option<DateTime<Utc>>,
+    /// The entry's `updated` timestamp as sent by the feed
+    pub updated_at_source: Option<DateTime<Utc>>,
+    pub og_image: Option<String>,
+    pub og_description: Option<String>,
+    pub og_site_name: Option<String>,
+    /// Language declared by the entry; the feed's language is used otherwise
+    pub language: Option<String>,
+}
+
+impl NewArticle {
+    /// Fingerprint of the title and text as sent by the feed (64-bit FNV-1a,
+    /// hex), stable across builds so stored hashes stay comparable
+    pub fn content_hash(&self) -> String {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let fields = [
+            Some(self.title.as_str()),
+            self.content.as_deref(),
+            self.summary.as_deref(),
+        ];
+        for field in fields {
+            for byte in field.unwrap_or_default().bytes().chain([0]) {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+        format!("{:016x}", hash)
+    }
+
+    /// Plain-text preview for the article lists, from the summary or else
+    /// the content; `None` if neither has any text
+    pub fn content_preview(&self) -> Option<String> {
+        self.summary
+            .as_deref()
+            .or(self.content.as_deref())
+            .map(|html| {
+                crate::domain::article_service::plain_text_excerpt(
+                    html,
+                    crate::domain::article_service::CONTENT_PREVIEW_CHARS,
+                )
+            })
+            .filter(|preview| !preview.is_empty())
+    }
+
+    /// Words in the content, or in the summary for summary-only entries
+    pub fn word_count(&self) -> Option<i64> {
+        self.content
+            .as_deref()
+            .or(self.summary.as_deref())
+            .map(crate::domain::article_service::word_count)
+    }
+}
+
+/// OpenGraph metadata found for an existing article
+#[derive(Debug, Clone)]
+pub struct OpenGraphUpdate {
+    pub article_id: i64,
     pub og_image: Option<String>,
     pub og_description: Option<String>,
     pub og_site_name: Option<String>,
@@ -54,13 +375,20 @@ pub struct Article {
     pub url: Option<String>,
     pub content: Option<String>,
     pub summary: Option<String>,
+    /// Plain-text excerpt of the summary or content, shown in the list views
+    pub content_preview: Option<String>,
+    /// Words in the content, or in the summary for summary-only entries
+    pub word_count: Option<i64>,
     pub author: Option<String>,
     pub published_at: Option<DateTime<Utc>>,
     pub is_read: bool,
     pub is_starred: bool,
+    pub is_read_later: bool,
     pub og_image: Option<String>,
     pub og_description: Option<String>,
     pub og_site_name: Option<String>,
+    /// Language tag of the entry or its feed, e.g. `en-US`
+    pub language: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -82,16 +410,42 @@ pub struct FeedTag {
     pub created_at: DateTime<Utc>,
 }
 
+/// Custom HTTP header sent when fetching a feed
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FeedHeader {
+    pub feed_id: i64,
+    pub name: String,
+    pub value: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Group {
     pub id: i64,
     pub name: String,
     pub parent_id: Option<i64>,
     pub position: i64,
+    pub collapsed: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Unguessable token granting read-only access to a group's articles
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ShareToken {
+    pub id: i64,
+    pub token: String,
+    pub group_id: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Unguessable token granting read access to the Atom feed of unread articles
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UnreadFeedToken {
+    pub id: i64,
+    pub token: String,
+    pub created_at: DateTime<Utc>,
+}
+
 /// Represents a group with its children for tree rendering
 #[derive(Debug, Clone, Serialize)]
 pub struct GroupNode {
@@ -132,6 +486,8 @@ pub struct Log {
     pub status_code: Option<i32>,
     pub error_message: Option<String>,
     pub retry_after: Option<String>,
+    pub duration_ms: Option<i64>,
+    pub entry_count: Option<i64>,
     pub fetched_at: DateTime<Utc>,
 }
 
@@ -142,6 +498,77 @@ pub struct LogWithFeed {
     pub feed_url: String,
 }
 
+/// Color scheme of the web interface
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Theme {
+    /// Follow the browser's light or dark preference
+    #[default]
+    Auto,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Auto, Theme::Light, Theme::Dark];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|theme| theme.as_str() == value.trim())
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Theme::Auto => "auto",
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Theme::Auto => "Auto",
+            Theme::Light => "Light",
+            Theme::Dark => "Dark",
+        }
+    }
+}
+
+/// Layout of the article list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ViewMode {
+    #[default]
+    Cards,
+    Compact,
+    Fullscreen,
+}
+
+impl ViewMode {
+    pub const ALL: [ViewMode; 3] = [ViewMode::Cards, ViewMode::Compact, ViewMode::Fullscreen];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|view_mode| view_mode.as_str() == value.trim())
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ViewMode::Cards => "cards",
+            ViewMode::Compact => "compact",
+            ViewMode::Fullscreen => "fullscreen",
+        }
+    }
+}
+
+/// Preferences stored server-side, so they are the same on every device
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Preferences {
+    pub theme: Theme,
+    /// Article list layout when the URL asks for none
+    pub default_view_mode: ViewMode,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,6 +579,9 @@ mod tests {
             url: "https://example.com/feed".to_string(),
             title: "Test Feed".to_string(),
             description: Some("A test feed".to_string()),
+            color: None,
+            fetch_frequency: None,
+            ignore_pattern: None,
         };
 
         let json = serde_json::to_string(&feed).unwrap();
@@ -168,6 +598,9 @@ mod tests {
             url: "https://example.com/feed".to_string(),
             title: "Test Feed".to_string(),
             description: None,
+            color: None,
+            fetch_frequency: None,
+            ignore_pattern: None,
         };
 
         assert_eq!(feed.url, "https://example.com/feed");
@@ -1,7 +1,12 @@
-use crate::domain::models::Article;
-use crate::infrastructure::repository;
+use crate::config::ReaderSanitization;
+use crate::domain::models::{Article, NewArticle};
+use crate::infrastructure::response_body::{self, BodyError};
+use crate::infrastructure::{repository, rss_fetcher, ssrf};
+use dom_query::{Document, Selection};
 use dom_smoothie::Readability;
 use sqlx::SqlitePool;
+use std::sync::OnceLock;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -20,24 +25,56 @@ pub enum ReaderServiceError {
 
     #[error("Failed to extract readable content")]
     ExtractionFailed,
+
+    #[error("URL blocked: points to internal/private network")]
+    SsrfBlocked,
+
+    #[error("Page is larger than {MAX_PAGE_BYTES} bytes")]
+    PageTooLarge,
 }
 
+/// Largest article page read for reader mode, full content or saving
+const MAX_PAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Redirects followed for an article page, each passing the SSRF checks
+const MAX_REDIRECTS: usize = 5;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Lazy-loader attributes holding the real image source, and the attribute they replace
+const LAZY_IMAGE_ATTRS: &[(&str, &str)] = &[("data-src", "src"), ("data-srcset", "srcset")];
+
+/// Page containers shown, in order of preference, when readability finds no article
+const FALLBACK_CONTAINERS: &[&str] = &["article", "main", "body"];
+
+/// Tags the strict reader policy removes on top of the feed content allowlist
+const STRICT_REMOVED_TAGS: &[&str] = &["img", "map", "area"];
+
+#[derive(Clone)]
 pub struct ReaderContent {
     pub article: Article,
     pub title: String,
     pub content: String,
     pub byline: Option<String>,
     pub excerpt: Option<String>,
+    /// Content is the sanitized page container because readability failed
+    pub degraded: bool,
+    /// Language declared by the article's feed
+    pub language: Option<String>,
 }
 
 pub async fn get_reader_content(
     pool: &SqlitePool,
     article_id: i64,
+    level: ReaderSanitization,
 ) -> Result<ReaderContent, ReaderServiceError> {
     // Get the article from database
     let article = repository::get_article_by_id(pool, article_id)
         .await?
         .ok_or(ReaderServiceError::NotFound)?;
+    let language = repository::get_feed_by_id(pool, article.feed_id)
+        .await?
+        .and_then(|feed| feed.language);
 
     // Get the article URL
     let article_url = article
@@ -45,24 +82,425 @@ pub async fn get_reader_content(
         .as_ref()
         .ok_or(ReaderServiceError::ExtractionFailed)?;
 
-    // Fetch the article content from the URL
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
-        .user_agent(crate::user_agent())
-        .build()?;
-
-    let response = client.get(article_url).send().await?;
-    let html = response.text().await?;
+    let html = fetch_article_page(article_url).await?;
 
     // Process with dom_smoothie
-    let mut readability = Readability::new(html, Some(article_url.as_str()), None)?;
-    let article_content = readability.parse()?;
+    let article_content = match parse_readable(&html, article_url) {
+        Ok(article_content) => article_content,
+        Err(e) => {
+            let content = fallback_content(&html, level).ok_or(e)?;
+            tracing::warn!(
+                "Readability failed for {}, showing the page container instead",
+                article_url
+            );
+            return Ok(ReaderContent {
+                title: article.title.clone(),
+                article,
+                content,
+                byline: None,
+                excerpt: None,
+                degraded: true,
+                language,
+            });
+        }
+    };
 
     Ok(ReaderContent {
         article: article.clone(),
         title: article_content.title,
-        content: article_content.content.to_string(),
+        content: clean_reader_html(&article_content.content, level),
         byline: article_content.byline,
         excerpt: article_content.excerpt,
+        degraded: false,
+        language,
+    })
+}
+
+/// Fetch an article's page and extract its readable content, without the
+/// degraded fallback of reader mode
+pub async fn fetch_full_content(
+    url: &str,
+    level: ReaderSanitization,
+) -> Result<String, ReaderServiceError> {
+    let html = fetch_article_page(url).await?;
+    extract_full_content(&html, url, level)
+}
+
+/// The sanitized readable content of an article page
+pub fn extract_full_content(
+    html: &str,
+    url: &str,
+    level: ReaderSanitization,
+) -> Result<String, ReaderServiceError> {
+    let article_content = parse_readable(html, url)?;
+    Ok(clean_reader_html(&article_content.content, level))
+}
+
+/// Fetch a page that isn't part of any feed and store its readable content
+/// as an article of the Saved feed. Saving the same URL again returns the
+/// stored article.
+pub async fn save_page(
+    pool: &SqlitePool,
+    url: &str,
+    level: ReaderSanitization,
+) -> Result<Article, ReaderServiceError> {
+    let html = fetch_article_page(url).await?;
+    store_saved_page(pool, url, &html, level).await
+}
+
+async fn store_saved_page(
+    pool: &SqlitePool,
+    url: &str,
+    html: &str,
+    level: ReaderSanitization,
+) -> Result<Article, ReaderServiceError> {
+    let mut article = saved_page_article(html, url, level)?;
+    let feed = repository::get_or_create_saved_feed(pool).await?;
+    article.feed_id = feed.id;
+
+    match repository::insert_article_if_new(pool, article).await? {
+        Some(article) => Ok(article),
+        None => repository::get_article_by_guid(pool, feed.id, url)
+            .await?
+            .ok_or(ReaderServiceError::NotFound),
+    }
+}
+
+/// Build the article for a saved page; the feed is filled in by the caller
+fn saved_page_article(
+    html: &str,
+    url: &str,
+    level: ReaderSanitization,
+) -> Result<NewArticle, ReaderServiceError> {
+    let page = parse_readable(html, url)?;
+    let title = match page.title.trim() {
+        "" => url.to_string(),
+        title => title.to_string(),
+    };
+
+    Ok(NewArticle {
+        feed_id: 0,
+        guid: url.to_string(),
+        title,
+        url: Some(url.to_string()),
+        content: Some(clean_reader_html(&page.content, level)),
+        summary: page.excerpt,
+        author: page.byline,
+        published_at: Some(chrono::Utc::now()),
+        updated_at_source: None,
+        og_image: page.image,
+        og_description: None,
+        og_site_name: page.site_name,
+        language: None,
+    })
+}
+
+/// Client shared by all article page fetches, built on first use
+fn client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        let builder = reqwest::Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .user_agent(crate::user_agent());
+        ssrf::protect(builder, MAX_REDIRECTS, ssrf::validate_redirect)
+            .build()
+            .expect("Failed to create article page client")
+    })
+}
+
+/// Download the HTML of an article page, refusing internal addresses (also
+/// as redirect targets) and pages over `MAX_PAGE_BYTES`
+async fn fetch_article_page(url: &str) -> Result<String, ReaderServiceError> {
+    // SSRF protection: article URLs come from the feed and are only checked here
+    if let Err(e) = ssrf::validate_url(url).await {
+        tracing::warn!("SSRF validation failed for article URL {}: {}", url, e);
+        return Err(ReaderServiceError::SsrfBlocked);
+    }
+
+    let response = match client().get(url).send().await {
+        Ok(response) => response,
+        Err(e) if ssrf::blocked_by(&e).is_some() => {
+            tracing::warn!("Request for article URL {} was blocked: {}", url, e);
+            return Err(ReaderServiceError::SsrfBlocked);
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let charset = rss_fetcher::header_charset(response.headers());
+    let body = response_body::read_capped(response, MAX_PAGE_BYTES)
+        .await
+        .map_err(|e| match e {
+            BodyError::TooLarge => ReaderServiceError::PageTooLarge,
+            BodyError::Request(e) => ReaderServiceError::HttpError(e),
+        })?;
+
+    let body = match charset {
+        Some(encoding) => encoding.decode(&body).0,
+        None => String::from_utf8_lossy(&body),
+    };
+    Ok(body.into_owned())
+}
+
+/// Extract the article of a page with dom_smoothie
+fn parse_readable(html: &str, url: &str) -> Result<dom_smoothie::Article, ReaderServiceError> {
+    let mut readability = Readability::new(html, Some(url), None)?;
+    let article_content = readability.parse()?;
+
+    if article_content.text_content.trim().is_empty() {
+        return Err(ReaderServiceError::ExtractionFailed);
+    }
+
+    Ok(article_content)
+}
+
+/// The sanitized contents of the first page container with anything left to show
+fn fallback_content(html: &str, level: ReaderSanitization) -> Option<String> {
+    let document = Document::from(html);
+
+    FALLBACK_CONTAINERS.iter().find_map(|container| {
+        let selection = document.select(container).first();
+        if !selection.exists() {
+            return None;
+        }
+
+        let content = clean_reader_html(&selection.inner_html(), level);
+        (!content.trim().is_empty()).then_some(content)
     })
 }
+
+/// Post-process extracted reader content: promote lazy-loaded image sources,
+/// drop tracking pixels and placeholders, and sanitize with the given policy
+fn clean_reader_html(html: &str, level: ReaderSanitization) -> String {
+    let document = Document::from(html);
+
+    for img in document.select("img").iter() {
+        for (lazy_attr, attr) in LAZY_IMAGE_ATTRS {
+            if let Some(value) = img.attr(lazy_attr) {
+                img.set_attr(attr, &value);
+                img.remove_attr(lazy_attr);
+            }
+        }
+
+        if is_tracking_or_placeholder_image(&img) {
+            img.remove();
+        }
+    }
+
+    sanitize(&document.html(), level)
+}
+
+/// Run reader HTML through ammonia; the standard level matches feed content
+fn sanitize(html: &str, level: ReaderSanitization) -> String {
+    match level {
+        ReaderSanitization::Standard => ammonia::clean(html),
+        ReaderSanitization::Strict => ammonia::Builder::default()
+            .rm_tags(STRICT_REMOVED_TAGS)
+            .clean(html)
+            .to_string(),
+    }
+}
+
+/// Tiny images are tracking pixels; images still on a `data:` source (or none
+/// at all) are lazy-load placeholders that never got a real URL
+fn is_tracking_or_placeholder_image(img: &Selection) -> bool {
+    let is_tiny = |name: &str| {
+        img.attr(name)
+            .and_then(|v| v.trim().trim_end_matches("px").parse::<u32>().ok())
+            .is_some_and(|size| size <= 1)
+    };
+
+    let has_real_src = img
+        .attr("src")
+        .is_some_and(|src| !src.trim().is_empty() && !src.trim_start().starts_with("data:"));
+
+    is_tiny("width") || is_tiny("height") || !has_real_src
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_reader_html_promotes_lazy_images() {
+        let html = r#"<p>Text</p><img src="data:image/gif;base64,R0lGOD" data-src="https://example.com/photo.jpg" alt="Photo">"#;
+
+        let cleaned = clean_reader_html(html, ReaderSanitization::Standard);
+
+        assert!(cleaned.contains(r#"src="https://example.com/photo.jpg""#));
+        assert!(!cleaned.contains("data-src"));
+        assert!(!cleaned.contains("data:image"));
+    }
+
+    #[test]
+    fn test_clean_reader_html_removes_tracking_pixels() {
+        let html = r#"<p>Text</p><img src="https://tracker.example/pixel.gif" width="1" height="1"><img src="https://example.com/real.png" width="600">"#;
+
+        let cleaned = clean_reader_html(html, ReaderSanitization::Standard);
+
+        assert!(!cleaned.contains("tracker.example"));
+        assert!(cleaned.contains("https://example.com/real.png"));
+        assert!(cleaned.contains("<p>Text</p>"));
+    }
+
+    #[test]
+    fn test_reader_output_strips_scripts() {
+        let page = r#"<html><head><title>Post</title></head><body><article>
+            <h1>Post</h1>
+            <p>The body of the post is long enough for readability to pick it up as the article.</p>
+            <script>alert("injected")</script>
+            <p onclick="alert('inline')">It has a second paragraph with an <img src="https://example.com/photo.jpg" alt="photo"> image.</p>
+            </article></body></html>"#;
+
+        let content = extract_full_content(
+            page,
+            "https://example.com/post",
+            ReaderSanitization::Standard,
+        )
+        .unwrap();
+        assert!(!content.contains("<script"));
+        assert!(!content.contains("injected"));
+        assert!(!content.contains("onclick"));
+        assert!(content.contains("example.com/photo.jpg"));
+
+        let strict = clean_reader_html(&content, ReaderSanitization::Strict);
+        assert!(!strict.contains("<img"));
+        assert!(strict.contains("second paragraph"));
+    }
+
+    #[test]
+    fn test_fallback_when_readability_fails() {
+        // Readability drops the heading repeating the title and is left with nothing
+        let html = r#"<html><head><title>Comic #42</title></head><body><main><h1>Comic #42</h1><img src="https://example.com/comic.png" alt="Comic"><script>track()</script></main><footer>Archive</footer></body></html>"#;
+
+        assert!(parse_readable(html, "https://example.com/comic/42").is_err());
+
+        let content =
+            fallback_content(html, ReaderSanitization::Standard).expect("fallback content");
+        assert!(content.contains("https://example.com/comic.png"));
+        assert!(!content.contains("track()"));
+        assert!(!content.contains("Archive"));
+    }
+
+    #[test]
+    fn test_fallback_content_empty_page() {
+        assert!(
+            fallback_content("<html><body>  </body></html>", ReaderSanitization::Standard)
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_saved_page_goes_to_saved_feed() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let url = "https://example.com/essay";
+        let html = r#"<html><head><title>An Essay</title></head><body><article>
+            <h1>An Essay</h1>
+            <p>This essay was not published in any feed, so it has to be saved by hand.</p>
+            <p>It has enough text for readability to consider it the main content of the page.</p>
+            </article></body></html>"#;
+
+        let article = store_saved_page(&pool, url, html, ReaderSanitization::Standard)
+            .await
+            .unwrap();
+
+        let feed = repository::get_feed_by_id(&pool, article.feed_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(feed.url, repository::SAVED_FEED_URL);
+        assert_eq!(feed.title, "Saved");
+        assert_eq!(article.title, "An Essay");
+        assert_eq!(article.url.as_deref(), Some(url));
+        assert!(article.content.unwrap().contains("saved by hand"));
+
+        // Saving again reuses the feed and the article
+        let again = store_saved_page(&pool, url, html, ReaderSanitization::Standard)
+            .await
+            .unwrap();
+        assert_eq!(again.id, article.id);
+        assert!(
+            repository::get_feeds_to_update(&pool, 0, chrono::NaiveTime::MIN)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        // Nor is it listed among the subscriptions
+        assert!(repository::list_feeds(&pool).await.unwrap().is_empty());
+        assert!(repository::list_feed_stats(&pool).await.unwrap().is_empty());
+        assert!(
+            repository::list_feeds_paged(&pool, &Default::default(), 10, 0)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    /// Serve `response` to one request from a local server; returns its URL
+    async fn serve(response: &'static str) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+            let _ = socket.write_all(response.as_bytes()).await;
+        });
+        format!("http://{addr}/article")
+    }
+
+    #[tokio::test]
+    async fn test_fetch_article_page_refuses_redirects_inwards_and_large_pages() {
+        // The local server stands in for a public page
+        ssrf::allow_loopback_on_this_thread();
+
+        let url =
+            serve("HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: 4\r\n\r\nPage")
+                .await;
+        assert_eq!(fetch_article_page(&url).await.unwrap(), "Page");
+
+        let url = serve("HTTP/1.1 302 Found\r\nLocation: http://169.254.169.254/latest/meta-data/\r\nContent-Length: 0\r\n\r\n").await;
+        assert!(matches!(
+            fetch_article_page(&url).await,
+            Err(ReaderServiceError::SsrfBlocked)
+        ));
+
+        let url = serve(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: 100000000\r\n\r\n",
+        )
+        .await;
+        assert!(matches!(
+            fetch_article_page(&url).await,
+            Err(ReaderServiceError::PageTooLarge)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_save_page_refuses_redirects_inwards() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        ssrf::allow_loopback_on_this_thread();
+
+        let url = serve("HTTP/1.1 302 Found\r\nLocation: http://169.254.169.254/latest/meta-data/\r\nContent-Length: 0\r\n\r\n").await;
+        assert!(matches!(
+            save_page(&pool, &url, ReaderSanitization::Standard).await,
+            Err(ReaderServiceError::SsrfBlocked)
+        ));
+
+        // Nothing was saved
+        let articles: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM articles")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(articles, 0);
+    }
+}
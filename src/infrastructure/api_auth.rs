@@ -0,0 +1,90 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::api::feeds::AppState;
+use crate::infrastructure::error_pages::json_error;
+
+/// Extract the bearer token from the Authorization header
+pub(crate) fn get_bearer_token(req: &Request) -> Option<&str> {
+    req.headers()
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+/// Compare tokens in constant time to avoid leaking the token through timing
+fn tokens_match(expected: &str, provided: &str) -> bool {
+    expected.len() == provided.len()
+        && expected
+            .bytes()
+            .zip(provided.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Optional API token middleware for the JSON API
+///
+/// When `API_TOKEN` is configured, requests must send
+/// `Authorization: Bearer <token>`. Without a configured token the API is open,
+/// like the rest of the app.
+pub async fn api_token_middleware(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if let Some(expected) = state.api_token.as_deref() {
+        let authorized = get_bearer_token(&req).is_some_and(|token| tokens_match(expected, token));
+
+        if !authorized {
+            tracing::warn!("API request rejected: missing or invalid token");
+            let mut response = json_error(
+                StatusCode::UNAUTHORIZED,
+                "Missing or invalid API token".to_string(),
+            );
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            return response;
+        }
+    }
+
+    next.run(req).await
+}
+
+/// Hide routes that must never be open, such as database maintenance,
+/// unless `API_TOKEN` is configured; the token itself is checked by
+/// [`api_token_middleware`]
+pub async fn require_api_token_middleware(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if state.api_token.is_none() {
+        tracing::warn!(
+            "Request to {} rejected: API_TOKEN is not configured",
+            req.uri().path()
+        );
+        return json_error(StatusCode::NOT_FOUND, "Not found".to_string());
+    }
+
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secret", "secreT"));
+        assert!(!tokens_match("secret", "secret2"));
+        assert!(!tokens_match("secret", ""));
+    }
+}
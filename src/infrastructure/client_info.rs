@@ -0,0 +1,174 @@
+//! The client's address and scheme as seen through trusted reverse proxies.
+//!
+//! `X-Forwarded-For` and `X-Forwarded-Proto` are only believed when the
+//! connection comes from a proxy listed in `TRUSTED_PROXY`; anyone else could
+//! send them to pose as another address or as HTTPS.
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::HeaderMap,
+    middleware::Next,
+    response::Response,
+};
+use ipnet::IpNet;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+/// Where a request really came from, attached to every request as an extension
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClientInfo {
+    /// Address of the client, `None` if the connection's peer is unknown
+    pub ip: Option<IpAddr>,
+    /// Whether the client used HTTPS
+    pub https: bool,
+}
+
+/// Attach the [`ClientInfo`] of the request, trusting the forwarding headers
+/// of the given proxy networks
+pub async fn client_info_middleware(
+    State(trusted): State<Arc<[IpNet]>>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let direct_https = req.uri().scheme_str() == Some("https");
+    let client_info = resolve_client_info(req.headers(), peer, direct_https, &trusted);
+    req.extensions_mut().insert(client_info);
+
+    next.run(req).await
+}
+
+/// Work out the client behind the connection's peer, following the forwarding
+/// headers only while they were added by trusted proxies
+pub fn resolve_client_info(
+    headers: &HeaderMap,
+    peer: Option<IpAddr>,
+    direct_https: bool,
+    trusted: &[IpNet],
+) -> ClientInfo {
+    let direct = ClientInfo {
+        ip: peer,
+        https: direct_https,
+    };
+    let is_trusted = |ip: &IpAddr| trusted.iter().any(|net| net.contains(ip));
+    if !peer.as_ref().is_some_and(is_trusted) {
+        return direct;
+    }
+
+    // Each proxy appends the address it received the request from, so walk
+    // back from the peer until an address that isn't a trusted proxy
+    let forwarded_for: Vec<IpAddr> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|ip| ip.trim().parse().ok())
+        .collect();
+    let ip = forwarded_for
+        .iter()
+        .rev()
+        .find(|ip| !is_trusted(ip))
+        .or(forwarded_for.first())
+        .copied()
+        .or(peer);
+
+    // The first proxy records the scheme the client used
+    let https = headers
+        .get("x-forwarded-proto")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .map_or(direct_https, |proto| {
+            proto.trim().eq_ignore_ascii_case("https")
+        });
+
+    ClientInfo { ip, https }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(forwarded_for: &str, forwarded_proto: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", forwarded_for.parse().unwrap());
+        headers.insert("x-forwarded-proto", forwarded_proto.parse().unwrap());
+        headers
+    }
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    #[test]
+    fn test_forwarding_headers_ignored_without_trusted_proxy() {
+        let headers = headers("203.0.113.7", "https");
+
+        let info = resolve_client_info(&headers, Some(ip("10.0.0.2")), false, &[]);
+
+        assert_eq!(
+            info,
+            ClientInfo {
+                ip: Some(ip("10.0.0.2")),
+                https: false
+            }
+        );
+    }
+
+    #[test]
+    fn test_forwarding_headers_ignored_from_untrusted_peer() {
+        let headers = headers("203.0.113.7", "https");
+        let trusted = ["10.0.0.0/8".parse().unwrap()];
+
+        let info = resolve_client_info(&headers, Some(ip("192.0.2.1")), false, &trusted);
+
+        assert_eq!(info.ip, Some(ip("192.0.2.1")));
+        assert!(!info.https);
+    }
+
+    #[test]
+    fn test_forwarding_headers_used_from_trusted_proxy() {
+        // The client spoofed the first entry; the proxy appended the real address
+        let headers = headers("198.51.100.9, 203.0.113.7", "https");
+        let trusted = ["10.0.0.0/8".parse().unwrap()];
+
+        let info = resolve_client_info(&headers, Some(ip("10.0.0.2")), false, &trusted);
+
+        assert_eq!(
+            info,
+            ClientInfo {
+                ip: Some(ip("203.0.113.7")),
+                https: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_chained_trusted_proxies_are_skipped() {
+        let headers = headers("203.0.113.7, 10.0.0.5", "http");
+        let trusted = ["10.0.0.0/8".parse().unwrap()];
+
+        let info = resolve_client_info(&headers, Some(ip("10.0.0.2")), true, &trusted);
+
+        assert_eq!(info.ip, Some(ip("203.0.113.7")));
+        assert!(!info.https);
+    }
+
+    #[test]
+    fn test_unknown_peer_is_not_trusted() {
+        let headers = headers("203.0.113.7", "https");
+        let trusted = ["0.0.0.0/0".parse().unwrap()];
+
+        let info = resolve_client_info(&headers, None, false, &trusted);
+
+        assert_eq!(
+            info,
+            ClientInfo {
+                ip: None,
+                https: false
+            }
+        );
+    }
+}
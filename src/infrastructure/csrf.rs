@@ -1,5 +1,5 @@
 use axum::{
-    extract::Request,
+    extract::{Request, State},
     http::{header, Method, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
@@ -7,6 +7,10 @@ use axum::{
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use rand::RngCore;
 
+use crate::config::CookieSameSite;
+use crate::infrastructure::api_auth::get_bearer_token;
+use crate::infrastructure::client_info::ClientInfo;
+
 const CSRF_COOKIE_NAME: &str = "csrf_token";
 const CSRF_HEADER_NAME: &str = "x-csrf-token";
 const TOKEN_LENGTH: usize = 32;
@@ -44,6 +48,42 @@ fn get_token_from_header(req: &Request) -> Option<String> {
         .map(|s| s.to_string())
 }
 
+/// Whether the client reached us over HTTPS, directly or through a proxy
+/// that says so in `X-Forwarded-Proto`. Behind the client info layer only
+/// trusted proxies are believed.
+fn is_https(req: &Request) -> bool {
+    if let Some(client_info) = req.extensions().get::<ClientInfo>() {
+        return client_info.https;
+    }
+
+    let forwarded_https = req
+        .headers()
+        .get("x-forwarded-proto")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(',').next())
+        .is_some_and(|proto| proto.trim().eq_ignore_ascii_case("https"));
+
+    forwarded_https || req.uri().scheme_str() == Some("https")
+}
+
+/// The `Set-Cookie` value for a new CSRF token.
+/// Not HttpOnly, so that JavaScript can read it for HTMX requests; SameSite
+/// keeps other origins from sending it, Secure keeps it off plain HTTP.
+fn csrf_cookie(token: &str, same_site: CookieSameSite, secure: bool) -> String {
+    let same_site = match same_site {
+        CookieSameSite::Strict => "Strict",
+        CookieSameSite::Lax => "Lax",
+    };
+    let mut cookie = format!(
+        "{}={}; Path=/; SameSite={}",
+        CSRF_COOKIE_NAME, token, same_site
+    );
+    if secure {
+        cookie.push_str("; Secure");
+    }
+    cookie
+}
+
 /// Check if this is a state-changing request that needs CSRF validation
 fn needs_csrf_validation(method: &Method) -> bool {
     matches!(
@@ -52,18 +92,32 @@ fn needs_csrf_validation(method: &Method) -> bool {
     )
 }
 
+/// Whether this is a JSON API request authenticated by a bearer token.
+/// Browsers don't attach an `Authorization` header to cross-site requests
+/// without a CORS preflight, which is never granted, so such a request can't
+/// be forged; the token itself is checked by the API token middleware.
+fn is_bearer_api_request(req: &Request) -> bool {
+    req.uri().path().starts_with("/api/") && get_bearer_token(req).is_some()
+}
+
 /// CSRF protection middleware
 ///
 /// This middleware implements the double-submit cookie pattern:
 /// 1. Sets a CSRF token cookie if not present
 /// 2. On state-changing requests (POST, PUT, DELETE, PATCH), validates that
-///    the X-CSRF-Token header matches the cookie value
-pub async fn csrf_middleware(req: Request, next: Next) -> Response {
+///    the X-CSRF-Token header matches the cookie value, unless it is an API
+///    request with a bearer token
+pub async fn csrf_middleware(
+    State(same_site): State<CookieSameSite>,
+    req: Request,
+    next: Next,
+) -> Response {
     let method = req.method().clone();
     let cookie_token = get_token_from_cookie(&req);
+    let secure = is_https(&req);
 
     // For state-changing requests, validate the CSRF token
-    if needs_csrf_validation(&method) {
+    if needs_csrf_validation(&method) && !is_bearer_api_request(&req) {
         let header_token = get_token_from_header(&req);
 
         match (&cookie_token, &header_token) {
@@ -101,14 +155,8 @@ pub async fn csrf_middleware(req: Request, next: Next) -> Response {
     let mut response = next.run(req).await;
 
     // Set the CSRF cookie if not present
-    // Note: NOT HttpOnly so that JavaScript can read it for HTMX requests
-    // SameSite=Strict provides protection against CSRF from other origins
     if cookie_token.is_none() {
-        let new_token = generate_token();
-        let cookie_value = format!(
-            "{}={}; Path=/; SameSite=Strict",
-            CSRF_COOKIE_NAME, new_token
-        );
+        let cookie_value = csrf_cookie(&generate_token(), same_site, secure);
         if let Ok(header_value) = cookie_value.parse() {
             response
                 .headers_mut()
@@ -118,3 +166,65 @@ pub async fn csrf_middleware(req: Request, next: Next) -> Response {
 
     response
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+
+    fn request(forwarded_proto: Option<&str>) -> Request {
+        let mut builder = Request::builder().uri("/articles");
+        if let Some(proto) = forwarded_proto {
+            builder = builder.header("x-forwarded-proto", proto);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn test_forwarded_https_is_detected() {
+        assert!(is_https(&request(Some("https"))));
+        assert!(is_https(&request(Some("HTTPS, http"))));
+        assert!(!is_https(&request(Some("http"))));
+        assert!(!is_https(&request(None)));
+    }
+
+    #[test]
+    fn test_client_info_decides_https() {
+        let mut req = request(Some("https"));
+        req.extensions_mut().insert(ClientInfo {
+            ip: None,
+            https: false,
+        });
+        assert!(!is_https(&req));
+    }
+
+    #[test]
+    fn test_only_api_requests_with_bearer_token_skip_validation() {
+        let with_bearer = |uri: &str| {
+            Request::builder()
+                .method(Method::POST)
+                .uri(uri)
+                .header(header::AUTHORIZATION, "Bearer secret")
+                .body(Body::empty())
+                .unwrap()
+        };
+        assert!(is_bearer_api_request(&with_bearer("/api/fetch")));
+        assert!(!is_bearer_api_request(&with_bearer("/feeds")));
+
+        let mut req = with_bearer("/api/fetch");
+        req.headers_mut()
+            .insert(header::AUTHORIZATION, "Basic c2VjcmV0".parse().unwrap());
+        assert!(!is_bearer_api_request(&req));
+    }
+
+    #[test]
+    fn test_cookie_is_secure_under_forwarded_https() {
+        let secure = is_https(&request(Some("https")));
+        let cookie = csrf_cookie("token", CookieSameSite::Strict, secure);
+        assert_eq!(cookie, "csrf_token=token; Path=/; SameSite=Strict; Secure");
+
+        let secure = is_https(&request(None));
+        let cookie = csrf_cookie("token", CookieSameSite::Lax, secure);
+        assert_eq!(cookie, "csrf_token=token; Path=/; SameSite=Lax");
+    }
+}
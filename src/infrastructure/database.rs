@@ -1,9 +1,20 @@
 use sqlx::{
-    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions},
-    Error as SqlxError,
+    sqlite::{
+        SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePool, SqlitePoolOptions,
+    },
+    Connection, Error as SqlxError,
 };
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
+/// Size of the database on disk before and after compaction, in bytes;
+/// `None` for an in-memory database
+#[derive(Debug, Clone, Copy)]
+pub struct VacuumReport {
+    pub size_before: Option<u64>,
+    pub size_after: Option<u64>,
+}
+
 pub async fn setup_database(url: &str) -> Result<SqlitePool, SqlxError> {
     let options = SqliteConnectOptions::from_str(url)?
         .create_if_missing(true)
@@ -33,3 +44,98 @@ pub async fn setup_database(url: &str) -> Result<SqlitePool, SqlxError> {
 
     Ok(pool)
 }
+
+/// Checkpoint the write-ahead log into the database file and rebuild it to
+/// reclaim the space of deleted rows. Both lock the database while they run.
+///
+/// VACUUM fails inside a transaction or while other statements are open on
+/// its connection, so it runs on a connection of its own rather than one
+/// borrowed from the pool.
+pub async fn vacuum(pool: &SqlitePool) -> Result<VacuumReport, SqlxError> {
+    let options = pool.connect_options();
+    let path = database_path(options.get_filename());
+    let size_before = path.as_deref().and_then(database_size);
+
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+    sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .execute(&mut conn)
+        .await?;
+    sqlx::query("VACUUM").execute(&mut conn).await?;
+    // VACUUM writes through the WAL again; fold it back into the file
+    sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .execute(&mut conn)
+        .await?;
+    conn.close().await?;
+
+    Ok(VacuumReport {
+        size_before,
+        size_after: path.as_deref().and_then(database_size),
+    })
+}
+
+/// Path of the key file of stored credentials, next to the database file;
+/// `None` for an in-memory database
+pub fn key_file_path(pool: &SqlitePool) -> Option<PathBuf> {
+    let mut path = database_path(pool.connect_options().get_filename())?.into_os_string();
+    path.push(".key");
+    Some(path.into())
+}
+
+/// Path of the database file, `None` for an in-memory database
+fn database_path(filename: &Path) -> Option<PathBuf> {
+    let in_memory = filename.as_os_str().is_empty()
+        || filename.to_string_lossy().starts_with(":memory:")
+        || filename.to_string_lossy().starts_with("file:");
+    (!in_memory).then(|| filename.to_path_buf())
+}
+
+/// Bytes taken by the database file and its write-ahead log
+fn database_size(path: &Path) -> Option<u64> {
+    let file = std::fs::metadata(path).ok()?.len();
+    let mut wal = path.as_os_str().to_owned();
+    wal.push("-wal");
+    let wal = std::fs::metadata(wal).map(|m| m.len()).unwrap_or(0);
+    Some(file + wal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_vacuum_reclaims_space_of_deleted_rows() {
+        let dir = std::env::temp_dir().join(format!("fluxfeed-vacuum-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let url = format!("sqlite://{}", dir.join("test.db").display());
+        let pool = setup_database(&url).await.unwrap();
+
+        sqlx::query("CREATE TABLE filler (data BLOB)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for _ in 0..50 {
+            sqlx::query("INSERT INTO filler VALUES (zeroblob(16384))")
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("DELETE FROM filler")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let report = vacuum(&pool).await.unwrap();
+        pool.close().await;
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let (before, after) = (report.size_before.unwrap(), report.size_after.unwrap());
+        assert!(after < before, "{} bytes before, {} after", before, after);
+    }
+
+    #[tokio::test]
+    async fn test_vacuum_in_memory_has_no_size() {
+        let pool = setup_database("sqlite::memory:").await.unwrap();
+        let report = vacuum(&pool).await.unwrap();
+        assert!(report.size_before.is_none() && report.size_after.is_none());
+    }
+}
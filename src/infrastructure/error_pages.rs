@@ -4,16 +4,37 @@ use axum::{
     http::{header, StatusCode},
     middleware::Next,
     response::{Html, IntoResponse, Response},
+    Json,
 };
+use serde::Serialize;
 
-use crate::web::templates::ErrorTemplate;
+use crate::domain::models::Theme;
+use crate::web::templates::{ErrorLink, ErrorTemplate};
+
+/// Title and message of a rendered error page, attached to the response so the
+/// middleware can re-encode the error for JSON clients
+#[derive(Clone)]
+pub struct ErrorDetails {
+    pub status_text: String,
+    pub message: String,
+}
+
+/// JSON body returned to API clients instead of an error page
+#[derive(Serialize)]
+struct JsonErrorBody {
+    error: String,
+    message: String,
+    status: u16,
+}
 
 /// Error page middleware
 ///
 /// This middleware intercepts error responses (4xx and 5xx status codes) that have
 /// plain text bodies and renders them as proper HTML error pages using the
-/// ErrorTemplate.
+/// ErrorTemplate. Requests under `/api` or accepting `application/json` get a
+/// JSON `{error, message, status}` body instead.
 pub async fn error_page_middleware(req: Request, next: Next) -> Response {
+    let wants_json = wants_json(&req);
     let response = next.run(req).await;
 
     // Only process error responses
@@ -22,10 +43,14 @@ pub async fn error_page_middleware(req: Request, next: Next) -> Response {
         return response;
     }
 
-    // Check if response is already HTML - if so, don't modify it
+    if wants_json {
+        return json_error_response(response);
+    }
+
+    // Check if response is already HTML or JSON (API errors) - if so, don't modify it
     if let Some(content_type) = response.headers().get(header::CONTENT_TYPE) {
         if let Ok(ct) = content_type.to_str() {
-            if ct.contains("text/html") {
+            if ct.contains("text/html") || ct.contains("application/json") {
                 return response;
             }
         }
@@ -36,38 +61,130 @@ pub async fn error_page_middleware(req: Request, next: Next) -> Response {
     let (parts, _body) = response.into_parts();
 
     let mut error_response = render_error_page(parts.status);
+    copy_cookies(&parts.headers, &mut error_response);
+    error_response
+}
+
+/// Fallback handler for paths no route matches
+pub async fn not_found() -> Response {
+    render_error_page(StatusCode::NOT_FOUND)
+}
 
-    // Copy over any cookies or other important headers
-    for (name, value) in parts.headers.iter() {
-        if name == header::SET_COOKIE {
-            error_response
-                .headers_mut()
-                .insert(name.clone(), value.clone());
+/// Whether the client asked for a machine-readable error body
+fn wants_json(req: &Request) -> bool {
+    let path = req.uri().path();
+    if path == "/api" || path.starts_with("/api/") {
+        return true;
+    }
+
+    req.headers()
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("application/json"))
+}
+
+/// Re-encode an error response as JSON, keeping the page's title and message
+fn json_error_response(response: Response) -> Response {
+    if let Some(content_type) = response.headers().get(header::CONTENT_TYPE) {
+        if let Ok(ct) = content_type.to_str() {
+            if ct.contains("application/json") {
+                return response;
+            }
         }
     }
 
+    let (parts, _body) = response.into_parts();
+    let details = parts
+        .extensions
+        .get::<ErrorDetails>()
+        .cloned()
+        .unwrap_or_else(|| ErrorDetails {
+            status_text: get_status_text(parts.status),
+            message: get_default_message(parts.status),
+        });
+
+    let mut error_response = json_body(parts.status, details.status_text, details.message);
+    copy_cookies(&parts.headers, &mut error_response);
     error_response
 }
 
+/// JSON error response for API handlers, in the format of re-encoded error pages
+pub fn json_error(status: StatusCode, message: String) -> Response {
+    json_body(status, get_status_text(status), message)
+}
+
+fn json_body(status: StatusCode, error: String, message: String) -> Response {
+    (
+        status,
+        Json(JsonErrorBody {
+            error,
+            message,
+            status: status.as_u16(),
+        }),
+    )
+        .into_response()
+}
+
+/// Copy over any cookies or other important headers
+fn copy_cookies(headers: &header::HeaderMap, response: &mut Response) {
+    for (name, value) in headers.iter() {
+        if name == header::SET_COOKIE || name == header::RETRY_AFTER {
+            response.headers_mut().insert(name.clone(), value.clone());
+        }
+    }
+}
+
 /// Render an error page with the given status code
 fn render_error_page(status: StatusCode) -> Response {
-    let status_code = status.as_u16();
-    let status_text = get_status_text(status);
-    let message = get_default_message(status);
+    render_error(status, get_status_text(status), get_default_message(status))
+}
+
+/// Render an error page with a custom title and message
+///
+/// The details are also attached as a response extension, so the middleware
+/// can turn the page into a JSON body when the client asked for one.
+pub fn render_error(status: StatusCode, status_text: String, message: String) -> Response {
+    render_error_page_with(status, status_text, message, None)
+}
 
+/// Render an error page that also offers a link, e.g. to the existing feed
+/// when adding a duplicate
+pub fn render_error_with_link(
+    status: StatusCode,
+    status_text: String,
+    message: String,
+    link: ErrorLink,
+) -> Response {
+    render_error_page_with(status, status_text, message, Some(link))
+}
+
+fn render_error_page_with(
+    status: StatusCode,
+    status_text: String,
+    message: String,
+    link: Option<ErrorLink>,
+) -> Response {
     let template = ErrorTemplate {
-        status_code,
-        status_text,
-        message,
+        status_code: status.as_u16(),
+        status_text: status_text.clone(),
+        message: message.clone(),
+        link,
+        // Rendered without database access; follows the system setting
+        theme: Theme::default(),
     };
 
-    match template.render() {
+    let mut response = match template.render() {
         Ok(html) => (status, Html(html)).into_response(),
         Err(err) => {
             tracing::error!("Error rendering error template: {}", err);
             (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response()
         }
-    }
+    };
+    response.extensions_mut().insert(ErrorDetails {
+        status_text,
+        message,
+    });
+    response
 }
 
 /// Get a human-readable status text for common HTTP status codes
@@ -0,0 +1,146 @@
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+use crate::domain::group_service;
+use crate::domain::models::{Feed, GroupNode};
+use crate::infrastructure::repository;
+
+/// How long a built group tree is reused; covers changes made outside the
+/// web handlers, such as feed titles picked up by the scheduler
+const GROUP_TREE_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Feeds and group tree shown in the article page's filter
+#[derive(Clone)]
+pub struct CachedGroupTree {
+    pub feeds: Vec<Feed>,
+    pub group_tree: Vec<GroupNode>,
+    pub ungrouped_feeds: Vec<Feed>,
+}
+
+/// Short-lived cache of the group tree, so every article page load doesn't
+/// reload all feeds and groups
+#[derive(Clone, Default)]
+pub struct GroupTreeCache {
+    entry: Arc<RwLock<Option<(Instant, CachedGroupTree)>>>,
+}
+
+impl GroupTreeCache {
+    /// The cached tree, rebuilt from the database when missing or expired
+    pub async fn get_or_load(&self, pool: &SqlitePool) -> Result<CachedGroupTree, sqlx::Error> {
+        if let Some((loaded_at, tree)) = self.entry.read().await.as_ref() {
+            if loaded_at.elapsed() < GROUP_TREE_CACHE_TTL {
+                return Ok(tree.clone());
+            }
+        }
+
+        let feeds = repository::list_feeds(pool).await?;
+        let groups = repository::list_groups(pool).await?;
+        let (group_tree, ungrouped_feeds) = group_service::build_group_tree(groups, feeds.clone());
+        let tree = CachedGroupTree {
+            feeds,
+            group_tree,
+            ungrouped_feeds,
+        };
+
+        *self.entry.write().await = Some((Instant::now(), tree.clone()));
+        Ok(tree)
+    }
+
+    pub async fn invalidate(&self) {
+        *self.entry.write().await = None;
+    }
+}
+
+/// Drop the cached group tree after any request that may change something.
+/// Besides feed and group edits, the tree's unread counts change with
+/// articles marked read, muted feeds and saved pages, so no non-GET route
+/// is left out.
+pub async fn invalidate_group_tree_cache(
+    State(cache): State<GroupTreeCache>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let mutates = !matches!(*req.method(), Method::GET | Method::HEAD);
+
+    let response = next.run(req).await;
+    if mutates {
+        cache.invalidate().await;
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::post, Router};
+    use axum_test::TestServer;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_group_mutation_invalidates_cache() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let cache = GroupTreeCache::default();
+
+        let app = Router::new()
+            .route(
+                "/groups",
+                post(|State(pool): State<SqlitePool>| async move {
+                    repository::create_group(&pool, "News", None).await.unwrap();
+                }),
+            )
+            .route(
+                "/articles/:id/toggle-feed-muted",
+                post(|State(pool): State<SqlitePool>| async move {
+                    repository::create_group(&pool, "Podcasts", None)
+                        .await
+                        .unwrap();
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                cache.clone(),
+                invalidate_group_tree_cache,
+            ))
+            .with_state(pool.clone());
+        let server = TestServer::new(app).unwrap();
+
+        assert!(cache
+            .get_or_load(&pool)
+            .await
+            .unwrap()
+            .group_tree
+            .is_empty());
+
+        // Changes behind the cache's back stay invisible until it expires
+        repository::create_group(&pool, "Blogs", None)
+            .await
+            .unwrap();
+        assert!(cache
+            .get_or_load(&pool)
+            .await
+            .unwrap()
+            .group_tree
+            .is_empty());
+
+        server.post("/groups").await;
+        let tree = cache.get_or_load(&pool).await.unwrap();
+        assert_eq!(tree.group_tree.len(), 2);
+
+        // Article routes change unread counts and muted feeds of the tree
+        server.post("/articles/1/toggle-feed-muted").await;
+        let tree = cache.get_or_load(&pool).await.unwrap();
+        assert_eq!(tree.group_tree.len(), 3);
+    }
+}
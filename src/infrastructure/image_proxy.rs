@@ -0,0 +1,338 @@
+//! Optional proxy for images in article content and for article thumbnails
+//! (`IMAGE_PROXY`). Image sources are rewritten to `/img`, which fetches them server-side, so the
+//! browser contacts no third-party hosts and http images still load on an
+//! https instance. Proxy URLs are signed, so only images of pages this
+//! instance rendered are fetched, not anything a third party links to.
+
+use crate::infrastructure::response_body::{read_capped, BodyError};
+use crate::infrastructure::secrets::SecretKey;
+use crate::infrastructure::ssrf;
+use crate::infrastructure::ttl_cache::TtlCache;
+use axum::body::Bytes;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use reqwest::header;
+use sha2::Sha256;
+use std::borrow::Cow;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use thiserror::Error;
+use url::{form_urlencoded, Url};
+
+/// Path of the proxy endpoint
+const PROXY_PATH: &str = "/img";
+
+/// Largest image passed on
+const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Raster image types passed on; SVG is refused, as it can carry scripts
+/// that would run on this origin
+const ALLOWED_IMAGE_TYPES: &[&str] = &[
+    "image/avif",
+    "image/bmp",
+    "image/gif",
+    "image/jpeg",
+    "image/png",
+    "image/webp",
+    "image/x-icon",
+    "image/vnd.microsoft.icon",
+];
+
+/// Redirects followed per image
+const MAX_REDIRECTS: usize = 5;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How long a fetched image is served from memory
+const IMAGE_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+/// Combined size of the cached images at most
+const IMAGE_CACHE_BYTES: usize = 32 * 1024 * 1024;
+
+#[derive(Error, Debug)]
+pub enum ImageProxyError {
+    #[error("URL blocked: points to internal/private network")]
+    SsrfBlocked,
+
+    #[error("Content type not allowed: {0}")]
+    NotAnImage(String),
+
+    #[error("Image is larger than {MAX_IMAGE_BYTES} bytes")]
+    TooLarge,
+
+    #[error("Image server answered {0}")]
+    UpstreamStatus(u16),
+
+    #[error("Request failed: {0}")]
+    RequestFailed(#[from] reqwest::Error),
+}
+
+#[derive(Clone)]
+pub struct ProxiedImage {
+    pub content_type: String,
+    pub body: Bytes,
+}
+
+/// Fetches images for the proxy and keeps recent ones in memory; cloning
+/// shares the cache. The default is turned off.
+#[derive(Clone)]
+pub struct ImageProxy {
+    enabled: bool,
+    signer: ImageSigner,
+    cache: Arc<TtlCache<String, ProxiedImage>>,
+}
+
+impl Default for ImageProxy {
+    fn default() -> Self {
+        Self::new(false, &SecretKey::default())
+    }
+}
+
+impl ImageProxy {
+    /// Proxy URLs are signed with a key derived from `secret_key`
+    pub fn new(enabled: bool, secret_key: &SecretKey) -> Self {
+        Self {
+            enabled,
+            signer: ImageSigner::new(secret_key),
+            cache: Arc::new(TtlCache::new(
+                IMAGE_CACHE_TTL,
+                IMAGE_CACHE_BYTES,
+                |image: &ProxiedImage| image.body.len(),
+            )),
+        }
+    }
+
+    /// Whether image sources are rewritten and served (`IMAGE_PROXY`)
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// What pages rewrite image sources with, `None` while turned off
+    pub fn signer(&self) -> Option<ImageSigner> {
+        self.enabled.then(|| self.signer.clone())
+    }
+
+    /// Whether `signature` is the one the pages of this instance gave the image URL
+    pub fn signature_matches(&self, url: &str, signature: &str) -> bool {
+        self.signer.signature_matches(url, signature)
+    }
+
+    /// The image at `url`, from the cache or fetched
+    pub async fn fetch(&self, url: &str) -> Result<ProxiedImage, ImageProxyError> {
+        if let Some(image) = self.cache.get(&url.to_string()).await {
+            return Ok(image);
+        }
+
+        let image = fetch_image(url).await?;
+        self.cache.insert(url.to_string(), image.clone()).await;
+        Ok(image)
+    }
+}
+
+/// Client shared by all image fetches, built on first use
+fn client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        let builder = reqwest::Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .user_agent(crate::user_agent());
+        ssrf::protect(builder, MAX_REDIRECTS, ssrf::validate_redirect)
+            .build()
+            .expect("Failed to create image proxy client")
+    })
+}
+
+/// Download an image, refusing internal addresses (also as redirect
+/// targets), types other than raster images and oversized bodies
+async fn fetch_image(url: &str) -> Result<ProxiedImage, ImageProxyError> {
+    if let Err(e) = ssrf::validate_url(url).await {
+        tracing::warn!("SSRF validation failed for image URL {}: {}", url, e);
+        return Err(ImageProxyError::SsrfBlocked);
+    }
+
+    let response = match client().get(url).send().await {
+        Ok(response) => response,
+        Err(e) if ssrf::blocked_by(&e).is_some() => return Err(ImageProxyError::SsrfBlocked),
+        Err(e) => return Err(e.into()),
+    };
+    if !response.status().is_success() {
+        return Err(ImageProxyError::UpstreamStatus(response.status().as_u16()));
+    }
+
+    let content_type = image_content_type(
+        response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok()),
+    )?;
+    let body = read_capped(response, MAX_IMAGE_BYTES)
+        .await
+        .map_err(|e| match e {
+            BodyError::TooLarge => ImageProxyError::TooLarge,
+            BodyError::Request(e) => ImageProxyError::RequestFailed(e),
+        })?;
+
+    Ok(ProxiedImage {
+        content_type,
+        body: Bytes::from(body),
+    })
+}
+
+/// The media type of a `Content-Type` header if it is an allowed image type
+fn image_content_type(content_type: Option<&str>) -> Result<String, ImageProxyError> {
+    let media_type = content_type
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+
+    if ALLOWED_IMAGE_TYPES.contains(&media_type.as_str()) {
+        Ok(media_type)
+    } else if media_type.is_empty() {
+        Err(ImageProxyError::NotAnImage("none".to_string()))
+    } else {
+        Err(ImageProxyError::NotAnImage(media_type))
+    }
+}
+
+/// Signs the proxy URLs of image sources
+#[derive(Clone)]
+pub struct ImageSigner {
+    key: [u8; 32],
+}
+
+impl ImageSigner {
+    pub fn new(secret_key: &SecretKey) -> Self {
+        Self {
+            key: secret_key.derive_key("image-proxy"),
+        }
+    }
+
+    /// Proxy URL of an image source; `None` for anything but absolute http(s)
+    /// URLs
+    pub fn proxied_src(&self, src: &str) -> Option<String> {
+        // Protocol-relative sources would otherwise load directly
+        let src = match src.strip_prefix("//") {
+            Some(rest) => Cow::Owned(format!("https://{}", rest)),
+            None => Cow::Borrowed(src),
+        };
+        let url = Url::parse(&src).ok()?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return None;
+        }
+
+        let encoded: String = form_urlencoded::byte_serialize(url.as_str().as_bytes()).collect();
+        Some(format!(
+            "{}?url={}&sig={}",
+            PROXY_PATH,
+            encoded,
+            URL_SAFE_NO_PAD.encode(self.url_mac(url.as_str()).finalize().into_bytes())
+        ))
+    }
+
+    /// Whether `signature` is the one `proxied_src` gave the image URL
+    pub fn signature_matches(&self, url: &str, signature: &str) -> bool {
+        let Ok(signature) = URL_SAFE_NO_PAD.decode(signature) else {
+            return false;
+        };
+        self.url_mac(url).verify_slice(&signature).is_ok()
+    }
+
+    /// HMAC of an image URL with the key of proxy URLs
+    fn url_mac(&self, url: &str) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC takes any key size");
+        mac.update(url.as_bytes());
+        mac
+    }
+
+    /// Route the images of sanitized HTML through the proxy; everything else
+    /// is kept as the feed content allowlist leaves it
+    pub fn proxy_images(&self, html: &str) -> String {
+        let signer = self.clone();
+        ammonia::Builder::default()
+            .attribute_filter(
+                move |element, attribute, value| match (element, attribute) {
+                    ("img", "src") => Some(
+                        signer
+                            .proxied_src(value)
+                            .map_or(Cow::Borrowed(value), Cow::Owned),
+                    ),
+                    _ => Some(Cow::Borrowed(value)),
+                },
+            )
+            .clean(html)
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_sources_are_rewritten() {
+        let html = r#"<p>Text <img src="http://cdn.example/a.png?w=1&amp;h=2" alt="A">
+            <img src="//cdn.example/b.gif"><img src="/static/local.png"></p>"#;
+
+        let proxied = ImageSigner::new(&SecretKey::random()).proxy_images(html);
+
+        assert!(proxied.contains(
+            r#"src="/img?url=http%3A%2F%2Fcdn.example%2Fa.png%3Fw%3D1%26h%3D2&amp;sig="#
+        ));
+        assert!(proxied.contains(r#"src="/img?url=https%3A%2F%2Fcdn.example%2Fb.gif&amp;sig="#));
+        assert!(proxied.contains(r#"src="/static/local.png""#));
+        assert!(proxied.contains(r#"alt="A""#));
+    }
+
+    #[test]
+    fn test_proxied_src_keeps_local_sources() {
+        let signer = ImageSigner::new(&SecretKey::random());
+        let proxied = signer.proxied_src("http://cdn.example/og.jpg").unwrap();
+        assert!(proxied.starts_with("/img?url=http%3A%2F%2Fcdn.example%2Fog.jpg&sig="));
+        assert_eq!(signer.proxied_src("/static/og.jpg"), None);
+        assert_eq!(signer.proxied_src("data:image/png;base64,AAAA"), None);
+    }
+
+    #[test]
+    fn test_proxy_urls_are_signed_per_image() {
+        let key = SecretKey::random();
+        let signer = ImageSigner::new(&key);
+        let proxied = signer.proxied_src("http://cdn.example/og.jpg").unwrap();
+        let (_, signature) = proxied.split_once("&sig=").unwrap();
+
+        assert!(signer.signature_matches("http://cdn.example/og.jpg", signature));
+        assert!(ImageSigner::new(&key).signature_matches("http://cdn.example/og.jpg", signature));
+        assert!(!signer.signature_matches("http://cdn.example/other.jpg", signature));
+        assert!(!signer.signature_matches("http://cdn.example/og.jpg", ""));
+        assert!(!signer.signature_matches("http://cdn.example/og.jpg", "not base64!"));
+
+        // Another key signs differently
+        assert!(!ImageSigner::new(&SecretKey::random())
+            .signature_matches("http://cdn.example/og.jpg", signature));
+    }
+
+    #[test]
+    fn test_only_raster_image_types_are_allowed() {
+        assert_eq!(
+            image_content_type(Some("Image/PNG; charset=binary")).unwrap(),
+            "image/png"
+        );
+        for rejected in [Some("image/svg+xml"), Some("text/html"), Some(""), None] {
+            assert!(matches!(
+                image_content_type(rejected),
+                Err(ImageProxyError::NotAnImage(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_internal_image_urls_are_blocked() {
+        let proxy = ImageProxy::default();
+
+        for url in ["http://127.0.0.1/pixel.png", "http://169.254.169.254/"] {
+            assert!(matches!(
+                proxy.fetch(url).await,
+                Err(ImageProxyError::SsrfBlocked)
+            ));
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! How far storing a fetched feed's entries has got, so the placeholder row
+//! of a feed being subscribed can show progress on large feeds
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Entries of a feed stored so far, out of those it sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InsertProgress {
+    pub stored: usize,
+    pub total: usize,
+}
+
+/// Shared by the fetchers and the feed row endpoint; cloning shares the state
+#[derive(Clone, Default)]
+pub struct InsertProgressStore {
+    feeds: Arc<Mutex<HashMap<i64, InsertProgress>>>,
+}
+
+/// Reports the progress of one feed until dropped
+pub struct InsertTracker {
+    feeds: Arc<Mutex<HashMap<i64, InsertProgress>>>,
+    feed_id: i64,
+}
+
+impl InsertProgressStore {
+    pub fn start(&self, feed_id: i64, total: usize) -> InsertTracker {
+        self.feeds
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(feed_id, InsertProgress { stored: 0, total });
+        InsertTracker {
+            feeds: self.feeds.clone(),
+            feed_id,
+        }
+    }
+
+    /// Progress of the feed's entries being stored, if any are
+    pub fn get(&self, feed_id: i64) -> Option<InsertProgress> {
+        self.feeds
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&feed_id)
+            .copied()
+    }
+}
+
+impl InsertTracker {
+    pub fn stored(&self, stored: usize) {
+        if let Some(progress) = self
+            .feeds
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_mut(&self.feed_id)
+        {
+            progress.stored = stored;
+        }
+    }
+}
+
+impl Drop for InsertTracker {
+    fn drop(&mut self) {
+        self.feeds
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.feed_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_is_reported_until_tracker_drops() {
+        let store = InsertProgressStore::default();
+        assert_eq!(store.get(1), None);
+
+        let tracker = store.start(1, 250);
+        tracker.stored(100);
+        assert_eq!(
+            store.clone().get(1),
+            Some(InsertProgress {
+                stored: 100,
+                total: 250
+            })
+        );
+        assert_eq!(store.get(2), None);
+
+        drop(tracker);
+        assert_eq!(store.get(1), None);
+    }
+}
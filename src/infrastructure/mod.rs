@@ -1,8 +1,19 @@
+pub mod api_auth;
+pub mod client_info;
 pub mod csrf;
 pub mod database;
 pub mod error_pages;
+pub mod group_tree_cache;
+pub mod image_proxy;
+pub mod insert_progress;
+pub mod reader_limiter;
 pub mod repository;
+pub mod response_body;
 pub mod rss_fetcher;
 pub mod scheduler;
+pub mod scheduler_status;
+pub mod secrets;
 pub mod security_headers;
+pub mod self_check;
 pub mod ssrf;
+pub mod ttl_cache;
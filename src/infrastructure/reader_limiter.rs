@@ -0,0 +1,131 @@
+//! Protection for reader mode, which fetches and parses the article's page
+//! on every request: a cap on concurrent extractions and a short-lived cache
+//! of the results, so reloading an article doesn't fetch it again.
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::domain::reader_service::ReaderContent;
+use crate::infrastructure::ttl_cache::TtlCache;
+
+/// Reader extractions that may run at the same time
+const READER_MAX_CONCURRENT: usize = 4;
+
+/// How long extracted reader content is reused
+const READER_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Articles whose reader content is kept at most
+const READER_CACHE_CAPACITY: usize = 64;
+
+/// Shared by all reader mode requests; cloning shares the limit and cache
+#[derive(Clone)]
+pub struct ReaderLimiter {
+    permits: Arc<Semaphore>,
+    cache: Arc<TtlCache<i64, ReaderContent>>,
+}
+
+impl Default for ReaderLimiter {
+    fn default() -> Self {
+        Self::new(READER_MAX_CONCURRENT)
+    }
+}
+
+impl ReaderLimiter {
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(max_concurrent)),
+            cache: Arc::new(TtlCache::new(
+                READER_CACHE_TTL,
+                READER_CACHE_CAPACITY,
+                |_| 1,
+            )),
+        }
+    }
+
+    /// A slot for one extraction, or `None` while all are taken
+    pub fn try_acquire(&self) -> Option<OwnedSemaphorePermit> {
+        self.permits.clone().try_acquire_owned().ok()
+    }
+
+    /// Reader content extracted for the article within the cache TTL
+    pub async fn cached(&self, article_id: i64) -> Option<ReaderContent> {
+        self.cache.get(&article_id).await
+    }
+
+    /// Remember extracted content, dropping expired entries and, when still
+    /// full, the oldest one
+    pub async fn store(&self, article_id: i64, content: ReaderContent) {
+        self.cache.insert(article_id, content).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::Article;
+    use chrono::Utc;
+
+    fn reader_content(title: &str) -> ReaderContent {
+        ReaderContent {
+            article: Article {
+                id: 1,
+                feed_id: 1,
+                guid: "guid-1".to_string(),
+                title: title.to_string(),
+                url: None,
+                content: None,
+                summary: None,
+                content_preview: None,
+                word_count: None,
+                author: None,
+                published_at: None,
+                is_read: false,
+                is_starred: false,
+                is_read_later: false,
+                og_image: None,
+                og_description: None,
+                og_site_name: None,
+                language: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            },
+            title: title.to_string(),
+            content: "<p>Body</p>".to_string(),
+            byline: None,
+            excerpt: None,
+            degraded: false,
+            language: None,
+        }
+    }
+
+    #[test]
+    fn test_extractions_beyond_the_limit_are_refused() {
+        let limiter = ReaderLimiter::new(2);
+
+        let first = limiter.try_acquire();
+        let second = limiter.clone().try_acquire();
+        assert!(first.is_some() && second.is_some());
+        assert!(limiter.try_acquire().is_none());
+
+        drop(first);
+        assert!(limiter.try_acquire().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_cache_evicts_oldest_when_full() {
+        let limiter = ReaderLimiter::default();
+        limiter.store(0, reader_content("Oldest")).await;
+        tokio::time::sleep(Duration::from_millis(2)).await;
+        for id in 1..READER_CACHE_CAPACITY as i64 {
+            limiter.store(id, reader_content("Stored")).await;
+        }
+        assert!(limiter.cached(0).await.is_some());
+
+        limiter.store(-1, reader_content("Newest")).await;
+
+        assert!(limiter.cached(0).await.is_none());
+        assert_eq!(limiter.cached(-1).await.unwrap().title, "Newest");
+        assert!(limiter.cached(1).await.is_some());
+    }
+}
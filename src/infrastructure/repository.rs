@@ -1,22 +1,41 @@
-use crate::domain::models::{Article, CreateFeed, Feed, Group, Log, LogWithFeed, NewArticle, Tag};
+use crate::domain::article_service::{
+    first_paragraph, plain_text_excerpt, word_count, CONTENT_PREVIEW_CHARS,
+};
+use crate::domain::feed_service::url_identity;
+use crate::domain::models::{
+    Article, ArticleListFilter, CreateFeed, Feed, FeedDiagnostic, FeedHeader, FeedListFilter,
+    FeedStats, FeedTtl, Group, Log, LogWithFeed, NewArticle, OpenGraphUpdate, Preferences,
+    ShareToken, Tag, Theme, UnreadFeedToken, ViewMode,
+};
+use crate::infrastructure::secrets::{self, SecretKey};
 use crate::web::templates::ArticleWithFeed;
-use chrono::Utc;
-use sqlx::{Error as SqlxError, Row, SqlitePool};
+use chrono::{NaiveTime, Utc};
+use sqlx::query::Query;
+use sqlx::sqlite::{Sqlite, SqliteArguments, SqliteRow};
+use sqlx::{Error as SqlxError, Row, SqliteExecutor, SqlitePool};
 use std::collections::HashMap;
+use tokio_stream::StreamExt;
+
+type SqliteQuery<'q> = Query<'q, Sqlite, SqliteArguments<'q>>;
 
 pub async fn create_feed(pool: &SqlitePool, create_feed: CreateFeed) -> Result<Feed, SqlxError> {
     let now = Utc::now();
 
     let feed = sqlx::query_as::<_, Feed>(
         r#"
-        INSERT INTO feeds (url, title, description, fetch_frequency, fetch_interval_minutes, created_at, updated_at)
-        VALUES (?, ?, ?, 'adaptive', 60, ?, ?)
+        INSERT INTO feeds (url, title, description, color, fetch_frequency, fetch_interval_minutes, ignore_pattern, created_at, updated_at, subscribed_at)
+        VALUES (?, ?, ?, COALESCE(?, '#3B82F6'), COALESCE(?, 'adaptive'), COALESCE(?, 60), ?, ?, ?, ?)
         RETURNING *
         "#,
     )
     .bind(&create_feed.url)
     .bind(&create_feed.title)
     .bind(&create_feed.description)
+    .bind(&create_feed.color)
+    .bind(create_feed.fetch_frequency.as_ref().map(|(frequency, _)| frequency))
+    .bind(create_feed.fetch_frequency.as_ref().map(|(_, minutes)| minutes))
+    .bind(&create_feed.ignore_pattern)
+    .bind(now)
     .bind(now)
     .bind(now)
     .fetch_one(pool)
@@ -25,19 +44,100 @@ pub async fn create_feed(pool: &SqlitePool, create_feed: CreateFeed) -> Result<F
     Ok(feed)
 }
 
+/// The feed subscribed under exactly this URL
+pub async fn get_feed_by_url(pool: &SqlitePool, url: &str) -> Result<Option<Feed>, SqlxError> {
+    sqlx::query_as::<_, Feed>("SELECT * FROM feeds WHERE url = ?")
+        .bind(url)
+        .fetch_optional(pool)
+        .await
+}
+
+/// URL of the feed holding pages saved by hand; the scheduler never fetches it
+pub const SAVED_FEED_URL: &str = "fluxfeed:saved";
+
+/// The feed that manually saved pages belong to, created on first use
+pub async fn get_or_create_saved_feed(pool: &SqlitePool) -> Result<Feed, SqlxError> {
+    let now = Utc::now();
+
+    sqlx::query(
+        r#"
+        INSERT INTO feeds (url, title, description, fetch_frequency, fetch_interval_minutes, created_at, updated_at, subscribed_at)
+        VALUES (?, 'Saved', 'Pages saved by hand', 'adaptive', 60, ?, ?, ?)
+        ON CONFLICT(url) DO NOTHING
+        "#,
+    )
+    .bind(SAVED_FEED_URL)
+    .bind(now)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await?;
+
+    sqlx::query_as::<_, Feed>("SELECT * FROM feeds WHERE url = ?")
+        .bind(SAVED_FEED_URL)
+        .fetch_one(pool)
+        .await
+}
+
+/// All subscribed feeds, pinned ones first, then newest first. The feed of
+/// saved pages is left out, as it is not fetched and has no settings.
 pub async fn list_feeds(pool: &SqlitePool) -> Result<Vec<Feed>, SqlxError> {
     let feeds = sqlx::query_as::<_, Feed>(
         r#"
         SELECT * FROM feeds
-        ORDER BY created_at DESC
+        WHERE url <> ?
+        ORDER BY pinned DESC, created_at DESC
         "#,
     )
+    .bind(SAVED_FEED_URL)
     .fetch_all(pool)
     .await?;
 
     Ok(feeds)
 }
 
+/// One page of feeds matching the filter, pinned ones first, then newest first
+pub async fn list_feeds_paged(
+    pool: &SqlitePool,
+    filter: &FeedListFilter,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Feed>, SqlxError> {
+    let mut conditions = vec!["url <> ?"];
+    if filter.search.is_some() {
+        conditions.push("(title LIKE ? OR url LIKE ?)");
+    }
+    if filter.group_id.is_some() {
+        conditions.push("group_id = ?");
+    }
+
+    let query_str = format!(
+        "SELECT * FROM feeds WHERE {} ORDER BY pinned DESC, created_at DESC LIMIT ? OFFSET ?",
+        conditions.join(" AND ")
+    );
+
+    let mut query = sqlx::query_as::<_, Feed>(&query_str).bind(SAVED_FEED_URL);
+    if let Some(ref search) = filter.search {
+        let pattern = format!("%{}%", search);
+        query = query.bind(pattern.clone()).bind(pattern);
+    }
+    if let Some(group_id) = filter.group_id {
+        query = query.bind(group_id);
+    }
+
+    query.bind(limit).bind(offset).fetch_all(pool).await
+}
+
+/// Number of subscribed feeds, not counting the feed of saved pages
+pub async fn count_feeds(pool: &SqlitePool) -> Result<i64, SqlxError> {
+    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM feeds WHERE url <> ?")
+        .bind(SAVED_FEED_URL)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(count.0)
+}
+
 pub async fn get_feed_by_id(pool: &SqlitePool, feed_id: i64) -> Result<Option<Feed>, SqlxError> {
     let feed = sqlx::query_as::<_, Feed>(
         r#"
@@ -66,12 +166,13 @@ pub async fn delete_feed(pool: &SqlitePool, feed_id: i64) -> Result<bool, SqlxEr
     Ok(result.rows_affected() > 0)
 }
 
-#[allow(dead_code)] // Used in tests to verify mark_all_articles_read
+#[allow(dead_code)] // Used in tests to verify mark_search_results_read
 pub async fn get_total_unread_count(pool: &SqlitePool) -> Result<i64, SqlxError> {
     let count: (i64,) = sqlx::query_as(
         r#"
         SELECT COUNT(*) FROM articles
         WHERE is_read = 0
+          AND feed_id NOT IN (SELECT id FROM feeds WHERE muted = 1)
         "#,
     )
     .fetch_one(pool)
@@ -82,16 +183,18 @@ pub async fn get_total_unread_count(pool: &SqlitePool) -> Result<i64, SqlxError>
 
 // Article repository methods
 
-pub async fn insert_article_if_new(
-    pool: &SqlitePool,
+pub async fn insert_article_if_new<'e>(
+    executor: impl SqliteExecutor<'e>,
     article: NewArticle,
 ) -> Result<Option<Article>, SqlxError> {
     let now = Utc::now();
 
     let result = sqlx::query_as::<_, Article>(
         r#"
-        INSERT INTO articles (feed_id, guid, title, url, content, summary, author, published_at, og_image, og_description, og_site_name, created_at, updated_at)
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        INSERT INTO articles (feed_id, guid, title, url, content, summary, author, published_at, updated_at_source, og_image, og_description, og_site_name, content_hash, content_preview, word_count, created_at, updated_at, is_read, language)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
+                COALESCE((SELECT mark_new_as_read FROM feeds WHERE id = ?), 0),
+                COALESCE(?, (SELECT language FROM feeds WHERE id = ?)))
         ON CONFLICT(feed_id, guid) DO NOTHING
         RETURNING *
         "#,
@@ -104,52 +207,220 @@ pub async fn insert_article_if_new(
     .bind(&article.summary)
     .bind(&article.author)
     .bind(article.published_at)
+    .bind(article.updated_at_source)
     .bind(&article.og_image)
     .bind(&article.og_description)
     .bind(&article.og_site_name)
+    .bind(article.content_hash())
+    .bind(article.content_preview())
+    .bind(article.word_count())
     .bind(now)
     .bind(now)
-    .fetch_optional(pool)
+    .bind(article.feed_id)
+    .bind(&article.language)
+    .bind(article.feed_id)
+    .fetch_optional(executor)
     .await?;
 
     Ok(result)
 }
 
-pub async fn update_feed_metadata(
-    pool: &SqlitePool,
+/// Insert articles with a single statement, skipping those whose GUID their
+/// feed already has. Returns the inserted articles, in no particular order.
+pub async fn insert_articles_if_new<'e>(
+    executor: impl SqliteExecutor<'e>,
+    articles: &[NewArticle],
+) -> Result<Vec<Article>, SqlxError> {
+    if articles.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let now = Utc::now();
+    let mut query = sqlx::QueryBuilder::new(
+        "INSERT INTO articles (feed_id, guid, title, url, content, summary, author, published_at, updated_at_source, og_image, og_description, og_site_name, content_hash, content_preview, word_count, created_at, updated_at, is_read, language) ",
+    );
+    query.push_values(articles, |mut row, article| {
+        row.push_bind(article.feed_id)
+            .push_bind(&article.guid)
+            .push_bind(&article.title)
+            .push_bind(&article.url)
+            .push_bind(&article.content)
+            .push_bind(&article.summary)
+            .push_bind(&article.author)
+            .push_bind(article.published_at)
+            .push_bind(article.updated_at_source)
+            .push_bind(&article.og_image)
+            .push_bind(&article.og_description)
+            .push_bind(&article.og_site_name)
+            .push_bind(article.content_hash())
+            .push_bind(article.content_preview())
+            .push_bind(article.word_count())
+            .push_bind(now)
+            .push_bind(now)
+            .push("COALESCE((SELECT mark_new_as_read FROM feeds WHERE id = ")
+            .push_bind_unseparated(article.feed_id)
+            .push_unseparated("), 0)")
+            // Entries without a language of their own are in the feed's
+            .push("COALESCE(")
+            .push_bind_unseparated(&article.language)
+            .push_unseparated(", (SELECT language FROM feeds WHERE id = ")
+            .push_bind_unseparated(article.feed_id)
+            .push_unseparated("))");
+    });
+    query.push(" ON CONFLICT(feed_id, guid) DO NOTHING RETURNING *");
+
+    query.build_query_as::<Article>().fetch_all(executor).await
+}
+
+/// Move an article (and the tombstone of a trimmed one) from the GUID an
+/// earlier strategy made to the current one, so the entry isn't stored
+/// again. Left alone if the new GUID is taken already.
+pub async fn remap_article_guid(
+    conn: &mut sqlx::SqliteConnection,
     feed_id: i64,
-    etag: Option<String>,
-    last_modified: Option<String>,
+    old_guid: &str,
+    new_guid: &str,
 ) -> Result<(), SqlxError> {
-    let now = Utc::now();
+    for table in ["articles", "trimmed_articles"] {
+        sqlx::query(&format!(
+            "UPDATE OR IGNORE {table} SET guid = ? WHERE feed_id = ? AND guid = ?"
+        ))
+        .bind(new_guid)
+        .bind(feed_id)
+        .bind(old_guid)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Replace the title and text of an existing article if the feed now sends
+/// different content under the same GUID; returns whether it was replaced.
+/// Articles stored before content was hashed only get their hash recorded.
+///
+/// This runs for every entry a feed still carries, so it is a single
+/// statement that touches no row when nothing changed. SQLite evaluates the
+/// `SET` expressions against the row before the update, so `content_hash`
+/// in them is the stored hash.
+pub async fn update_article_if_content_changed(
+    conn: &mut sqlx::SqliteConnection,
+    article: &NewArticle,
+) -> Result<bool, SqlxError> {
+    let replaced: Option<bool> = sqlx::query_scalar(
+        r#"
+        UPDATE articles
+        SET title = CASE WHEN content_hash IS NULL THEN title ELSE ?1 END,
+            content = CASE WHEN content_hash IS NULL THEN content ELSE ?2 END,
+            summary = CASE WHEN content_hash IS NULL THEN summary ELSE ?3 END,
+            content_preview = CASE WHEN content_hash IS NULL THEN content_preview ELSE ?4 END,
+            word_count = CASE WHEN content_hash IS NULL THEN word_count ELSE ?5 END,
+            content_changed_at = CASE WHEN content_hash IS NULL THEN content_changed_at ELSE ?7 END,
+            updated_at = CASE WHEN content_hash IS NULL THEN updated_at ELSE ?7 END,
+            content_hash = ?6
+        WHERE feed_id = ?8 AND guid = ?9 AND content_hash IS NOT ?6
+        RETURNING content_changed_at IS ?7
+        "#,
+    )
+    .bind(&article.title)
+    .bind(&article.content)
+    .bind(&article.summary)
+    .bind(article.content_preview())
+    .bind(article.word_count())
+    .bind(article.content_hash())
+    .bind(Utc::now())
+    .bind(article.feed_id)
+    .bind(&article.guid)
+    .fetch_optional(&mut *conn)
+    .await?;
 
+    Ok(replaced.unwrap_or(false))
+}
+
+/// Number of a feed's articles whose content changed under their GUID since
+/// the given time; a high count means the feed doesn't keep its GUIDs stable
+pub async fn count_content_changes_since<'e>(
+    executor: impl SqliteExecutor<'e>,
+    feed_id: i64,
+    since: chrono::DateTime<Utc>,
+) -> Result<i64, SqlxError> {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM articles WHERE feed_id = ? AND content_changed_at >= ?",
+    )
+    .bind(feed_id)
+    .bind(since)
+    .fetch_one(executor)
+    .await
+}
+
+/// Record a newer source `updated` timestamp for an existing article.
+/// published_at is deliberately left alone so edits don't reorder the list.
+pub async fn update_article_source_timestamp<'e>(
+    executor: impl SqliteExecutor<'e>,
+    feed_id: i64,
+    guid: &str,
+    updated_at_source: chrono::DateTime<Utc>,
+) -> Result<(), SqlxError> {
     sqlx::query(
+        r#"
+        UPDATE articles
+        SET updated_at_source = ?
+        WHERE feed_id = ? AND guid = ?
+          AND (updated_at_source IS NULL OR updated_at_source < ?)
+        "#,
+    )
+    .bind(updated_at_source)
+    .bind(feed_id)
+    .bind(guid)
+    .bind(updated_at_source)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Flip whether a feed is muted; returns false if the feed doesn't exist
+pub async fn toggle_feed_muted(pool: &SqlitePool, feed_id: i64) -> Result<bool, SqlxError> {
+    let result = sqlx::query(
         r#"
         UPDATE feeds
-        SET last_fetched_at = ?,
-            etag = ?,
-            last_modified = ?,
-            updated_at = ?
+        SET muted = NOT muted, updated_at = ?
         WHERE id = ?
         "#,
     )
-    .bind(now)
-    .bind(etag)
-    .bind(last_modified)
-    .bind(now)
+    .bind(Utc::now())
     .bind(feed_id)
     .execute(pool)
     .await?;
 
-    Ok(())
+    Ok(result.rows_affected() > 0)
 }
 
-pub async fn update_feed_details(
-    pool: &SqlitePool,
+/// Flip whether a feed is pinned; returns false if the feed doesn't exist
+pub async fn toggle_feed_pinned(pool: &SqlitePool, feed_id: i64) -> Result<bool, SqlxError> {
+    let result = sqlx::query(
+        r#"
+        UPDATE feeds
+        SET pinned = NOT pinned, updated_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(Utc::now())
+    .bind(feed_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn update_feed_details<'e>(
+    executor: impl SqliteExecutor<'e>,
     feed_id: i64,
     title: Option<String>,
     description: Option<String>,
     site_url: Option<String>,
+    language: Option<String>,
     etag: Option<String>,
     last_modified: Option<String>,
 ) -> Result<(), SqlxError> {
@@ -158,9 +429,10 @@ pub async fn update_feed_details(
     sqlx::query(
         r#"
         UPDATE feeds
-        SET title = COALESCE(?, title),
+        SET title = CASE WHEN title_is_custom THEN title ELSE COALESCE(?, title) END,
             description = COALESCE(?, description),
             site_url = ?,
+            language = ?,
             last_fetched_at = ?,
             etag = ?,
             last_modified = ?,
@@ -171,12 +443,13 @@ pub async fn update_feed_details(
     .bind(title)
     .bind(description)
     .bind(site_url)
+    .bind(language)
     .bind(now)
     .bind(etag)
     .bind(last_modified)
     .bind(now)
     .bind(feed_id)
-    .execute(pool)
+    .execute(executor)
     .await?;
 
     Ok(())
@@ -202,119 +475,112 @@ pub async fn touch_feed(pool: &SqlitePool, feed_id: i64) -> Result<(), SqlxError
     Ok(())
 }
 
-pub async fn get_feeds_to_update(pool: &SqlitePool) -> Result<Vec<Feed>, SqlxError> {
+/// Replace the stored ETag, e.g. when a 304 response carries a new validator
+pub async fn update_feed_etag(
+    pool: &SqlitePool,
+    feed_id: i64,
+    etag: &str,
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+        UPDATE feeds
+        SET etag = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(etag)
+    .bind(feed_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Due feeds, least recently fetched first and at most `limit` of them (`0`
+/// for no limit). Fetching moves a feed to the back, so a cycle that can't
+/// cover every due feed leaves the rest at the front of the next one. Feeds
+/// whose quiet hours include `local_time` are left for a later cycle.
+pub async fn get_feeds_to_update(
+    pool: &SqlitePool,
+    limit: i64,
+    local_time: NaiveTime,
+) -> Result<Vec<Feed>, SqlxError> {
+    // Quiet hours are stored as `HH:MM-HH:MM`, so the times compare as text
     let feeds = sqlx::query_as::<_, Feed>(
         r#"
-        SELECT * FROM feeds
-        WHERE last_fetched_at IS NULL
-           OR datetime(last_fetched_at, '+' || fetch_interval_minutes || ' minutes') <= datetime('now')
-        ORDER BY last_fetched_at ASC NULLS FIRST
+        SELECT feeds.* FROM feeds, (SELECT ? AS now) AS clock
+        WHERE (last_fetched_at IS NULL
+           OR datetime(last_fetched_at, '+' || fetch_interval_minutes || ' minutes') <= datetime('now'))
+          AND (next_retry_at IS NULL OR datetime(next_retry_at) <= datetime('now'))
+          AND url <> ?
+          AND NOT COALESCE(
+              CASE WHEN substr(quiet_hours, 1, 5) < substr(quiet_hours, 7, 5)
+                   THEN clock.now >= substr(quiet_hours, 1, 5) AND clock.now < substr(quiet_hours, 7, 5)
+                   ELSE clock.now >= substr(quiet_hours, 1, 5) OR clock.now < substr(quiet_hours, 7, 5)
+              END, 0)
+        ORDER BY last_fetched_at ASC NULLS FIRST, id ASC
+        LIMIT ?
         "#,
     )
+    .bind(local_time.format("%H:%M").to_string())
+    .bind(SAVED_FEED_URL)
+    // SQLite treats a negative LIMIT as unbounded
+    .bind(if limit > 0 { limit } else { -1 })
     .fetch_all(pool)
     .await?;
 
     Ok(feeds)
 }
 
+/// Store a feed's failure count and the time before which it must not be retried
+pub async fn update_feed_failure_state(
+    pool: &SqlitePool,
+    feed_id: i64,
+    consecutive_failures: i64,
+    next_retry_at: Option<chrono::DateTime<Utc>>,
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+        UPDATE feeds
+        SET consecutive_failures = ?,
+            next_retry_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(consecutive_failures)
+    .bind(next_retry_at)
+    .bind(feed_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
 // Article query methods
 
 /// Fetch articles with feed data in a single JOIN query (solves N+1 problem)
-#[allow(clippy::too_many_arguments)]
 pub async fn list_articles_with_feeds(
     pool: &SqlitePool,
-    feed_ids: Option<Vec<i64>>,
-    is_read: Option<bool>,
-    is_starred: Option<bool>,
-    search_query: Option<String>,
-    date_from: Option<chrono::DateTime<chrono::Utc>>,
-    date_to: Option<chrono::DateTime<chrono::Utc>>,
+    filter: &ArticleListFilter,
     limit: i64,
     offset: i64,
 ) -> Result<Vec<ArticleWithFeed>, SqlxError> {
-    // Build base query with JOIN
-    let base_query = if search_query.is_some() {
-        r#"SELECT
-            a.id, a.feed_id, a.guid, a.title, a.url, a.content, a.summary,
-            a.author, a.published_at, a.is_read, a.is_starred,
-            a.og_image, a.og_description, a.og_site_name,
-            a.created_at, a.updated_at,
-            f.title as feed_title, f.color as feed_color
-        FROM articles a
-        INNER JOIN feeds f ON f.id = a.feed_id
-        INNER JOIN articles_fts ON a.id = articles_fts.rowid"#
-    } else {
+    let (joins, where_clause) = article_filter_sql(filter);
+
+    // The id tiebreaker makes the order total, so offset pages neither
+    // repeat nor skip articles with equal timestamps
+    let query_str = format!(
         r#"SELECT
             a.id, a.feed_id, a.guid, a.title, a.url, a.content, a.summary,
-            a.author, a.published_at, a.is_read, a.is_starred,
-            a.og_image, a.og_description, a.og_site_name,
+            a.content_preview, a.word_count, a.author, a.published_at, a.is_read, a.is_starred, a.is_read_later,
+            a.og_image, a.og_description, a.og_site_name, a.language,
             a.created_at, a.updated_at,
-            f.title as feed_title, f.color as feed_color
-        FROM articles a
-        INNER JOIN feeds f ON f.id = a.feed_id"#
-    };
-
-    // Build WHERE conditions - collect both clause and value together
-    let mut conditions = Vec::new();
-
-    if search_query.is_some() {
-        conditions.push("articles_fts MATCH ?".to_string());
-    }
-    if let Some(ref ids) = feed_ids {
-        if !ids.is_empty() {
-            let placeholders: Vec<&str> = ids.iter().map(|_| "?").collect();
-            conditions.push(format!("a.feed_id IN ({})", placeholders.join(", ")));
-        }
-    }
-    if is_read.is_some() {
-        conditions.push("a.is_read = ?".to_string());
-    }
-    if is_starred.is_some() {
-        conditions.push("a.is_starred = ?".to_string());
-    }
-    if date_from.is_some() {
-        conditions.push("a.published_at >= ?".to_string());
-    }
-    if date_to.is_some() {
-        conditions.push("a.published_at <= ?".to_string());
-    }
-
-    // Construct WHERE clause
-    let where_clause = if conditions.is_empty() {
-        String::new()
-    } else {
-        format!(" WHERE {}", conditions.join(" AND "))
-    };
-
-    // Build complete query
-    let query_str = format!(
-        "{}{} ORDER BY a.published_at DESC, a.created_at DESC LIMIT ? OFFSET ?",
-        base_query, where_clause
+            f.title as feed_title, f.color as feed_color, f.muted as feed_muted,
+            f.prefer_summary as feed_prefer_summary
+        FROM articles a{}{} ORDER BY a.published_at DESC, a.created_at DESC, a.id DESC LIMIT ? OFFSET ?"#,
+        joins, where_clause
     );
-
-    // Bind parameters in the same order as conditions were added
-    let mut query = sqlx::query(&query_str);
-
-    if let Some(search) = search_query {
-        query = query.bind(search);
-    }
-    if let Some(ref ids) = feed_ids {
-        for id in ids {
-            query = query.bind(*id);
-        }
-    }
-    if let Some(read) = is_read {
-        query = query.bind(read);
-    }
-    if let Some(starred) = is_starred {
-        query = query.bind(starred);
-    }
-    if let Some(from) = date_from {
-        query = query.bind(from);
-    }
-    if let Some(to) = date_to {
-        query = query.bind(to);
-    }
+    let query = bind_article_filters(sqlx::query(&query_str), filter);
 
     let rows = query.bind(limit).bind(offset).fetch_all(pool).await?;
 
@@ -329,24 +595,32 @@ pub async fn list_articles_with_feeds(
             url: row.get("url"),
             content: row.get("content"),
             summary: row.get("summary"),
+            content_preview: row.get("content_preview"),
+            word_count: row.get("word_count"),
             author: row.get("author"),
             published_at: row.get("published_at"),
             is_read: row.get("is_read"),
             is_starred: row.get("is_starred"),
+            is_read_later: row.get("is_read_later"),
             og_image: row.get("og_image"),
             og_description: row.get("og_description"),
             og_site_name: row.get("og_site_name"),
+            language: row.get("language"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
         };
 
         let feed_title: String = row.get("feed_title");
         let feed_color: String = row.get("feed_color");
+        let feed_muted: bool = row.get("feed_muted");
+        let feed_prefer_summary: bool = row.get("feed_prefer_summary");
 
         articles_with_feed.push(ArticleWithFeed {
             article,
             feed_title,
             feed_color,
+            feed_muted,
+            feed_prefer_summary,
             tags: Vec::new(), // Tags are populated separately via batch fetch
         });
     }
@@ -354,6 +628,30 @@ pub async fn list_articles_with_feeds(
     Ok(articles_with_feed)
 }
 
+/// Repopulate the full-text index from the articles table.
+/// Returns the number of indexed articles.
+pub async fn rebuild_fts(pool: &SqlitePool) -> Result<u64, SqlxError> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM articles_fts")
+        .execute(&mut *tx)
+        .await?;
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO articles_fts(rowid, article_id, title, content, summary, author, feed_title)
+        SELECT a.id, a.id, a.title, a.content, a.summary, a.author, f.title
+        FROM articles a
+        INNER JOIN feeds f ON f.id = a.feed_id
+        "#,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
+
 /// Fetch a single article with feed data via JOIN
 pub async fn get_article_with_feed_by_id(
     pool: &SqlitePool,
@@ -362,10 +660,11 @@ pub async fn get_article_with_feed_by_id(
     let row = sqlx::query(
         r#"SELECT
             a.id, a.feed_id, a.guid, a.title, a.url, a.content, a.summary,
-            a.author, a.published_at, a.is_read, a.is_starred,
-            a.og_image, a.og_description, a.og_site_name,
+            a.content_preview, a.word_count, a.author, a.published_at, a.is_read, a.is_starred, a.is_read_later,
+            a.og_image, a.og_description, a.og_site_name, a.language,
             a.created_at, a.updated_at,
-            f.title as feed_title, f.color as feed_color
+            f.title as feed_title, f.color as feed_color, f.muted as feed_muted,
+            f.prefer_summary as feed_prefer_summary
         FROM articles a
         INNER JOIN feeds f ON f.id = a.feed_id
         WHERE a.id = ?"#,
@@ -384,25 +683,33 @@ pub async fn get_article_with_feed_by_id(
             url: row.get("url"),
             content: row.get("content"),
             summary: row.get("summary"),
+            content_preview: row.get("content_preview"),
+            word_count: row.get("word_count"),
             author: row.get("author"),
             published_at: row.get("published_at"),
             is_read: row.get("is_read"),
             is_starred: row.get("is_starred"),
+            is_read_later: row.get("is_read_later"),
             og_image: row.get("og_image"),
             og_description: row.get("og_description"),
             og_site_name: row.get("og_site_name"),
+            language: row.get("language"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
         };
 
         let feed_title: String = row.get("feed_title");
         let feed_color: String = row.get("feed_color");
+        let feed_muted: bool = row.get("feed_muted");
+        let feed_prefer_summary: bool = row.get("feed_prefer_summary");
         let tags = get_feed_tags(pool, feed_id).await?;
 
         Ok(Some(ArticleWithFeed {
             article,
             feed_title,
             feed_color,
+            feed_muted,
+            feed_prefer_summary,
             tags,
         }))
     } else {
@@ -422,6 +729,21 @@ pub async fn get_article_by_id(
     Ok(article)
 }
 
+pub async fn get_article_by_guid(
+    pool: &SqlitePool,
+    feed_id: i64,
+    guid: &str,
+) -> Result<Option<Article>, SqlxError> {
+    let article =
+        sqlx::query_as::<_, Article>("SELECT * FROM articles WHERE feed_id = ? AND guid = ?")
+            .bind(feed_id)
+            .bind(guid)
+            .fetch_optional(pool)
+            .await?;
+
+    Ok(article)
+}
+
 pub async fn update_article_read_status(
     pool: &SqlitePool,
     article_id: i64,
@@ -456,12 +778,54 @@ pub async fn update_article_starred_status(
     Ok(())
 }
 
-pub async fn update_article_opengraph(
+pub async fn update_article_read_later_status(
     pool: &SqlitePool,
     article_id: i64,
-    og_image: Option<String>,
-    og_description: Option<String>,
-    og_site_name: Option<String>,
+    is_read_later: bool,
+) -> Result<(), SqlxError> {
+    let now = Utc::now();
+
+    sqlx::query("UPDATE articles SET is_read_later = ?, updated_at = ? WHERE id = ?")
+        .bind(is_read_later)
+        .bind(now)
+        .bind(article_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Articles of a feed that have a link but no OpenGraph image yet, newest first
+pub async fn get_articles_missing_opengraph(
+    pool: &SqlitePool,
+    feed_id: i64,
+    limit: i64,
+) -> Result<Vec<(i64, String)>, SqlxError> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, url
+        FROM articles
+        WHERE feed_id = ? AND og_image IS NULL AND url IS NOT NULL
+        ORDER BY COALESCE(published_at, created_at) DESC, id DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(feed_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.get("id"), row.get("url")))
+        .collect())
+}
+
+/// Store fetched OpenGraph metadata. An article the feed sent no summary for
+/// gets the description as its summary; a real summary is never replaced.
+pub async fn update_article_opengraph<'e>(
+    executor: impl SqliteExecutor<'e>,
+    update: &OpenGraphUpdate,
 ) -> Result<(), SqlxError> {
     let now = Utc::now();
 
@@ -471,54 +835,362 @@ pub async fn update_article_opengraph(
         SET og_image = COALESCE(?, og_image),
             og_description = COALESCE(?, og_description),
             og_site_name = COALESCE(?, og_site_name),
+            summary = CASE WHEN TRIM(COALESCE(summary, '')) = '' THEN COALESCE(?, summary) ELSE summary END,
+            content_preview = COALESCE(content_preview, ?),
             updated_at = ?
         WHERE id = ?
         "#,
     )
-    .bind(og_image)
-    .bind(og_description)
-    .bind(og_site_name)
+    .bind(&update.og_image)
+    .bind(&update.og_description)
+    .bind(&update.og_site_name)
+    .bind(&update.og_description)
+    .bind(update.og_description.as_deref().and_then(summary_preview))
     .bind(now)
-    .bind(article_id)
-    .execute(pool)
+    .bind(update.article_id)
+    .execute(executor)
     .await?;
 
     Ok(())
 }
 
-pub async fn mark_all_articles_read(
-    pool: &SqlitePool,
-    feed_id: Option<i64>,
+/// List preview of a summary filled in after insert; `None` if it has no text
+fn summary_preview(summary: &str) -> Option<String> {
+    let preview = plain_text_excerpt(summary, CONTENT_PREVIEW_CHARS);
+    (!preview.is_empty()).then_some(preview)
+}
+
+/// Apply several OpenGraph updates in a single transaction, so a large fetch
+/// costs one WAL commit instead of one per article
+pub async fn update_articles_opengraph(
+    pool: &SqlitePool,
+    updates: &[OpenGraphUpdate],
+) -> Result<(), SqlxError> {
+    let mut tx = pool.begin().await?;
+    for update in updates {
+        update_article_opengraph(&mut *tx, update).await?;
+    }
+    tx.commit().await
+}
+
+/// Replace an article's content, e.g. with the full text fetched from its
+/// page, and recount its words. An empty summary is filled from the first
+/// paragraph.
+pub async fn update_article_content(
+    pool: &SqlitePool,
+    article_id: i64,
+    content: &str,
+) -> Result<(), SqlxError> {
+    let summary = first_paragraph(content);
+
+    sqlx::query(
+        r#"
+        UPDATE articles
+        SET content = ?, word_count = ?,
+            summary = CASE WHEN TRIM(COALESCE(summary, '')) = '' THEN COALESCE(?, summary) ELSE summary END,
+            content_preview = COALESCE(content_preview, ?),
+            updated_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(content)
+    .bind(word_count(content))
+    .bind(&summary)
+    .bind(summary.as_deref().and_then(summary_preview))
+    .bind(Utc::now())
+    .bind(article_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Delete articles until at most `max_articles` are stored: the oldest read
+/// articles first, then, if that isn't enough, the oldest unread ones.
+/// Starred and read-later articles, saved pages and articles of `never_trim`
+/// feeds are kept, so the total may stay above the cap. The GUIDs of deleted
+/// articles are remembered so that fetching them again doesn't bring them
+/// back. Returns the number of deleted articles.
+pub async fn trim_articles(pool: &SqlitePool, max_articles: i64) -> Result<u64, SqlxError> {
+    let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM articles")
+        .fetch_one(pool)
+        .await?;
+    let excess = total.0 - max_articles;
+    if excess <= 0 {
+        return Ok(0);
+    }
+
+    let trimmed = r#"
+        SELECT a.id FROM articles a
+        INNER JOIN feeds f ON f.id = a.feed_id
+        WHERE a.is_starred = 0 AND a.is_read_later = 0
+          AND f.never_trim = 0 AND f.url <> ?
+        ORDER BY a.is_read DESC, COALESCE(a.published_at, a.created_at) ASC, a.id ASC
+        LIMIT ?
+    "#;
+
+    // Both statements select the same articles within the transaction
+    let mut tx = pool.begin().await?;
+    sqlx::query(&format!(
+        "INSERT OR IGNORE INTO trimmed_articles (feed_id, guid) \
+         SELECT feed_id, guid FROM articles WHERE id IN ({})",
+        trimmed
+    ))
+    .bind(SAVED_FEED_URL)
+    .bind(excess)
+    .execute(&mut *tx)
+    .await?;
+    let result = sqlx::query(&format!("DELETE FROM articles WHERE id IN ({})", trimmed))
+        .bind(SAVED_FEED_URL)
+        .bind(excess)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Forget the trimmed GUIDs of a feed that are not among `current_guids`,
+/// the entries it carries now: they can't come back, so their tombstones
+/// would only pile up. Returns the number of forgotten GUIDs.
+pub async fn prune_trimmed_articles(
+    executor: impl sqlx::SqliteExecutor<'_>,
+    feed_id: i64,
+    current_guids: &[String],
 ) -> Result<u64, SqlxError> {
-    let now = Utc::now();
+    let current_guids = serde_json::Value::from(current_guids).to_string();
+    let result = sqlx::query(
+        r#"
+        DELETE FROM trimmed_articles
+        WHERE feed_id = ? AND guid NOT IN (SELECT value FROM json_each(?))
+        "#,
+    )
+    .bind(feed_id)
+    .bind(current_guids)
+    .execute(executor)
+    .await?;
 
-    let result = if let Some(fid) = feed_id {
-        sqlx::query(
-            "UPDATE articles SET is_read = 1, updated_at = ? WHERE feed_id = ? AND is_read = 0",
-        )
-        .bind(now)
-        .bind(fid)
-        .execute(pool)
-        .await?
+    Ok(result.rows_affected())
+}
+
+/// Mark read every unread article an article listing with `filter` returns,
+/// without the paging
+pub async fn mark_search_results_read(
+    pool: &SqlitePool,
+    filter: &ArticleListFilter,
+) -> Result<u64, SqlxError> {
+    let (joins, where_clause) = article_filter_sql(filter);
+    let query_str = format!(
+        "UPDATE articles SET is_read = 1, updated_at = ? WHERE is_read = 0 AND id IN \
+         (SELECT a.id FROM articles a{}{})",
+        joins, where_clause
+    );
+    let query = bind_article_filters(sqlx::query(&query_str).bind(Utc::now()), filter);
+
+    Ok(query.execute(pool).await?.rows_affected())
+}
+
+/// JOINs and WHERE clause selecting the articles `filter` matches, for a
+/// query over `articles a`
+fn article_filter_sql(filter: &ArticleListFilter) -> (String, String) {
+    let mut joins = String::from(" INNER JOIN feeds f ON f.id = a.feed_id");
+    let mut conditions = Vec::new();
+
+    if filter.search_query.is_some() {
+        joins.push_str(" INNER JOIN articles_fts ON a.id = articles_fts.rowid");
+        conditions.push("articles_fts MATCH ?".to_string());
+    }
+    match filter.feed_ids {
+        Some(ref ids) if !ids.is_empty() => {
+            let placeholders: Vec<&str> = ids.iter().map(|_| "?").collect();
+            conditions.push(format!("a.feed_id IN ({})", placeholders.join(", ")));
+        }
+        // Starred and read-later articles were kept on purpose, so muting
+        // leaves those lists alone
+        _ if filter.is_starred == Some(true) || filter.is_read_later == Some(true) => {}
+        // Muted feeds only show up when selected explicitly
+        _ => conditions.push("f.muted = 0".to_string()),
+    }
+    if !filter.tag_ids.is_empty() {
+        let placeholders = vec!["?"; filter.tag_ids.len()].join(", ");
+        conditions.push(format!(
+            "(a.feed_id IN (SELECT feed_id FROM feed_tags WHERE tag_id IN ({0})) \
+             OR a.id IN (SELECT article_id FROM article_tags WHERE tag_id IN ({0})))",
+            placeholders
+        ));
+    }
+    if filter.is_read.is_some() {
+        conditions.push("a.is_read = ?".to_string());
+    }
+    if filter.is_starred.is_some() {
+        conditions.push("a.is_starred = ?".to_string());
+    }
+    if filter.is_read_later.is_some() {
+        conditions.push("a.is_read_later = ?".to_string());
+    }
+    if filter.date_from.is_some() {
+        conditions.push("a.published_at >= ?".to_string());
+    }
+    if filter.date_to.is_some() {
+        conditions.push("a.published_at <= ?".to_string());
+    }
+    if filter.created_after.is_some() {
+        conditions.push("a.created_at > ?".to_string());
+    }
+    if filter.language.is_some() {
+        // A primary language also matches its regional variants
+        conditions.push("(a.language = ? COLLATE NOCASE OR a.language LIKE ? || '-%')".to_string());
+    }
+
+    let where_clause = if conditions.is_empty() {
+        String::new()
     } else {
-        sqlx::query("UPDATE articles SET is_read = 1, updated_at = ? WHERE is_read = 0")
-            .bind(now)
-            .execute(pool)
-            .await?
+        format!(" WHERE {}", conditions.join(" AND "))
     };
+    (joins, where_clause)
+}
 
-    Ok(result.rows_affected())
+/// Bind filter values in the same order as `article_filter_sql` added conditions
+fn bind_article_filters<'q>(
+    mut query: SqliteQuery<'q>,
+    filter: &'q ArticleListFilter,
+) -> SqliteQuery<'q> {
+    if let Some(ref search) = filter.search_query {
+        query = query.bind(search);
+    }
+    if let Some(ref ids) = filter.feed_ids {
+        for id in ids {
+            query = query.bind(*id);
+        }
+    }
+    // The tag IDs appear twice: once for feed tags, once for article tags
+    for _ in 0..2 {
+        for id in &filter.tag_ids {
+            query = query.bind(*id);
+        }
+    }
+    if let Some(read) = filter.is_read {
+        query = query.bind(read);
+    }
+    if let Some(starred) = filter.is_starred {
+        query = query.bind(starred);
+    }
+    if let Some(read_later) = filter.is_read_later {
+        query = query.bind(read_later);
+    }
+    if let Some(from) = filter.date_from {
+        query = query.bind(from);
+    }
+    if let Some(to) = filter.date_to {
+        query = query.bind(to);
+    }
+    if let Some(after) = filter.created_after {
+        query = query.bind(after);
+    }
+    if let Some(ref language) = filter.language {
+        query = query.bind(language).bind(language);
+    }
+    query
+}
+
+/// Set the read flag of the given articles, returning how many actually changed
+pub async fn set_articles_read_status(
+    pool: &SqlitePool,
+    article_ids: &[i64],
+    is_read: bool,
+) -> Result<u64, SqlxError> {
+    set_articles_flag(pool, "is_read", article_ids, is_read).await
+}
+
+/// Set the starred flag of the given articles, returning how many actually changed
+pub async fn set_articles_starred_status(
+    pool: &SqlitePool,
+    article_ids: &[i64],
+    is_starred: bool,
+) -> Result<u64, SqlxError> {
+    set_articles_flag(pool, "is_starred", article_ids, is_starred).await
+}
+
+async fn set_articles_flag(
+    pool: &SqlitePool,
+    column: &'static str,
+    article_ids: &[i64],
+    value: bool,
+) -> Result<u64, SqlxError> {
+    if article_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let placeholders: Vec<&str> = article_ids.iter().map(|_| "?").collect();
+    let query_str = format!(
+        "UPDATE articles SET {column} = ?, updated_at = ? WHERE {column} != ? AND id IN ({})",
+        placeholders.join(", ")
+    );
+
+    let mut query = sqlx::query(&query_str)
+        .bind(value)
+        .bind(Utc::now())
+        .bind(value);
+    for id in article_ids {
+        query = query.bind(*id);
+    }
+
+    Ok(query.execute(pool).await?.rows_affected())
+}
+
+/// Set the read and starred flags of articles from `(url, is_read,
+/// is_starred)` states, matching URLs by feed URL identity so scheme and
+/// trailing-slash differences don't matter. Returns the URLs that matched no
+/// stored article.
+pub async fn apply_article_states(
+    pool: &SqlitePool,
+    states: &[(String, bool, bool)],
+) -> Result<Vec<String>, SqlxError> {
+    let articles: Vec<(i64, String)> =
+        sqlx::query_as("SELECT id, url FROM articles WHERE url IS NOT NULL")
+            .fetch_all(pool)
+            .await?;
+    let mut ids_by_url: HashMap<String, Vec<i64>> = HashMap::new();
+    for (id, url) in articles {
+        ids_by_url.entry(url_identity(&url)).or_default().push(id);
+    }
+
+    let now = Utc::now();
+    let mut unmatched = Vec::new();
+    let mut tx = pool.begin().await?;
+    for (url, is_read, is_starred) in states {
+        let Some(ids) = ids_by_url.get(&url_identity(url)) else {
+            unmatched.push(url.clone());
+            continue;
+        };
+        for id in ids {
+            sqlx::query(
+                "UPDATE articles SET is_read = ?, is_starred = ?, updated_at = ? WHERE id = ?",
+            )
+            .bind(is_read)
+            .bind(is_starred)
+            .bind(now)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+    tx.commit().await?;
+
+    Ok(unmatched)
 }
 
 pub async fn get_article_counts(pool: &SqlitePool) -> Result<ArticleCounts, SqlxError> {
-    let counts: (i64, i64, i64, i64) = sqlx::query_as(
+    let counts: (i64, i64, i64, i64, i64) = sqlx::query_as(
         r#"
         SELECT
-            COUNT(*) as total,
-            SUM(CASE WHEN is_read = 0 THEN 1 ELSE 0 END) as unread,
-            SUM(CASE WHEN is_read = 1 THEN 1 ELSE 0 END) as read,
-            SUM(CASE WHEN is_starred = 1 THEN 1 ELSE 0 END) as starred
-        FROM articles
+            COALESCE(SUM(CASE WHEN f.muted = 0 THEN 1 ELSE 0 END), 0) as total,
+            COALESCE(SUM(CASE WHEN f.muted = 0 AND a.is_read = 0 THEN 1 ELSE 0 END), 0) as unread,
+            COALESCE(SUM(CASE WHEN f.muted = 0 AND a.is_read = 1 THEN 1 ELSE 0 END), 0) as read,
+            COALESCE(SUM(CASE WHEN a.is_starred = 1 THEN 1 ELSE 0 END), 0) as starred,
+            COALESCE(SUM(CASE WHEN a.is_read_later = 1 THEN 1 ELSE 0 END), 0) as read_later
+        FROM articles a
+        INNER JOIN feeds f ON f.id = a.feed_id
         "#,
     )
     .fetch_one(pool)
@@ -529,6 +1201,7 @@ pub async fn get_article_counts(pool: &SqlitePool) -> Result<ArticleCounts, Sqlx
         unread: counts.1,
         read: counts.2,
         starred: counts.3,
+        read_later: counts.4,
     })
 }
 
@@ -538,6 +1211,7 @@ pub struct ArticleCounts {
     pub unread: i64,
     pub read: i64,
     pub starred: i64,
+    pub read_later: i64,
 }
 
 /// Get unread article counts per feed
@@ -558,6 +1232,61 @@ pub async fn get_feed_unread_counts(
     Ok(rows.into_iter().collect())
 }
 
+/// List all feeds with total and unread article counts and the latest fetch status
+pub async fn list_feed_stats(pool: &SqlitePool) -> Result<Vec<FeedStats>, SqlxError> {
+    let stats = sqlx::query_as::<_, FeedStats>(
+        r#"
+        SELECT
+            f.id, f.title, f.url, f.site_url, f.group_id, f.language, f.last_fetched_at,
+            COUNT(a.id) AS total_articles,
+            COALESCE(SUM(CASE WHEN a.is_read = 0 THEN 1 ELSE 0 END), 0) AS unread,
+            (
+                SELECT l.log_type FROM logs l
+                WHERE l.feed_id = f.id
+                ORDER BY l.fetched_at DESC, l.id DESC
+                LIMIT 1
+            ) AS last_status
+        FROM feeds f
+        LEFT JOIN articles a ON a.feed_id = f.id
+        WHERE f.url <> ?
+        GROUP BY f.id
+        ORDER BY f.title ASC
+        "#,
+    )
+    .bind(SAVED_FEED_URL)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(stats)
+}
+
+/// All feeds with their latest fetch log, for triaging stuck or failing feeds
+pub async fn list_feed_diagnostics(pool: &SqlitePool) -> Result<Vec<FeedDiagnostic>, SqlxError> {
+    let diagnostics = sqlx::query_as::<_, FeedDiagnostic>(
+        r#"
+        SELECT
+            f.id, f.title, f.url, f.last_fetched_at,
+            l.log_type AS last_status,
+            l.error_message AS last_error,
+            l.fetched_at AS last_logged_at
+        FROM feeds f
+        LEFT JOIN logs l ON l.id = (
+            SELECT id FROM logs
+            WHERE feed_id = f.id
+            ORDER BY fetched_at DESC, id DESC
+            LIMIT 1
+        )
+        WHERE f.url <> ?
+        ORDER BY f.title ASC
+        "#,
+    )
+    .bind(SAVED_FEED_URL)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(diagnostics)
+}
+
 // Tag operations
 
 pub async fn list_tags(pool: &SqlitePool) -> Result<Vec<Tag>, SqlxError> {
@@ -705,33 +1434,9 @@ pub async fn get_tags_for_feeds(
     Ok(result)
 }
 
-/// Get all feed IDs that have any of the specified tags
-pub async fn get_feed_ids_by_tags(
-    pool: &SqlitePool,
-    tag_ids: &[i64],
-) -> Result<Vec<i64>, SqlxError> {
-    if tag_ids.is_empty() {
-        return Ok(vec![]);
-    }
-
-    let placeholders: Vec<&str> = tag_ids.iter().map(|_| "?").collect();
-    let query_str = format!(
-        "SELECT DISTINCT feed_id FROM feed_tags WHERE tag_id IN ({})",
-        placeholders.join(", ")
-    );
-
-    let mut query = sqlx::query_scalar(&query_str);
-    for tag_id in tag_ids {
-        query = query.bind(*tag_id);
-    }
-
-    let feed_ids: Vec<i64> = query.fetch_all(pool).await?;
-    Ok(feed_ids)
-}
-
 /// Replace all tags for a feed with the given tag IDs
 pub async fn set_feed_tags(
-    pool: &SqlitePool,
+    conn: &mut sqlx::SqliteConnection,
     feed_id: i64,
     tag_ids: &[i64],
 ) -> Result<(), SqlxError> {
@@ -742,7 +1447,7 @@ pub async fn set_feed_tags(
         "#,
     )
     .bind(feed_id)
-    .execute(pool)
+    .execute(&mut *conn)
     .await?;
 
     // Insert new tags
@@ -755,55 +1460,310 @@ pub async fn set_feed_tags(
         )
         .bind(feed_id)
         .bind(tag_id)
-        .execute(pool)
+        .execute(&mut *conn)
         .await?;
     }
 
     Ok(())
 }
 
-// Group operations
+/// Attach tags to an article by name, creating missing tags with the default
+/// color. Names match existing tags case-insensitively.
+pub async fn add_article_tags(
+    conn: &mut sqlx::SqliteConnection,
+    article_id: i64,
+    names: &[String],
+) -> Result<(), SqlxError> {
+    for name in names {
+        sqlx::query(
+            r#"
+            INSERT INTO tags (name)
+            SELECT ?
+            WHERE NOT EXISTS (SELECT 1 FROM tags WHERE name = ? COLLATE NOCASE)
+            "#,
+        )
+        .bind(name)
+        .bind(name)
+        .execute(&mut *conn)
+        .await?;
 
-pub async fn list_groups(pool: &SqlitePool) -> Result<Vec<Group>, SqlxError> {
-    let groups = sqlx::query_as::<_, Group>(
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO article_tags (article_id, tag_id)
+            SELECT ?, id FROM tags WHERE name = ? COLLATE NOCASE
+            ORDER BY id
+            LIMIT 1
+            "#,
+        )
+        .bind(article_id)
+        .bind(name)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Batch-fetch the tags attached to individual articles
+pub async fn get_tags_for_articles(
+    pool: &SqlitePool,
+    article_ids: &[i64],
+) -> Result<HashMap<i64, Vec<Tag>>, SqlxError> {
+    if article_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let placeholders: Vec<&str> = article_ids.iter().map(|_| "?").collect();
+    let query_str = format!(
         r#"
-        SELECT * FROM groups
-        ORDER BY parent_id NULLS FIRST, position ASC, name ASC
+        SELECT at.article_id, t.id, t.name, t.color, t.style, t.created_at
+        FROM article_tags at
+        INNER JOIN tags t ON t.id = at.tag_id
+        WHERE at.article_id IN ({})
+        ORDER BY t.name ASC
         "#,
-    )
-    .fetch_all(pool)
-    .await?;
+        placeholders.join(", ")
+    );
 
-    Ok(groups)
+    let mut query = sqlx::query(&query_str);
+    for id in article_ids {
+        query = query.bind(*id);
+    }
+
+    let rows = query.fetch_all(pool).await?;
+
+    let mut result: HashMap<i64, Vec<Tag>> = HashMap::new();
+    for row in rows {
+        let article_id: i64 = row.get("article_id");
+        let tag = Tag {
+            id: row.get("id"),
+            name: row.get("name"),
+            color: row.get("color"),
+            style: row.get("style"),
+            created_at: row.get("created_at"),
+        };
+        result.entry(article_id).or_default().push(tag);
+    }
+
+    Ok(result)
 }
 
-/// Get all feed IDs that belong to a group or any of its descendants (recursive)
-pub async fn get_feed_ids_in_group_recursive(
+// Feed header operations
+
+pub async fn get_feed_headers(
     pool: &SqlitePool,
-    group_id: i64,
-) -> Result<Vec<i64>, SqlxError> {
-    let rows = sqlx::query_scalar::<_, i64>(
+    feed_id: i64,
+    key: &SecretKey,
+) -> Result<Vec<FeedHeader>, SqlxError> {
+    let headers = sqlx::query_as::<_, FeedHeader>(
         r#"
-        WITH RECURSIVE descendants AS (
-            SELECT id FROM groups WHERE id = ?
-            UNION ALL
-            SELECT g.id FROM groups g
-            INNER JOIN descendants d ON g.parent_id = d.id
-        )
-        SELECT f.id FROM feeds f
-        WHERE f.group_id IN (SELECT id FROM descendants)
+        SELECT feed_id, name, value FROM feed_headers
+        WHERE feed_id = ?
+        ORDER BY name ASC
         "#,
     )
-    .bind(group_id)
+    .bind(feed_id)
     .fetch_all(pool)
     .await?;
 
-    Ok(rows)
+    // Credentials are stored encrypted; one that can't be decrypted is
+    // left out rather than sent garbled
+    let headers = headers
+        .into_iter()
+        .filter_map(|mut header| match key.open(&header.value) {
+            Ok(value) => {
+                header.value = value;
+                Some(header)
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Dropping the {} header of feed {}: {}",
+                    header.name,
+                    feed_id,
+                    e
+                );
+                None
+            }
+        })
+        .collect();
+
+    Ok(headers)
 }
 
-/// Get all descendant group IDs of a group (recursive)
-pub async fn get_descendant_group_ids(
-    pool: &SqlitePool,
+/// Whether a custom header carries credentials, which are stored encrypted
+fn is_credential_header(name: &str) -> bool {
+    name.eq_ignore_ascii_case("authorization")
+}
+
+/// Replace all custom request headers for a feed
+pub async fn set_feed_headers(
+    conn: &mut sqlx::SqliteConnection,
+    feed_id: i64,
+    headers: &[(String, String)],
+    key: &SecretKey,
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+        DELETE FROM feed_headers WHERE feed_id = ?
+        "#,
+    )
+    .bind(feed_id)
+    .execute(&mut *conn)
+    .await?;
+
+    for (name, value) in headers {
+        let value = if is_credential_header(name) {
+            key.seal(value)
+        } else {
+            value.clone()
+        };
+        sqlx::query(
+            r#"
+            INSERT INTO feed_headers (feed_id, name, value)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(feed_id)
+        .bind(name)
+        .bind(value)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// The feed's bearer token, decrypted. Only the fetcher reads it; the edit
+/// form just shows whether one is set. A token that can't be decrypted, e.g.
+/// after the key changed, counts as unset.
+pub async fn get_feed_bearer_token(
+    pool: &SqlitePool,
+    feed_id: i64,
+    key: &SecretKey,
+) -> Result<Option<String>, SqlxError> {
+    let token: Option<(Option<String>,)> =
+        sqlx::query_as("SELECT bearer_token FROM feeds WHERE id = ?")
+            .bind(feed_id)
+            .fetch_optional(pool)
+            .await?;
+
+    let Some(token) = token.and_then(|(token,)| token) else {
+        return Ok(None);
+    };
+    match key.open(&token) {
+        Ok(token) => Ok(Some(token)),
+        Err(e) => {
+            tracing::warn!("Ignoring the bearer token of feed {}: {}", feed_id, e);
+            Ok(None)
+        }
+    }
+}
+
+/// Set or clear the feed's bearer token, which is stored encrypted
+pub async fn set_feed_bearer_token<'e>(
+    executor: impl SqliteExecutor<'e>,
+    feed_id: i64,
+    token: Option<&str>,
+    key: &SecretKey,
+) -> Result<(), SqlxError> {
+    sqlx::query("UPDATE feeds SET bearer_token = ? WHERE id = ?")
+        .bind(token.map(|token| key.seal(token)))
+        .bind(feed_id)
+        .execute(executor)
+        .await?;
+
+    Ok(())
+}
+
+/// Encrypt bearer tokens and credential headers still stored in plain text,
+/// such as those from before encryption. Returns how many were encrypted.
+pub async fn seal_stored_credentials(
+    pool: &SqlitePool,
+    key: &SecretKey,
+) -> Result<usize, SqlxError> {
+    let mut tx = pool.begin().await?;
+    let mut sealed = 0;
+
+    let tokens: Vec<(i64, String)> =
+        sqlx::query_as("SELECT id, bearer_token FROM feeds WHERE bearer_token IS NOT NULL")
+            .fetch_all(&mut *tx)
+            .await?;
+    for (feed_id, token) in tokens {
+        if secrets::is_sealed(&token) {
+            continue;
+        }
+        sqlx::query("UPDATE feeds SET bearer_token = ? WHERE id = ?")
+            .bind(key.seal(&token))
+            .bind(feed_id)
+            .execute(&mut *tx)
+            .await?;
+        sealed += 1;
+    }
+
+    let headers: Vec<(i64, String, String)> = sqlx::query_as(
+        "SELECT feed_id, name, value FROM feed_headers WHERE lower(name) = 'authorization'",
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+    for (feed_id, name, value) in headers {
+        if secrets::is_sealed(&value) {
+            continue;
+        }
+        sqlx::query("UPDATE feed_headers SET value = ? WHERE feed_id = ? AND name = ?")
+            .bind(key.seal(&value))
+            .bind(feed_id)
+            .bind(name)
+            .execute(&mut *tx)
+            .await?;
+        sealed += 1;
+    }
+
+    tx.commit().await?;
+    Ok(sealed)
+}
+
+// Group operations
+
+pub async fn list_groups(pool: &SqlitePool) -> Result<Vec<Group>, SqlxError> {
+    let groups = sqlx::query_as::<_, Group>(
+        r#"
+        SELECT * FROM groups
+        ORDER BY parent_id NULLS FIRST, position ASC, name ASC
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(groups)
+}
+
+/// Get all feed IDs that belong to a group or any of its descendants (recursive)
+pub async fn get_feed_ids_in_group_recursive(
+    pool: &SqlitePool,
+    group_id: i64,
+) -> Result<Vec<i64>, SqlxError> {
+    let rows = sqlx::query_scalar::<_, i64>(
+        r#"
+        WITH RECURSIVE descendants AS (
+            SELECT id FROM groups WHERE id = ?
+            UNION ALL
+            SELECT g.id FROM groups g
+            INNER JOIN descendants d ON g.parent_id = d.id
+        )
+        SELECT f.id FROM feeds f
+        WHERE f.group_id IN (SELECT id FROM descendants)
+        "#,
+    )
+    .bind(group_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Get all descendant group IDs of a group (recursive)
+pub async fn get_descendant_group_ids(
+    pool: &SqlitePool,
     group_id: i64,
 ) -> Result<Vec<i64>, SqlxError> {
     let rows = sqlx::query_scalar::<_, i64>(
@@ -845,6 +1805,22 @@ pub async fn update_group_parent(
     Ok(())
 }
 
+/// Flip whether a group is collapsed in the group tree
+pub async fn toggle_group_collapsed(pool: &SqlitePool, id: i64) -> Result<bool, SqlxError> {
+    let result = sqlx::query(
+        r#"
+        UPDATE groups
+        SET collapsed = NOT collapsed, updated_at = CURRENT_TIMESTAMP
+        WHERE id = ?
+        "#,
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
 pub async fn get_group(pool: &SqlitePool, id: i64) -> Result<Option<Group>, SqlxError> {
     let group = sqlx::query_as::<_, Group>(
         r#"
@@ -930,8 +1906,8 @@ pub async fn delete_group(pool: &SqlitePool, id: i64) -> Result<(), SqlxError> {
     Ok(())
 }
 
-pub async fn update_feed_group(
-    pool: &SqlitePool,
+pub async fn update_feed_group<'e>(
+    executor: impl SqliteExecutor<'e>,
     feed_id: i64,
     group_id: Option<i64>,
 ) -> Result<(), SqlxError> {
@@ -944,6 +1920,190 @@ pub async fn update_feed_group(
     )
     .bind(group_id)
     .bind(feed_id)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Replace a feed's URL, e.g. with the feed discovered on the page it was added by
+pub async fn update_feed_url(pool: &SqlitePool, feed_id: i64, url: &str) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+        UPDATE feeds
+        SET url = ?, updated_at = CURRENT_TIMESTAMP
+        WHERE id = ?
+        "#,
+    )
+    .bind(url)
+    .bind(feed_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Whether a fetch of the feed was attempted yet, successful or not
+pub async fn feed_has_logs(pool: &SqlitePool, feed_id: i64) -> Result<bool, SqlxError> {
+    sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM logs WHERE feed_id = ?)")
+        .bind(feed_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Move several feeds into a group (or out of any group) in one transaction
+pub async fn update_feeds_group(
+    pool: &SqlitePool,
+    feed_ids: &[i64],
+    group_id: Option<i64>,
+) -> Result<(), SqlxError> {
+    let mut tx = pool.begin().await?;
+
+    for feed_id in feed_ids {
+        sqlx::query(
+            r#"
+            UPDATE feeds
+            SET group_id = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            "#,
+        )
+        .bind(group_id)
+        .bind(feed_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+// Share token operations
+
+pub async fn list_share_tokens(
+    pool: &SqlitePool,
+    group_id: i64,
+) -> Result<Vec<ShareToken>, SqlxError> {
+    let tokens = sqlx::query_as::<_, ShareToken>(
+        r#"
+        SELECT * FROM share_tokens
+        WHERE group_id = ?
+        ORDER BY created_at ASC, id ASC
+        "#,
+    )
+    .bind(group_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(tokens)
+}
+
+pub async fn get_share_token(
+    pool: &SqlitePool,
+    token: &str,
+) -> Result<Option<ShareToken>, SqlxError> {
+    let share_token = sqlx::query_as::<_, ShareToken>(
+        r#"
+        SELECT * FROM share_tokens WHERE token = ?
+        "#,
+    )
+    .bind(token)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(share_token)
+}
+
+pub async fn create_share_token(
+    pool: &SqlitePool,
+    group_id: i64,
+    token: &str,
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+        INSERT INTO share_tokens (token, group_id)
+        VALUES (?, ?)
+        "#,
+    )
+    .bind(token)
+    .bind(group_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Revoke a share token, scoped to its group so a stale form cannot revoke another group's link
+pub async fn delete_share_token(
+    pool: &SqlitePool,
+    group_id: i64,
+    id: i64,
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+        DELETE FROM share_tokens WHERE id = ? AND group_id = ?
+        "#,
+    )
+    .bind(id)
+    .bind(group_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+// Unread feed token operations
+
+pub async fn list_unread_feed_tokens(pool: &SqlitePool) -> Result<Vec<UnreadFeedToken>, SqlxError> {
+    let tokens = sqlx::query_as::<_, UnreadFeedToken>(
+        r#"
+        SELECT * FROM unread_feed_tokens
+        ORDER BY created_at ASC, id ASC
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(tokens)
+}
+
+pub async fn get_unread_feed_token(
+    pool: &SqlitePool,
+    token: &str,
+) -> Result<Option<UnreadFeedToken>, SqlxError> {
+    let unread_feed_token = sqlx::query_as::<_, UnreadFeedToken>(
+        r#"
+        SELECT * FROM unread_feed_tokens WHERE token = ?
+        "#,
+    )
+    .bind(token)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(unread_feed_token)
+}
+
+pub async fn create_unread_feed_token(pool: &SqlitePool, token: &str) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+        INSERT INTO unread_feed_tokens (token)
+        VALUES (?)
+        "#,
+    )
+    .bind(token)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_unread_feed_token(pool: &SqlitePool, id: i64) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+        DELETE FROM unread_feed_tokens WHERE id = ?
+        "#,
+    )
+    .bind(id)
     .execute(pool)
     .await?;
 
@@ -951,6 +2111,7 @@ pub async fn update_feed_group(
 }
 
 // Log operations
+#[allow(clippy::too_many_arguments)]
 pub async fn insert_log(
     pool: &SqlitePool,
     feed_id: i64,
@@ -958,11 +2119,13 @@ pub async fn insert_log(
     status_code: Option<i32>,
     error_message: Option<&str>,
     retry_after: Option<&str>,
+    duration_ms: Option<i64>,
+    entry_count: Option<i64>,
 ) -> Result<(), SqlxError> {
     sqlx::query(
         r#"
-        INSERT INTO logs (feed_id, log_type, status_code, error_message, retry_after)
-        VALUES (?, ?, ?, ?, ?)
+        INSERT INTO logs (feed_id, log_type, status_code, error_message, retry_after, duration_ms, entry_count)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(feed_id)
@@ -970,6 +2133,8 @@ pub async fn insert_log(
     .bind(status_code)
     .bind(error_message)
     .bind(retry_after)
+    .bind(duration_ms)
+    .bind(entry_count)
     .execute(pool)
     .await?;
 
@@ -984,9 +2149,53 @@ pub async fn list_logs_with_feeds(
     limit: i64,
     offset: i64,
 ) -> Result<Vec<LogWithFeed>, SqlxError> {
+    let query_str = format!(
+        "{} LIMIT ? OFFSET ?",
+        logs_with_feeds_sql(feed_id, feed_name, log_type)
+    );
+    let query = bind_log_filters(sqlx::query(&query_str), feed_id, feed_name, log_type);
+
+    let rows = query.bind(limit).bind(offset).fetch_all(pool).await?;
+
+    Ok(rows.iter().map(log_with_feed_from_row).collect())
+}
+
+/// Visit every log matching the filters, newest first, without loading them all
+/// into memory. Stops early when `on_log` resolves to `false`.
+pub async fn for_each_log_with_feed<F, Fut>(
+    pool: &SqlitePool,
+    feed_id: Option<i64>,
+    feed_name: Option<&str>,
+    log_type: Option<&str>,
+    mut on_log: F,
+) -> Result<(), SqlxError>
+where
+    F: FnMut(LogWithFeed) -> Fut,
+    Fut: std::future::Future<Output = bool>,
+{
+    let query_str = logs_with_feeds_sql(feed_id, feed_name, log_type);
+    let query = bind_log_filters(sqlx::query(&query_str), feed_id, feed_name, log_type);
+
+    let mut rows = query.fetch(pool);
+    while let Some(row) = rows.next().await {
+        if !on_log(log_with_feed_from_row(&row?)).await {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// SELECT for logs joined with their feed, filtered and ordered newest first
+fn logs_with_feeds_sql(
+    feed_id: Option<i64>,
+    feed_name: Option<&str>,
+    log_type: Option<&str>,
+) -> String {
     // Base query with JOIN
     let base_query = r#"SELECT
-            l.id, l.feed_id, l.log_type, l.status_code, l.error_message, l.retry_after, l.fetched_at,
+            l.id, l.feed_id, l.log_type, l.status_code, l.error_message, l.retry_after,
+            l.duration_ms, l.entry_count, l.fetched_at,
             f.title as feed_title, f.url as feed_url
         FROM logs l
         INNER JOIN feeds f ON f.id = l.feed_id"#;
@@ -1011,15 +2220,16 @@ pub async fn list_logs_with_feeds(
         format!(" WHERE {}", conditions.join(" AND "))
     };
 
-    // Build complete query
-    let query_str = format!(
-        "{}{} ORDER BY l.fetched_at DESC LIMIT ? OFFSET ?",
-        base_query, where_clause
-    );
-
-    // Bind parameters in the same order as conditions were added
-    let mut query = sqlx::query(&query_str);
+    format!("{}{} ORDER BY l.fetched_at DESC", base_query, where_clause)
+}
 
+/// Bind filter values in the same order as `logs_with_feeds_sql` added conditions
+fn bind_log_filters<'q>(
+    mut query: SqliteQuery<'q>,
+    feed_id: Option<i64>,
+    feed_name: Option<&str>,
+    log_type: Option<&'q str>,
+) -> SqliteQuery<'q> {
     if let Some(id) = feed_id {
         query = query.bind(id);
     }
@@ -1029,58 +2239,71 @@ pub async fn list_logs_with_feeds(
     if let Some(lt) = log_type {
         query = query.bind(lt);
     }
+    query
+}
 
-    let rows = query.bind(limit).bind(offset).fetch_all(pool).await?;
-
-    let mut logs = Vec::new();
-    for row in rows {
-        let log = Log {
-            id: row.get("id"),
-            feed_id: row.get("feed_id"),
-            log_type: row.get("log_type"),
-            status_code: row.get("status_code"),
-            error_message: row.get("error_message"),
-            retry_after: row.get("retry_after"),
-            fetched_at: row.get("fetched_at"),
-        };
-
-        let log_with_feed = LogWithFeed {
-            log,
-            feed_title: row.get("feed_title"),
-            feed_url: row.get("feed_url"),
-        };
+fn log_with_feed_from_row(row: &SqliteRow) -> LogWithFeed {
+    let log = Log {
+        id: row.get("id"),
+        feed_id: row.get("feed_id"),
+        log_type: row.get("log_type"),
+        status_code: row.get("status_code"),
+        error_message: row.get("error_message"),
+        retry_after: row.get("retry_after"),
+        duration_ms: row.get("duration_ms"),
+        entry_count: row.get("entry_count"),
+        fetched_at: row.get("fetched_at"),
+    };
 
-        logs.push(log_with_feed);
+    LogWithFeed {
+        log,
+        feed_title: row.get("feed_title"),
+        feed_url: row.get("feed_url"),
     }
+}
+
+/// Forget the GUID strategy the stored articles were made with, once a fetch
+/// has moved them over to the current one
+pub async fn clear_previous_guid_strategy<'e>(
+    executor: impl SqliteExecutor<'e>,
+    feed_id: i64,
+) -> Result<(), SqlxError> {
+    sqlx::query("UPDATE feeds SET previous_guid_strategy = NULL WHERE id = ?")
+        .bind(feed_id)
+        .execute(executor)
+        .await?;
 
-    Ok(logs)
+    Ok(())
 }
 
-/// Update only TTL (for custom frequency mode - store but don't use)
-pub async fn update_feed_ttl_only(
-    pool: &SqlitePool,
+/// Update only the announced TTL and where it came from; it is displayed,
+/// not used for scheduling
+pub async fn update_feed_ttl_only<'e>(
+    executor: impl SqliteExecutor<'e>,
     feed_id: i64,
-    ttl_minutes: Option<i64>,
+    ttl: FeedTtl,
 ) -> Result<(), SqlxError> {
     sqlx::query!(
         r#"
         UPDATE feeds
         SET ttl_minutes = ?,
+            ttl_from_headers = ?,
             updated_at = datetime('now')
         WHERE id = ?
         "#,
-        ttl_minutes,
+        ttl.minutes,
+        ttl.from_headers,
         feed_id
     )
-    .execute(pool)
+    .execute(executor)
     .await?;
 
     Ok(())
 }
 
 /// Update adaptive fetch state (consecutive_new_articles counter and interval)
-pub async fn update_adaptive_fetch_state(
-    pool: &SqlitePool,
+pub async fn update_adaptive_fetch_state<'e>(
+    executor: impl SqliteExecutor<'e>,
     feed_id: i64,
     consecutive_new_articles: i64,
     fetch_interval_minutes: i64,
@@ -1097,16 +2320,20 @@ pub async fn update_adaptive_fetch_state(
         fetch_interval_minutes,
         feed_id
     )
-    .execute(pool)
+    .execute(executor)
     .await?;
 
     Ok(())
 }
 
-/// Update feed's editable properties (title, URL, description, frequency and color)
+/// Update feed's editable properties (title, URL, description, frequency, color,
+/// trimming, full-content fetching, summary preference, read state of new
+/// articles and GUID strategy). A changed title is marked as custom; a `None`
+/// GUID strategy is left as is, a changed one remembers the strategy the
+/// stored GUIDs were made with.
 #[allow(clippy::too_many_arguments)]
-pub async fn update_feed_properties(
-    pool: &SqlitePool,
+pub async fn update_feed_properties<'e>(
+    executor: impl SqliteExecutor<'e>,
     feed_id: i64,
     title: &str,
     url: &str,
@@ -1115,29 +2342,124 @@ pub async fn update_feed_properties(
     fetch_interval_minutes: i64,
     color: &str,
     ignore_pattern: Option<&str>,
+    never_trim: bool,
+    auto_fetch_full_content: bool,
+    prefer_summary: bool,
+    mark_new_as_read: bool,
+    guid_strategy: Option<&str>,
+    quiet_hours: Option<&str>,
 ) -> Result<(), SqlxError> {
     sqlx::query!(
         r#"
         UPDATE feeds
-        SET title = ?,
+        SET title_is_custom = title_is_custom OR title <> ?,
+            title = ?,
             url = ?,
             description = ?,
             fetch_frequency = ?,
             fetch_interval_minutes = ?,
             color = ?,
             ignore_pattern = ?,
+            never_trim = ?,
+            auto_fetch_full_content = ?,
+            prefer_summary = ?,
+            mark_new_as_read = ?,
+            previous_guid_strategy = CASE WHEN COALESCE(?, guid_strategy) <> guid_strategy
+                THEN COALESCE(previous_guid_strategy, guid_strategy)
+                ELSE previous_guid_strategy END,
+            guid_strategy = COALESCE(?, guid_strategy),
+            quiet_hours = ?,
             updated_at = datetime('now')
         WHERE id = ?
         "#,
         title,
+        title,
         url,
         description,
         fetch_frequency,
         fetch_interval_minutes,
         color,
         ignore_pattern,
+        never_trim,
+        auto_fetch_full_content,
+        prefer_summary,
+        mark_new_as_read,
+        guid_strategy,
+        guid_strategy,
+        quiet_hours,
         feed_id
     )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_setting(pool: &SqlitePool, key: &str) -> Result<Option<String>, SqlxError> {
+    sqlx::query_scalar("SELECT value FROM settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn set_setting(pool: &SqlitePool, key: &str, value: &str) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value, updated_at)
+        VALUES (?, ?, CURRENT_TIMESTAMP)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(key)
+    .bind(value)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// The stored preferences; unknown values read as the defaults
+pub async fn get_preferences(pool: &SqlitePool) -> Result<Preferences, SqlxError> {
+    let row = sqlx::query("SELECT theme, default_view_mode FROM preferences WHERE id = 1")
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row
+        .map(|row| Preferences {
+            theme: Theme::parse(row.get("theme")).unwrap_or_default(),
+            default_view_mode: ViewMode::parse(row.get("default_view_mode")).unwrap_or_default(),
+        })
+        .unwrap_or_default())
+}
+
+pub async fn set_theme(pool: &SqlitePool, theme: Theme) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+        INSERT INTO preferences (id, theme, updated_at)
+        VALUES (1, ?, CURRENT_TIMESTAMP)
+        ON CONFLICT(id) DO UPDATE SET theme = excluded.theme, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(theme.as_str())
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn set_default_view_mode(
+    pool: &SqlitePool,
+    view_mode: ViewMode,
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+        INSERT INTO preferences (id, default_view_mode, updated_at)
+        VALUES (1, ?, CURRENT_TIMESTAMP)
+        ON CONFLICT(id) DO UPDATE
+            SET default_view_mode = excluded.default_view_mode, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(view_mode.as_str())
     .execute(pool)
     .await?;
 
@@ -1173,6 +2495,9 @@ mod tests {
             url: "https://example.com/feed".to_string(),
             title: "Test Feed".to_string(),
             description: Some("Test description".to_string()),
+            color: None,
+            fetch_frequency: None,
+            ignore_pattern: None,
         };
 
         let feed = super::create_feed(&pool, feed_data)
@@ -1192,6 +2517,41 @@ mod tests {
         assert_eq!(retrieved.url, feed.url);
     }
 
+    #[tokio::test]
+    async fn test_create_feed_color() {
+        let pool = setup_test_db().await;
+
+        let feed = super::create_feed(
+            &pool,
+            CreateFeed {
+                url: "https://example.com/a.xml".to_string(),
+                title: "A".to_string(),
+                description: None,
+                color: Some("#D9467A".to_string()),
+                fetch_frequency: None,
+                ignore_pattern: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(feed.color, "#D9467A");
+
+        let feed = super::create_feed(
+            &pool,
+            CreateFeed {
+                url: "https://example.com/b.xml".to_string(),
+                title: "B".to_string(),
+                description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(feed.color, "#3B82F6");
+    }
+
     #[tokio::test]
     async fn test_list_feeds() {
         let pool = setup_test_db().await;
@@ -1200,11 +2560,17 @@ mod tests {
             url: "https://example.com/feed1".to_string(),
             title: "Feed 1".to_string(),
             description: None,
+            color: None,
+            fetch_frequency: None,
+            ignore_pattern: None,
         };
         let feed2 = CreateFeed {
             url: "https://example.com/feed2".to_string(),
             title: "Feed 2".to_string(),
             description: None,
+            color: None,
+            fetch_frequency: None,
+            ignore_pattern: None,
         };
 
         super::create_feed(&pool, feed1).await.unwrap();
@@ -1222,6 +2588,9 @@ mod tests {
             url: "https://example.com/feed".to_string(),
             title: "Test Feed".to_string(),
             description: None,
+            color: None,
+            fetch_frequency: None,
+            ignore_pattern: None,
         };
 
         let feed = super::create_feed(&pool, create_feed_data).await.unwrap();
@@ -1251,6 +2620,9 @@ mod tests {
                 url: "https://example.com/feed".to_string(),
                 title: "Test Feed".to_string(),
                 description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
             },
         )
         .await
@@ -1267,9 +2639,11 @@ mod tests {
                 summary: Some("Summary".to_string()),
                 author: Some("Author".to_string()),
                 published_at: Some(Utc::now()),
+                updated_at_source: None,
                 og_image: None,
                 og_description: None,
                 og_site_name: None,
+                language: None,
             },
         )
         .await
@@ -1292,9 +2666,11 @@ mod tests {
                 summary: None,
                 author: None,
                 published_at: None,
+                updated_at_source: None,
                 og_image: None,
                 og_description: None,
                 og_site_name: None,
+                language: None,
             },
         )
         .await
@@ -1303,6 +2679,144 @@ mod tests {
         assert!(duplicate.is_none());
     }
 
+    #[tokio::test]
+    async fn test_empty_summary_is_backfilled_from_og_description() {
+        let pool = setup_test_db().await;
+
+        let feed = super::create_feed(
+            &pool,
+            CreateFeed {
+                url: "https://example.com/feed".to_string(),
+                title: "Test Feed".to_string(),
+                description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let mut ids = Vec::new();
+        for (guid, summary) in [("bare", None), ("summarized", Some("Feed summary"))] {
+            let article = insert_article_if_new(
+                &pool,
+                NewArticle {
+                    feed_id: feed.id,
+                    guid: guid.to_string(),
+                    title: guid.to_string(),
+                    url: Some(format!("https://example.com/{}", guid)),
+                    content: None,
+                    summary: summary.map(str::to_string),
+                    author: None,
+                    published_at: None,
+                    updated_at_source: None,
+                    og_image: None,
+                    og_description: None,
+                    og_site_name: None,
+                    language: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+            ids.push(article.id);
+        }
+
+        let updates: Vec<OpenGraphUpdate> = ids
+            .iter()
+            .map(|&article_id| OpenGraphUpdate {
+                article_id,
+                og_image: None,
+                og_description: Some("Page description".to_string()),
+                og_site_name: None,
+            })
+            .collect();
+        update_articles_opengraph(&pool, &updates).await.unwrap();
+
+        let bare = get_article_by_id(&pool, ids[0]).await.unwrap().unwrap();
+        assert_eq!(bare.summary.as_deref(), Some("Page description"));
+        assert_eq!(bare.content_preview.as_deref(), Some("Page description"));
+
+        let summarized = get_article_by_id(&pool, ids[1]).await.unwrap().unwrap();
+        assert_eq!(summarized.summary.as_deref(), Some("Feed summary"));
+        assert_eq!(
+            summarized.og_description.as_deref(),
+            Some("Page description")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_flagged_feed_inserts_articles_read() {
+        let pool = setup_test_db().await;
+
+        let mut feeds = Vec::new();
+        for name in ["reference", "news"] {
+            let feed = super::create_feed(
+                &pool,
+                CreateFeed {
+                    url: format!("https://example.com/{}.xml", name),
+                    title: name.to_string(),
+                    description: None,
+                    color: None,
+                    fetch_frequency: None,
+                    ignore_pattern: None,
+                },
+            )
+            .await
+            .unwrap();
+            feeds.push(feed);
+        }
+        let reference = &feeds[0];
+        update_feed_properties(
+            &pool,
+            reference.id,
+            &reference.title,
+            &reference.url,
+            None,
+            "adaptive",
+            60,
+            "#3B82F6",
+            None,
+            false,
+            false,
+            false,
+            true,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let mut read = Vec::new();
+        for feed in &feeds {
+            let article = insert_article_if_new(
+                &pool,
+                NewArticle {
+                    feed_id: feed.id,
+                    guid: "guid-1".to_string(),
+                    title: "Article".to_string(),
+                    url: None,
+                    content: None,
+                    summary: None,
+                    author: None,
+                    published_at: None,
+                    updated_at_source: None,
+                    og_image: None,
+                    og_description: None,
+                    og_site_name: None,
+                    language: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+            read.push(article.is_read);
+        }
+
+        assert_eq!(read, vec![true, false]);
+    }
+
     #[tokio::test]
     async fn test_update_article_read_status() {
         let pool = setup_test_db().await;
@@ -1313,6 +2827,9 @@ mod tests {
                 url: "https://example.com/feed".to_string(),
                 title: "Test Feed".to_string(),
                 description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
             },
         )
         .await
@@ -1329,9 +2846,11 @@ mod tests {
                 summary: None,
                 author: None,
                 published_at: None,
+                updated_at_source: None,
                 og_image: None,
                 og_description: None,
                 og_site_name: None,
+                language: None,
             },
         )
         .await
@@ -1349,7 +2868,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_mark_all_articles_read() {
+    async fn test_mark_unfiltered_search_results_read() {
         let pool = setup_test_db().await;
 
         let feed = super::create_feed(
@@ -1358,6 +2877,9 @@ mod tests {
                 url: "https://example.com/feed".to_string(),
                 title: "Test Feed".to_string(),
                 description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
             },
         )
         .await
@@ -1376,9 +2898,11 @@ mod tests {
                     summary: None,
                     author: None,
                     published_at: None,
+                    updated_at_source: None,
                     og_image: None,
                     og_description: None,
                     og_site_name: None,
+                    language: None,
                 },
             )
             .await
@@ -1388,7 +2912,9 @@ mod tests {
         let unread_count = get_total_unread_count(&pool).await.unwrap();
         assert_eq!(unread_count, 3);
 
-        let affected = mark_all_articles_read(&pool, None).await.unwrap();
+        let affected = mark_search_results_read(&pool, &ArticleListFilter::default())
+            .await
+            .unwrap();
         assert_eq!(affected, 3);
 
         let unread_count = get_total_unread_count(&pool).await.unwrap();
@@ -1396,7 +2922,146 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_update_feed_metadata() {
+    async fn test_set_articles_read_and_starred_status() {
+        let pool = setup_test_db().await;
+
+        let feed = super::create_feed(
+            &pool,
+            CreateFeed {
+                url: "https://example.com/feed".to_string(),
+                title: "Test Feed".to_string(),
+                description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let mut ids = Vec::new();
+        for i in 1..=3 {
+            let article = insert_article_if_new(
+                &pool,
+                NewArticle {
+                    feed_id: feed.id,
+                    guid: format!("guid-{}", i),
+                    title: format!("Article {}", i),
+                    url: None,
+                    content: None,
+                    summary: None,
+                    author: None,
+                    published_at: None,
+                    updated_at_source: None,
+                    og_image: None,
+                    og_description: None,
+                    og_site_name: None,
+                    language: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+            ids.push(article.id);
+        }
+
+        assert_eq!(set_articles_read_status(&pool, &[], true).await.unwrap(), 0);
+
+        let affected = set_articles_read_status(&pool, &ids[..2], true)
+            .await
+            .unwrap();
+        assert_eq!(affected, 2);
+        assert_eq!(get_total_unread_count(&pool).await.unwrap(), 1);
+
+        // Articles that already have the requested state are not counted
+        let affected = set_articles_read_status(&pool, &ids, true).await.unwrap();
+        assert_eq!(affected, 1);
+
+        let affected = set_articles_read_status(&pool, &ids[..1], false)
+            .await
+            .unwrap();
+        assert_eq!(affected, 1);
+        assert_eq!(get_total_unread_count(&pool).await.unwrap(), 1);
+
+        let affected = set_articles_starred_status(&pool, &ids, true)
+            .await
+            .unwrap();
+        assert_eq!(affected, 3);
+        assert_eq!(get_article_counts(&pool).await.unwrap().starred, 3);
+
+        let affected = set_articles_starred_status(&pool, &ids[1..], false)
+            .await
+            .unwrap();
+        assert_eq!(affected, 2);
+        assert_eq!(get_article_counts(&pool).await.unwrap().starred, 1);
+
+        // Unknown ids are ignored
+        let affected = set_articles_starred_status(&pool, &[9999], true)
+            .await
+            .unwrap();
+        assert_eq!(affected, 0);
+    }
+
+    #[tokio::test]
+    async fn test_apply_article_states_matches_by_url_identity() {
+        let pool = setup_test_db().await;
+
+        let feed = super::create_feed(
+            &pool,
+            CreateFeed {
+                url: "https://example.com/feed".to_string(),
+                title: "Test Feed".to_string(),
+                description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let mut ids = Vec::new();
+        for i in 1..=2 {
+            let article = insert_article_if_new(
+                &pool,
+                NewArticle {
+                    feed_id: feed.id,
+                    guid: format!("guid-{}", i),
+                    title: format!("Article {}", i),
+                    url: Some(format!("https://example.com/posts/{}/", i)),
+                    content: None,
+                    summary: None,
+                    author: None,
+                    published_at: None,
+                    updated_at_source: None,
+                    og_image: None,
+                    og_description: None,
+                    og_site_name: None,
+                    language: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+            ids.push(article.id);
+        }
+
+        let states = vec![
+            ("http://example.com/posts/1".to_string(), true, true),
+            ("https://example.com/posts/2/".to_string(), true, false),
+            ("https://example.com/posts/3".to_string(), true, true),
+        ];
+        let unmatched = apply_article_states(&pool, &states).await.unwrap();
+        assert_eq!(unmatched, vec!["https://example.com/posts/3".to_string()]);
+
+        let first = get_article_by_id(&pool, ids[0]).await.unwrap().unwrap();
+        assert!(first.is_read && first.is_starred);
+        let second = get_article_by_id(&pool, ids[1]).await.unwrap().unwrap();
+        assert!(second.is_read && !second.is_starred);
+    }
+
+    #[tokio::test]
+    async fn test_update_feed_details_stores_validators() {
         let pool = setup_test_db().await;
 
         let feed = super::create_feed(
@@ -1405,6 +3070,9 @@ mod tests {
                 url: "https://example.com/feed".to_string(),
                 title: "Test Feed".to_string(),
                 description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
             },
         )
         .await
@@ -1413,9 +3081,13 @@ mod tests {
         assert!(feed.etag.is_none());
         assert!(feed.last_modified.is_none());
 
-        update_feed_metadata(
+        update_feed_details(
             &pool,
             feed.id,
+            None,
+            None,
+            None,
+            None,
             Some("etag-123".to_string()),
             Some("Mon, 01 Jan 2024 00:00:00 GMT".to_string()),
         )
@@ -1431,35 +3103,128 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_list_articles_with_filters() {
+    async fn test_weak_and_strong_etags_are_stored_verbatim() {
         let pool = setup_test_db().await;
-
         let feed = super::create_feed(
             &pool,
             CreateFeed {
                 url: "https://example.com/feed".to_string(),
                 title: "Test Feed".to_string(),
                 description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
             },
         )
         .await
         .unwrap();
 
-        // Insert articles with different read statuses
-        let article1 = insert_article_if_new(
-            &pool,
-            NewArticle {
-                feed_id: feed.id,
-                guid: "guid-1".to_string(),
-                title: "Unread Article".to_string(),
-                url: None,
-                content: None,
-                summary: None,
+        let weak = Some(r#"W/"abc""#.to_string());
+        update_feed_details(&pool, feed.id, None, None, None, None, weak, None)
+            .await
+            .unwrap();
+        let stored = get_feed_by_id(&pool, feed.id).await.unwrap().unwrap();
+        assert_eq!(stored.etag.as_deref(), Some(r#"W/"abc""#));
+
+        // A 304 switching to the strong form replaces the weak validator
+        update_feed_etag(&pool, feed.id, r#""abc""#).await.unwrap();
+        let stored = get_feed_by_id(&pool, feed.id).await.unwrap().unwrap();
+        assert_eq!(stored.etag.as_deref(), Some(r#""abc""#));
+    }
+
+    #[tokio::test]
+    async fn test_insert_log_with_fetch_stats() {
+        let pool = setup_test_db().await;
+
+        let feed = super::create_feed(
+            &pool,
+            CreateFeed {
+                url: "https://example.com/feed".to_string(),
+                title: "Test Feed".to_string(),
+                description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        insert_log(
+            &pool,
+            feed.id,
+            "success",
+            None,
+            None,
+            None,
+            Some(250),
+            Some(12),
+        )
+        .await
+        .unwrap();
+        insert_log(
+            &pool,
+            feed.id,
+            "error",
+            Some(500),
+            Some("boom"),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let logs = list_logs_with_feeds(&pool, Some(feed.id), None, Some("success"), 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].log.duration_ms, Some(250));
+        assert_eq!(logs[0].log.entry_count, Some(12));
+
+        let logs = list_logs_with_feeds(&pool, Some(feed.id), None, Some("error"), 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert!(logs[0].log.duration_ms.is_none());
+        assert!(logs[0].log.entry_count.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_articles_with_filters() {
+        let pool = setup_test_db().await;
+
+        let feed = super::create_feed(
+            &pool,
+            CreateFeed {
+                url: "https://example.com/feed".to_string(),
+                title: "Test Feed".to_string(),
+                description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        // Insert articles with different read statuses
+        let article1 = insert_article_if_new(
+            &pool,
+            NewArticle {
+                feed_id: feed.id,
+                guid: "guid-1".to_string(),
+                title: "Unread Article".to_string(),
+                url: None,
+                content: None,
+                summary: None,
                 author: None,
                 published_at: None,
+                updated_at_source: None,
                 og_image: None,
                 og_description: None,
                 og_site_name: None,
+                language: None,
             },
         )
         .await
@@ -1477,9 +3242,11 @@ mod tests {
                 summary: None,
                 author: None,
                 published_at: None,
+                updated_at_source: None,
                 og_image: None,
                 og_description: None,
                 og_site_name: None,
+                language: None,
             },
         )
         .await
@@ -1491,24 +3258,1007 @@ mod tests {
             .unwrap();
 
         // Test filter by unread
-        let unread =
-            list_articles_with_feeds(&pool, None, Some(false), None, None, None, None, 10, 0)
-                .await
-                .unwrap();
+        let unread = list_articles_with_feeds(
+            &pool,
+            &ArticleListFilter {
+                is_read: Some(false),
+                ..Default::default()
+            },
+            10,
+            0,
+        )
+        .await
+        .unwrap();
         assert_eq!(unread.len(), 1);
         assert_eq!(unread[0].article.id, article1.id);
 
         // Test filter by read
-        let read = list_articles_with_feeds(&pool, None, Some(true), None, None, None, None, 10, 0)
-            .await
-            .unwrap();
+        let read = list_articles_with_feeds(
+            &pool,
+            &ArticleListFilter {
+                is_read: Some(true),
+                ..Default::default()
+            },
+            10,
+            0,
+        )
+        .await
+        .unwrap();
         assert_eq!(read.len(), 1);
         assert_eq!(read[0].article.id, article2.id);
 
         // Test no filter
-        let all = list_articles_with_feeds(&pool, None, None, None, None, None, None, 10, 0)
+        let all = list_articles_with_feeds(&pool, &ArticleListFilter::default(), 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 2);
+
+        // Read later is independent of read state
+        update_article_read_later_status(&pool, article2.id, true)
+            .await
+            .unwrap();
+        let read_later = list_articles_with_feeds(
+            &pool,
+            &ArticleListFilter {
+                is_read_later: Some(true),
+                ..Default::default()
+            },
+            10,
+            0,
+        )
+        .await
+        .unwrap();
+        assert_eq!(read_later.len(), 1);
+        assert_eq!(read_later[0].article.id, article2.id);
+        assert!(read_later[0].article.is_read_later);
+        assert_eq!(get_article_counts(&pool).await.unwrap().read_later, 1);
+    }
+
+    #[tokio::test]
+    async fn test_list_articles_filtered_by_language() {
+        let pool = setup_test_db().await;
+        let feed = create_feed(
+            &pool,
+            CreateFeed {
+                url: "https://example.com/feed".to_string(),
+                title: "Mixed Feed".to_string(),
+                description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
+            },
+        )
+        .await
+        .unwrap();
+        update_feed_details(
+            &pool,
+            feed.id,
+            None,
+            None,
+            None,
+            Some("de".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let articles = [
+            ("english", Some("en-US")),
+            ("german", None),
+            ("british", Some("en-GB")),
+            ("french", Some("fr")),
+        ];
+        for (guid, language) in articles {
+            insert_articles_if_new(
+                &pool,
+                &[NewArticle {
+                    feed_id: feed.id,
+                    guid: guid.to_string(),
+                    title: guid.to_string(),
+                    url: None,
+                    content: None,
+                    summary: None,
+                    author: None,
+                    published_at: None,
+                    updated_at_source: None,
+                    og_image: None,
+                    og_description: None,
+                    og_site_name: None,
+                    language: language.map(String::from),
+                }],
+            )
+            .await
+            .unwrap();
+        }
+
+        let titles = |language: &'static str| {
+            let pool = pool.clone();
+            async move {
+                let mut titles: Vec<String> = list_articles_with_feeds(
+                    &pool,
+                    &ArticleListFilter {
+                        language: Some(language.to_string()),
+                        ..Default::default()
+                    },
+                    10,
+                    0,
+                )
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|item| item.article.title)
+                .collect();
+                titles.sort();
+                titles
+            }
+        };
+
+        // A primary language matches its regional variants
+        assert_eq!(titles("en").await, vec!["british", "english"]);
+        assert_eq!(titles("en-us").await, vec!["english"]);
+        // Entries without a language inherit the feed's
+        assert_eq!(titles("de").await, vec!["german"]);
+        assert!(titles("es").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_articles_filtered_by_feed_or_article_tag() {
+        let pool = setup_test_db().await;
+
+        let mut feed_ids = Vec::new();
+        for name in ["tagged", "untagged"] {
+            let feed = super::create_feed(
+                &pool,
+                CreateFeed {
+                    url: format!("https://example.com/{}", name),
+                    title: name.to_string(),
+                    description: None,
+                    color: None,
+                    fetch_frequency: None,
+                    ignore_pattern: None,
+                },
+            )
+            .await
+            .unwrap();
+            for n in 1..=2 {
+                insert_article_if_new(
+                    &pool,
+                    NewArticle {
+                        feed_id: feed.id,
+                        guid: format!("{}-{}", name, n),
+                        title: format!("{} {}", name, n),
+                        url: None,
+                        content: None,
+                        summary: None,
+                        author: None,
+                        published_at: None,
+                        updated_at_source: None,
+                        og_image: None,
+                        og_description: None,
+                        og_site_name: None,
+                        language: None,
+                    },
+                )
+                .await
+                .unwrap();
+            }
+            feed_ids.push(feed.id);
+        }
+
+        let feed_tag = create_tag(&pool, "news", "#000000", "solid").await.unwrap();
+        set_feed_tags(
+            &mut pool.acquire().await.unwrap(),
+            feed_ids[0],
+            &[feed_tag.id],
+        )
+        .await
+        .unwrap();
+        // Only one article of the untagged feed carries the category
+        let article = get_article_by_guid(&pool, feed_ids[1], "untagged-1")
+            .await
+            .unwrap()
+            .unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        add_article_tags(&mut conn, article.id, &["rust".to_string()])
+            .await
+            .unwrap();
+        drop(conn);
+        let article_tag = list_tags(&pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|tag| tag.name == "rust")
+            .unwrap();
+
+        let titles = |tag_ids: Vec<i64>| {
+            let pool = pool.clone();
+            async move {
+                let filter = ArticleListFilter {
+                    tag_ids,
+                    ..Default::default()
+                };
+                let mut titles: Vec<String> = list_articles_with_feeds(&pool, &filter, 10, 0)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|item| item.article.title)
+                    .collect();
+                titles.sort();
+                titles
+            }
+        };
+
+        assert_eq!(titles(vec![article_tag.id]).await, vec!["untagged 1"]);
+        assert_eq!(
+            titles(vec![feed_tag.id]).await,
+            vec!["tagged 1", "tagged 2"]
+        );
+        assert_eq!(
+            titles(vec![feed_tag.id, article_tag.id]).await,
+            vec!["tagged 1", "tagged 2", "untagged 1"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_muted_feed_is_hidden_unless_selected() {
+        let pool = setup_test_db().await;
+
+        let mut feed_ids = Vec::new();
+        for name in ["loud", "quiet"] {
+            let feed = super::create_feed(
+                &pool,
+                CreateFeed {
+                    url: format!("https://example.com/{}", name),
+                    title: name.to_string(),
+                    description: None,
+                    color: None,
+                    fetch_frequency: None,
+                    ignore_pattern: None,
+                },
+            )
+            .await
+            .unwrap();
+            insert_article_if_new(
+                &pool,
+                NewArticle {
+                    feed_id: feed.id,
+                    guid: format!("{}-1", name),
+                    title: format!("From {}", name),
+                    url: None,
+                    content: None,
+                    summary: None,
+                    author: None,
+                    published_at: None,
+                    updated_at_source: None,
+                    og_image: None,
+                    og_description: None,
+                    og_site_name: None,
+                    language: None,
+                },
+            )
+            .await
+            .unwrap();
+            feed_ids.push(feed.id);
+        }
+        let quiet = feed_ids[1];
+
+        assert!(toggle_feed_muted(&pool, quiet).await.unwrap());
+        assert!(get_feed_by_id(&pool, quiet).await.unwrap().unwrap().muted);
+
+        let all = list_articles_with_feeds(&pool, &ArticleListFilter::default(), 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].feed_title, "loud");
+        assert_eq!(get_article_counts(&pool).await.unwrap().unread, 1);
+        assert_eq!(get_total_unread_count(&pool).await.unwrap(), 1);
+
+        let selected = list_articles_with_feeds(
+            &pool,
+            &ArticleListFilter {
+                feed_ids: Some(vec![quiet]),
+                ..Default::default()
+            },
+            10,
+            0,
+        )
+        .await
+        .unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].article.title, "From quiet");
+        assert!(selected[0].feed_muted);
+
+        // Starred and read-later articles of a muted feed stay listed
+        let quiet_article = selected[0].article.id;
+        update_article_starred_status(&pool, quiet_article, true)
+            .await
+            .unwrap();
+        update_article_read_later_status(&pool, quiet_article, true)
+            .await
+            .unwrap();
+        for filter in [
+            ArticleListFilter {
+                is_starred: Some(true),
+                ..Default::default()
+            },
+            ArticleListFilter {
+                is_read_later: Some(true),
+                ..Default::default()
+            },
+        ] {
+            let listed = list_articles_with_feeds(&pool, &filter, 10, 0)
+                .await
+                .unwrap();
+            assert_eq!(listed.len(), 1);
+            assert_eq!(listed[0].article.id, quiet_article);
+        }
+        let counts = get_article_counts(&pool).await.unwrap();
+        assert_eq!(
+            (counts.unread, counts.starred, counts.read_later),
+            (1, 1, 1)
+        );
+
+        // Unmuting brings the articles back
+        toggle_feed_muted(&pool, quiet).await.unwrap();
+        let all = list_articles_with_feeds(&pool, &ArticleListFilter::default(), 10, 0)
             .await
             .unwrap();
         assert_eq!(all.len(), 2);
     }
+
+    /// Article of `feed_id` published `days_ago` days ago, under its title as GUID
+    fn dated_article(feed_id: i64, title: &str, days_ago: i64) -> NewArticle {
+        NewArticle {
+            feed_id,
+            guid: title.to_string(),
+            title: title.to_string(),
+            url: None,
+            content: None,
+            summary: None,
+            author: None,
+            published_at: Some(Utc::now() - chrono::Duration::days(days_ago)),
+            updated_at_source: None,
+            og_image: None,
+            og_description: None,
+            og_site_name: None,
+            language: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_trim_articles_deletes_oldest_read_articles_first() {
+        let pool = setup_test_db().await;
+        let feed = super::create_feed(
+            &pool,
+            CreateFeed {
+                url: "https://example.com/feed".to_string(),
+                title: "Test Feed".to_string(),
+                description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
+            },
+        )
+        .await
+        .unwrap();
+        let saved = get_or_create_saved_feed(&pool).await.unwrap();
+
+        // Oldest first; everything but "unread" has been read
+        let titles = ["saved", "starred", "later", "unread", "old", "older", "new"];
+        let mut ids = HashMap::new();
+        for (n, title) in titles.into_iter().enumerate() {
+            let feed_id = if title == "saved" { saved.id } else { feed.id };
+            let article =
+                insert_article_if_new(&pool, dated_article(feed_id, title, 20 - n as i64))
+                    .await
+                    .unwrap()
+                    .unwrap();
+            if title != "unread" {
+                update_article_read_status(&pool, article.id, true)
+                    .await
+                    .unwrap();
+            }
+            ids.insert(title, article.id);
+        }
+        update_article_starred_status(&pool, ids["starred"], true)
+            .await
+            .unwrap();
+        update_article_read_later_status(&pool, ids["later"], true)
+            .await
+            .unwrap();
+
+        // Read articles go first, while unread, flagged and saved ones stay
+        assert_eq!(trim_articles(&pool, 5).await.unwrap(), 2);
+        for title in titles {
+            let kept = get_article_by_id(&pool, ids[title])
+                .await
+                .unwrap()
+                .is_some();
+            assert_eq!(kept, !["old", "older"].contains(&title), "{}", title);
+        }
+        assert_eq!(trim_articles(&pool, 4).await.unwrap(), 1);
+        assert!(get_article_by_id(&pool, ids["new"])
+            .await
+            .unwrap()
+            .is_none());
+
+        // Without read articles left, unread ones go too; flagged and saved
+        // ones never do
+        assert_eq!(trim_articles(&pool, 3).await.unwrap(), 1);
+        assert!(get_article_by_id(&pool, ids["unread"])
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(trim_articles(&pool, 0).await.unwrap(), 0);
+
+        // A trimmed entry that the feed still carries is not stored again
+        let batch = [
+            dated_article(feed.id, "old", 16),
+            dated_article(feed.id, "newer", 0),
+        ];
+        let inserted = insert_articles_if_new(&pool, &batch).await.unwrap();
+        assert_eq!(inserted.len(), 1);
+        assert_eq!(inserted[0].title, "newer");
+        assert!(
+            insert_article_if_new(&pool, dated_article(feed.id, "new", 14))
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prune_trimmed_articles_keeps_carried_guids() {
+        let pool = setup_test_db().await;
+        let feed = super::create_feed(
+            &pool,
+            CreateFeed {
+                url: "https://example.com/feed".to_string(),
+                title: "Test Feed".to_string(),
+                description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
+            },
+        )
+        .await
+        .unwrap();
+        for title in ["gone", "carried"] {
+            let article = insert_article_if_new(&pool, dated_article(feed.id, title, 10))
+                .await
+                .unwrap()
+                .unwrap();
+            update_article_read_status(&pool, article.id, true)
+                .await
+                .unwrap();
+        }
+        assert_eq!(trim_articles(&pool, 0).await.unwrap(), 2);
+
+        let carried = dated_article(feed.id, "carried", 10);
+        let pruned = prune_trimmed_articles(&pool, feed.id, std::slice::from_ref(&carried.guid))
+            .await
+            .unwrap();
+        assert_eq!(pruned, 1);
+
+        // The entry still in the feed stays trimmed, the other may return
+        assert!(insert_article_if_new(&pool, carried)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(
+            insert_article_if_new(&pool, dated_article(feed.id, "gone", 10))
+                .await
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_share_token_lifecycle() {
+        let pool = setup_test_db().await;
+        let group = create_group(&pool, "Shared", None).await.unwrap();
+        let other = create_group(&pool, "Other", None).await.unwrap();
+
+        create_share_token(&pool, group.id, "token-a")
+            .await
+            .unwrap();
+        create_share_token(&pool, group.id, "token-b")
+            .await
+            .unwrap();
+
+        let tokens = list_share_tokens(&pool, group.id).await.unwrap();
+        assert_eq!(tokens.len(), 2);
+        let share = get_share_token(&pool, "token-a").await.unwrap().unwrap();
+        assert_eq!(share.group_id, group.id);
+        assert!(get_share_token(&pool, "missing").await.unwrap().is_none());
+
+        // Revoking through the wrong group is a no-op
+        delete_share_token(&pool, other.id, share.id).await.unwrap();
+        assert!(get_share_token(&pool, "token-a").await.unwrap().is_some());
+
+        delete_share_token(&pool, group.id, share.id).await.unwrap();
+        assert!(get_share_token(&pool, "token-a").await.unwrap().is_none());
+
+        // Deleting the group revokes its remaining tokens
+        delete_group(&pool, group.id).await.unwrap();
+        assert!(get_share_token(&pool, "token-b").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_never_trim_feed_keeps_its_articles() {
+        let pool = setup_test_db().await;
+        let feed = super::create_feed(
+            &pool,
+            CreateFeed {
+                url: "https://example.com/feed".to_string(),
+                title: "Reference".to_string(),
+                description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert!(!feed.never_trim);
+
+        update_feed_properties(
+            &pool,
+            feed.id,
+            "Reference",
+            &feed.url,
+            None,
+            "adaptive",
+            60,
+            "#3B82F6",
+            None,
+            true,
+            false,
+            false,
+            false,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let feed = get_feed_by_id(&pool, feed.id).await.unwrap().unwrap();
+        assert!(feed.never_trim);
+
+        // Old read articles of the feed survive any cap
+        let article = insert_article_if_new(&pool, dated_article(feed.id, "kept", 365))
+            .await
+            .unwrap()
+            .unwrap();
+        update_article_read_status(&pool, article.id, true)
+            .await
+            .unwrap();
+        assert_eq!(trim_articles(&pool, 0).await.unwrap(), 0);
+        assert!(get_article_by_id(&pool, article.id)
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_list_feeds_paged_searches_title_and_url() {
+        let pool = setup_test_db().await;
+        for (url, title) in [
+            ("https://blog.rust-lang.org/feed.xml", "Rust Blog"),
+            ("https://example.com/rust.xml", "Weekly Digest"),
+            ("https://news.example.org/feed", "World News"),
+        ] {
+            super::create_feed(
+                &pool,
+                CreateFeed {
+                    url: url.to_string(),
+                    title: title.to_string(),
+                    description: None,
+                    color: None,
+                    fetch_frequency: None,
+                    ignore_pattern: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let filter = FeedListFilter {
+            search: Some("rust".to_string()),
+            group_id: None,
+        };
+        let mut titles: Vec<String> = list_feeds_paged(&pool, &filter, 10, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|f| f.title)
+            .collect();
+        titles.sort();
+        assert_eq!(titles, vec!["Rust Blog", "Weekly Digest"]);
+
+        let page = list_feeds_paged(&pool, &FeedListFilter::default(), 2, 2)
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_list_articles_created_after() {
+        let pool = setup_test_db().await;
+        let feed = super::create_feed(
+            &pool,
+            CreateFeed {
+                url: "https://example.com/feed".to_string(),
+                title: "Test Feed".to_string(),
+                description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
+            },
+        )
+        .await
+        .unwrap();
+        for guid in ["old", "new"] {
+            insert_article_if_new(
+                &pool,
+                NewArticle {
+                    feed_id: feed.id,
+                    guid: guid.to_string(),
+                    title: guid.to_string(),
+                    url: None,
+                    content: None,
+                    summary: None,
+                    author: None,
+                    published_at: None,
+                    updated_at_source: None,
+                    og_image: None,
+                    og_description: None,
+                    og_site_name: None,
+                    language: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+        let last_visit = Utc::now() - chrono::Duration::hours(1);
+        sqlx::query("UPDATE articles SET created_at = ? WHERE guid = 'old'")
+            .bind(last_visit - chrono::Duration::hours(1))
+            .execute(&pool)
+            .await
+            .unwrap();
+        // Read articles still count as new when they arrived after the visit
+        sqlx::query("UPDATE articles SET is_read = 1 WHERE guid = 'new'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let articles = list_articles_with_feeds(
+            &pool,
+            &ArticleListFilter {
+                created_after: Some(last_visit),
+                ..Default::default()
+            },
+            10,
+            0,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].article.guid, "new");
+    }
+
+    #[tokio::test]
+    async fn test_pages_of_articles_with_equal_timestamps_dont_overlap() {
+        let pool = setup_test_db().await;
+        let feed = super::create_feed(
+            &pool,
+            CreateFeed {
+                url: "https://example.com/feed".to_string(),
+                title: "Test Feed".to_string(),
+                description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
+            },
+        )
+        .await
+        .unwrap();
+        let published_at = Utc::now() - chrono::Duration::hours(1);
+        for i in 0..7 {
+            insert_article_if_new(
+                &pool,
+                NewArticle {
+                    feed_id: feed.id,
+                    guid: format!("guid-{}", i),
+                    title: format!("Article {}", i),
+                    url: None,
+                    content: None,
+                    summary: None,
+                    author: None,
+                    published_at: Some(published_at),
+                    updated_at_source: None,
+                    og_image: None,
+                    og_description: None,
+                    og_site_name: None,
+                    language: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+        // Same batch: identical created_at as well
+        sqlx::query("UPDATE articles SET created_at = ?")
+            .bind(published_at)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let mut seen = Vec::new();
+        for offset in (0..7).step_by(3) {
+            let page = list_articles_with_feeds(&pool, &ArticleListFilter::default(), 3, offset)
+                .await
+                .unwrap();
+            seen.extend(page.into_iter().map(|a| a.article.id));
+        }
+
+        let mut expected: Vec<i64> = seen.clone();
+        expected.sort_unstable_by(|a, b| b.cmp(a));
+        expected.dedup();
+        assert_eq!(seen.len(), 7);
+        assert_eq!(seen, expected);
+    }
+
+    #[tokio::test]
+    async fn test_search_matches_author_and_feed_title() {
+        let pool = setup_test_db().await;
+        let feed = super::create_feed(
+            &pool,
+            CreateFeed {
+                url: "https://example.com/feed".to_string(),
+                title: "Planet Ferris".to_string(),
+                description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
+            },
+        )
+        .await
+        .unwrap();
+        for (guid, author) in [("a", "Grace Hopper"), ("b", "Alan Turing")] {
+            insert_article_if_new(
+                &pool,
+                NewArticle {
+                    feed_id: feed.id,
+                    guid: guid.to_string(),
+                    title: "Untitled".to_string(),
+                    url: None,
+                    content: None,
+                    summary: None,
+                    author: Some(author.to_string()),
+                    published_at: None,
+                    updated_at_source: None,
+                    og_image: None,
+                    og_description: None,
+                    og_site_name: None,
+                    language: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let search = |query: &str| {
+            let pool = pool.clone();
+            let filter = ArticleListFilter {
+                search_query: Some(query.to_string()),
+                ..Default::default()
+            };
+            async move { list_articles_with_feeds(&pool, &filter, 10, 0).await }
+        };
+
+        let by_author = search("hopper").await.unwrap();
+        assert_eq!(by_author.len(), 1);
+        assert_eq!(by_author[0].article.guid, "a");
+        assert_eq!(search("ferris").await.unwrap().len(), 2);
+
+        // Renaming the feed carries over into the index
+        sqlx::query("UPDATE feeds SET title = 'Crustacean Weekly' WHERE id = ?")
+            .bind(feed.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(search("ferris").await.unwrap().is_empty());
+        assert_eq!(search("crustacean").await.unwrap().len(), 2);
+
+        // A wiped index is restored by a rebuild
+        sqlx::query("DELETE FROM articles_fts")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(search("turing").await.unwrap().is_empty());
+        assert_eq!(rebuild_fts(&pool).await.unwrap(), 2);
+        assert_eq!(search("turing").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_feeds_to_update_rotate_across_cycles() {
+        let pool = setup_test_db().await;
+        for name in ["a", "b", "c"] {
+            super::create_feed(
+                &pool,
+                CreateFeed {
+                    url: format!("https://example.com/{}.xml", name),
+                    title: name.to_string(),
+                    description: None,
+                    color: None,
+                    fetch_frequency: None,
+                    ignore_pattern: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+        // Every feed stays due, as if each cycle ran longer than its interval
+        sqlx::query("UPDATE feeds SET fetch_interval_minutes = 0")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let mut cycles = Vec::new();
+        let mut clock = Utc::now() - chrono::Duration::hours(1);
+        for _ in 0..3 {
+            let batch = get_feeds_to_update(&pool, 2, NaiveTime::MIN).await.unwrap();
+            for feed in &batch {
+                clock += chrono::Duration::minutes(1);
+                sqlx::query("UPDATE feeds SET last_fetched_at = ? WHERE id = ?")
+                    .bind(clock)
+                    .bind(feed.id)
+                    .execute(&pool)
+                    .await
+                    .unwrap();
+            }
+            cycles.push(batch.into_iter().map(|f| f.title).collect::<Vec<_>>());
+        }
+
+        assert_eq!(cycles, vec![vec!["a", "b"], vec!["c", "a"], vec!["b", "c"]]);
+        assert_eq!(
+            get_feeds_to_update(&pool, 0, NaiveTime::MIN)
+                .await
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+
+    #[tokio::test]
+    async fn test_feed_in_quiet_hours_is_not_due() {
+        let pool = setup_test_db().await;
+        for (name, quiet_hours) in [("night", Some("22:00-07:00")), ("always", None)] {
+            let feed = super::create_feed(
+                &pool,
+                CreateFeed {
+                    url: format!("https://example.com/{}.xml", name),
+                    title: name.to_string(),
+                    description: None,
+                    color: None,
+                    fetch_frequency: None,
+                    ignore_pattern: None,
+                },
+            )
+            .await
+            .unwrap();
+            sqlx::query("UPDATE feeds SET quiet_hours = ? WHERE id = ?")
+                .bind(quiet_hours)
+                .bind(feed.id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let due_at = |hour, minute| {
+            let pool = pool.clone();
+            async move {
+                let time = NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+                get_feeds_to_update(&pool, 0, time)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|feed| feed.title)
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(due_at(23, 30).await, vec!["always"]);
+        assert_eq!(due_at(6, 59).await, vec!["always"]);
+        // Still due once the window is over
+        assert_eq!(due_at(7, 0).await, vec!["night", "always"]);
+    }
+
+    #[tokio::test]
+    async fn test_feed_credentials_are_stored_encrypted() {
+        let pool = setup_test_db().await;
+        let feed = create_feed(
+            &pool,
+            CreateFeed {
+                url: "https://example.com/private.xml".to_string(),
+                title: "Private".to_string(),
+                description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
+            },
+        )
+        .await
+        .unwrap();
+        let stored = |query: &'static str| {
+            let pool = pool.clone();
+            async move {
+                sqlx::query_scalar::<_, String>(query)
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let key = SecretKey::random();
+        set_feed_bearer_token(&pool, feed.id, Some("token-123"), &key)
+            .await
+            .unwrap();
+        let headers = vec![
+            (
+                "Authorization".to_string(),
+                "Basic dXNlcjpwYXNz".to_string(),
+            ),
+            ("Referer".to_string(), "https://example.com/".to_string()),
+        ];
+        set_feed_headers(&mut pool.acquire().await.unwrap(), feed.id, &headers, &key)
+            .await
+            .unwrap();
+
+        assert!(!stored("SELECT bearer_token FROM feeds")
+            .await
+            .contains("token-123"));
+        assert!(
+            !stored("SELECT value FROM feed_headers WHERE name = 'Authorization'")
+                .await
+                .contains("dXNlcjpwYXNz")
+        );
+        assert_eq!(
+            stored("SELECT value FROM feed_headers WHERE name = 'Referer'").await,
+            "https://example.com/"
+        );
+        assert_eq!(
+            get_feed_bearer_token(&pool, feed.id, &key)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("token-123")
+        );
+        let loaded: Vec<_> = get_feed_headers(&pool, feed.id, &key)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|h| (h.name, h.value))
+            .collect();
+        assert_eq!(loaded, headers);
+
+        // Credentials stored in plain text before encryption are sealed
+        sqlx::query("UPDATE feeds SET bearer_token = 'legacy-token'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(seal_stored_credentials(&pool, &key).await.unwrap(), 1);
+        assert!(secrets::is_sealed(
+            &stored("SELECT bearer_token FROM feeds").await
+        ));
+        assert_eq!(
+            get_feed_bearer_token(&pool, feed.id, &key)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("legacy-token")
+        );
+    }
 }
@@ -0,0 +1,78 @@
+//! Reading response bodies of pages, feeds and images fetched from third
+//! parties, none of which may grow past a size limit in memory.
+
+use reqwest::Response;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum BodyError {
+    #[error("Body is larger than the limit")]
+    TooLarge,
+
+    #[error("Reading the body failed: {0}")]
+    Request(#[from] reqwest::Error),
+}
+
+/// Read a response body of at most `max` bytes. An announced length over
+/// the limit is refused before anything is downloaded.
+pub async fn read_capped(mut response: Response, max: usize) -> Result<Vec<u8>, BodyError> {
+    if response
+        .content_length()
+        .is_some_and(|length| length > max as u64)
+    {
+        return Err(BodyError::TooLarge);
+    }
+
+    // The declared length may be missing or wrong, so count while reading
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > max {
+            return Err(BodyError::TooLarge);
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serve one response from a local server; returns its URL
+    async fn serve(response: &'static str) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+            let _ = socket.write_all(response.as_bytes()).await;
+        });
+        format!("http://{addr}/")
+    }
+
+    #[tokio::test]
+    async fn test_read_capped_refuses_larger_bodies() {
+        let url = serve("HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello").await;
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(read_capped(response, 5).await.unwrap(), b"hello");
+
+        // Refused by the announced length
+        let url = serve("HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello").await;
+        let response = reqwest::get(&url).await.unwrap();
+        assert!(matches!(
+            read_capped(response, 4).await,
+            Err(BodyError::TooLarge)
+        ));
+
+        // Refused while reading, without an announced length
+        let url = serve("HTTP/1.1 200 OK\r\nConnection: close\r\n\r\nhello").await;
+        let response = reqwest::get(&url).await.unwrap();
+        assert!(matches!(
+            read_capped(response, 4).await,
+            Err(BodyError::TooLarge)
+        ));
+    }
+}
@@ -1,8 +1,15 @@
+use crate::config::HostPolicy;
+use crate::domain::models::FeedTtl;
+use crate::infrastructure::response_body::{read_capped, BodyError};
 use crate::infrastructure::ssrf;
+use dom_query::Document;
+use encoding_rs::{Encoding, UTF_8};
 use feed_rs::parser;
 use quick_xml::events::Event;
 use quick_xml::Reader;
-use reqwest::{header, Client, StatusCode};
+use reqwest::{header, Client, Response, StatusCode};
+use std::borrow::Cow;
+use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
 
@@ -27,6 +34,20 @@ pub enum FetchError {
 
     #[error("URL blocked: points to internal/private network")]
     SsrfBlocked,
+
+    #[error("Host not allowed for feeds: {0}")]
+    HostNotAllowed(String),
+
+    #[error("Not a feed: server sent {0}")]
+    NotAFeed(String),
+
+    #[error("Page is larger than {MAX_PAGE_BYTES} bytes")]
+    PageTooLarge,
+
+    /// 401 with a `WWW-Authenticate` challenge: the feed's credentials are
+    /// missing, wrong or expired
+    #[error("Authentication failed or expired ({challenge}), update the feed's credentials")]
+    AuthenticationFailed { challenge: String },
 }
 
 pub enum FetchResult {
@@ -34,25 +55,108 @@ pub enum FetchResult {
         feed: Box<feed_rs::model::Feed>,
         etag: Option<String>,
         last_modified: Option<String>,
+        /// The feed's `<ttl>`, else the caching hint of the response
+        ttl: Option<FeedTtl>,
+    },
+    /// Unchanged since the cached validators; carries the ETag the server sent
+    /// along, which may differ from the stored one (e.g. strong vs. weak), and
+    /// the caching hint of the response in minutes
+    NotModified {
+        etag: Option<String>,
         ttl: Option<i64>,
     },
-    NotModified,
 }
 
+/// Read the body of an HTML page, refusing pages over `MAX_PAGE_BYTES`
+async fn read_page(response: Response) -> Result<String, FetchError> {
+    let charset = header_charset(response.headers());
+    let body = read_capped(response, MAX_PAGE_BYTES)
+        .await
+        .map_err(|e| match e {
+            BodyError::TooLarge => FetchError::PageTooLarge,
+            BodyError::Request(e) => FetchError::NetworkError(e),
+        })?;
+
+    let body = match charset {
+        Some(encoding) => encoding.decode(&body).0,
+        None => String::from_utf8_lossy(&body),
+    };
+    Ok(body.into_owned())
+}
+
+/// The `WWW-Authenticate` challenge of a 401 response
+fn auth_challenge(status: StatusCode, headers: &header::HeaderMap) -> Option<String> {
+    if status != StatusCode::UNAUTHORIZED {
+        return None;
+    }
+    headers
+        .get(header::WWW_AUTHENTICATE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Headers that per-feed custom headers may not set.
+///
+/// Hop-by-hop and connection-level headers are managed by the HTTP client,
+/// and the conditional GET headers are managed by the fetcher itself.
+pub const FORBIDDEN_CUSTOM_HEADERS: &[&str] = &[
+    "connection",
+    "content-length",
+    "host",
+    "if-modified-since",
+    "if-none-match",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// `type` values of `<link rel="alternate">` elements that point to a feed.
+///
+/// JSON feeds need no special handling past discovery: the feed parser
+/// detects JSON Feed documents alongside RSS and Atom.
+const FEED_LINK_TYPES: &[&str] = &[
+    "application/rss+xml",
+    "application/atom+xml",
+    "application/feed+json",
+    "application/json",
+];
+
+/// Redirects followed per request
+const MAX_REDIRECTS: usize = 10;
+
+/// Largest HTML page read when looking for the feed it advertises
+const MAX_PAGE_BYTES: usize = 2 * 1024 * 1024;
+
 pub struct RssFetcher {
     client: Client,
+    /// Hosts feeds may be fetched from
+    host_policy: Arc<HostPolicy>,
 }
 
 impl RssFetcher {
-    pub fn new() -> Result<Self, FetchError> {
-        let client = Client::builder()
+    pub fn new(host_policy: &HostPolicy) -> Result<Self, FetchError> {
+        let builder = Client::builder()
             .user_agent(crate::user_agent())
             .gzip(true)
             .brotli(true)
-            .timeout(Duration::from_secs(30))
-            .build()?;
+            .timeout(Duration::from_secs(30));
+        // Redirect targets get the same checks as the feed URL itself
+        let host_policy = Arc::new(host_policy.clone());
+        let redirect_policy = host_policy.clone();
+        let client = ssrf::protect(builder, MAX_REDIRECTS, move |url| {
+            ssrf::validate_feed_redirect(url, &redirect_policy)
+        })
+        .build()?;
 
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            host_policy,
+        })
     }
 
     pub async fn fetch_feed(
@@ -60,38 +164,102 @@ impl RssFetcher {
         url: &str,
         etag: Option<&str>,
         last_modified: Option<&str>,
+        custom_headers: &[(String, String)],
     ) -> Result<FetchResult, FetchError> {
-        // SSRF protection: validate URL at fetch time to prevent DNS rebinding
-        if let Err(e) = ssrf::validate_url(url) {
-            tracing::warn!(
-                "SSRF validation failed at fetch time for URL {}: {}",
-                url,
-                e
-            );
-            return Err(FetchError::SsrfBlocked);
+        self.fetch_discovering(url, etag, last_modified, custom_headers)
+            .await
+            .map(|(result, _)| result)
+    }
+
+    /// Fetch a feed like `fetch_feed`. If `url` is an HTML page, the feed it
+    /// advertises is fetched instead and its URL returned along with it.
+    pub async fn fetch_discovering(
+        &self,
+        url: &str,
+        etag: Option<&str>,
+        last_modified: Option<&str>,
+        custom_headers: &[(String, String)],
+    ) -> Result<(FetchResult, Option<String>), FetchError> {
+        let response = self
+            .send_checked(url, etag, last_modified, custom_headers)
+            .await?;
+        if let Some(result) = not_modified(url, &response, etag) {
+            return Ok((result, None));
         }
 
-        let mut request = self.client.get(url);
+        // Look at the Content-Type before downloading a possibly large body
+        match content_kind(response.headers()) {
+            ContentKind::Feed => Ok((self.parse_response(url, response).await?, None)),
+            ContentKind::NotAFeed(content_type) => {
+                tracing::warn!(
+                    "Skipping {}: content type {} is not a feed",
+                    url,
+                    content_type
+                );
+                Err(FetchError::NotAFeed(content_type))
+            }
+            ContentKind::Html => {
+                let page_url = response.url().to_string();
+                let body = read_page(response).await?;
+                let Some(feed_url) = discover_feed_links(&body, &page_url).into_iter().next()
+                else {
+                    tracing::warn!("Skipping {}: HTML page without a feed link", url);
+                    return Err(FetchError::NotAFeed("text/html".to_string()));
+                };
 
-        // Add conditional GET headers
-        if let Some(etag) = etag {
-            request = request.header(header::IF_NONE_MATCH, etag);
+                // The stored cache headers belong to the page, not the discovered feed
+                tracing::info!("Discovered feed {} on page {}", feed_url, url);
+                let custom_headers = headers_for(url, &feed_url, custom_headers);
+                let response = self
+                    .send_checked(&feed_url, None, None, custom_headers)
+                    .await?;
+                if let Some(result) = not_modified(&feed_url, &response, None) {
+                    return Ok((result, Some(feed_url)));
+                }
+                let result = match content_kind(response.headers()) {
+                    ContentKind::Feed => self.parse_response(&feed_url, response).await?,
+                    ContentKind::NotAFeed(content_type) => {
+                        return Err(FetchError::NotAFeed(content_type))
+                    }
+                    ContentKind::Html => return Err(FetchError::NotAFeed("text/html".to_string())),
+                };
+                Ok((result, Some(feed_url)))
+            }
         }
-        if let Some(modified) = last_modified {
-            request = request.header(header::IF_MODIFIED_SINCE, modified);
+    }
+
+    /// Send the feed request after SSRF validation. Returns an error for any
+    /// unsuccessful status other than 304 Not Modified.
+    async fn send_checked(
+        &self,
+        url: &str,
+        etag: Option<&str>,
+        last_modified: Option<&str>,
+        custom_headers: &[(String, String)],
+    ) -> Result<Response, FetchError> {
+        // SSRF protection: validate URL at fetch time to prevent DNS rebinding
+        match ssrf::validate_feed_url(url, &self.host_policy).await {
+            Ok(()) => {}
+            Err(ssrf::SsrfError::HostNotAllowed(host)) => {
+                return Err(FetchError::HostNotAllowed(host))
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "SSRF validation failed at fetch time for URL {}: {}",
+                    url,
+                    e
+                );
+                return Err(FetchError::SsrfBlocked);
+            }
         }
 
-        tracing::debug!("Fetching feed: {}", url);
-        let response = request.send().await?;
+        let request = self.build_request(url, etag, last_modified, custom_headers);
 
-        // Handle 304 Not Modified (feed unchanged)
-        if response.status() == StatusCode::NOT_MODIFIED {
-            tracing::debug!("Feed not modified: {}", url);
-            return Ok(FetchResult::NotModified);
-        }
+        tracing::debug!("Fetching feed: {}", url);
+        let response = request.send().await.map_err(request_error)?;
 
-        // Check for successful response
-        if !response.status().is_success() {
+        // Check for successful response (304 Not Modified is handled by the caller)
+        if !response.status().is_success() && response.status() != StatusCode::NOT_MODIFIED {
             let status = response.status();
             let retry_after = response
                 .headers()
@@ -101,6 +269,10 @@ impl RssFetcher {
 
             tracing::warn!("Feed fetch failed with status {}: {}", status, url);
 
+            if let Some(challenge) = auth_challenge(status, response.headers()) {
+                return Err(FetchError::AuthenticationFailed { challenge });
+            }
+
             let message = format!(
                 "{} - {}",
                 status.as_u16(),
@@ -114,18 +286,19 @@ impl RssFetcher {
             });
         }
 
+        Ok(response)
+    }
+
+    /// Read and parse the body of a successful feed response
+    async fn parse_response(
+        &self,
+        url: &str,
+        response: Response,
+    ) -> Result<FetchResult, FetchError> {
         // Extract new cache headers
-        let new_etag = response
-            .headers()
-            .get(header::ETAG)
-            .and_then(|v| v.to_str().ok())
-            .map(String::from);
-
-        let new_last_modified = response
-            .headers()
-            .get(header::LAST_MODIFIED)
-            .and_then(|v| v.to_str().ok())
-            .map(String::from);
+        let new_etag = etag_header(response.headers());
+
+        let new_last_modified = last_modified_header(response.headers(), url);
 
         tracing::debug!(
             "Feed response headers - ETag: {:?}, Last-Modified: {:?}",
@@ -133,13 +306,19 @@ impl RssFetcher {
             new_last_modified
         );
 
-        let body = response.text().await?;
+        // Keep the raw bytes: the parser honours the XML encoding declaration,
+        // which decoding to a String up front would ignore
+        let charset = header_charset(response.headers());
+        let header_ttl = header_ttl(response.headers());
+        let bytes = response.bytes().await?;
+        let body = feed_bytes(&bytes, charset);
 
-        // Extract TTL from raw XML before parsing
-        let ttl = extract_ttl_from_xml(&body);
+        // Extract TTL from raw XML before parsing; the headers only stand in
+        // for a missing <ttl>, as they do on 304 responses
+        let ttl = feed_ttl(extract_ttl_from_xml(&body), header_ttl);
 
         // Parse the feed
-        let feed = parser::parse(body.as_bytes()).map_err(|e| {
+        let feed = parser::parse(&body[..]).map_err(|e| {
             tracing::error!("Feed parsing error for {}: {}", url, e);
             FetchError::ParseError(e.to_string())
         })?;
@@ -160,18 +339,297 @@ impl RssFetcher {
             ttl,
         })
     }
+
+    /// Build the GET request for a feed, including conditional and custom headers
+    fn build_request(
+        &self,
+        url: &str,
+        etag: Option<&str>,
+        last_modified: Option<&str>,
+        custom_headers: &[(String, String)],
+    ) -> reqwest::RequestBuilder {
+        let mut request = self.client.get(url);
+
+        // Custom headers first, so they can never shadow the conditional GET headers
+        for (name, value) in custom_headers {
+            if FORBIDDEN_CUSTOM_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+                tracing::warn!("Skipping forbidden custom header {} for {}", name, url);
+                continue;
+            }
+            match (
+                header::HeaderName::from_bytes(name.as_bytes()),
+                header::HeaderValue::from_str(value),
+            ) {
+                (Ok(name), Ok(value)) => request = request.header(name, value),
+                _ => tracing::warn!("Skipping invalid custom header {} for {}", name, url),
+            }
+        }
+
+        // Add conditional GET headers
+        if let Some(etag) = etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        if let Some(modified) = last_modified.filter(|m| is_http_date(m)) {
+            request = request.header(header::IF_MODIFIED_SINCE, modified);
+        }
+
+        request
+    }
+}
+
+/// The custom headers to send along to `request_url`. They may carry
+/// credentials, so they only go to the origin of the feed's own URL.
+pub fn headers_for<'a>(
+    feed_url: &str,
+    request_url: &str,
+    custom_headers: &'a [(String, String)],
+) -> &'a [(String, String)] {
+    if same_origin(feed_url, request_url) {
+        custom_headers
+    } else {
+        if !custom_headers.is_empty() {
+            tracing::debug!(
+                "Not sending the custom headers of {} to {}",
+                feed_url,
+                request_url
+            );
+        }
+        &[]
+    }
+}
+
+/// Whether two URLs share scheme, host and port
+pub fn same_origin(a: &str, b: &str) -> bool {
+    match (url::Url::parse(a), url::Url::parse(b)) {
+        (Ok(a), Ok(b)) => a.origin() == b.origin(),
+        _ => false,
+    }
+}
+
+/// Whether the response means the feed is unchanged: a 304, or a 200 whose
+/// ETag weakly matches the one we sent (a server ignoring `If-None-Match`,
+/// which RFC 7232 evaluates with the weak comparison)
+fn not_modified(url: &str, response: &Response, sent_etag: Option<&str>) -> Option<FetchResult> {
+    let etag = etag_header(response.headers());
+    let ttl = header_ttl(response.headers());
+
+    if response.status() == StatusCode::NOT_MODIFIED {
+        tracing::debug!("Feed not modified: {}", url);
+        return Some(FetchResult::NotModified { etag, ttl });
+    }
+
+    match (sent_etag, etag.as_deref()) {
+        (Some(sent), Some(received)) if etags_weakly_match(sent, received) => {
+            tracing::debug!("Feed not modified (matching ETag {}): {}", received, url);
+            Some(FetchResult::NotModified { etag, ttl })
+        }
+        _ => None,
+    }
+}
+
+/// The response's `ETag`, exactly as sent (weak `W/` prefix included), so it
+/// can be echoed back verbatim in `If-None-Match`
+fn etag_header(headers: &header::HeaderMap) -> Option<String> {
+    let value = headers.get(header::ETAG)?.to_str().ok()?.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Weak comparison of two entity tags (RFC 7232, section 2.3.2): the opaque
+/// tags must match, whether either is marked weak or not
+fn etags_weakly_match(a: &str, b: &str) -> bool {
+    fn opaque_tag(etag: &str) -> &str {
+        let etag = etag.trim();
+        etag.strip_prefix("W/").unwrap_or(etag)
+    }
+    opaque_tag(a) == opaque_tag(b)
+}
+
+/// The TTL a feed announces: its `<ttl>` element, else the caching headers
+fn feed_ttl(xml_ttl: Option<i64>, header_ttl: Option<i64>) -> Option<FeedTtl> {
+    match (xml_ttl, header_ttl) {
+        (Some(minutes), _) => Some(FeedTtl {
+            minutes,
+            from_headers: false,
+        }),
+        (None, Some(minutes)) => Some(FeedTtl {
+            minutes,
+            from_headers: true,
+        }),
+        (None, None) => None,
+    }
+}
+
+/// How long the response may be cached in whole minutes, rounded up: its
+/// `Cache-Control: max-age`, unless caching is ruled out. Unlike the `<ttl>`
+/// element it also arrives with 304 responses. A `Retry-After` on a
+/// successful response says nothing about caching and is ignored.
+fn header_ttl(headers: &header::HeaderMap) -> Option<i64> {
+    let directives: Vec<&str> = headers
+        .get_all(header::CACHE_CONTROL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+    if directives
+        .iter()
+        .any(|d| d.eq_ignore_ascii_case("no-cache") || d.eq_ignore_ascii_case("no-store"))
+    {
+        return None;
+    }
+    let seconds = directives.iter().find_map(|directive| {
+        let (name, value) = directive.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("max-age")
+            .then(|| value.trim().trim_matches('"').parse::<i64>().ok())?
+    })?;
+    (seconds > 0).then(|| (seconds + 59) / 60)
+}
+
+/// The response's `Last-Modified` value, if it is a valid HTTP date.
+/// Echoing a malformed date in `If-Modified-Since` defeats conditional GET,
+/// so such values are dropped rather than stored.
+fn last_modified_header(headers: &header::HeaderMap, url: &str) -> Option<String> {
+    let value = headers.get(header::LAST_MODIFIED)?.to_str().ok()?;
+    if !is_http_date(value) {
+        tracing::debug!("Ignoring invalid Last-Modified {:?} from {}", value, url);
+        return None;
+    }
+    Some(value.to_string())
+}
+
+fn is_http_date(value: &str) -> bool {
+    httpdate::parse_http_date(value).is_ok()
+}
+
+/// What a response's `Content-Type` says about its body
+#[derive(Debug, PartialEq)]
+enum ContentKind {
+    /// A feed, or a type real feeds are served with often enough to try parsing
+    Feed,
+    /// An HTML page, which may link to its feed
+    Html,
+    /// Clearly not a feed (images, PDFs, archives, ...)
+    NotAFeed(String),
+}
+
+/// Media type prefixes that are never feeds
+const NON_FEED_TYPE_PREFIXES: &[&str] = &["image/", "audio/", "video/", "font/"];
+
+/// Media types that are never feeds
+const NON_FEED_TYPES: &[&str] = &[
+    "application/pdf",
+    "application/zip",
+    "application/gzip",
+    "application/x-gzip",
+    "application/x-tar",
+    "application/x-7z-compressed",
+    "application/wasm",
+];
+
+/// Classify a response by its `Content-Type`.
+///
+/// This errs on the side of parsing: a missing header, `text/plain` or
+/// `application/octet-stream` are all seen on real feeds.
+fn content_kind(headers: &header::HeaderMap) -> ContentKind {
+    let Some(value) = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+    else {
+        return ContentKind::Feed;
+    };
+    let media_type = value
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    if media_type == "text/html" {
+        ContentKind::Html
+    } else if NON_FEED_TYPES.contains(&media_type.as_str())
+        || NON_FEED_TYPE_PREFIXES
+            .iter()
+            .any(|prefix| media_type.starts_with(prefix))
+    {
+        ContentKind::NotAFeed(media_type)
+    } else {
+        ContentKind::Feed
+    }
+}
+
+/// Find the feed links an HTML page advertises via `<link rel="alternate">`,
+/// resolved against the page URL, in document order
+pub fn discover_feed_links(html: &str, page_url: &str) -> Vec<String> {
+    let Ok(base) = url::Url::parse(page_url) else {
+        return Vec::new();
+    };
+
+    Document::from(html)
+        .select("link[rel][type][href]")
+        .iter()
+        .filter(|link| {
+            let rel = link.attr("rel").unwrap_or_default().to_ascii_lowercase();
+            let link_type = link.attr("type").unwrap_or_default().to_ascii_lowercase();
+            rel.split_ascii_whitespace().any(|r| r == "alternate")
+                && FEED_LINK_TYPES.contains(&link_type.trim())
+        })
+        .filter_map(|link| base.join(link.attr("href")?.trim()).ok())
+        .filter(|resolved| matches!(resolved.scheme(), "http" | "https"))
+        .map(|resolved| resolved.to_string())
+        .collect()
 }
 
 impl Default for RssFetcher {
     fn default() -> Self {
-        Self::new().expect("Failed to create RssFetcher")
+        Self::new(&HostPolicy::default()).expect("Failed to create RssFetcher")
     }
 }
 
+/// The encoding named by the `charset` parameter of the Content-Type header
+pub fn header_charset(headers: &header::HeaderMap) -> Option<&'static Encoding> {
+    let value = headers.get(header::CONTENT_TYPE)?.to_str().ok()?;
+    value.split(';').skip(1).find_map(|param| {
+        let (name, label) = param.split_once('=')?;
+        if !name.trim().eq_ignore_ascii_case("charset") {
+            return None;
+        }
+        Encoding::for_label(label.trim().trim_matches('"').as_bytes())
+    })
+}
+
+/// The feed body as the parser should see it.
+///
+/// A byte order mark or an `encoding` in the XML declaration is left for the
+/// parser to honour. Only when the document says nothing about its encoding
+/// is a non-UTF-8 charset from the HTTP header applied by transcoding the
+/// body to UTF-8, which is what the parser assumes by default.
+fn feed_bytes<'a>(body: &'a [u8], charset: Option<&'static Encoding>) -> Cow<'a, [u8]> {
+    let Some(encoding) = charset.filter(|encoding| *encoding != UTF_8) else {
+        return Cow::Borrowed(body);
+    };
+    if Encoding::for_bom(body).is_some() || declares_encoding(body) {
+        return Cow::Borrowed(body);
+    }
+    let (decoded, _) = encoding.decode_without_bom_handling(body);
+    Cow::Owned(decoded.into_owned().into_bytes())
+}
+
+/// Whether the body opens with an XML declaration that names an encoding
+fn declares_encoding(body: &[u8]) -> bool {
+    if !body.starts_with(b"<?xml") {
+        return false;
+    }
+    let Some(end) = body.windows(2).position(|pair| pair == b"?>") else {
+        return false;
+    };
+    body[..end].windows(8).any(|name| name == b"encoding")
+}
+
 /// Extract TTL (Time To Live) from RSS 2.0 feed XML
 /// Returns TTL in minutes if found
-fn extract_ttl_from_xml(xml: &str) -> Option<i64> {
-    let mut reader = Reader::from_str(xml);
+fn extract_ttl_from_xml(xml: &[u8]) -> Option<i64> {
+    let mut reader = Reader::from_reader(xml);
     reader.config_mut().trim_text(true);
 
     let mut buf = Vec::new();
@@ -211,3 +669,383 @@ fn extract_ttl_from_xml(xml: &str) -> Option<i64> {
 
     None
 }
+
+/// A failed request as a fetch error; a redirect or address refused by the
+/// SSRF checks is reported like a blocked feed URL
+fn request_error(e: reqwest::Error) -> FetchError {
+    match ssrf::blocked_by(&e) {
+        Some(ssrf::SsrfError::HostNotAllowed(host)) => FetchError::HostNotAllowed(host.clone()),
+        Some(_) => FetchError::SsrfBlocked,
+        None => FetchError::NetworkError(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auth_challenge_only_for_401_with_header() {
+        let mut headers = header::HeaderMap::new();
+        assert_eq!(auth_challenge(StatusCode::UNAUTHORIZED, &headers), None);
+
+        headers.insert(
+            header::WWW_AUTHENTICATE,
+            header::HeaderValue::from_static("Bearer realm=\"feeds\""),
+        );
+        assert_eq!(
+            auth_challenge(StatusCode::UNAUTHORIZED, &headers).as_deref(),
+            Some("Bearer realm=\"feeds\"")
+        );
+        assert_eq!(auth_challenge(StatusCode::FORBIDDEN, &headers), None);
+    }
+
+    #[test]
+    fn test_build_request_sends_custom_headers() {
+        let fetcher = RssFetcher::new(&HostPolicy::default()).unwrap();
+        let custom_headers = vec![
+            ("referer".to_string(), "https://example.com/".to_string()),
+            ("x-api-token".to_string(), "abc123".to_string()),
+            ("host".to_string(), "evil.example".to_string()),
+        ];
+
+        let request = fetcher
+            .build_request(
+                "https://example.com/feed.xml",
+                Some("\"v1\""),
+                None,
+                &custom_headers,
+            )
+            .build()
+            .unwrap();
+        let headers = request.headers();
+
+        assert_eq!(headers["referer"], "https://example.com/");
+        assert_eq!(headers["x-api-token"], "abc123");
+        assert_eq!(headers[header::IF_NONE_MATCH], "\"v1\"");
+        assert!(headers.get(header::HOST).is_none());
+    }
+
+    #[test]
+    fn test_feed_ttl_prefers_xml_over_headers() {
+        let from_xml = FeedTtl {
+            minutes: 30,
+            from_headers: false,
+        };
+        assert_eq!(feed_ttl(Some(30), Some(120)), Some(from_xml));
+        assert_eq!(
+            feed_ttl(None, Some(120)),
+            Some(FeedTtl {
+                minutes: 120,
+                from_headers: true,
+            })
+        );
+        assert_eq!(feed_ttl(None, None), None);
+    }
+
+    #[test]
+    fn test_header_ttl_from_max_age() {
+        let mut headers = header::HeaderMap::new();
+        assert_eq!(header_ttl(&headers), None);
+
+        // Retry-After on a successful response is no caching hint
+        headers.insert(
+            header::RETRY_AFTER,
+            header::HeaderValue::from_static("7200"),
+        );
+        assert_eq!(header_ttl(&headers), None);
+
+        // Rounded up to whole minutes
+        headers.insert(
+            header::CACHE_CONTROL,
+            header::HeaderValue::from_static("public, Max-Age=90"),
+        );
+        assert_eq!(header_ttl(&headers), Some(2));
+
+        headers.insert(
+            header::CACHE_CONTROL,
+            header::HeaderValue::from_static("no-cache, max-age=600"),
+        );
+        assert_eq!(header_ttl(&headers), None);
+    }
+
+    #[test]
+    fn test_last_modified_header_drops_invalid_dates() {
+        let mut headers = header::HeaderMap::new();
+        headers.insert(
+            header::LAST_MODIFIED,
+            header::HeaderValue::from_static("Tue, 15 Nov 1994 12:45:26 GMT"),
+        );
+        assert_eq!(
+            last_modified_header(&headers, "https://example.com/feed.xml").as_deref(),
+            Some("Tue, 15 Nov 1994 12:45:26 GMT")
+        );
+
+        headers.insert(
+            header::LAST_MODIFIED,
+            header::HeaderValue::from_static("2024-01-01 yesterday-ish"),
+        );
+        assert_eq!(
+            last_modified_header(&headers, "https://example.com/feed.xml"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_discover_feed_links_finds_json_feed() {
+        let html = r#"<!DOCTYPE html>
+            <html><head>
+              <link rel="stylesheet" type="text/css" href="/style.css">
+              <link rel="alternate" type="application/feed+json" title="JSON" href="/feed.json">
+            </head><body></body></html>"#;
+
+        assert_eq!(
+            discover_feed_links(html, "https://blog.example.com/posts/"),
+            vec!["https://blog.example.com/feed.json".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_custom_headers_stay_on_the_feed_origin() {
+        let custom_headers = vec![("authorization".to_string(), "Bearer secret".to_string())];
+        let page_url = "https://blog.example.com/";
+        let page = r#"<html><head>
+            <link rel="alternate" type="application/rss+xml" href="https://feeds.other.example/blog.xml">
+            <link rel="alternate" type="application/atom+xml" href="/atom.xml">
+        </head></html>"#;
+        let links = discover_feed_links(page, page_url);
+
+        assert!(headers_for(page_url, &links[0], &custom_headers).is_empty());
+        assert_eq!(
+            headers_for(page_url, &links[1], &custom_headers),
+            custom_headers
+        );
+        // Same host, but another scheme or port is another origin
+        assert!(headers_for(
+            page_url,
+            "http://blog.example.com/atom.xml",
+            &custom_headers
+        )
+        .is_empty());
+        assert!(headers_for(
+            page_url,
+            "https://blog.example.com:8443/atom.xml",
+            &custom_headers
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_discover_feed_links_keeps_document_order() {
+        let html = r#"<html><head>
+              <link rel="alternate" type="application/atom+xml" href="https://example.com/atom.xml">
+              <link rel="alternate" type="application/json" href="feed.json">
+              <link rel="alternate" type="text/html" href="/de/">
+              <link rel="icon" type="application/rss+xml" href="/not-a-feed">
+            </head></html>"#;
+
+        assert_eq!(
+            discover_feed_links(html, "https://example.com/"),
+            vec![
+                "https://example.com/atom.xml".to_string(),
+                "https://example.com/feed.json".to_string(),
+            ]
+        );
+    }
+
+    fn content_type(value: &'static str) -> header::HeaderMap {
+        let mut headers = header::HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static(value),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_content_kind_rejects_binaries() {
+        assert_eq!(
+            content_kind(&content_type("image/png")),
+            ContentKind::NotAFeed("image/png".to_string())
+        );
+        assert_eq!(
+            content_kind(&content_type("Application/PDF; qs=0.9")),
+            ContentKind::NotAFeed("application/pdf".to_string())
+        );
+    }
+
+    #[test]
+    fn test_content_kind_is_permissive_for_feeds() {
+        for value in [
+            "application/rss+xml; charset=utf-8",
+            "application/atom+xml",
+            "application/feed+json",
+            "text/xml",
+            "text/plain",
+            "application/octet-stream",
+        ] {
+            assert_eq!(
+                content_kind(&content_type(value)),
+                ContentKind::Feed,
+                "{}",
+                value
+            );
+        }
+        assert_eq!(content_kind(&header::HeaderMap::new()), ContentKind::Feed);
+        assert_eq!(
+            content_kind(&content_type("text/html; charset=utf-8")),
+            ContentKind::Html
+        );
+    }
+
+    #[test]
+    fn test_etags_weakly_match() {
+        assert!(etags_weakly_match(r#""abc""#, r#""abc""#));
+        assert!(etags_weakly_match(r#"W/"abc""#, r#""abc""#));
+        assert!(etags_weakly_match(r#""abc""#, r#"W/"abc""#));
+        assert!(etags_weakly_match(r#"W/"abc""#, r#"W/"abc""#));
+        assert!(!etags_weakly_match(r#"W/"abc""#, r#"W/"abd""#));
+        assert!(!etags_weakly_match(r#""abc""#, "abc"));
+    }
+
+    #[test]
+    fn test_weak_etag_round_trips_verbatim() {
+        let mut headers = header::HeaderMap::new();
+        headers.insert(
+            header::ETAG,
+            header::HeaderValue::from_static(r#" W/"5e1-1a2b" "#),
+        );
+        let stored = etag_header(&headers).unwrap();
+        assert_eq!(stored, r#"W/"5e1-1a2b""#);
+
+        let fetcher = RssFetcher::new(&HostPolicy::default()).unwrap();
+        for etag in [stored.as_str(), r#""strong-1""#] {
+            let request = fetcher
+                .build_request("https://example.com/feed.xml", Some(etag), None, &[])
+                .build()
+                .unwrap();
+            assert_eq!(request.headers()[header::IF_NONE_MATCH], etag);
+        }
+    }
+
+    /// An RSS document with one item, encoded as ISO-8859-1
+    fn latin1_feed(declaration: &str) -> Vec<u8> {
+        let xml = format!(
+            "{}<rss version=\"2.0\"><channel><title>Caf\u{e9} du Monde</title>\
+             <item><title>Cr\u{e8}me br\u{fb}l\u{e9}e</title><guid>1</guid></item>\
+             </channel></rss>",
+            declaration
+        );
+        // Every character above is below U+0100, so each maps to one byte
+        xml.chars().map(|c| c as u8).collect()
+    }
+
+    fn parsed_titles(body: &[u8], charset: Option<&'static Encoding>) -> (String, String) {
+        let feed = parser::parse(&feed_bytes(body, charset)[..]).unwrap();
+        (
+            feed.title.unwrap().content,
+            feed.entries[0].title.clone().unwrap().content,
+        )
+    }
+
+    #[test]
+    fn test_latin1_feed_decodes_from_xml_declaration() {
+        let body = latin1_feed("<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>");
+        let expected = (
+            "Caf\u{e9} du Monde".to_string(),
+            "Cr\u{e8}me br\u{fb}l\u{e9}e".to_string(),
+        );
+
+        // Without a charset header, and despite a wrong one
+        assert_eq!(parsed_titles(&body, None), expected);
+        assert_eq!(parsed_titles(&body, Some(UTF_8)), expected);
+    }
+
+    #[test]
+    fn test_latin1_feed_decodes_from_header_charset() {
+        let body = latin1_feed("<?xml version=\"1.0\"?>");
+        let charset = header_charset(&content_type("application/rss+xml; charset=\"ISO-8859-1\""));
+        assert!(charset.is_some());
+
+        assert_eq!(
+            parsed_titles(&body, charset),
+            (
+                "Caf\u{e9} du Monde".to_string(),
+                "Cr\u{e8}me br\u{fb}l\u{e9}e".to_string()
+            )
+        );
+        assert_eq!(header_charset(&content_type("application/rss+xml")), None);
+    }
+
+    /// Serve one HTML response from a local server; returns its URL
+    async fn serve_page(head: &'static str, body: Vec<u8>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+            let _ = socket.write_all(head.as_bytes()).await;
+            let _ = socket.write_all(&body).await;
+        });
+        format!("http://{addr}/")
+    }
+
+    #[tokio::test]
+    async fn test_read_page_caps_body_size() {
+        // Sent directly, as a local URL would not pass validation
+        let fetcher = RssFetcher::new(&HostPolicy::default()).unwrap();
+        let page = b"<html><head><link rel=\"alternate\" type=\"application/rss+xml\" href=\"/feed.xml\"></head></html>";
+        let url = serve_page(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n\r\n",
+            page.to_vec(),
+        )
+        .await;
+        let response = fetcher.client.get(&url).send().await.unwrap();
+        let body = read_page(response).await.unwrap();
+        assert_eq!(
+            discover_feed_links(&body, &url),
+            vec![format!("{url}feed.xml")]
+        );
+
+        // Without a Content-Length, the size is counted while reading
+        let url = serve_page(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nConnection: close\r\n\r\n",
+            vec![b' '; MAX_PAGE_BYTES + 1],
+        )
+        .await;
+        let response = fetcher.client.get(&url).send().await.unwrap();
+        assert!(matches!(
+            read_page(response).await,
+            Err(FetchError::PageTooLarge)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_redirect_to_blocked_host_is_refused() {
+        // A local server stands in for a public feed redirecting inwards
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+            let response = "HTTP/1.1 301 Moved Permanently\r\nLocation: http://169.254.169.254/feed.xml\r\nContent-Length: 0\r\n\r\n";
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        // Sent directly, as the feed URL itself would not pass validation
+        let fetcher = RssFetcher::new(&HostPolicy::default()).unwrap();
+        let err = fetcher
+            .client
+            .get(format!("http://{addr}/feed.xml"))
+            .send()
+            .await
+            .map_err(request_error)
+            .unwrap_err();
+
+        assert!(matches!(err, FetchError::SsrfBlocked));
+    }
+}
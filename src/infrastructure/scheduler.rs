@@ -1,10 +1,63 @@
 use crate::api::feeds::AppState;
-use crate::domain::models::NewArticle;
-use crate::infrastructure::{repository, rss_fetcher};
+use crate::config::{AdaptiveInterval, FetchDelays, HostPolicy, ReaderSanitization};
+use crate::domain::models::{Article, Feed, FeedTtl, GuidStrategy, NewArticle, OpenGraphUpdate};
+use crate::domain::reader_service::{self, ReaderServiceError};
+use crate::infrastructure::insert_progress::InsertProgressStore;
+use crate::infrastructure::scheduler_status::CycleSummary;
+use crate::infrastructure::secrets::SecretKey;
+use crate::infrastructure::{repository, response_body, rss_fetcher, ssrf};
 use chrono::Utc;
-use std::time::Duration;
+use rand::Rng;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
 use tokio_cron_scheduler::{Job, JobScheduler};
 
+/// Minutes between fetch cycles of the background scheduler
+pub const FETCH_CYCLE_MINUTES: u32 = 5;
+
+/// Maximum number of OpenGraph pages fetched concurrently for one feed update
+const OPENGRAPH_CONCURRENCY: usize = 3;
+
+/// Feed entries stored per multi-row insert, so a large feed neither builds
+/// one huge statement nor holds all its articles in memory at once
+const ARTICLE_INSERT_BATCH: usize = 100;
+
+/// Number of OpenGraph results collected before they are written in one
+/// transaction
+const OPENGRAPH_BATCH_SIZE: usize = 20;
+
+/// Maximum number of articles re-enriched by one OpenGraph refresh request
+const OPENGRAPH_REFRESH_LIMIT: i64 = 200;
+
+/// Largest article page read for its OpenGraph metadata
+const OPENGRAPH_MAX_BYTES: usize = 2 * 1024 * 1024;
+
+/// Redirects followed per article page
+const OPENGRAPH_MAX_REDIRECTS: usize = 5;
+
+const OPENGRAPH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum number of new articles per feed update whose full page is fetched
+const FULL_CONTENT_LIMIT: usize = 10;
+
+/// Maximum number of article pages extracted concurrently; readability is
+/// heavier than OpenGraph parsing
+const FULL_CONTENT_CONCURRENCY: usize = 2;
+
+/// Maximum number of entry categories turned into tags per article
+const MAX_CATEGORY_TAGS: usize = 5;
+
+/// Categories longer than this are more likely sentences than topics
+const MAX_CATEGORY_TAG_LEN: usize = 40;
+
+/// Articles rewritten under an existing GUID within `CONTENT_CHANGE_WINDOW_DAYS`
+/// before a feed is reported as not keeping its GUIDs stable
+const FREQUENT_CONTENT_CHANGES: i64 = 10;
+
+/// Period over which content changes under existing GUIDs are counted
+const CONTENT_CHANGE_WINDOW_DAYS: i64 = 7;
+
 /// Check if a reqwest error is a connection, DNS, or SSL error (feed-side problems)
 fn is_connection_dns_or_ssl_error(err: &reqwest::Error) -> bool {
     // Check for connection errors (connection refused, network unreachable, etc.)
@@ -40,112 +93,281 @@ fn is_connection_dns_or_ssl_error(err: &reqwest::Error) -> bool {
 
 pub async fn start_scheduler(state: AppState) -> Result<JobScheduler, Box<dyn std::error::Error>> {
     let scheduler = JobScheduler::new().await?;
+    let status = state.scheduler_status.clone();
 
-    // Fetch all feeds every 5 minutes
-    let schedule = "0 */5 * * * *"; // Every 5 minutes
+    // Fetch all due feeds every few minutes
+    let schedule = format!("0 */{} * * * *", FETCH_CYCLE_MINUTES);
 
     scheduler
-        .add(Job::new_async(schedule, move |_uuid, _lock| {
+        .add(Job::new_async(schedule.as_str(), move |_uuid, _lock| {
             let state = state.clone();
             Box::pin(async move {
                 if let Err(e) = fetch_all_feeds(&state).await {
-                    tracing::error!("Feed fetch cycle failed: {}", e);
+                    tracing::error!(error = %e, "Feed fetch cycle failed");
                 }
             })
         })?)
         .await?;
 
     scheduler.start().await?;
-    tracing::info!("Feed scheduler started (every 5 minutes)");
+    status.set_enabled();
+    tracing::info!(
+        "Feed scheduler started (every {} minutes)",
+        FETCH_CYCLE_MINUTES
+    );
 
     Ok(scheduler)
 }
 
-/// Fetch and process a single feed, inserting new articles
+/// Fetch and process a single feed, inserting new articles and reporting
+/// how many are stored to `progress`
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(skip_all, fields(feed_id = feed.id, feed_url = %feed.url))]
 pub async fn fetch_single_feed(
     pool: &sqlx::SqlitePool,
     feed: &crate::domain::models::Feed,
+    delays: &FetchDelays,
+    adaptive: &AdaptiveInterval,
+    reader_sanitization: ReaderSanitization,
+    host_policy: &HostPolicy,
+    secret_key: &SecretKey,
+    progress: Option<&InsertProgressStore>,
 ) -> Result<FetchSingleFeedResult, Box<dyn std::error::Error>> {
-    tracing::debug!("Processing feed: {} ({})", feed.title, feed.url);
+    tracing::debug!(feed_title = %feed.title, "Processing feed");
 
-    let fetcher = rss_fetcher::RssFetcher::new()?;
+    let fetcher = rss_fetcher::RssFetcher::new(host_policy)?;
+    let custom_headers = load_custom_headers(pool, feed.id, secret_key).await?;
 
-    match fetcher
-        .fetch_feed(
+    let started = Instant::now();
+    let result = fetcher
+        .fetch_discovering(
             &feed.url,
             feed.etag.as_deref(),
             feed.last_modified.as_deref(),
+            &custom_headers,
         )
-        .await
-    {
-        Ok(rss_fetcher::FetchResult::Updated {
-            feed: parsed_feed,
-            etag,
-            last_modified,
-            ttl,
-        }) => handle_feed_update(pool, feed, *parsed_feed, etag, last_modified, ttl).await,
-        Ok(rss_fetcher::FetchResult::NotModified) => handle_feed_not_modified(pool, feed).await,
-        Err(e) => handle_feed_fetch_error(pool, feed, e).await,
+        .await;
+    let duration_ms = started.elapsed().as_millis() as i64;
+
+    match result {
+        Ok((
+            rss_fetcher::FetchResult::Updated {
+                feed: parsed_feed,
+                etag,
+                last_modified,
+                ttl,
+            },
+            discovered_url,
+        )) => {
+            let update = FeedUpdate {
+                parsed_feed: *parsed_feed,
+                discovered_url,
+                etag,
+                last_modified,
+                ttl,
+                duration_ms,
+                og_delay: delays.inter_og,
+                adaptive,
+                reader_sanitization,
+                progress,
+            };
+            handle_feed_update(pool, feed, update).await
+        }
+        Ok((rss_fetcher::FetchResult::NotModified { etag, ttl }, _)) => {
+            let not_modified = FeedNotModified {
+                etag,
+                ttl,
+                duration_ms,
+                adaptive,
+            };
+            handle_feed_not_modified(pool, feed, not_modified).await
+        }
+        Err(e) => handle_feed_fetch_error(pool, feed, e, duration_ms).await,
     }
 }
 
-/// Handle successful feed update: log, update TTL, update metadata, insert articles
-async fn handle_feed_update(
+/// Load a feed's custom request headers as (name, value) pairs, with its
+/// bearer token as the `authorization` header
+async fn load_custom_headers(
     pool: &sqlx::SqlitePool,
-    feed: &crate::domain::models::Feed,
+    feed_id: i64,
+    secret_key: &SecretKey,
+) -> Result<Vec<(String, String)>, sqlx::Error> {
+    let mut headers: Vec<(String, String)> =
+        repository::get_feed_headers(pool, feed_id, secret_key)
+            .await?
+            .into_iter()
+            .map(|h| (h.name, h.value))
+            .collect();
+    if let Some(token) = repository::get_feed_bearer_token(pool, feed_id, secret_key).await? {
+        headers.retain(|(name, _)| !name.eq_ignore_ascii_case("authorization"));
+        headers.push(("authorization".to_string(), format!("Bearer {}", token)));
+    }
+    Ok(headers)
+}
+
+/// A fetched feed with the response's caching data, and how to process it
+struct FeedUpdate<'a> {
     parsed_feed: feed_rs::model::Feed,
+    /// Where the feed was found if the feed URL is an HTML page
+    discovered_url: Option<String>,
     etag: Option<String>,
     last_modified: Option<String>,
+    ttl: Option<FeedTtl>,
+    duration_ms: i64,
+    og_delay: Duration,
+    adaptive: &'a AdaptiveInterval,
+    /// Sanitization of full content fetched for new articles
+    reader_sanitization: ReaderSanitization,
+    progress: Option<&'a InsertProgressStore>,
+}
+
+/// A 304 response's caching data, and how to adjust the fetch interval
+struct FeedNotModified<'a> {
+    etag: Option<String>,
     ttl: Option<i64>,
+    duration_ms: i64,
+    adaptive: &'a AdaptiveInterval,
+}
+
+/// Handle successful feed update: log, update TTL, update metadata, insert articles
+async fn handle_feed_update(
+    pool: &sqlx::SqlitePool,
+    feed: &crate::domain::models::Feed,
+    update: FeedUpdate<'_>,
 ) -> Result<FetchSingleFeedResult, Box<dyn std::error::Error>> {
+    let FeedUpdate {
+        mut parsed_feed,
+        discovered_url,
+        etag,
+        last_modified,
+        ttl,
+        duration_ms,
+        og_delay,
+        adaptive,
+        reader_sanitization,
+        progress,
+    } = update;
+    let entry_count = parsed_feed.entries.len();
     tracing::info!(
-        "Feed updated: {} ({} entries)",
-        feed.title,
-        parsed_feed.entries.len()
+        feed_title = %feed.title,
+        entry_count,
+        duration_ms,
+        "Feed updated"
     );
 
-    // Log successful fetch
-    repository::insert_log(pool, feed.id, "success", None, None, None).await?;
-
-    // Store TTL for display purposes (custom mode) or info
-    if ttl.is_some() && feed.ttl_minutes != ttl {
-        repository::update_feed_ttl_only(pool, feed.id, ttl).await?;
+    // Remember where older entries live (RFC 5005), for backfill on subscription
+    let feed_url = discovered_url.as_deref().unwrap_or(&feed.url);
+    let archive_url = archive_link(&parsed_feed, feed_url);
+    inherit_feed_language(&mut parsed_feed);
+    let entries = std::mem::take(&mut parsed_feed.entries);
+    let carried_guids: Vec<String> = entries
+        .iter()
+        .map(|entry| generate_guid(entry, feed.guid_strategy()))
+        .collect();
+
+    // Articles, metadata (with the new ETag) and adaptive state commit
+    // together, so an error halfway through leaves the feed to be fetched
+    // again in full
+    let mut tx = pool.begin().await?;
+    let (new_articles_count, opengraph_queue) =
+        insert_articles_in(&mut tx, feed, entries, progress).await?;
+
+    // Store the announced TTL; a <ttl> is only displayed, a caching hint
+    // also defers the next fetch below
+    if let Some(ttl) = ttl.filter(|&ttl| feed.ttl() != Some(ttl)) {
+        repository::update_feed_ttl_only(&mut *tx, feed.id, ttl).await?;
     }
 
     // Update feed metadata from RSS
-    update_feed_metadata_from_rss(pool, feed, &parsed_feed, etag, last_modified).await?;
+    update_feed_metadata_from_rss(&mut *tx, feed, &parsed_feed, etag, last_modified).await?;
+
+    // Update adaptive fetch interval based on whether we got new articles
+    update_adaptive_interval(&mut *tx, feed, new_articles_count, adaptive).await?;
+
+    // All entries now carry GUIDs of the current strategy
+    if feed.pending_guid_remap().is_some() {
+        repository::clear_previous_guid_strategy(&mut *tx, feed.id).await?;
+    }
+
+    // Trimmed entries the feed dropped can't come back; an empty feed is
+    // more likely a glitch, so its tombstones are kept
+    if !carried_guids.is_empty() {
+        repository::prune_trimmed_articles(&mut *tx, feed.id, &carried_guids).await?;
+    }
+
+    tx.commit().await?;
 
-    // Insert articles and spawn OpenGraph fetching
-    let new_articles_count = insert_articles_from_entries(
+    // A homepage URL is replaced by the feed it advertises, so later fetches
+    // skip the page and send the cache headers to the URL they came from
+    if let Some(feed_url) = discovered_url.as_deref() {
+        adopt_discovered_url(pool, feed, feed_url).await;
+    }
+
+    // Log successful fetch
+    repository::insert_log(
         pool,
         feed.id,
-        parsed_feed.entries,
-        feed.ignore_pattern.as_deref(),
+        "success",
+        None,
+        None,
+        None,
+        Some(duration_ms),
+        Some(entry_count as i64),
     )
     .await?;
 
-    // Update adaptive fetch interval based on whether we got new articles
-    update_adaptive_interval(pool, feed, new_articles_count).await?;
+    reset_failure_backoff(pool, feed).await?;
+    defer_for_cache_hint(
+        pool,
+        feed,
+        ttl.filter(|ttl| ttl.from_headers).map(|ttl| ttl.minutes),
+    )
+    .await?;
 
-    Ok(FetchSingleFeedResult::Updated { new_articles_count })
+    // Only committed articles are handed to the background fetchers
+    spawn_full_content_fetch(
+        pool,
+        full_content_queue(feed, &opengraph_queue),
+        og_delay,
+        reader_sanitization,
+    );
+    spawn_opengraph_fetch(pool, opengraph_queue, og_delay);
+
+    Ok(FetchSingleFeedResult::Updated {
+        new_articles_count,
+        archive_url,
+    })
 }
 
-/// Minimum fetch interval: 1 hour
-const MIN_INTERVAL_MINUTES: i64 = 60;
-/// Maximum fetch interval: 1 week
-const MAX_INTERVAL_MINUTES: i64 = 10080;
+/// Store the feed found on the page a feed was added by as its URL; a feed
+/// already subscribed by that URL keeps the page URL in place
+async fn adopt_discovered_url(
+    pool: &sqlx::SqlitePool,
+    feed: &crate::domain::models::Feed,
+    feed_url: &str,
+) {
+    if let Err(e) = repository::update_feed_url(pool, feed.id, feed_url).await {
+        tracing::warn!(
+            discovered_url = feed_url,
+            error = %e,
+            "Keeping the feed URL instead of the discovered one"
+        );
+    }
+}
 
 /// Update adaptive fetch interval based on whether new articles were found.
 ///
 /// Algorithm:
 /// - If no new articles: double the interval
-/// - If new articles two fetches in a row: halve the interval
-/// - Interval is clamped between 1 hour and 1 week
+/// - If new articles `consecutive_fetches` fetches in a row: halve the interval
+/// - Interval is clamped between the configured minimum and maximum
 /// - Only applies to feeds with fetch_frequency = "adaptive"
 async fn update_adaptive_interval(
-    pool: &sqlx::SqlitePool,
+    executor: impl sqlx::SqliteExecutor<'_>,
     feed: &crate::domain::models::Feed,
     new_articles_count: usize,
+    adaptive: &AdaptiveInterval,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Only adjust interval for adaptive mode
     if feed.fetch_frequency != "adaptive" {
@@ -154,52 +376,70 @@ async fn update_adaptive_interval(
 
     let has_new_articles = new_articles_count > 0;
 
-    let (new_interval, new_consecutive) = if has_new_articles {
-        // We got new articles
-        if feed.consecutive_new_articles >= 1 {
-            // Two consecutive fetches with new articles: halve the interval
-            let halved = (feed.fetch_interval_minutes / 2).max(MIN_INTERVAL_MINUTES);
-            tracing::info!(
-                "Feed {} had new articles 2x in a row, halving interval: {}m -> {}m",
-                feed.id,
-                feed.fetch_interval_minutes,
-                halved
-            );
-            (halved, 2) // Cap at 2 to avoid unbounded growth
-        } else {
-            // First fetch with new articles, just increment counter
-            tracing::debug!(
-                "Feed {} had new articles, incrementing consecutive counter",
-                feed.id
-            );
-            (feed.fetch_interval_minutes, 1)
-        }
+    let (new_interval, new_consecutive) = next_adaptive_interval(
+        adaptive,
+        feed.fetch_interval_minutes,
+        feed.consecutive_new_articles,
+        has_new_articles,
+    );
+
+    if has_new_articles && new_consecutive >= adaptive.consecutive_fetches {
+        tracing::info!(
+            consecutive_new_articles = new_consecutive,
+            old_interval_minutes = feed.fetch_interval_minutes,
+            new_interval_minutes = new_interval,
+            "Feed had new articles repeatedly, halving interval"
+        );
+    } else if has_new_articles {
+        tracing::debug!(
+            consecutive_new_articles = new_consecutive,
+            "Feed had new articles, incrementing consecutive counter"
+        );
     } else {
-        // No new articles: double the interval
-        let doubled = (feed.fetch_interval_minutes * 2).min(MAX_INTERVAL_MINUTES);
         tracing::info!(
-            "Feed {} had no new articles, doubling interval: {}m -> {}m",
-            feed.id,
-            feed.fetch_interval_minutes,
-            doubled
+            old_interval_minutes = feed.fetch_interval_minutes,
+            new_interval_minutes = new_interval,
+            "Feed had no new articles, doubling interval"
         );
-        (doubled, 0) // Reset consecutive counter
-    };
+    }
 
     // Only update if something changed
     if new_interval != feed.fetch_interval_minutes
         || new_consecutive != feed.consecutive_new_articles
     {
-        repository::update_adaptive_fetch_state(pool, feed.id, new_consecutive, new_interval)
+        repository::update_adaptive_fetch_state(executor, feed.id, new_consecutive, new_interval)
             .await?;
     }
 
     Ok(())
 }
 
+/// Next (interval, consecutive counter) of an adaptive feed after a fetch
+fn next_adaptive_interval(
+    adaptive: &AdaptiveInterval,
+    interval_minutes: i64,
+    consecutive_new_articles: i64,
+    has_new_articles: bool,
+) -> (i64, i64) {
+    if !has_new_articles {
+        // No new articles: double the interval and reset the counter
+        let doubled = (interval_minutes * 2).min(adaptive.max_interval_minutes);
+        return (doubled, 0);
+    }
+
+    // Cap the counter at the threshold to avoid unbounded growth
+    let consecutive = (consecutive_new_articles + 1).min(adaptive.consecutive_fetches);
+    if consecutive >= adaptive.consecutive_fetches {
+        let halved = (interval_minutes / 2).max(adaptive.min_interval_minutes);
+        (halved, consecutive)
+    } else {
+        (interval_minutes, consecutive)
+    }
+}
+
 /// Extract and update feed metadata from RSS feed
 async fn update_feed_metadata_from_rss(
-    pool: &sqlx::SqlitePool,
+    executor: impl sqlx::SqliteExecutor<'_>,
     feed: &crate::domain::models::Feed,
     parsed_feed: &feed_rs::model::Feed,
     etag: Option<String>,
@@ -208,6 +448,7 @@ async fn update_feed_metadata_from_rss(
     let rss_title = parsed_feed.title.as_ref().map(|t| t.content.clone());
     let rss_description = parsed_feed.description.as_ref().map(|d| d.content.clone());
     let feed_site_url = parsed_feed.links.first().map(|link| link.href.clone());
+    let feed_language = parsed_feed.language.as_deref().and_then(language_tag);
 
     // Implement description fallback logic:
     // If no description exists in DB, use RSS feed's title
@@ -222,11 +463,12 @@ async fn update_feed_metadata_from_rss(
     };
 
     repository::update_feed_details(
-        pool,
+        executor,
         feed.id,
         rss_title,
         feed_description,
         feed_site_url,
+        feed_language,
         etag,
         last_modified,
     )
@@ -235,101 +477,416 @@ async fn update_feed_metadata_from_rss(
     Ok(())
 }
 
-/// Insert articles from feed entries and spawn OpenGraph fetching
+/// Give entries without a declared language that of the feed, which is
+/// only stored after the entries
+fn inherit_feed_language(parsed_feed: &mut feed_rs::model::Feed) {
+    for entry in &mut parsed_feed.entries {
+        if entry.language.is_none() {
+            entry.language.clone_from(&parsed_feed.language);
+        }
+    }
+}
+
+/// The entry's declared language as a tag, see [`language_tag`]
+fn extract_language(entry: &feed_rs::model::Entry) -> Option<String> {
+    entry.language.as_deref().and_then(language_tag)
+}
+
+/// Normalize a declared feed language to a tag usable in a `lang` attribute,
+/// with the usual casing restored (feed_rs lowercases it): `en_us` becomes
+/// `en-US`. Anything that doesn't look like a tag is dropped.
+fn language_tag(language: &str) -> Option<String> {
+    let parts: Vec<&str> = language.trim().split(['-', '_']).collect();
+    let valid = parts.len() <= 8
+        && parts.iter().all(|part| {
+            (1..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+    if !valid {
+        return None;
+    }
+
+    let parts: Vec<String> = parts
+        .iter()
+        .enumerate()
+        .map(|(i, part)| {
+            let is_alpha = part.chars().all(|c| c.is_ascii_alphabetic());
+            match part.len() {
+                // Region, e.g. US
+                2 if i > 0 && is_alpha => part.to_ascii_uppercase(),
+                // Script, e.g. Latn
+                4 if i > 0 && is_alpha => {
+                    let lower = part.to_ascii_lowercase();
+                    lower[..1].to_ascii_uppercase() + &lower[1..]
+                }
+                _ => part.to_ascii_lowercase(),
+            }
+        })
+        .collect();
+
+    Some(parts.join("-"))
+}
+
+/// [`insert_articles_in`] a transaction of its own
 async fn insert_articles_from_entries(
     pool: &sqlx::SqlitePool,
-    feed_id: i64,
+    feed: &crate::domain::models::Feed,
+    entries: Vec<feed_rs::model::Entry>,
+    progress: Option<&InsertProgressStore>,
+) -> Result<(usize, Vec<(i64, String)>), Box<dyn std::error::Error>> {
+    let mut tx = pool.begin().await?;
+    let inserted = insert_articles_in(&mut tx, feed, entries, progress).await?;
+    tx.commit().await?;
+    Ok(inserted)
+}
+
+/// Insert articles from feed entries on `conn`, `ARTICLE_INSERT_BATCH` at a
+/// time with one statement per batch, reporting progress after each. Returns
+/// the number of new articles and the (article_id, url) pairs to fetch
+/// OpenGraph metadata for.
+async fn insert_articles_in(
+    conn: &mut sqlx::SqliteConnection,
+    feed: &crate::domain::models::Feed,
     entries: Vec<feed_rs::model::Entry>,
-    ignore_pattern: Option<&str>,
-) -> Result<usize, Box<dyn std::error::Error>> {
+    progress: Option<&InsertProgressStore>,
+) -> Result<(usize, Vec<(i64, String)>), Box<dyn std::error::Error>> {
     let mut new_articles_count = 0;
+    let mut changed_articles_count = 0;
     let mut article_ids_to_fetch = Vec::new();
 
     // Compile the ignore pattern regex once if provided
-    let ignore_regex = ignore_pattern
+    let ignore_regex = feed
+        .ignore_pattern
+        .as_deref()
         .map(regex::Regex::new)
         .transpose()
         .map_err(|e| format!("Invalid ignore pattern regex: {}", e))?;
 
+    let total = entries.len();
+    let tracker = progress.map(|progress| progress.start(feed.id, total));
+    let mut stored = 0;
+    let mut entries = entries.into_iter();
+
+    loop {
+        // Entries are consumed batch by batch, so only one batch of
+        // articles is built at a time
+        let batch: Vec<_> = entries.by_ref().take(ARTICLE_INSERT_BATCH).collect();
+        if batch.is_empty() {
+            break;
+        }
+        stored += batch.len();
+
+        let (new_count, changed_count) = insert_entry_batch(
+            &mut *conn,
+            feed,
+            ignore_regex.as_ref(),
+            batch,
+            &mut article_ids_to_fetch,
+        )
+        .await?;
+
+        new_articles_count += new_count;
+        changed_articles_count += changed_count;
+        if let Some(tracker) = &tracker {
+            tracker.stored(stored);
+        }
+    }
+
+    if changed_articles_count > 0 {
+        report_content_changes(conn, feed.id, changed_articles_count).await?;
+    }
+
+    Ok((new_articles_count, article_ids_to_fetch))
+}
+
+/// Insert one batch of entries with a single statement, then take over
+/// changes to the entries that were already stored. Returns the number of
+/// new and of changed articles; new articles with a URL are added to
+/// `article_ids_to_fetch`.
+async fn insert_entry_batch(
+    conn: &mut sqlx::SqliteConnection,
+    feed: &crate::domain::models::Feed,
+    ignore_regex: Option<&regex::Regex>,
+    entries: Vec<feed_rs::model::Entry>,
+    article_ids_to_fetch: &mut Vec<(i64, String)>,
+) -> Result<(usize, usize), Box<dyn std::error::Error>> {
+    let feed_id = feed.id;
+    let guid_strategy = feed.guid_strategy();
+    let previous_strategy = feed.pending_guid_remap();
+    let mut new_articles = Vec::with_capacity(entries.len());
+    let mut categories = Vec::with_capacity(entries.len());
+
     for entry in entries {
-        let guid = generate_guid(&entry);
         let title = extract_title(&entry);
 
         // Skip articles matching the ignore pattern
-        if let Some(ref re) = ignore_regex {
+        if let Some(re) = ignore_regex {
             if re.is_match(&title) {
-                tracing::debug!("Ignoring article matching pattern: {}", title);
+                tracing::debug!(article_title = %title, "Ignoring article matching pattern");
                 continue;
             }
         }
 
-        let url = extract_url(&entry);
-        let content = extract_content(&entry);
-        let summary = extract_summary(&entry);
-        let author = extract_author(&entry);
-        let published_at = extract_published_date(&entry);
+        let guid = generate_guid(&entry, guid_strategy);
+        if let Some(previous) = previous_strategy {
+            let old_guid = generate_guid(&entry, previous);
+            if old_guid != guid {
+                repository::remap_article_guid(&mut *conn, feed_id, &old_guid, &guid).await?;
+            }
+        }
+
+        categories.push(extract_categories(&entry));
+        new_articles.push(NewArticle {
+            feed_id,
+            guid,
+            title,
+            url: extract_url(&entry),
+            content: extract_content(&entry),
+            summary: extract_summary(&entry),
+            author: extract_author(&entry),
+            published_at: extract_published_date(&entry),
+            updated_at_source: extract_updated_date(&entry),
+            og_image: None,
+            og_description: None,
+            og_site_name: None,
+            language: extract_language(&entry),
+        });
+    }
 
-        // Insert article without OpenGraph data
-        match repository::insert_article_if_new(
-            pool,
-            NewArticle {
-                feed_id,
-                guid,
-                title,
-                url: url.clone(),
-                content,
-                summary,
-                author,
-                published_at,
-                og_image: None,
-                og_description: None,
-                og_site_name: None,
-            },
-        )
-        .await
-        {
-            Ok(Some(article)) => {
-                new_articles_count += 1;
-                // Queue this article for OpenGraph fetching if it has a URL
-                if let Some(article_url) = url {
-                    article_ids_to_fetch.push((article.id, article_url));
-                }
+    // Insert articles without OpenGraph data
+    let inserted = match repository::insert_articles_if_new(&mut *conn, &new_articles).await {
+        Ok(inserted) => inserted,
+        Err(e) => {
+            tracing::warn!(
+                error = %e,
+                "Failed to insert article batch, inserting one by one"
+            );
+            insert_articles_one_by_one(conn, &new_articles).await
+        }
+    };
+    let mut inserted: HashMap<String, i64> = inserted
+        .into_iter()
+        .map(|article| (article.guid, article.id))
+        .collect();
+
+    let mut new_articles_count = 0;
+    let mut changed_articles_count = 0;
+
+    // In entry order; a GUID repeated within the batch counts as existing
+    for (new_article, categories) in new_articles.into_iter().zip(categories) {
+        if let Some(article_id) = inserted.remove(&new_article.guid) {
+            new_articles_count += 1;
+            if let Err(e) = repository::add_article_tags(&mut *conn, article_id, &categories).await
+            {
+                tracing::warn!(article_id, error = %e, "Failed to tag article");
             }
-            Ok(None) => {
-                // Article already exists (duplicate)
+            // Queue this article for OpenGraph fetching if it has a URL
+            if let Some(article_url) = new_article.url {
+                article_ids_to_fetch.push((article_id, article_url));
             }
-            Err(e) => {
-                tracing::warn!("Failed to insert article: {}", e);
+            continue;
+        }
+
+        // Article already exists; take over content the feed changed
+        // under the same GUID, and a newer source timestamp
+        match repository::update_article_if_content_changed(&mut *conn, &new_article).await {
+            Ok(true) => {
+                tracing::debug!(guid = %new_article.guid, "Article content changed, updated");
+                changed_articles_count += 1;
+            }
+            Ok(false) => {}
+            Err(e) => tracing::warn!(
+                guid = %new_article.guid,
+                error = %e,
+                "Failed to update changed article"
+            ),
+        }
+        if let Some(updated) = new_article.updated_at_source {
+            if let Err(e) = repository::update_article_source_timestamp(
+                &mut *conn,
+                feed_id,
+                &new_article.guid,
+                updated,
+            )
+            .await
+            {
+                tracing::warn!(
+                    guid = %new_article.guid,
+                    error = %e,
+                    "Failed to update article timestamp"
+                );
             }
         }
     }
 
-    // Spawn background task to fetch OpenGraph metadata
-    if !article_ids_to_fetch.is_empty() {
-        let pool_clone = pool.clone();
-        tokio::spawn(async move {
-            fetch_opengraph_for_articles(pool_clone, article_ids_to_fetch).await;
-        });
+    Ok((new_articles_count, changed_articles_count))
+}
+
+/// Insert articles one statement each, so one bad entry doesn't cost the
+/// whole batch; failed entries are logged and skipped
+async fn insert_articles_one_by_one(
+    conn: &mut sqlx::SqliteConnection,
+    articles: &[NewArticle],
+) -> Vec<Article> {
+    let mut inserted = Vec::new();
+    for article in articles {
+        match repository::insert_article_if_new(&mut *conn, article.clone()).await {
+            Ok(Some(article)) => inserted.push(article),
+            Ok(None) => {}
+            Err(e) => tracing::warn!(guid = %article.guid, error = %e, "Failed to insert article"),
+        }
+    }
+    inserted
+}
+
+/// Log articles a feed rewrote under their GUIDs, and warn if it does so often
+async fn report_content_changes(
+    conn: &mut sqlx::SqliteConnection,
+    feed_id: i64,
+    changed_articles_count: usize,
+) -> Result<(), sqlx::Error> {
+    tracing::info!(
+        changed_articles_count,
+        "Feed changed the content of existing articles"
+    );
+
+    let since = Utc::now() - chrono::Duration::days(CONTENT_CHANGE_WINDOW_DAYS);
+    let recent_changes = repository::count_content_changes_since(conn, feed_id, since).await?;
+    if recent_changes >= FREQUENT_CONTENT_CHANGES {
+        tracing::warn!(
+            recent_changes,
+            window_days = CONTENT_CHANGE_WINDOW_DAYS,
+            "Feed often changes the content of articles under existing GUIDs; its GUIDs may not be stable"
+        );
+    }
+
+    Ok(())
+}
+
+/// Spawn a background task fetching OpenGraph metadata for the articles
+fn spawn_opengraph_fetch(
+    pool: &sqlx::SqlitePool,
+    articles: Vec<(i64, String)>, // (article_id, url)
+    og_delay: Duration,
+) {
+    if articles.is_empty() {
+        return;
+    }
+    let pool_clone = pool.clone();
+    tokio::spawn(async move {
+        fetch_opengraph_for_articles(pool_clone, articles, og_delay).await;
+    });
+}
+
+/// New articles whose full page should replace the feed content: none unless
+/// the feed asks for it, and at most `FULL_CONTENT_LIMIT`
+fn full_content_queue(
+    feed: &crate::domain::models::Feed,
+    new_articles: &[(i64, String)],
+) -> Vec<(i64, String)> {
+    if !feed.auto_fetch_full_content {
+        return Vec::new();
+    }
+
+    new_articles
+        .iter()
+        .take(FULL_CONTENT_LIMIT)
+        .cloned()
+        .collect()
+}
+
+/// Spawn a background task replacing the articles' content with the readable
+/// text of their pages
+fn spawn_full_content_fetch(
+    pool: &sqlx::SqlitePool,
+    articles: Vec<(i64, String)>, // (article_id, url)
+    host_delay: Duration,
+    level: ReaderSanitization,
+) {
+    if articles.is_empty() {
+        return;
     }
+    let pool_clone = pool.clone();
+    tokio::spawn(async move {
+        fetch_full_content_for_articles(pool_clone, articles, host_delay, level).await;
+    });
+}
+
+/// Longest a caching hint defers a feed's next fetch
+const MAX_CACHE_HINT_MINUTES: i64 = 24 * 60;
+
+/// Hold the feed until the copy the server declared fresh (`max-age`)
+/// expires, if that is later than its fetch interval: fetching earlier would
+/// only get the same copy again. Capped at `MAX_CACHE_HINT_MINUTES`.
+async fn defer_for_cache_hint(
+    pool: &sqlx::SqlitePool,
+    feed: &crate::domain::models::Feed,
+    hint_minutes: Option<i64>,
+) -> Result<(), sqlx::Error> {
+    let Some(minutes) = hint_minutes
+        .map(|minutes| minutes.min(MAX_CACHE_HINT_MINUTES))
+        .filter(|&minutes| minutes > feed.fetch_interval_minutes)
+    else {
+        return Ok(());
+    };
 
-    Ok(new_articles_count)
+    tracing::debug!(
+        cache_minutes = minutes,
+        "Feed is cached, deferring its next fetch"
+    );
+    let next_fetch_at = Utc::now() + chrono::Duration::minutes(minutes);
+    repository::update_feed_failure_state(pool, feed.id, 0, Some(next_fetch_at)).await
 }
 
 /// Handle feed not modified: log, update last_fetched_at, and adjust adaptive interval
 async fn handle_feed_not_modified(
     pool: &sqlx::SqlitePool,
     feed: &crate::domain::models::Feed,
+    not_modified: FeedNotModified<'_>,
 ) -> Result<FetchSingleFeedResult, Box<dyn std::error::Error>> {
-    tracing::debug!("Feed not modified: {}", feed.title);
+    let FeedNotModified {
+        etag,
+        ttl,
+        duration_ms,
+        adaptive,
+    } = not_modified;
+    tracing::debug!("Feed not modified");
 
-    // Log not modified fetch
-    repository::insert_log(pool, feed.id, "not_modified", None, None, None).await?;
+    // Log not modified fetch (no body, so no entries to count)
+    repository::insert_log(
+        pool,
+        feed.id,
+        "not_modified",
+        None,
+        None,
+        None,
+        Some(duration_ms),
+        None,
+    )
+    .await?;
 
     // Just update last_fetched_at
     repository::touch_feed(pool, feed.id).await?;
+    reset_failure_backoff(pool, feed).await?;
+    defer_for_cache_hint(pool, feed, ttl).await?;
+
+    // Keep the validator the server sent last, so it is resent exactly as given
+    if let Some(etag) = etag.filter(|etag| feed.etag.as_ref() != Some(etag)) {
+        repository::update_feed_etag(pool, feed.id, &etag).await?;
+    }
+
+    // The body's <ttl> is out of reach, but the caching hint is shown in its
+    // place; it never replaces a TTL the feed itself announced
+    let ttl = ttl.map(|minutes| FeedTtl {
+        minutes,
+        from_headers: true,
+    });
+    let feed_has_ttl = feed.ttl().is_some_and(|stored| !stored.from_headers);
+    if let Some(ttl) = ttl.filter(|&ttl| !feed_has_ttl && feed.ttl() != Some(ttl)) {
+        repository::update_feed_ttl_only(pool, feed.id, ttl).await?;
+    }
 
     // Not modified means no new articles - update adaptive interval
-    update_adaptive_interval(pool, feed, 0).await?;
+    update_adaptive_interval(pool, feed, 0, adaptive).await?;
 
     Ok(FetchSingleFeedResult::NotModified)
 }
@@ -339,8 +896,9 @@ async fn handle_feed_fetch_error(
     pool: &sqlx::SqlitePool,
     feed: &crate::domain::models::Feed,
     error: rss_fetcher::FetchError,
+    duration_ms: i64,
 ) -> Result<FetchSingleFeedResult, Box<dyn std::error::Error>> {
-    tracing::warn!("Failed to fetch feed {}: {}", feed.url, error);
+    tracing::warn!(error = %error, "Failed to fetch feed");
 
     // Extract error details for logging
     let (log_type, status_code, retry_after) = match &error {
@@ -360,6 +918,7 @@ async fn handle_feed_fetch_error(
                 retry_after.as_deref(),
             )
         }
+        rss_fetcher::FetchError::AuthenticationFailed { .. } => ("auth_failed", Some(401), None),
         _ => ("error", None, None),
     };
 
@@ -373,6 +932,8 @@ async fn handle_feed_fetch_error(
         status_code,
         Some(&error_message),
         retry_after,
+        Some(duration_ms),
+        None,
     )
     .await?;
 
@@ -386,77 +947,324 @@ async fn handle_feed_fetch_error(
             // Check for connection/DNS/SSL errors
             is_connection_dns_or_ssl_error(req_err)
         }
+        // The host now resolves to a private address; retrying soon won't help
+        rss_fetcher::FetchError::SsrfBlocked => true,
+        // The host policy only changes with a restart
+        rss_fetcher::FetchError::HostNotAllowed(_) => true,
+        // The URL serves something else entirely; retrying soon won't help either
+        rss_fetcher::FetchError::NotAFeed(_) | rss_fetcher::FetchError::PageTooLarge => true,
+        // Only new credentials help, which the user has to enter
+        rss_fetcher::FetchError::AuthenticationFailed { .. } => true,
         _ => false, // HTTP errors, parse errors = our-side problem
     };
 
+    let consecutive_failures = feed.consecutive_failures + 1;
     if is_feed_side_problem {
         tracing::info!(
-            "Feed-side problem for {}, will retry based on normal interval",
-            feed.url
+            consecutive_failures,
+            "Feed-side problem, will retry based on normal interval"
         );
         repository::touch_feed(pool, feed.id).await?;
+        repository::update_feed_failure_state(pool, feed.id, consecutive_failures, None).await?;
     } else {
+        // Don't update last_fetched_at, but hold the feed back with exponential backoff
+        let backoff = retry_backoff(consecutive_failures, random_jitter());
         tracing::info!(
-            "Transient/our-side problem for {}, will retry in 5 minutes",
-            feed.url
+            consecutive_failures,
+            retry_in_minutes = backoff.num_minutes(),
+            "Transient/our-side problem, will retry with backoff"
         );
-        // Don't update last_fetched_at - will be retried on next scheduler cycle
+        repository::update_feed_failure_state(
+            pool,
+            feed.id,
+            consecutive_failures,
+            Some(Utc::now() + backoff),
+        )
+        .await?;
     }
 
     Err(error.into())
 }
 
+/// Delay before the first retry after a transient error (one scheduler cycle)
+const RETRY_BASE_MINUTES: i64 = 5;
+/// Upper bound for the retry delay: 6 hours
+const RETRY_CAP_MINUTES: i64 = 360;
+/// Maximum relative jitter applied to retry delays (±20%)
+const RETRY_JITTER: f64 = 0.2;
+
+/// Retry delay after `failures` transient errors in a row:
+/// `min(base * 2^(failures - 1), cap)`, stretched by `jitter` (clamped to ±`RETRY_JITTER`)
+fn retry_backoff(failures: i64, jitter: f64) -> chrono::Duration {
+    let exponent = (failures - 1).clamp(0, 16) as u32;
+    let minutes = (RETRY_BASE_MINUTES << exponent).min(RETRY_CAP_MINUTES);
+    let factor = 1.0 + jitter.clamp(-RETRY_JITTER, RETRY_JITTER);
+    chrono::Duration::seconds(((minutes * 60) as f64 * factor).round() as i64)
+}
+
+/// Random jitter so feeds failing together don't retry in lockstep
+fn random_jitter() -> f64 {
+    rand::thread_rng().gen_range(-RETRY_JITTER..=RETRY_JITTER)
+}
+
+/// Clear the failure count and retry hold after a successful fetch
+async fn reset_failure_backoff(
+    pool: &sqlx::SqlitePool,
+    feed: &crate::domain::models::Feed,
+) -> Result<(), sqlx::Error> {
+    if feed.consecutive_failures > 0 || feed.next_retry_at.is_some() {
+        repository::update_feed_failure_state(pool, feed.id, 0, None).await?;
+    }
+    Ok(())
+}
+
 pub enum FetchSingleFeedResult {
-    Updated { new_articles_count: usize },
+    Updated {
+        new_articles_count: usize,
+        /// Link to the next page of older entries, if the feed is paged or archived
+        archive_url: Option<String>,
+    },
     NotModified,
 }
 
-async fn fetch_all_feeds(state: &AppState) -> Result<(), Box<dyn std::error::Error>> {
-    tracing::info!("Starting feed fetch cycle");
+/// Find the RFC 5005 link to older entries of a feed page (`next` for paged
+/// feeds, `prev-archive` for archived feeds), resolved against the page URL
+pub fn archive_link(feed: &feed_rs::model::Feed, page_url: &str) -> Option<String> {
+    let link = ["next", "prev-archive"].iter().find_map(|rel| {
+        feed.links
+            .iter()
+            .find(|link| link.rel.as_deref() == Some(rel))
+    })?;
+
+    let base = url::Url::parse(page_url).ok()?;
+    let resolved = base.join(&link.href).ok()?;
+    matches!(resolved.scheme(), "http" | "https").then(|| resolved.to_string())
+}
 
-    // Get all feeds that need updating
-    let feeds = repository::get_feeds_to_update(&state.db_pool).await?;
+/// Backfill older entries of a newly created feed by following its archive
+/// links for up to `max_pages` pages. Returns the number of new articles.
+#[tracing::instrument(skip_all, fields(feed_id = feed.id, feed_url = %feed.url))]
+pub async fn backfill_archive(
+    pool: &sqlx::SqlitePool,
+    feed: &crate::domain::models::Feed,
+    start_url: String,
+    max_pages: usize,
+    delays: &FetchDelays,
+    host_policy: &HostPolicy,
+    secret_key: &SecretKey,
+) -> usize {
+    let fetcher = match rss_fetcher::RssFetcher::new(host_policy) {
+        Ok(fetcher) => fetcher,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to create fetcher for archive backfill");
+            return 0;
+        }
+    };
+    let custom_headers = load_custom_headers(pool, feed.id, secret_key)
+        .await
+        .unwrap_or_default();
+
+    let fetch_page = |url: String| {
+        let (fetcher, custom_headers) = (&fetcher, &custom_headers);
+        async move {
+            // Same politeness delay as between feeds; the fetcher enforces SSRF checks
+            tokio::time::sleep(delays.inter_feed).await;
+            let custom_headers = rss_fetcher::headers_for(&feed.url, &url, custom_headers);
+            match fetcher.fetch_feed(&url, None, None, custom_headers).await {
+                Ok(rss_fetcher::FetchResult::Updated { feed, .. }) => Some(*feed),
+                Ok(rss_fetcher::FetchResult::NotModified { .. }) => None,
+                Err(e) => {
+                    tracing::warn!(page_url = %url, error = %e, "Failed to fetch archive page");
+                    None
+                }
+            }
+        }
+    };
+
+    walk_archive_pages(
+        pool,
+        feed,
+        start_url,
+        max_pages,
+        delays.inter_og,
+        fetch_page,
+    )
+    .await
+}
 
-    tracing::info!("Found {} feeds to update", feeds.len());
+/// Follow archive links page by page, inserting each page's entries
+async fn walk_archive_pages<F, Fut>(
+    pool: &sqlx::SqlitePool,
+    feed: &crate::domain::models::Feed,
+    start_url: String,
+    max_pages: usize,
+    og_delay: Duration,
+    mut fetch_page: F,
+) -> usize
+where
+    F: FnMut(String) -> Fut,
+    Fut: std::future::Future<Output = Option<feed_rs::model::Feed>>,
+{
+    let mut visited = std::collections::HashSet::new();
+    let mut next_url = Some(start_url);
+    let mut new_articles_count = 0;
 
-    if feeds.is_empty() {
-        return Ok(());
-    }
+    while let Some(url) = next_url.take() {
+        // Stop at the page limit and on link cycles
+        if visited.len() >= max_pages || !visited.insert(url.clone()) {
+            break;
+        }
 
-    let mut new_articles_total = 0;
-    let mut updated_feeds_count = 0;
+        let Some(page) = fetch_page(url.clone()).await else {
+            break;
+        };
+        next_url = archive_link(&page, &url);
 
-    // Process feeds sequentially with rate limiting
-    for feed in feeds {
-        match fetch_single_feed(&state.db_pool, &feed).await {
-            Ok(FetchSingleFeedResult::Updated { new_articles_count }) => {
-                new_articles_total += new_articles_count;
-                updated_feeds_count += 1;
-            }
-            Ok(FetchSingleFeedResult::NotModified) => {
-                // Feed not modified
+        match insert_articles_from_entries(pool, feed, page.entries, None).await {
+            Ok((count, opengraph_queue)) => {
+                new_articles_count += count;
+                spawn_opengraph_fetch(pool, opengraph_queue, og_delay);
             }
             Err(e) => {
-                tracing::warn!("Failed to fetch feed {}: {}", feed.url, e);
+                tracing::warn!(
+                    page_url = %url,
+                    error = %e,
+                    "Failed to insert archive entries"
+                );
+                break;
             }
         }
-
-        // Rate limiting: 500ms delay between requests
-        tokio::time::sleep(Duration::from_millis(500)).await;
     }
 
     tracing::info!(
-        "Feed fetch cycle complete: {} feeds updated, {} new articles",
-        updated_feeds_count,
-        new_articles_total
+        new_articles_count,
+        pages = visited.len(),
+        "Archive backfill complete"
     );
 
-    Ok(())
+    new_articles_count
 }
 
-// Helper functions to extract data from feed entries
+/// Feeds to fetch now: none during the global quiet hours, otherwise the due
+/// feeds outside their own quiet hours. Skipped feeds stay due, so they are
+/// fetched once the window ends.
+pub async fn due_feeds(state: &AppState) -> Result<Vec<Feed>, sqlx::Error> {
+    let local_time = Utc::now().with_timezone(&state.display_timezone).time();
+
+    if let Some(quiet_hours) = state
+        .quiet_hours
+        .filter(|quiet_hours| quiet_hours.contains(local_time))
+    {
+        tracing::info!(%quiet_hours, "Quiet hours, not fetching feeds");
+        return Ok(Vec::new());
+    }
+
+    repository::get_feeds_to_update(&state.db_pool, state.max_feeds_per_cycle, local_time).await
+}
+
+async fn fetch_all_feeds(state: &AppState) -> Result<(), Box<dyn std::error::Error>> {
+    tracing::info!("Starting feed fetch cycle");
+
+    // Get all feeds that need updating
+    let feeds = due_feeds(state).await?;
+
+    tracing::info!(feed_count = feeds.len(), "Found feeds to update");
+    let status = &state.scheduler_status;
+    status.cycle_started(feeds.len()).await;
+
+    if feeds.is_empty() {
+        status.cycle_finished(CycleSummary::default()).await;
+        return Ok(());
+    }
+
+    let mut summary = CycleSummary::default();
+
+    // Process feeds sequentially with rate limiting
+    for feed in feeds {
+        let in_flight = status.fetch_started();
+        match fetch_single_feed(
+            &state.db_pool,
+            &feed,
+            &state.fetch_delays,
+            &state.adaptive_interval,
+            state.reader_sanitization,
+            &state.fetch_host_policy,
+            &state.secret_key,
+            Some(&state.insert_progress),
+        )
+        .await
+        {
+            Ok(FetchSingleFeedResult::Updated {
+                new_articles_count, ..
+            }) => {
+                summary.new_articles += new_articles_count;
+                summary.feeds_updated += 1;
+            }
+            Ok(FetchSingleFeedResult::NotModified) => {
+                // Feed not modified
+            }
+            Err(e) => {
+                summary.feeds_failed += 1;
+                tracing::warn!(
+                    feed_id = feed.id,
+                    feed_url = %feed.url,
+                    error = %e,
+                    "Failed to fetch feed"
+                );
+            }
+        }
+        drop(in_flight);
+        summary.feeds_fetched += 1;
+
+        // Rate limiting: configurable delay between requests
+        tokio::time::sleep(state.fetch_delays.inter_feed).await;
+    }
+
+    tracing::info!(
+        feeds_updated = summary.feeds_updated,
+        new_articles = summary.new_articles,
+        "Feed fetch cycle complete"
+    );
+
+    enforce_article_limit(&state.db_pool, state.storage_limits.max_articles).await;
+    status.cycle_finished(summary).await;
+
+    Ok(())
+}
+
+/// Trim the oldest articles beyond `MAX_ARTICLES`; failures are only logged
+pub async fn enforce_article_limit(pool: &sqlx::SqlitePool, max_articles: Option<i64>) {
+    let Some(max_articles) = max_articles else {
+        return;
+    };
+
+    match repository::trim_articles(pool, max_articles).await {
+        Ok(0) => {}
+        Ok(deleted) => tracing::info!(
+            "Trimmed {} old articles to stay within {} articles",
+            deleted,
+            max_articles
+        ),
+        Err(e) => tracing::warn!("Failed to trim articles: {}", e),
+    }
+}
+
+// Helper functions to extract data from feed entries
+
+/// GUID of an entry under the feed's strategy. A strategy whose field is
+/// missing from the entry falls back to the automatic choice.
+fn generate_guid(entry: &feed_rs::model::Entry, strategy: GuidStrategy) -> String {
+    match strategy {
+        GuidStrategy::Link => {
+            if let Some(link) = entry.links.first() {
+                return link.href.clone();
+            }
+        }
+        GuidStrategy::Id if !entry.id.is_empty() => return entry.id.clone(),
+        GuidStrategy::TitleDate => return title_date_guid(entry),
+        GuidStrategy::Auto | GuidStrategy::Id => {}
+    }
 
-fn generate_guid(entry: &feed_rs::model::Entry) -> String {
     // Use entry ID if available and not empty
     if !entry.id.is_empty() {
         entry.id.clone()
@@ -470,20 +1278,24 @@ fn generate_guid(entry: &feed_rs::model::Entry) -> String {
         format!("{}-{}", link.href, title)
     } else {
         // Fallback: use title + published date
-        let title = entry
-            .title
-            .as_ref()
-            .map(|t| t.content.as_str())
-            .unwrap_or("untitled");
-        let date = entry
-            .published
-            .or(entry.updated)
-            .map(|d| d.to_rfc3339())
-            .unwrap_or_else(|| Utc::now().to_rfc3339());
-        format!("{}-{}", title, date)
+        title_date_guid(entry)
     }
 }
 
+fn title_date_guid(entry: &feed_rs::model::Entry) -> String {
+    let title = entry
+        .title
+        .as_ref()
+        .map(|t| t.content.as_str())
+        .unwrap_or("untitled");
+    let date = entry
+        .published
+        .or(entry.updated)
+        .map(|d| d.to_rfc3339())
+        .unwrap_or_else(|| Utc::now().to_rfc3339());
+    format!("{}-{}", title, date)
+}
+
 fn extract_title(entry: &feed_rs::model::Entry) -> String {
     entry
         .title
@@ -518,6 +1330,29 @@ fn extract_author(entry: &feed_rs::model::Entry) -> Option<String> {
     entry.authors.first().map(|author| author.name.clone())
 }
 
+/// Tag names from the entry's categories: whitespace collapsed, duplicates
+/// dropped regardless of case, at most `MAX_CATEGORY_TAGS`
+fn extract_categories(entry: &feed_rs::model::Entry) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for category in &entry.categories {
+        let raw = category.label.as_deref().unwrap_or(&category.term);
+        let name = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+        if name.is_empty()
+            || name.chars().count() > MAX_CATEGORY_TAG_LEN
+            || names.iter().any(|n| n.eq_ignore_ascii_case(&name))
+        {
+            continue;
+        }
+        names.push(name);
+        if names.len() == MAX_CATEGORY_TAGS {
+            break;
+        }
+    }
+    names
+}
+
+/// Entries without `published` fall back to `updated`. This is only used on
+/// first insert, so later edits never change an article's position.
 fn extract_published_date(entry: &feed_rs::model::Entry) -> Option<chrono::DateTime<Utc>> {
     entry
         .published
@@ -525,10 +1360,15 @@ fn extract_published_date(entry: &feed_rs::model::Entry) -> Option<chrono::DateT
         .map(|dt| dt.with_timezone(&Utc))
 }
 
+fn extract_updated_date(entry: &feed_rs::model::Entry) -> Option<chrono::DateTime<Utc>> {
+    entry.updated.map(|dt| dt.with_timezone(&Utc))
+}
+
 /// Fetch OpenGraph metadata for multiple articles in the background
 async fn fetch_opengraph_for_articles(
     pool: sqlx::SqlitePool,
     articles: Vec<(i64, String)>, // (article_id, url)
+    host_delay: Duration,
 ) {
     let article_count = articles.len();
     tracing::info!(
@@ -536,53 +1376,249 @@ async fn fetch_opengraph_for_articles(
         article_count
     );
 
-    for (article_id, url) in articles {
-        // Fetch OpenGraph metadata
-        let (og_image, og_description, og_site_name) = extract_opengraph_from_url(&url).await;
+    let client = match opengraph_client() {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("Failed to build OpenGraph client: {}", e);
+            return;
+        }
+    };
+    let mut host_slots = HashMap::new();
+    let mut pending = articles.into_iter();
+    let mut tasks = JoinSet::new();
+    let mut batch = Vec::with_capacity(OPENGRAPH_BATCH_SIZE);
+
+    loop {
+        // Keep a bounded number of fetches in flight
+        while tasks.len() < OPENGRAPH_CONCURRENCY {
+            let Some((article_id, url)) = pending.next() else {
+                break;
+            };
+            let start_at = reserve_host_slot(&mut host_slots, &url, host_delay);
+            let client = client.clone();
+            tasks.spawn(async move {
+                tokio::time::sleep_until(start_at).await;
+                let opengraph = match fetch_opengraph_page(&client, &url).await {
+                    // Parsing is CPU-bound, so keep it off the async workers
+                    Some(html) => {
+                        tokio::task::spawn_blocking(move || extract_opengraph(&url, html))
+                            .await
+                            .unwrap_or((None, None, None))
+                    }
+                    None => (None, None, None),
+                };
+                (article_id, opengraph)
+            });
+        }
 
-        // Update article with OpenGraph data if any was found
-        if og_image.is_some() || og_description.is_some() || og_site_name.is_some() {
-            match repository::update_article_opengraph(
-                &pool,
-                article_id,
-                og_image,
-                og_description,
-                og_site_name,
-            )
-            .await
-            {
-                Ok(_) => {
-                    tracing::debug!("Updated OpenGraph data for article {}", article_id);
+        // Results are collected and written in batches
+        match tasks.join_next().await {
+            Some(Ok((article_id, (og_image, og_description, og_site_name)))) => {
+                if og_image.is_none() && og_description.is_none() && og_site_name.is_none() {
+                    continue;
                 }
-                Err(e) => {
+                batch.push(OpenGraphUpdate {
+                    article_id,
+                    og_image,
+                    og_description,
+                    og_site_name,
+                });
+                if batch.len() >= OPENGRAPH_BATCH_SIZE {
+                    store_opengraph_batch(&pool, &mut batch).await;
+                }
+            }
+            Some(Err(e)) => tracing::warn!("OpenGraph fetch task failed: {}", e),
+            None => break,
+        }
+    }
+    store_opengraph_batch(&pool, &mut batch).await;
+
+    tracing::info!(
+        "Completed background OpenGraph fetch for {} articles",
+        article_count
+    );
+}
+
+async fn fetch_full_content_for_articles(
+    pool: sqlx::SqlitePool,
+    articles: Vec<(i64, String)>, // (article_id, url)
+    host_delay: Duration,
+    level: ReaderSanitization,
+) {
+    tracing::info!(
+        "Starting background full-content fetch for {} articles",
+        articles.len()
+    );
+
+    let mut host_slots = HashMap::new();
+    let mut pending = articles.into_iter();
+    let mut tasks = JoinSet::new();
+
+    loop {
+        while tasks.len() < FULL_CONTENT_CONCURRENCY {
+            let Some((article_id, url)) = pending.next() else {
+                break;
+            };
+            let start_at = reserve_host_slot(&mut host_slots, &url, host_delay);
+            tasks.spawn(async move {
+                tokio::time::sleep_until(start_at).await;
+                (
+                    article_id,
+                    reader_service::fetch_full_content(&url, level).await,
+                )
+            });
+        }
+
+        match tasks.join_next().await {
+            Some(Ok((article_id, content))) => {
+                store_article_full_content(&pool, article_id, content).await;
+            }
+            Some(Err(e)) => tracing::warn!("Full-content fetch task failed: {}", e),
+            None => break,
+        }
+    }
+}
+
+/// Replace an article's content with its extracted page, keeping the feed
+/// content when extraction failed
+async fn store_article_full_content(
+    pool: &sqlx::SqlitePool,
+    article_id: i64,
+    content: Result<String, ReaderServiceError>,
+) {
+    let content = match content {
+        Ok(content) => content,
+        Err(e) => {
+            tracing::debug!(
+                "Failed to fetch full content for article {}: {}",
+                article_id,
+                e
+            );
+            return;
+        }
+    };
+
+    if let Err(e) = repository::update_article_content(pool, article_id, &content).await {
+        tracing::warn!(
+            "Failed to store full content for article {}: {}",
+            article_id,
+            e
+        );
+    }
+}
+
+/// Queue a background OpenGraph fetch for a feed's articles that have no
+/// image yet, at most `OPENGRAPH_REFRESH_LIMIT` of them. Returns how many
+/// articles were queued.
+pub async fn refresh_feed_opengraph(
+    pool: &sqlx::SqlitePool,
+    feed_id: i64,
+    delays: &FetchDelays,
+) -> Result<usize, sqlx::Error> {
+    let articles =
+        repository::get_articles_missing_opengraph(pool, feed_id, OPENGRAPH_REFRESH_LIMIT).await?;
+    let queued = articles.len();
+    spawn_opengraph_fetch(pool, articles, delays.inter_og);
+
+    Ok(queued)
+}
+
+/// Reserve the next request slot for the URL's host, spacing requests to the
+/// same host by `delay`. Returns when the request may start.
+fn reserve_host_slot(
+    slots: &mut HashMap<String, tokio::time::Instant>,
+    url: &str,
+    delay: Duration,
+) -> tokio::time::Instant {
+    let host = url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_default();
+    let now = tokio::time::Instant::now();
+    let start_at = slots.get(&host).map_or(now, |&next| next.max(now));
+    slots.insert(host, start_at + delay);
+    start_at
+}
+
+/// Write collected OpenGraph data in one transaction and clear the batch.
+/// If the transaction fails, each update is retried on its own so one bad row
+/// doesn't lose the rest.
+async fn store_opengraph_batch(pool: &sqlx::SqlitePool, batch: &mut Vec<OpenGraphUpdate>) {
+    if batch.is_empty() {
+        return;
+    }
+
+    match repository::update_articles_opengraph(pool, batch).await {
+        Ok(()) => {
+            tracing::debug!("Updated OpenGraph data for {} articles", batch.len());
+        }
+        Err(e) => {
+            tracing::warn!(
+                "Batched OpenGraph update failed, writing articles one by one: {}",
+                e
+            );
+            for update in batch.iter() {
+                if let Err(e) = repository::update_article_opengraph(pool, update).await {
                     tracing::warn!(
                         "Failed to update OpenGraph for article {}: {}",
-                        article_id,
+                        update.article_id,
                         e
                     );
                 }
             }
         }
+    }
+    batch.clear();
+}
 
-        // Small delay between requests to be a good citizen
-        tokio::time::sleep(Duration::from_millis(100)).await;
+/// HTTP client for article pages; every connection and redirect hop passes
+/// the SSRF checks
+fn opengraph_client() -> Result<reqwest::Client, reqwest::Error> {
+    let builder = reqwest::Client::builder()
+        .timeout(OPENGRAPH_TIMEOUT)
+        .user_agent(crate::user_agent());
+    ssrf::protect(builder, OPENGRAPH_MAX_REDIRECTS, ssrf::validate_redirect).build()
+}
+
+/// Download an article page, refusing internal addresses (also as redirect
+/// targets) and bodies over `OPENGRAPH_MAX_BYTES`
+async fn fetch_opengraph_page(client: &reqwest::Client, url: &str) -> Option<String> {
+    if let Err(e) = ssrf::validate_url(url).await {
+        tracing::warn!("SSRF validation failed for article URL {}: {}", url, e);
+        return None;
     }
 
-    tracing::info!(
-        "Completed background OpenGraph fetch for {} articles",
-        article_count
-    );
+    let response = match client.get(url).send().await {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => {
+            tracing::debug!("Article page {} answered {}", url, response.status());
+            return None;
+        }
+        Err(e) => {
+            tracing::debug!("Failed to fetch article page {}: {}", url, e);
+            return None;
+        }
+    };
+    let body = match response_body::read_capped(response, OPENGRAPH_MAX_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::debug!("Skipping article page {} for OpenGraph: {}", url, e);
+            return None;
+        }
+    };
+
+    Some(String::from_utf8_lossy(&body).into_owned())
 }
 
-async fn extract_opengraph_from_url(
+/// OpenGraph image, description and site name of a downloaded article page
+fn extract_opengraph(
     url_str: &str,
+    html: String,
 ) -> (Option<String>, Option<String>, Option<String>) {
-    // Try to fetch and parse OpenGraph metadata
-    match webpage::Webpage::from_url(url_str, webpage::WebpageOptions::default()) {
-        Ok(webpage) => {
+    match webpage::HTML::from_string(html, Some(url_str.to_string())) {
+        Ok(html) => {
             // Sanitize and validate og_image URL (prevent XSS via malicious URLs)
-            let og_image = webpage
-                .html
+            let og_image = html
                 .opengraph
                 .images
                 .first()
@@ -598,19 +1634,17 @@ async fn extract_opengraph_from_url(
                 });
 
             // Sanitize og_description to prevent stored XSS
-            let og_description = webpage
-                .html
+            let og_description = html
                 .opengraph
                 .properties
-                .get("og:description")
+                .get("description")
                 .map(|s| ammonia::clean(s));
 
             // Sanitize og_site_name to prevent stored XSS
-            let og_site_name = webpage
-                .html
+            let og_site_name = html
                 .opengraph
                 .properties
-                .get("og:site_name")
+                .get("site_name")
                 .map(|s| ammonia::clean(s));
 
             tracing::debug!(
@@ -624,8 +1658,1146 @@ async fn extract_opengraph_from_url(
             (og_image, og_description, og_site_name)
         }
         Err(e) => {
-            tracing::debug!("Failed to extract OpenGraph from {}: {}", url_str, e);
+            tracing::debug!("Failed to parse OpenGraph from {}: {}", url_str, e);
             (None, None, None)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::{ArticleListFilter, CreateFeed, Feed};
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    const FIRST_PAGE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <id>urn:example:feed</id>
+  <title>Paged Feed</title>
+  <updated>2024-01-03T00:00:00Z</updated>
+  <link rel="next" href="/feed.xml?page=2"/>
+  <entry><id>urn:example:3</id><title>New post</title><updated>2024-01-03T00:00:00Z</updated></entry>
+</feed>"#;
+
+    const SECOND_PAGE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <id>urn:example:feed</id>
+  <title>Paged Feed</title>
+  <updated>2024-01-02T00:00:00Z</updated>
+  <link rel="previous" href="/feed.xml"/>
+  <entry><id>urn:example:2</id><title>Old post 2</title><updated>2024-01-02T00:00:00Z</updated></entry>
+  <entry><id>urn:example:1</id><title>Old post 1</title><updated>2024-01-01T00:00:00Z</updated></entry>
+</feed>"#;
+
+    #[test]
+    fn test_reserve_host_slot_throttles_per_host() {
+        let mut slots = HashMap::new();
+        let delay = Duration::from_millis(100);
+
+        let first = reserve_host_slot(&mut slots, "https://example.com/a", delay);
+        let second = reserve_host_slot(&mut slots, "https://example.com/b", delay);
+        let other = reserve_host_slot(&mut slots, "https://other.example/a", delay);
+
+        assert_eq!(second, first + delay);
+        assert!(other < second);
+    }
+
+    #[test]
+    fn test_archive_link_resolves_next_page() {
+        let page = feed_rs::parser::parse(FIRST_PAGE.as_bytes()).unwrap();
+        assert_eq!(
+            archive_link(&page, "https://example.com/feed.xml").as_deref(),
+            Some("https://example.com/feed.xml?page=2")
+        );
+
+        let last_page = feed_rs::parser::parse(SECOND_PAGE.as_bytes()).unwrap();
+        assert!(archive_link(&last_page, "https://example.com/feed.xml?page=2").is_none());
+    }
+
+    async fn setup_feed() -> (sqlx::SqlitePool, Feed) {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let feed = repository::create_feed(
+            &pool,
+            CreateFeed {
+                url: "https://example.com/feed.xml".to_string(),
+                title: "Paged Feed".to_string(),
+                description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
+            },
+        )
+        .await
+        .unwrap();
+        (pool, feed)
+    }
+
+    #[tokio::test]
+    async fn test_walk_archive_pages_backfills_second_page() {
+        let (pool, feed) = setup_feed().await;
+
+        let first_page = feed_rs::parser::parse(FIRST_PAGE.as_bytes()).unwrap();
+        let start_url = archive_link(&first_page, &feed.url).unwrap();
+        let pages = HashMap::from([(start_url.clone(), SECOND_PAGE)]);
+
+        let count = walk_archive_pages(&pool, &feed, start_url, 3, Duration::ZERO, |url| {
+            let page = pages.get(&url).copied();
+            async move { page.and_then(|xml| feed_rs::parser::parse(xml.as_bytes()).ok()) }
+        })
+        .await;
+
+        assert_eq!(count, 2);
+        let articles = repository::list_articles_with_feeds(
+            &pool,
+            &ArticleListFilter {
+                feed_ids: Some(vec![feed.id]),
+                ..Default::default()
+            },
+            10,
+            0,
+        )
+        .await
+        .unwrap();
+        let titles: Vec<_> = articles.iter().map(|a| a.article.title.as_str()).collect();
+        assert!(titles.contains(&"Old post 1"));
+        assert!(titles.contains(&"Old post 2"));
+    }
+
+    #[test]
+    fn test_link_guid_strategy_ignores_changing_ids() {
+        let entry_with_id = |id: &str| {
+            let xml = format!(
+                r#"<feed xmlns="http://www.w3.org/2005/Atom"><id>urn:example:feed</id><title>Feed</title>
+                <entry><id>{id}</id><title>Post</title><link href="https://example.com/post"/></entry></feed>"#
+            );
+            feed_rs::parser::parse(xml.as_bytes())
+                .unwrap()
+                .entries
+                .remove(0)
+        };
+        let first = entry_with_id("urn:example:1700000000");
+        let second = entry_with_id("urn:example:1700000600");
+
+        assert_eq!(
+            generate_guid(&first, GuidStrategy::Link),
+            "https://example.com/post"
+        );
+        assert_eq!(
+            generate_guid(&first, GuidStrategy::Link),
+            generate_guid(&second, GuidStrategy::Link)
+        );
+        assert_ne!(
+            generate_guid(&first, GuidStrategy::Auto),
+            generate_guid(&second, GuidStrategy::Auto)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_switching_guid_strategy_stores_no_duplicates() {
+        let (pool, feed) = setup_feed().await;
+        let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom"><id>urn:example:feed</id><title>Feed</title>
+            <entry><id>urn:example:1</id><title>One</title><link href="https://example.com/1"/></entry>
+            <entry><id>urn:example:2</id><title>Two</title><link href="https://example.com/2"/></entry></feed>"#;
+        let entries = || feed_rs::parser::parse(xml.as_bytes()).unwrap().entries;
+
+        let (inserted, _) = insert_articles_from_entries(&pool, &feed, entries(), None)
+            .await
+            .unwrap();
+        repository::update_feed_properties(
+            &pool,
+            feed.id,
+            &feed.title,
+            &feed.url,
+            None,
+            &feed.fetch_frequency,
+            feed.fetch_interval_minutes,
+            &feed.color,
+            None,
+            false,
+            false,
+            false,
+            false,
+            Some("link"),
+            None,
+        )
+        .await
+        .unwrap();
+        let feed = repository::get_feed_by_id(&pool, feed.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(feed.pending_guid_remap(), Some(GuidStrategy::Auto));
+
+        let (refetched, _) = insert_articles_from_entries(&pool, &feed, entries(), None)
+            .await
+            .unwrap();
+
+        assert_eq!((inserted, refetched), (2, 0));
+        let guids: Vec<String> = sqlx::query_scalar("SELECT guid FROM articles ORDER BY guid")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(guids, ["https://example.com/1", "https://example.com/2"]);
+    }
+
+    #[tokio::test]
+    async fn test_failing_entry_does_not_fail_its_batch() {
+        let (pool, feed) = setup_feed().await;
+        sqlx::query(
+            "CREATE TRIGGER reject_bad BEFORE INSERT ON articles WHEN NEW.guid = 'urn:example:bad'
+             BEGIN SELECT RAISE(ABORT, 'rejected'); END",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom"><id>urn:example:feed</id><title>Feed</title>
+            <entry><id>urn:example:1</id><title>One</title></entry>
+            <entry><id>urn:example:bad</id><title>Bad</title></entry>
+            <entry><id>urn:example:2</id><title>Two</title></entry></feed>"#;
+        let entries = feed_rs::parser::parse(xml.as_bytes()).unwrap().entries;
+
+        let (inserted, _) = insert_articles_from_entries(&pool, &feed, entries, None)
+            .await
+            .unwrap();
+
+        assert_eq!(inserted, 2);
+        let guids: Vec<String> = sqlx::query_scalar("SELECT guid FROM articles ORDER BY guid")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(guids, ["urn:example:1", "urn:example:2"]);
+    }
+
+    #[tokio::test]
+    async fn test_refetch_with_newer_updated_keeps_published_at() {
+        let (pool, feed) = setup_feed().await;
+        let entry_at = |updated: &str| {
+            let xml = format!(
+                r#"<feed xmlns="http://www.w3.org/2005/Atom"><id>urn:example:feed</id><title>Feed</title>
+                <updated>{updated}</updated>
+                <entry><id>urn:example:1</id><title>Post</title><updated>{updated}</updated></entry></feed>"#
+            );
+            feed_rs::parser::parse(xml.as_bytes()).unwrap().entries
+        };
+
+        let (inserted, _) =
+            insert_articles_from_entries(&pool, &feed, entry_at("2024-01-01T00:00:00Z"), None)
+                .await
+                .unwrap();
+        let (refetched, _) =
+            insert_articles_from_entries(&pool, &feed, entry_at("2024-06-01T00:00:00Z"), None)
+                .await
+                .unwrap();
+
+        assert_eq!((inserted, refetched), (1, 0));
+        let (published_at, updated_at_source): (String, String) = sqlx::query_as(
+            "SELECT published_at, updated_at_source FROM articles WHERE guid = 'urn:example:1'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert!(published_at.starts_with("2024-01-01"));
+        assert!(updated_at_source.starts_with("2024-06-01"));
+    }
+
+    #[tokio::test]
+    async fn test_changed_content_under_same_guid_is_taken_over() {
+        let (pool, feed) = setup_feed().await;
+        let entry_with = |content: &str| {
+            let xml = format!(
+                r#"<feed xmlns="http://www.w3.org/2005/Atom"><id>urn:example:feed</id><title>Feed</title>
+                <entry><id>urn:example:1</id><title>Post</title><content type="html">{content}</content></entry></feed>"#
+            );
+            feed_rs::parser::parse(xml.as_bytes()).unwrap().entries
+        };
+        let stored = |pool: sqlx::SqlitePool| async move {
+            sqlx::query_as::<_, (String, String, Option<String>)>(
+                "SELECT content, content_hash, content_changed_at FROM articles WHERE guid = 'urn:example:1'",
+            )
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+        };
+
+        insert_articles_from_entries(&pool, &feed, entry_with("First"), None)
+            .await
+            .unwrap();
+        let (content, first_hash, changed_at) = stored(pool.clone()).await;
+        assert_eq!(content, "First");
+        assert!(changed_at.is_none());
+
+        // The same content again is no change
+        insert_articles_from_entries(&pool, &feed, entry_with("First"), None)
+            .await
+            .unwrap();
+        assert!(stored(pool.clone()).await.2.is_none());
+
+        let (inserted, _) = insert_articles_from_entries(&pool, &feed, entry_with("Second"), None)
+            .await
+            .unwrap();
+        let (content, second_hash, changed_at) = stored(pool.clone()).await;
+        assert_eq!(inserted, 0);
+        assert_eq!(content, "Second");
+        assert_ne!(first_hash, second_hash);
+        assert!(changed_at.is_some());
+
+        let since = Utc::now() - chrono::Duration::days(1);
+        let changes = repository::count_content_changes_since(&pool, feed.id, since)
+            .await
+            .unwrap();
+        assert_eq!(changes, 1);
+    }
+
+    #[tokio::test]
+    async fn test_large_feed_is_stored_in_batches() {
+        let (pool, feed) = setup_feed().await;
+        let count = ARTICLE_INSERT_BATCH * 5 + 7;
+        let entries: String = (0..count)
+            .map(|i| format!("<entry><id>urn:example:{i}</id><title>Post {i}</title></entry>"))
+            .collect();
+        // A repeated GUID within a batch is stored once
+        let xml = format!(
+            r#"<feed xmlns="http://www.w3.org/2005/Atom"><id>urn:example:feed</id><title>Feed</title>
+            {entries}<entry><id>urn:example:3</id><title>Post 3 again</title></entry></feed>"#
+        );
+        let entries = feed_rs::parser::parse(xml.as_bytes()).unwrap().entries;
+        let progress = InsertProgressStore::default();
+
+        let (inserted, _) = insert_articles_from_entries(&pool, &feed, entries, Some(&progress))
+            .await
+            .unwrap();
+
+        assert_eq!(inserted, count);
+        let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM articles")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(stored, count as i64);
+        assert_eq!(progress.get(feed.id), None);
+
+        // Refetching stores nothing new
+        let xml = format!(
+            r#"<feed xmlns="http://www.w3.org/2005/Atom"><id>urn:example:feed</id><title>Feed</title>
+            <entry><id>urn:example:{}</id><title>Newest</title></entry>
+            <entry><id>urn:example:0</id><title>Post 0</title></entry></feed>"#,
+            count
+        );
+        let entries = feed_rs::parser::parse(xml.as_bytes()).unwrap().entries;
+        let (inserted, _) = insert_articles_from_entries(&pool, &feed, entries, None)
+            .await
+            .unwrap();
+        assert_eq!(inserted, 1);
+    }
+
+    #[tokio::test]
+    async fn test_inserted_article_gets_plain_text_preview() {
+        let (pool, feed) = setup_feed().await;
+        let paragraphs = "<p>Some <b>bold</b> &amp; long\n  text.</p>\n".repeat(100);
+        let xml = format!(
+            r#"<feed xmlns="http://www.w3.org/2005/Atom"><id>urn:example:feed</id><title>Feed</title>
+            <entry><id>urn:example:1</id><title>Post</title><content type="html"><![CDATA[{paragraphs}]]></content></entry></feed>"#
+        );
+        let entries = feed_rs::parser::parse(xml.as_bytes()).unwrap().entries;
+
+        insert_articles_from_entries(&pool, &feed, entries, None)
+            .await
+            .unwrap();
+
+        let preview: String = sqlx::query_scalar("SELECT content_preview FROM articles")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(preview.starts_with("Some bold & long text. Some bold"));
+        assert!(!preview.contains('<') && !preview.contains('\n'));
+        assert_eq!(
+            preview.chars().count(),
+            crate::domain::article_service::CONTENT_PREVIEW_CHARS
+        );
+        assert!(preview.ends_with('…'));
+    }
+
+    #[tokio::test]
+    async fn test_unhashed_article_only_gets_its_hash() {
+        let (pool, feed) = setup_feed().await;
+        let entries = || {
+            let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom"><id>urn:example:feed</id><title>Feed</title>
+                <entry><id>urn:example:1</id><title>Post</title><content type="html">Body</content></entry></feed>"#;
+            feed_rs::parser::parse(xml.as_bytes()).unwrap().entries
+        };
+
+        insert_articles_from_entries(&pool, &feed, entries(), None)
+            .await
+            .unwrap();
+        // As stored before content was hashed
+        sqlx::query("UPDATE articles SET content_hash = NULL, content = 'Old'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        insert_articles_from_entries(&pool, &feed, entries(), None)
+            .await
+            .unwrap();
+
+        let (content, content_hash): (String, Option<String>) =
+            sqlx::query_as("SELECT content, content_hash FROM articles")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(content, "Old");
+        assert!(content_hash.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_failed_update_rolls_back_metadata() {
+        let (pool, feed) = setup_feed().await;
+        // The metadata update fails after the articles were inserted
+        sqlx::query(
+            "CREATE TRIGGER fail_feed_update BEFORE UPDATE ON feeds \
+             BEGIN SELECT RAISE(ABORT, 'forced failure'); END",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let parsed = feed_rs::parser::parse(FIRST_PAGE.as_bytes()).unwrap();
+
+        let result = handle_feed_update(
+            &pool,
+            &feed,
+            FeedUpdate {
+                parsed_feed: parsed,
+                discovered_url: None,
+                etag: Some("\"v2\"".to_string()),
+                last_modified: None,
+                ttl: None,
+                duration_ms: 10,
+                og_delay: Duration::ZERO,
+                adaptive: &AdaptiveInterval::default(),
+                reader_sanitization: ReaderSanitization::default(),
+                progress: None,
+            },
+        )
+        .await;
+
+        let Err(e) = result else {
+            panic!("Update succeeded despite the failing trigger");
+        };
+        assert!(e.to_string().contains("forced failure"));
+        let stored = repository::get_feed_by_id(&pool, feed.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.etag, None);
+        assert_eq!(stored.description, None);
+        assert_eq!(stored.last_fetched_at, None);
+        let (articles, logs): (i64, i64) =
+            sqlx::query_as("SELECT (SELECT COUNT(*) FROM articles), (SELECT COUNT(*) FROM logs)")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!((articles, logs), (0, 0));
+    }
+
+    /// Intervals after each of a run of fetches that all found new articles
+    fn ramp(adaptive: &AdaptiveInterval, fetches: usize) -> Vec<i64> {
+        let (mut interval, mut consecutive) = (480, 0);
+        (0..fetches)
+            .map(|_| {
+                (interval, consecutive) =
+                    next_adaptive_interval(adaptive, interval, consecutive, true);
+                interval
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_adaptive_interval_default_ramp() {
+        let adaptive = AdaptiveInterval::default();
+
+        assert_eq!(ramp(&adaptive, 5), vec![480, 240, 120, 60, 60]);
+        assert_eq!(next_adaptive_interval(&adaptive, 240, 2, false), (480, 0));
+        assert_eq!(
+            next_adaptive_interval(&adaptive, 8000, 0, false),
+            (10080, 0)
+        );
+    }
+
+    #[test]
+    fn test_adaptive_interval_follows_configured_threshold_and_bounds() {
+        let eager = AdaptiveInterval {
+            consecutive_fetches: 1,
+            ..AdaptiveInterval::default()
+        };
+        assert_eq!(ramp(&eager, 4), vec![240, 120, 60, 60]);
+
+        let cautious = AdaptiveInterval {
+            consecutive_fetches: 4,
+            min_interval_minutes: 15,
+            max_interval_minutes: 720,
+        };
+        assert_eq!(ramp(&cautious, 6), vec![480, 480, 480, 240, 120, 60]);
+        assert_eq!(next_adaptive_interval(&cautious, 480, 4, false), (720, 0));
+    }
+
+    #[test]
+    fn test_retry_backoff_doubles_up_to_cap() {
+        let minutes: Vec<_> = (1..=8)
+            .map(|failures| retry_backoff(failures, 0.0).num_minutes())
+            .collect();
+        assert_eq!(minutes, vec![5, 10, 20, 40, 80, 160, 320, 360]);
+        assert_eq!(retry_backoff(1000, 0.0).num_minutes(), RETRY_CAP_MINUTES);
+    }
+
+    #[test]
+    fn test_retry_backoff_jitter_is_bounded() {
+        assert_eq!(retry_backoff(1, 0.2).num_seconds(), 360);
+        assert_eq!(retry_backoff(1, -5.0).num_seconds(), 240);
+        for _ in 0..100 {
+            let secs = retry_backoff(2, random_jitter()).num_seconds();
+            assert!((480..=720).contains(&secs));
+        }
+    }
+
+    fn not_modified(ttl: Option<i64>, adaptive: &AdaptiveInterval) -> FeedNotModified<'_> {
+        FeedNotModified {
+            etag: None,
+            ttl,
+            duration_ms: 5,
+            adaptive,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_not_modified_with_caching_hint_updates_ttl() {
+        let (pool, feed) = setup_feed().await;
+        let adaptive = AdaptiveInterval::default();
+
+        handle_feed_not_modified(&pool, &feed, not_modified(Some(120), &adaptive))
+            .await
+            .unwrap();
+        let feed = repository::get_feed_by_id(&pool, feed.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(feed.ttl_minutes, Some(120));
+
+        // Without a hint the stored TTL is kept
+        handle_feed_not_modified(&pool, &feed, not_modified(None, &adaptive))
+            .await
+            .unwrap();
+        let feed = repository::get_feed_by_id(&pool, feed.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(feed.ttl_minutes, Some(120));
+
+        // A <ttl> from the feed itself isn't replaced by a later hint
+        let announced = FeedTtl {
+            minutes: 30,
+            from_headers: false,
+        };
+        repository::update_feed_ttl_only(&pool, feed.id, announced)
+            .await
+            .unwrap();
+        let feed = repository::get_feed_by_id(&pool, feed.id)
+            .await
+            .unwrap()
+            .unwrap();
+        handle_feed_not_modified(&pool, &feed, not_modified(Some(120), &adaptive))
+            .await
+            .unwrap();
+        let feed = repository::get_feed_by_id(&pool, feed.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(feed.ttl(), Some(announced));
+    }
+
+    #[tokio::test]
+    async fn test_caching_hint_defers_next_fetch() {
+        let (pool, feed) = setup_feed().await;
+        let adaptive = AdaptiveInterval::default();
+        let due = || repository::get_feeds_to_update(&pool, 0, chrono::NaiveTime::MIN);
+
+        // Shorter than the fetch interval: nothing to defer
+        let hint = feed.fetch_interval_minutes / 2;
+        handle_feed_not_modified(&pool, &feed, not_modified(Some(hint), &adaptive))
+            .await
+            .unwrap();
+        let feed = repository::get_feed_by_id(&pool, feed.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(feed.next_retry_at.is_none());
+
+        // Longer: the feed waits until the cached copy expires, a day at most
+        handle_feed_not_modified(&pool, &feed, not_modified(Some(7 * 24 * 60), &adaptive))
+            .await
+            .unwrap();
+        let feed = repository::get_feed_by_id(&pool, feed.id)
+            .await
+            .unwrap()
+            .unwrap();
+        let held_for = feed.next_retry_at.unwrap() - Utc::now();
+        assert!(held_for > chrono::Duration::hours(23));
+        assert!(held_for <= chrono::Duration::hours(24));
+        assert_eq!(feed.consecutive_failures, 0);
+
+        // Due again once the interval passed, but still held by the hint
+        sqlx::query("UPDATE feeds SET last_fetched_at = datetime('now', '-1 day')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(due().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_backoff_holds_feed_until_reset() {
+        let (pool, feed) = setup_feed().await;
+        let next_retry_at = Utc::now() + chrono::Duration::minutes(30);
+        repository::update_feed_failure_state(&pool, feed.id, 3, Some(next_retry_at))
+            .await
+            .unwrap();
+
+        let due = repository::get_feeds_to_update(&pool, 0, chrono::NaiveTime::MIN)
+            .await
+            .unwrap();
+        assert!(due.is_empty());
+
+        let feed = repository::get_feed_by_id(&pool, feed.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(feed.consecutive_failures, 3);
+        reset_failure_backoff(&pool, &feed).await.unwrap();
+
+        let due = repository::get_feeds_to_update(&pool, 0, chrono::NaiveTime::MIN)
+            .await
+            .unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].consecutive_failures, 0);
+        assert!(due[0].next_retry_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stored_feed_resolving_to_private_ip_is_blocked() {
+        let (pool, _) = setup_feed().await;
+        // Stored directly, as if the host resolved publicly when it was added
+        let feed = repository::create_feed(
+            &pool,
+            CreateFeed {
+                url: "http://localhost:1/feed.xml".to_string(),
+                title: "Rebound Feed".to_string(),
+                description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let result = fetch_single_feed(
+            &pool,
+            &feed,
+            &FetchDelays::default(),
+            &AdaptiveInterval::default(),
+            ReaderSanitization::default(),
+            &HostPolicy::default(),
+            &SecretKey::default(),
+            None,
+        )
+        .await;
+
+        let err = result.err().unwrap();
+        assert!(matches!(
+            err.downcast_ref::<rss_fetcher::FetchError>(),
+            Some(rss_fetcher::FetchError::SsrfBlocked)
+        ));
+        let logs = repository::list_logs_with_feeds(&pool, Some(feed.id), None, None, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(logs[0].log.log_type, "error");
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_fetch_adopts_discovered_feed_url() {
+        let (pool, _) = setup_feed().await;
+
+        // A local server stands in for a homepage advertising its feed
+        ssrf::allow_loopback_on_this_thread();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = [0u8; 1024];
+                let n = socket.read(&mut request).await.unwrap();
+                let (content_type, body) = if request[..n].starts_with(b"GET /feed.xml") {
+                    (
+                        "application/atom+xml",
+                        r#"<feed xmlns="http://www.w3.org/2005/Atom"><id>urn:example:feed</id><title>Blog</title>
+                        <entry><id>urn:example:1</id><title>Post</title></entry></feed>"#,
+                    )
+                } else {
+                    (
+                        "text/html",
+                        r#"<html><head><link rel="alternate" type="application/atom+xml" href="/feed.xml"></head></html>"#,
+                    )
+                };
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    content_type,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        let create = |url: String| {
+            repository::create_feed(
+                &pool,
+                CreateFeed {
+                    url,
+                    title: "Blog".to_string(),
+                    description: None,
+                    color: None,
+                    fetch_frequency: None,
+                    ignore_pattern: None,
+                },
+            )
+        };
+        let fetch = |feed: Feed| {
+            let pool = pool.clone();
+            async move {
+                fetch_single_feed(
+                    &pool,
+                    &feed,
+                    &FetchDelays::default(),
+                    &AdaptiveInterval::default(),
+                    ReaderSanitization::default(),
+                    &HostPolicy::default(),
+                    &SecretKey::default(),
+                    None,
+                )
+                .await
+                .unwrap();
+                repository::get_feed_by_id(&pool, feed.id)
+                    .await
+                    .unwrap()
+                    .unwrap()
+            }
+        };
+
+        // Added by its homepage, e.g. from OPML, without the interactive form
+        let homepage = create(format!("http://{addr}/")).await.unwrap();
+        let feed = fetch(homepage).await;
+        assert_eq!(feed.url, format!("http://{addr}/feed.xml"));
+
+        // A feed already subscribed by the discovered URL keeps the page URL
+        let duplicate = create(format!("http://{addr}/blog")).await.unwrap();
+        let feed = fetch(duplicate).await;
+        assert_eq!(feed.url, format!("http://{addr}/blog"));
+    }
+
+    #[tokio::test]
+    async fn test_bearer_token_is_sent_as_authorization() {
+        let (pool, feed) = setup_feed().await;
+        let key = SecretKey::random();
+        let headers = vec![("referer".to_string(), "https://example.com/".to_string())];
+        repository::set_feed_headers(&mut pool.acquire().await.unwrap(), feed.id, &headers, &key)
+            .await
+            .unwrap();
+        assert_eq!(
+            load_custom_headers(&pool, feed.id, &key).await.unwrap(),
+            headers
+        );
+
+        repository::set_feed_bearer_token(&pool, feed.id, Some("secret"), &key)
+            .await
+            .unwrap();
+        let loaded = load_custom_headers(&pool, feed.id, &key).await.unwrap();
+        assert!(loaded.contains(&("authorization".to_string(), "Bearer secret".to_string())));
+        assert_eq!(loaded.len(), 2);
+
+        // The token is kept out of the feed itself, which is served as JSON
+        let feed = repository::get_feed_by_id(&pool, feed.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!serde_json::to_string(&feed).unwrap().contains("secret"));
+    }
+
+    #[tokio::test]
+    async fn test_authentication_failure_is_logged_as_auth_failed() {
+        let (pool, feed) = setup_feed().await;
+
+        let error = rss_fetcher::FetchError::AuthenticationFailed {
+            challenge: "Bearer realm=\"example\", error=\"invalid_token\"".to_string(),
+        };
+        // The error is passed on after logging
+        assert!(handle_feed_fetch_error(&pool, &feed, error, 10)
+            .await
+            .is_err());
+        let error = rss_fetcher::FetchError::RequestFailed {
+            status: reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+            message: "500 - Internal Server Error".to_string(),
+            retry_after: None,
+        };
+        assert!(handle_feed_fetch_error(&pool, &feed, error, 10)
+            .await
+            .is_err());
+
+        let logs = repository::list_logs_with_feeds(&pool, Some(feed.id), None, None, 10, 0)
+            .await
+            .unwrap();
+        let auth_log = logs
+            .iter()
+            .find(|l| l.log.log_type == "auth_failed")
+            .unwrap();
+        assert_eq!(auth_log.log.status_code, Some(401));
+        assert!(auth_log
+            .log
+            .error_message
+            .as_deref()
+            .unwrap()
+            .contains("invalid_token"));
+        assert!(logs
+            .iter()
+            .any(|l| l.log.log_type == "error" && l.log.status_code == Some(500)));
+    }
+
+    #[tokio::test]
+    async fn test_entry_categories_become_article_tags() {
+        let (pool, feed) = setup_feed().await;
+        let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom"><id>urn:example:feed</id><title>Feed</title>
+            <entry><id>urn:example:1</id><title>Post</title>
+            <category term="rust"/><category term="web  dev"/><category term="Rust"/></entry></feed>"#;
+        let entries = feed_rs::parser::parse(xml.as_bytes()).unwrap().entries;
+        insert_articles_from_entries(&pool, &feed, entries, None)
+            .await
+            .unwrap();
+
+        let article = repository::get_article_by_guid(&pool, feed.id, "urn:example:1")
+            .await
+            .unwrap()
+            .unwrap();
+        let tags = repository::get_tags_for_articles(&pool, &[article.id])
+            .await
+            .unwrap();
+        let names: Vec<_> = tags[&article.id].iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["rust", "web dev"]);
+    }
+
+    #[tokio::test]
+    async fn test_full_content_replaces_summary_of_flagged_feed() {
+        let (pool, mut feed) = setup_feed().await;
+        let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom"><id>urn:example:feed</id><title>Feed</title>
+            <entry><id>urn:example:1</id><title>Post</title><link href="https://example.com/post"/>
+            <summary>Summary only</summary></entry></feed>"#;
+        let entries = feed_rs::parser::parse(xml.as_bytes()).unwrap().entries;
+        let (_, new_articles) = insert_articles_from_entries(&pool, &feed, entries, None)
+            .await
+            .unwrap();
+
+        assert!(full_content_queue(&feed, &new_articles).is_empty());
+        feed.auto_fetch_full_content = true;
+        let queue = full_content_queue(&feed, &new_articles);
+        assert_eq!(queue.len(), 1);
+
+        let (article_id, url) = &queue[0];
+        let page = r#"<html><head><title>Post</title></head><body><article>
+            <h1>Post</h1>
+            <p>The full text of the post is considerably longer than the summary in the feed.</p>
+            <p>It spans several paragraphs that readers would otherwise only see on the site.</p>
+            </article></body></html>"#;
+        store_article_full_content(
+            &pool,
+            *article_id,
+            reader_service::extract_full_content(page, url, ReaderSanitization::default()),
+        )
+        .await;
+
+        let article = repository::get_article_by_id(&pool, *article_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(article
+            .content
+            .unwrap()
+            .contains("considerably longer than the summary"));
+    }
+
+    #[tokio::test]
+    async fn test_full_content_redirected_inwards_stores_nothing() {
+        let (pool, feed) = setup_feed().await;
+
+        // A local server stands in for a public page redirecting inwards
+        ssrf::allow_loopback_on_this_thread();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+            let response = "HTTP/1.1 302 Found\r\nLocation: http://169.254.169.254/latest/meta-data/\r\nContent-Length: 0\r\n\r\n";
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let xml = format!(
+            r#"<feed xmlns="http://www.w3.org/2005/Atom"><id>urn:example:feed</id><title>Feed</title>
+            <entry><id>urn:example:1</id><title>Post</title><link href="http://{addr}/post"/>
+            <summary>Summary only</summary></entry></feed>"#
+        );
+        let entries = feed_rs::parser::parse(xml.as_bytes()).unwrap().entries;
+        let (_, new_articles) = insert_articles_from_entries(&pool, &feed, entries, None)
+            .await
+            .unwrap();
+        let article_id = new_articles[0].0;
+
+        fetch_full_content_for_articles(
+            pool.clone(),
+            new_articles,
+            Duration::ZERO,
+            ReaderSanitization::default(),
+        )
+        .await;
+
+        let article = repository::get_article_by_id(&pool, article_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(article.content, None);
+        assert_eq!(article.summary.as_deref(), Some("Summary only"));
+    }
+
+    #[tokio::test]
+    async fn test_custom_title_survives_feed_update() {
+        let (pool, feed) = setup_feed().await;
+        let rename = |title: &'static str| {
+            let pool = pool.clone();
+            let url = feed.url.clone();
+            async move {
+                repository::update_feed_properties(
+                    &pool, feed.id, title, &url, None, "adaptive", 60, "#3B82F6", None, false,
+                    false, false, false, None, None,
+                )
+                .await
+                .unwrap();
+                repository::get_feed_by_id(&pool, feed.id)
+                    .await
+                    .unwrap()
+                    .unwrap()
+            }
+        };
+
+        // Saving the form without touching the title keeps it automatic
+        assert!(!rename("Paged Feed").await.title_is_custom);
+        let feed = rename("Paged Feed (work)").await;
+        assert!(feed.title_is_custom);
+
+        let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom"><id>urn:example:feed</id>
+            <title>Renamed Upstream</title></feed>"#;
+        let parsed = feed_rs::parser::parse(xml.as_bytes()).unwrap();
+        handle_feed_update(
+            &pool,
+            &feed,
+            FeedUpdate {
+                parsed_feed: parsed,
+                discovered_url: None,
+                etag: None,
+                last_modified: None,
+                ttl: None,
+                duration_ms: 10,
+                og_delay: Duration::ZERO,
+                adaptive: &AdaptiveInterval::default(),
+                reader_sanitization: ReaderSanitization::default(),
+                progress: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let stored = repository::get_feed_by_id(&pool, feed.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.title, "Paged Feed (work)");
+        assert!(stored.last_fetched_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_feed_language_is_stored() {
+        let (pool, feed) = setup_feed().await;
+        let xml = r#"<rss version="2.0"><channel><title>Feed</title>
+            <link>https://example.com/</link><language>en-US</language></channel></rss>"#;
+        let parsed = feed_rs::parser::parse(xml.as_bytes()).unwrap();
+        handle_feed_update(
+            &pool,
+            &feed,
+            FeedUpdate {
+                parsed_feed: parsed,
+                discovered_url: None,
+                etag: None,
+                last_modified: None,
+                ttl: None,
+                duration_ms: 10,
+                og_delay: Duration::ZERO,
+                adaptive: &AdaptiveInterval::default(),
+                reader_sanitization: ReaderSanitization::default(),
+                progress: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let stored = repository::get_feed_by_id(&pool, feed.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.language.as_deref(), Some("en-US"));
+    }
+
+    #[test]
+    fn test_language_tag_normalizes_declared_language() {
+        assert_eq!(language_tag(" de_de ").as_deref(), Some("de-DE"));
+        assert_eq!(language_tag("EN").as_deref(), Some("en"));
+        assert_eq!(language_tag("zh-hant-tw").as_deref(), Some("zh-Hant-TW"));
+        assert_eq!(language_tag(""), None);
+        assert_eq!(language_tag("en\" onload=\"x"), None);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_leaves_subscribed_at_unchanged() {
+        let (pool, feed) = setup_feed().await;
+        assert_eq!(feed.subscribed_at, feed.created_at);
+
+        let first_page = feed_rs::parser::parse(FIRST_PAGE.as_bytes()).unwrap();
+        handle_feed_update(
+            &pool,
+            &feed,
+            FeedUpdate {
+                parsed_feed: first_page,
+                discovered_url: None,
+                etag: None,
+                last_modified: None,
+                ttl: None,
+                duration_ms: 10,
+                og_delay: Duration::ZERO,
+                adaptive: &AdaptiveInterval::default(),
+                reader_sanitization: ReaderSanitization::default(),
+                progress: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let stored = repository::get_feed_by_id(&pool, feed.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(stored.last_fetched_at.is_some());
+        assert_eq!(stored.subscribed_at, feed.subscribed_at);
+    }
+
+    #[tokio::test]
+    async fn test_opengraph_batch_stores_every_update() {
+        let (pool, feed) = setup_feed().await;
+        let mut batch = Vec::new();
+        for i in 0..OPENGRAPH_BATCH_SIZE + 5 {
+            let article = repository::insert_article_if_new(
+                &pool,
+                NewArticle {
+                    feed_id: feed.id,
+                    guid: format!("post-{}", i),
+                    title: format!("Post {}", i),
+                    url: Some(format!("https://example.com/{}", i)),
+                    content: None,
+                    summary: None,
+                    author: None,
+                    published_at: None,
+                    updated_at_source: None,
+                    og_image: None,
+                    og_description: None,
+                    og_site_name: None,
+                    language: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+            batch.push(OpenGraphUpdate {
+                article_id: article.id,
+                og_image: Some(format!("https://example.com/{}.png", i)),
+                og_description: None,
+                og_site_name: Some("Example".to_string()),
+            });
+        }
+
+        store_opengraph_batch(&pool, &mut batch).await;
+
+        assert!(batch.is_empty());
+        let stored: Vec<(Option<String>, Option<String>)> =
+            sqlx::query_as("SELECT og_image, og_site_name FROM articles ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(stored.len(), OPENGRAPH_BATCH_SIZE + 5);
+        for (i, (og_image, og_site_name)) in stored.iter().enumerate() {
+            assert_eq!(
+                og_image.as_deref(),
+                Some(format!("https://example.com/{}.png", i).as_str())
+            );
+            assert_eq!(og_site_name.as_deref(), Some("Example"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_opengraph_fetch_refuses_internal_urls_and_redirects() {
+        let client = opengraph_client().unwrap();
+        assert_eq!(
+            fetch_opengraph_page(&client, "http://127.0.0.1/").await,
+            None
+        );
+
+        // A local server stands in for a public page redirecting inwards
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+            let response = "HTTP/1.1 302 Found\r\nLocation: http://169.254.169.254/latest/meta-data/\r\nContent-Length: 0\r\n\r\n";
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let err = client
+            .get(format!("http://{addr}/article"))
+            .send()
+            .await
+            .unwrap_err();
+        assert!(err.is_redirect());
+    }
+
+    #[test]
+    fn test_extract_opengraph_sanitizes_values() {
+        let html = r#"<html><head>
+            <meta property="og:image" content="javascript:alert(1)">
+            <meta property="og:description" content="<script>x</script>Text">
+            <meta property="og:site_name" content="Example">
+            </head></html>"#;
+
+        let (og_image, og_description, og_site_name) =
+            extract_opengraph("https://example.com/post", html.to_string());
+
+        assert_eq!(og_image, None);
+        assert_eq!(og_description.as_deref(), Some("Text"));
+        assert_eq!(og_site_name.as_deref(), Some("Example"));
+    }
+}
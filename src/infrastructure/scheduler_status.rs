@@ -0,0 +1,136 @@
+//! What the background scheduler is doing and what its last fetch cycle
+//! achieved, for the status endpoint
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Outcome of a completed fetch cycle
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CycleSummary {
+    /// Feeds that were fetched, whatever the result
+    pub feeds_fetched: usize,
+    /// Feeds whose fetch returned new content
+    pub feeds_updated: usize,
+    pub feeds_failed: usize,
+    pub new_articles: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct CycleState {
+    started_at: Option<DateTime<Utc>>,
+    finished_at: Option<DateTime<Utc>>,
+    feeds_due: usize,
+    last_cycle: Option<CycleSummary>,
+}
+
+/// Point-in-time view of the scheduler
+#[derive(Debug, Clone, Serialize)]
+pub struct SchedulerSnapshot {
+    pub enabled: bool,
+    pub running: bool,
+    pub last_cycle_started_at: Option<DateTime<Utc>>,
+    pub last_cycle_finished_at: Option<DateTime<Utc>>,
+    /// Feeds found due when the latest cycle started
+    pub feeds_due: usize,
+    /// Counters of the last completed cycle
+    pub last_cycle: Option<CycleSummary>,
+    pub in_flight_fetches: usize,
+}
+
+/// Shared by the scheduler and the status endpoint; cloning shares the state
+#[derive(Clone, Default)]
+pub struct SchedulerStatus {
+    enabled: Arc<AtomicBool>,
+    in_flight: Arc<AtomicUsize>,
+    cycle: Arc<RwLock<CycleState>>,
+}
+
+/// Counts a fetch as in flight until dropped
+pub struct InFlightFetch {
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl Drop for InFlightFetch {
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl SchedulerStatus {
+    pub fn set_enabled(&self) {
+        self.enabled.store(true, Ordering::Relaxed);
+    }
+
+    pub async fn cycle_started(&self, feeds_due: usize) {
+        let mut cycle = self.cycle.write().await;
+        cycle.started_at = Some(Utc::now());
+        cycle.feeds_due = feeds_due;
+    }
+
+    pub async fn cycle_finished(&self, summary: CycleSummary) {
+        let mut cycle = self.cycle.write().await;
+        cycle.finished_at = Some(Utc::now());
+        cycle.last_cycle = Some(summary);
+    }
+
+    pub fn fetch_started(&self) -> InFlightFetch {
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        InFlightFetch {
+            in_flight: self.in_flight.clone(),
+        }
+    }
+
+    pub async fn snapshot(&self) -> SchedulerSnapshot {
+        let cycle = self.cycle.read().await.clone();
+        SchedulerSnapshot {
+            enabled: self.enabled.load(Ordering::Relaxed),
+            // A cycle is running from its start until a later finish
+            running: match (cycle.started_at, cycle.finished_at) {
+                (Some(started), Some(finished)) => started > finished,
+                (started, _) => started.is_some(),
+            },
+            last_cycle_started_at: cycle.started_at,
+            last_cycle_finished_at: cycle.finished_at,
+            feeds_due: cycle.feeds_due,
+            last_cycle: cycle.last_cycle,
+            in_flight_fetches: self.in_flight.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_snapshot_follows_cycle() {
+        let status = SchedulerStatus::default();
+        let snapshot = status.snapshot().await;
+        assert!(!snapshot.enabled && !snapshot.running);
+        assert_eq!(snapshot.last_cycle, None);
+
+        status.set_enabled();
+        status.cycle_started(3).await;
+        let fetch = status.fetch_started();
+        let snapshot = status.snapshot().await;
+        assert!(snapshot.enabled && snapshot.running);
+        assert_eq!(snapshot.feeds_due, 3);
+        assert_eq!(snapshot.in_flight_fetches, 1);
+
+        drop(fetch);
+        let summary = CycleSummary {
+            feeds_fetched: 3,
+            feeds_updated: 2,
+            feeds_failed: 1,
+            new_articles: 5,
+        };
+        status.cycle_finished(summary).await;
+        let snapshot = status.snapshot().await;
+        assert!(!snapshot.running);
+        assert_eq!(snapshot.in_flight_fetches, 0);
+        assert_eq!(snapshot.last_cycle, Some(summary));
+    }
+}
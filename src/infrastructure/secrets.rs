@@ -0,0 +1,209 @@
+//! Encryption of credentials stored in the database, such as feed bearer
+//! tokens, with a key kept outside of it: `SECRET_KEY`, or a key file next
+//! to the database that is created on first start. Both hold a random key
+//! rather than a passphrase. Keys for other purposes, such as signing URLs,
+//! are derived from it.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use std::fmt;
+use std::io::Write;
+use std::path::Path;
+use thiserror::Error;
+
+/// Prefix of encrypted values; values without it were stored in plain text
+/// before encryption and are sealed on startup
+const SEALED_PREFIX: &str = "enc:v1:";
+
+const KEY_BYTES: usize = 32;
+
+const NONCE_BYTES: usize = 12;
+
+#[derive(Error, Debug)]
+pub enum SecretError {
+    #[error("Stored secret is corrupt or was encrypted with another key")]
+    Undecryptable,
+
+    #[error("Key file {0}: {1}")]
+    KeyFile(String, std::io::Error),
+
+    #[error("Key file {0} does not hold a key")]
+    InvalidKeyFile(String),
+}
+
+/// Key of stored credentials. Without a configured one (in-memory databases,
+/// tests) the default is a random key that lasts as long as the process.
+#[derive(Clone)]
+pub struct SecretKey([u8; KEY_BYTES]);
+
+impl Default for SecretKey {
+    fn default() -> Self {
+        Self::random()
+    }
+}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretKey(..)")
+    }
+}
+
+impl SecretKey {
+    /// The configured key (`SECRET_KEY`)
+    pub fn new(key: [u8; KEY_BYTES]) -> Self {
+        Self(key)
+    }
+
+    pub fn random() -> Self {
+        Self(random_key())
+    }
+
+    /// The key stored in `path`, creating the file with a random key
+    /// (readable only by its owner) if it doesn't exist yet
+    pub fn load_key_file(path: &Path) -> Result<Self, SecretError> {
+        let file_name = path.display().to_string();
+        let encoded = match std::fs::read_to_string(path) {
+            Ok(encoded) => encoded,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let encoded = STANDARD.encode(random_key());
+                create_key_file(path, &encoded)
+                    .map_err(|e| SecretError::KeyFile(file_name.clone(), e))?;
+                tracing::info!("Created key file {} for stored credentials", file_name);
+                encoded
+            }
+            Err(e) => return Err(SecretError::KeyFile(file_name, e)),
+        };
+
+        STANDARD
+            .decode(encoded.trim())
+            .ok()
+            .and_then(|key| <[u8; KEY_BYTES]>::try_from(key).ok())
+            .map(Self)
+            .ok_or(SecretError::InvalidKeyFile(file_name))
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(Key::from_slice(&self.0))
+    }
+
+    /// A key for `purpose`, derived from this one so it is the same on every
+    /// instance sharing the key
+    pub fn derive_key(&self, purpose: &str) -> [u8; KEY_BYTES] {
+        let mut mac =
+            <Hmac<Sha256> as Mac>::new_from_slice(&self.0).expect("HMAC takes any key size");
+        mac.update(purpose.as_bytes());
+        mac.finalize().into_bytes().into()
+    }
+
+    /// Encrypt a credential for storage
+    pub fn seal(&self, plaintext: &str) -> String {
+        let mut nonce = [0u8; NONCE_BYTES];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher()
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_bytes())
+            .expect("Encrypting a credential failed");
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        format!("{}{}", SEALED_PREFIX, STANDARD.encode(sealed))
+    }
+
+    /// Decrypt a stored credential; a value stored before encryption is
+    /// returned as is
+    pub fn open(&self, stored: &str) -> Result<String, SecretError> {
+        let Some(encoded) = stored.strip_prefix(SEALED_PREFIX) else {
+            return Ok(stored.to_string());
+        };
+
+        let sealed = STANDARD
+            .decode(encoded)
+            .map_err(|_| SecretError::Undecryptable)?;
+        if sealed.len() < NONCE_BYTES {
+            return Err(SecretError::Undecryptable);
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_BYTES);
+        let plaintext = self
+            .cipher()
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| SecretError::Undecryptable)?;
+        String::from_utf8(plaintext).map_err(|_| SecretError::Undecryptable)
+    }
+}
+
+fn create_key_file(path: &Path, encoded: &str) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    writeln!(file, "{}", encoded)
+}
+
+fn random_key() -> [u8; KEY_BYTES] {
+    let mut key = [0u8; KEY_BYTES];
+    rand::thread_rng().fill_bytes(&mut key);
+    key
+}
+
+/// Whether a stored credential is encrypted
+pub fn is_sealed(stored: &str) -> bool {
+    stored.starts_with(SEALED_PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sealed_secret_opens_to_the_plaintext() {
+        let key = SecretKey::random();
+        let sealed = key.seal("token-123");
+
+        assert!(is_sealed(&sealed));
+        assert!(!sealed.contains("token-123"));
+        assert_ne!(sealed, key.seal("token-123"));
+        assert_eq!(key.open(&sealed).unwrap(), "token-123");
+
+        // Plain text from before encryption is passed through
+        assert_eq!(key.open("token-123").unwrap(), "token-123");
+
+        let mut bytes = STANDARD.decode(&sealed[SEALED_PREFIX.len()..]).unwrap();
+        bytes[NONCE_BYTES] ^= 1;
+        let tampered = format!("{}{}", SEALED_PREFIX, STANDARD.encode(bytes));
+        assert!(matches!(
+            key.open(&tampered),
+            Err(SecretError::Undecryptable)
+        ));
+
+        // Nor does another key open it
+        assert!(matches!(
+            SecretKey::random().open(&sealed),
+            Err(SecretError::Undecryptable)
+        ));
+    }
+
+    #[test]
+    fn test_key_file_is_created_once() {
+        let dir = std::env::temp_dir().join(format!("fluxfeed-key-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir(&dir).unwrap();
+        let path = dir.join("fluxfeed.db.key");
+
+        let key = SecretKey::load_key_file(&path).unwrap();
+        let stored = std::fs::read_to_string(&path).unwrap();
+        let reloaded = SecretKey::load_key_file(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), stored);
+        assert_eq!(reloaded.open(&key.seal("token")).unwrap(), "token");
+
+        std::fs::write(&path, "not a key").unwrap();
+        assert!(matches!(
+            SecretKey::load_key_file(&path),
+            Err(SecretError::InvalidKeyFile(_))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
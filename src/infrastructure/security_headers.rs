@@ -1,10 +1,68 @@
 use axum::{
-    extract::Request,
+    extract::{Request, State},
     http::header::{HeaderName, HeaderValue},
     middleware::Next,
     response::Response,
 };
 
+use crate::config::CspSources;
+
+/// The Content-Security-Policy header with configured sources merged into the
+/// defaults, or the defaults alone if that isn't a valid header value
+pub fn content_security_policy(extra: &CspSources) -> HeaderValue {
+    HeaderValue::from_str(&build_csp(extra)).unwrap_or_else(|e| {
+        tracing::error!(
+            "Invalid Content-Security-Policy, keeping the default: {}",
+            e
+        );
+        HeaderValue::from_str(&build_csp(&CspSources::default()))
+            .expect("default CSP is a valid header value")
+    })
+}
+
+/// Build the Content-Security-Policy from the secure defaults plus extra sources
+///
+/// - default-src 'self': Only allow resources from same origin
+/// - script-src 'self' 'unsafe-inline': Allow scripts from same origin and inline (for HTMX)
+/// - style-src 'self' 'unsafe-inline': Allow styles from same origin and inline
+/// - img-src 'self' https: data:: Allow images from same origin, HTTPS sources, and data URIs
+/// - font-src 'self': Only fonts from same origin
+/// - connect-src 'self': Only AJAX/fetch to same origin
+/// - frame-ancestors 'none': Prevent framing (like X-Frame-Options)
+pub fn build_csp(extra: &CspSources) -> String {
+    let directives: [(&str, &[&str], &[String]); 7] = [
+        ("default-src", &["'self'"], &[]),
+        (
+            "script-src",
+            &["'self'", "'unsafe-inline'"],
+            &extra.script_src,
+        ),
+        (
+            "style-src",
+            &["'self'", "'unsafe-inline'"],
+            &extra.style_src,
+        ),
+        ("img-src", &["'self'", "https:", "data:"], &extra.img_src),
+        ("font-src", &["'self'"], &extra.font_src),
+        ("connect-src", &["'self'"], &extra.connect_src),
+        ("frame-ancestors", &["'none'"], &[]),
+    ];
+
+    directives
+        .iter()
+        .map(|(name, defaults, extra)| {
+            let mut sources: Vec<&str> = defaults.to_vec();
+            for source in extra.iter() {
+                if !sources.contains(&source.as_str()) {
+                    sources.push(source);
+                }
+            }
+            format!("{} {}", name, sources.join(" "))
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 /// Security headers middleware
 ///
 /// Adds standard security headers to all responses:
@@ -12,8 +70,12 @@ use axum::{
 /// - X-Content-Type-Options: Prevents MIME sniffing
 /// - X-XSS-Protection: Legacy XSS protection for older browsers
 /// - Referrer-Policy: Controls referrer information
-/// - Content-Security-Policy: Prevents XSS and injection attacks
-pub async fn security_headers_middleware(req: Request, next: Next) -> Response {
+/// - Content-Security-Policy: Prevents XSS and injection attacks, the given policy
+pub async fn security_headers_middleware(
+    State(policy): State<HeaderValue>,
+    req: Request,
+    next: Next,
+) -> Response {
     let mut response = next.run(req).await;
     let headers = response.headers_mut();
 
@@ -41,26 +103,47 @@ pub async fn security_headers_middleware(req: Request, next: Next) -> Response {
         HeaderValue::from_static("strict-origin-when-cross-origin"),
     );
 
-    // Content Security Policy
-    // - default-src 'self': Only allow resources from same origin
-    // - script-src 'self' 'unsafe-inline': Allow scripts from same origin and inline (for HTMX)
-    // - style-src 'self' 'unsafe-inline': Allow styles from same origin and inline
-    // - img-src 'self' https: data:: Allow images from same origin, HTTPS sources, and data URIs
-    // - font-src 'self': Only fonts from same origin
-    // - connect-src 'self': Only AJAX/fetch to same origin
-    // - frame-ancestors 'none': Prevent framing (like X-Frame-Options)
-    headers.insert(
-        HeaderName::from_static("content-security-policy"),
-        HeaderValue::from_static(
-            "default-src 'self'; \
-             script-src 'self' 'unsafe-inline'; \
-             style-src 'self' 'unsafe-inline'; \
-             img-src 'self' https: data:; \
-             font-src 'self'; \
-             connect-src 'self'; \
-             frame-ancestors 'none'",
-        ),
-    );
+    // Content Security Policy, see build_csp
+    headers.insert(HeaderName::from_static("content-security-policy"), policy);
 
     response
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_csp_is_strict() {
+        assert_eq!(
+            build_csp(&CspSources::default()),
+            "default-src 'self'; script-src 'self' 'unsafe-inline'; \
+             style-src 'self' 'unsafe-inline'; img-src 'self' https: data:; \
+             font-src 'self'; connect-src 'self'; frame-ancestors 'none'"
+        );
+    }
+
+    #[test]
+    fn test_csp_merges_extra_sources() {
+        let policy = build_csp(&CspSources {
+            font_src: vec!["https://fonts.example.com".to_string()],
+            img_src: vec!["'self'".to_string(), "http://images.local".to_string()],
+            ..Default::default()
+        });
+
+        assert!(policy.contains("font-src 'self' https://fonts.example.com;"));
+        assert!(policy.contains("img-src 'self' https: data: http://images.local;"));
+        assert!(policy.contains("script-src 'self' 'unsafe-inline';"));
+        assert!(policy.ends_with("frame-ancestors 'none'"));
+    }
+
+    #[test]
+    fn test_invalid_extra_source_keeps_default_policy() {
+        let policy = content_security_policy(&CspSources {
+            img_src: vec!["https://images.example.com\nX-Injected: 1".to_string()],
+            ..Default::default()
+        });
+
+        assert_eq!(policy, build_csp(&CspSources::default()).as_str());
+    }
+}
@@ -1,6 +1,9 @@
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, ToSocketAddrs};
+use crate::config::HostPolicy;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
 use thiserror::Error;
-use url::Url;
+use url::{Host, Url};
 
 #[derive(Error, Debug)]
 pub enum SsrfError {
@@ -15,6 +18,9 @@ pub enum SsrfError {
 
     #[error("URL scheme not allowed: {0}")]
     InvalidScheme(String),
+
+    #[error("Host not allowed for feeds: {0}")]
+    HostNotAllowed(String),
 }
 
 /// Check if an IPv4 address is private/internal
@@ -108,9 +114,19 @@ fn is_private_ip(ip: &IpAddr) -> bool {
 /// 2. Resolves the hostname to IP addresses
 /// 3. Checks that none of the resolved IPs are private/internal
 ///
-/// This should be called both at feed creation time and at fetch time
-/// to protect against DNS rebinding attacks.
-pub fn validate_url(url_str: &str) -> Result<(), SsrfError> {
+/// This reports a blocked URL before a request is made. The request itself
+/// resolves the host again, so clients also need [`protect`], whose resolver
+/// applies the same check to the addresses actually connected to.
+pub async fn validate_url(url_str: &str) -> Result<(), SsrfError> {
+    let socket_addr = socket_addr(url_str)?;
+    let addrs = tokio::net::lookup_host(socket_addr)
+        .await
+        .map_err(|e| SsrfError::DnsResolutionFailed(e.to_string()))?;
+    check_resolved(url_str, addrs)
+}
+
+/// The `host:port` to resolve for a URL, if it has an allowed scheme
+fn socket_addr(url_str: &str) -> Result<String, SsrfError> {
     // Parse URL
     let url = Url::parse(url_str).map_err(|e| SsrfError::InvalidUrl(e.to_string()))?;
 
@@ -128,12 +144,12 @@ pub fn validate_url(url_str: &str) -> Result<(), SsrfError> {
     // Get port (default to 80 for http, 443 for https)
     let port = url.port_or_known_default().unwrap_or(80);
 
-    // Resolve hostname to IP addresses
-    let socket_addr = format!("{}:{}", host, port);
-    let addrs: Vec<_> = socket_addr
-        .to_socket_addrs()
-        .map_err(|e| SsrfError::DnsResolutionFailed(e.to_string()))?
-        .collect();
+    Ok(format!("{}:{}", host, port))
+}
+
+/// Refuse a URL unless it resolved, and only to public addresses
+fn check_resolved(url_str: &str, addrs: impl Iterator<Item = SocketAddr>) -> Result<(), SsrfError> {
+    let addrs: Vec<_> = addrs.collect();
 
     if addrs.is_empty() {
         return Err(SsrfError::DnsResolutionFailed(
@@ -143,7 +159,7 @@ pub fn validate_url(url_str: &str) -> Result<(), SsrfError> {
 
     // Check all resolved IPs
     for addr in addrs {
-        if is_private_ip(&addr.ip()) {
+        if is_private_ip(&addr.ip()) && !loopback_allowed(&addr.ip()) {
             tracing::warn!(
                 "SSRF protection: URL {} resolves to private IP {}",
                 url_str,
@@ -156,6 +172,134 @@ pub fn validate_url(url_str: &str) -> Result<(), SsrfError> {
     Ok(())
 }
 
+#[cfg(test)]
+thread_local! {
+    static LOOPBACK_ALLOWED: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// Let checks on the current thread pass loopback addresses, so a local
+/// server can stand in for a public page; other internal ranges stay blocked
+#[cfg(test)]
+pub fn allow_loopback_on_this_thread() {
+    LOOPBACK_ALLOWED.with(|allowed| allowed.set(true));
+}
+
+#[cfg(test)]
+fn loopback_allowed(ip: &IpAddr) -> bool {
+    ip.is_loopback() && LOOPBACK_ALLOWED.with(|allowed| allowed.get())
+}
+
+#[cfg(not(test))]
+fn loopback_allowed(_ip: &IpAddr) -> bool {
+    false
+}
+
+/// Validate a feed URL: its host must pass the host policy before the SSRF
+/// checks of [`validate_url`] apply
+pub async fn validate_feed_url(url_str: &str, policy: &HostPolicy) -> Result<(), SsrfError> {
+    check_host_policy(url_str, policy)?;
+    validate_url(url_str).await
+}
+
+/// Check a redirect target without resolving it: the scheme, and the address
+/// if the host is one. Host names are checked by [`PublicResolver`] when the
+/// client connects.
+pub fn validate_redirect(url_str: &str) -> Result<(), SsrfError> {
+    let url = Url::parse(url_str).map_err(|e| SsrfError::InvalidUrl(e.to_string()))?;
+    match url.scheme() {
+        "http" | "https" => {}
+        scheme => return Err(SsrfError::InvalidScheme(scheme.to_string())),
+    }
+
+    let ip = match url.host() {
+        Some(Host::Ipv4(ip)) => IpAddr::V4(ip),
+        Some(Host::Ipv6(ip)) => IpAddr::V6(ip),
+        Some(Host::Domain(_)) => return Ok(()),
+        None => return Err(SsrfError::InvalidUrl("No host in URL".to_string())),
+    };
+    check_resolved(url_str, std::iter::once(SocketAddr::new(ip, 0)))
+}
+
+/// [`validate_redirect`] for feeds, which also have to pass the host policy
+pub fn validate_feed_redirect(url_str: &str, policy: &HostPolicy) -> Result<(), SsrfError> {
+    check_host_policy(url_str, policy)?;
+    validate_redirect(url_str)
+}
+
+/// Resolver that drops private and internal addresses from every lookup,
+/// so the addresses checked are the ones connected to; a host resolving only
+/// to such addresses fails with [`SsrfError::PrivateIpAddress`]
+pub struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| !is_private_ip(&addr.ip()) || loopback_allowed(&addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                tracing::warn!(
+                    "SSRF protection: {} resolves only to private IPs",
+                    name.as_str()
+                );
+                return Err(SsrfError::PrivateIpAddress.into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Install the SSRF checks on a client: connections only go to public
+/// addresses, and at most `max_redirects` redirects are followed, each of
+/// which must pass `validate` (e.g. [`validate_redirect`])
+pub fn protect(
+    builder: reqwest::ClientBuilder,
+    max_redirects: usize,
+    validate: impl Fn(&str) -> Result<(), SsrfError> + Send + Sync + 'static,
+) -> reqwest::ClientBuilder {
+    builder
+        .dns_resolver(Arc::new(PublicResolver))
+        .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= max_redirects {
+                attempt.stop()
+            } else if let Err(e) = validate(attempt.url().as_str()) {
+                attempt.error(e)
+            } else {
+                attempt.follow()
+            }
+        }))
+}
+
+/// The SSRF check that failed a request, if one did; the checks of
+/// [`protect`] surface somewhere in the chain of error sources
+pub fn blocked_by(e: &reqwest::Error) -> Option<&SsrfError> {
+    let mut source = std::error::Error::source(e);
+    while let Some(error) = source {
+        if let Some(blocked) = error.downcast_ref() {
+            return Some(blocked);
+        }
+        source = error.source();
+    }
+    None
+}
+
+fn check_host_policy(url_str: &str, policy: &HostPolicy) -> Result<(), SsrfError> {
+    if policy.is_empty() {
+        return Ok(());
+    }
+    let url = Url::parse(url_str).map_err(|e| SsrfError::InvalidUrl(e.to_string()))?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| SsrfError::InvalidUrl("No host in URL".to_string()))?;
+    if policy.allows(host) {
+        Ok(())
+    } else {
+        tracing::warn!("Host policy: {} is not allowed for feeds", host);
+        Err(SsrfError::HostNotAllowed(host.to_string()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -215,4 +359,76 @@ mod tests {
         assert!(is_private_ipv6(&Ipv6Addr::new(0xfc00, 0, 0, 0, 0, 0, 0, 1)));
         assert!(is_private_ipv6(&Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 1)));
     }
+
+    #[tokio::test]
+    async fn test_validate_url_refuses_internal_addresses() {
+        for url in ["http://127.0.0.1/feed", "http://[::1]:8080/feed"] {
+            assert!(matches!(
+                validate_url(url).await,
+                Err(SsrfError::PrivateIpAddress)
+            ));
+            assert!(matches!(
+                validate_redirect(url),
+                Err(SsrfError::PrivateIpAddress)
+            ));
+        }
+        assert!(validate_redirect("https://example.com/feed").is_ok());
+        assert!(matches!(
+            validate_url("file:///etc/passwd").await,
+            Err(SsrfError::InvalidScheme(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_protected_client_refuses_private_host_names() {
+        // Something is listening, so only the resolver can stop the request
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let client = protect(reqwest::Client::builder(), 1, validate_redirect)
+            .build()
+            .unwrap();
+        let error = client
+            .get(format!("http://localhost:{}/", port))
+            .send()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            blocked_by(&error),
+            Some(SsrfError::PrivateIpAddress)
+        ));
+    }
+
+    fn host_policy(allowlist: &[&str], blocklist: &[&str]) -> HostPolicy {
+        HostPolicy {
+            allowlist: allowlist.iter().map(|p| p.to_string()).collect(),
+            blocklist: blocklist.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_host_policy_allows_listed_host() {
+        let policy = host_policy(&["*.example.com"], &[]);
+        assert!(check_host_policy("https://blog.example.com/feed.xml", &policy).is_ok());
+        assert!(check_host_policy("https://anything.test/rss", &HostPolicy::default()).is_ok());
+    }
+
+    #[test]
+    fn test_host_policy_rejects_blocked_host() {
+        let policy = host_policy(&[], &["tracker.example"]);
+        assert!(matches!(
+            check_host_policy("https://TRACKER.example/feed", &policy),
+            Err(SsrfError::HostNotAllowed(host)) if host == "tracker.example"
+        ));
+        assert!(check_host_policy("https://news.example/feed", &policy).is_ok());
+    }
+
+    #[test]
+    fn test_host_policy_rejects_allowlist_miss() {
+        let policy = host_policy(&["example.com"], &[]);
+        assert!(matches!(
+            check_host_policy("https://example.org/feed", &policy),
+            Err(SsrfError::HostNotAllowed(_))
+        ));
+    }
 }
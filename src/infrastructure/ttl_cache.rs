@@ -0,0 +1,95 @@
+//! Small in-memory cache whose entries expire after a fixed time. When it
+//! is full, the oldest entries make room for new ones.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+pub struct TtlCache<K, V> {
+    ttl: Duration,
+    /// Combined weight of the entries at most
+    capacity: usize,
+    /// Weight of an entry, e.g. 1 to count entries or its size in bytes
+    weigh: fn(&V) -> usize,
+    entries: RwLock<HashMap<K, (Instant, V)>>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> TtlCache<K, V> {
+    pub fn new(ttl: Duration, capacity: usize, weigh: fn(&V) -> usize) -> Self {
+        Self {
+            ttl,
+            capacity,
+            weigh,
+            entries: Default::default(),
+        }
+    }
+
+    /// The value stored for `key` within the TTL
+    pub async fn get(&self, key: &K) -> Option<V> {
+        let entries = self.entries.read().await;
+        let (stored_at, value) = entries.get(key)?;
+        (stored_at.elapsed() < self.ttl).then(|| value.clone())
+    }
+
+    /// Remember a value, dropping expired entries and then the oldest ones
+    /// until it fits. A value weighing more than the capacity is not kept.
+    pub async fn insert(&self, key: K, value: V) {
+        let mut entries = self.entries.write().await;
+        entries.retain(|_, (stored_at, _)| stored_at.elapsed() < self.ttl);
+        entries.remove(&key);
+
+        let weight = (self.weigh)(&value);
+        if weight > self.capacity {
+            return;
+        }
+        let mut size: usize = entries.values().map(|(_, value)| (self.weigh)(value)).sum();
+        while size + weight > self.capacity {
+            let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, (stored_at, _))| *stored_at)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            if let Some((_, evicted)) = entries.remove(&oldest) {
+                size -= (self.weigh)(&evicted);
+            }
+        }
+
+        entries.insert(key, (Instant::now(), value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_oldest_entries_make_room_by_weight() {
+        let cache: TtlCache<&str, Vec<u8>> =
+            TtlCache::new(Duration::from_secs(60), 10, |value| value.len());
+        cache.insert("a", vec![0; 4]).await;
+        tokio::time::sleep(Duration::from_millis(2)).await;
+        cache.insert("b", vec![0; 4]).await;
+
+        cache.insert("c", vec![0; 4]).await;
+        assert!(cache.get(&"a").await.is_none());
+        assert!(cache.get(&"b").await.is_some());
+        assert!(cache.get(&"c").await.is_some());
+
+        // Too heavy to keep at all, so nothing is evicted for it
+        cache.insert("d", vec![0; 11]).await;
+        assert!(cache.get(&"d").await.is_none());
+        assert!(cache.get(&"c").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_expired_entries_are_not_returned() {
+        let cache: TtlCache<i64, &str> = TtlCache::new(Duration::from_millis(1), 8, |_| 1);
+        cache.insert(1, "stale").await;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        assert!(cache.get(&1).await.is_none());
+    }
+}
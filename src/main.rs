@@ -17,37 +17,55 @@ pub fn user_agent() -> String {
 use api::feeds::AppState;
 use axum::{
     middleware,
-    response::Redirect,
+    response::Response,
     routing::{delete, get, post, put},
     Router,
 };
-use config::Config;
+use config::{Config, LogFormat};
+use infrastructure::api_auth::{api_token_middleware, require_api_token_middleware};
+use infrastructure::client_info::client_info_middleware;
 use infrastructure::csrf::csrf_middleware;
 use infrastructure::database::setup_database;
-use infrastructure::error_pages::error_page_middleware;
-use infrastructure::security_headers::security_headers_middleware;
+use infrastructure::error_pages::{self, error_page_middleware};
+use infrastructure::group_tree_cache::invalidate_group_tree_cache;
+use infrastructure::secrets::SecretKey;
+use infrastructure::security_headers::{content_security_policy, security_headers_middleware};
+use std::net::SocketAddr;
 use tower_http::{compression::CompressionLayer, services::ServeDir, trace::TraceLayer};
 
-async fn index() -> Redirect {
-    Redirect::to("/articles")
+async fn index() -> Response {
+    web::redirect::safe_redirect("/articles", &[])
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Load configuration (first, so LOG_FORMAT and RUST_LOG may come from .env)
+    let config = Config::from_env()?;
+
     // Initialize tracing
     // Default to info level in production; use RUST_LOG env var to override
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "fluxfeed=info,tower_http=info".into()),
-        )
-        .init();
+    let subscriber = tracing_subscriber::fmt().with_env_filter(
+        tracing_subscriber::EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| "fluxfeed=info,tower_http=info".into()),
+    );
+    match config.log_format {
+        LogFormat::Json => subscriber.json().init(),
+        LogFormat::Pretty => subscriber.init(),
+    }
+    for warning in &config.warnings {
+        tracing::warn!("{}", warning);
+    }
 
-    // Load configuration
-    let config = Config::from_env()?;
+    // Sum up the configuration and check DNS and SSRF protection in the
+    // background; problems are only logged
+    tracing::info!("{}", infrastructure::self_check::config_summary(&config));
+    tokio::spawn(infrastructure::self_check::run_checks());
 
     // Setup database
-    tracing::info!("Connecting to database: {}", config.database_url);
+    tracing::info!(
+        "Connecting to database: {}",
+        infrastructure::self_check::redact_database_url(&config.database_url)
+    );
     let db_pool = setup_database(&config.database_url).await?;
 
     // Run migrations
@@ -55,17 +73,107 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     sqlx::migrate!().run(&db_pool).await?;
     tracing::info!("Migrations complete");
 
+    // Credentials stored in the database are encrypted with a key kept
+    // outside of it
+    let secret_key = match (
+        config.secret_key,
+        infrastructure::database::key_file_path(&db_pool),
+    ) {
+        (Some(secret_key), _) => SecretKey::new(secret_key),
+        (None, Some(path)) => SecretKey::load_key_file(&path)?,
+        (None, None) => {
+            tracing::warn!(
+                "No SECRET_KEY and no key file for this database: feed credentials saved now can't be decrypted after a restart"
+            );
+            SecretKey::random()
+        }
+    };
+    let sealed = infrastructure::repository::seal_stored_credentials(&db_pool, &secret_key).await?;
+    if sealed > 0 {
+        tracing::info!("Encrypted {} stored feed credentials", sealed);
+    }
+
+    if config.rebuild_fts_on_startup {
+        tracing::info!("Rebuilding full-text search index");
+        let indexed = infrastructure::repository::rebuild_fts(&db_pool).await?;
+        tracing::info!("Indexed {} articles", indexed);
+    }
+
     // Create shared application state
     let state = AppState {
         db_pool: db_pool.clone(),
         import_jobs: std::sync::Arc::new(
             tokio::sync::RwLock::new(std::collections::HashMap::new()),
         ),
+        fetch_delays: config.fetch_delays,
+        adaptive_interval: config.adaptive_interval,
+        archive_backfill_pages: config.archive_backfill_pages,
+        max_feeds_per_cycle: config.max_feeds_per_cycle,
+        quiet_hours: config.quiet_hours,
+        storage_limits: config.storage_limits,
+        api_token: config.api_token.clone(),
+        page_size: config.page_size,
+        feed_sort: config.feed_sort,
+        group_tree_cache: Default::default(),
+        reader_limiter: Default::default(),
+        scheduler_status: Default::default(),
+        insert_progress: Default::default(),
+        image_proxy: infrastructure::image_proxy::ImageProxy::new(config.image_proxy, &secret_key),
+        display_timezone: config.display_timezone,
+        reader_sanitization: config.reader_sanitization,
+        fetch_host_policy: std::sync::Arc::new(config.fetch_host_policy.clone()),
+        secret_key,
+    };
+
+    // Start background scheduler for RSS fetching, unless another instance
+    // does the fetching
+    let _scheduler = if config.disable_scheduler {
+        tracing::info!("Scheduler disabled, feeds are not fetched in the background");
+        None
+    } else {
+        tracing::info!("Starting RSS feed scheduler");
+        Some(infrastructure::scheduler::start_scheduler(state.clone()).await?)
     };
 
-    // Start background scheduler for RSS fetching
-    tracing::info!("Starting RSS feed scheduler");
-    let _scheduler = infrastructure::scheduler::start_scheduler(state.clone()).await?;
+    let csp = content_security_policy(&config.csp_sources);
+
+    // Read-only shared views live on their own branch: no CSRF cookie and no
+    // app error pages, so nothing links back into the rest of the UI
+    let share_routes = Router::new()
+        .route("/share/:token", get(api::share::show_shared_group))
+        .route(
+            "/feed/:token/unread.atom",
+            get(api::unread_feed::show_unread_feed),
+        )
+        .layer(middleware::from_fn_with_state(
+            csp.clone(),
+            security_headers_middleware,
+        ));
+
+    // JSON API routes, optionally protected by an API token
+    let api_routes = Router::new()
+        .route("/api/feeds", get(api::feed_stats::list_feed_stats))
+        .route(
+            "/api/feeds/:id/articles",
+            get(api::feed_articles::list_feed_articles),
+        )
+        .route(
+            "/api/feeds/validate",
+            post(api::feed_validation::validate_feed),
+        )
+        .route("/api/fetch", post(api::manual_fetch::trigger_fetch))
+        .route(
+            "/api/maintenance/vacuum",
+            post(api::maintenance::vacuum_database).route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                require_api_token_middleware,
+            )),
+        )
+        .route("/api/status", get(api::status::show_status))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            api_token_middleware,
+        ));
 
     // Build router
     let app = Router::new()
@@ -76,6 +184,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             get(api::feeds::list_feeds).post(api::feeds::create_feed),
         )
         .route("/feeds/new", get(api::feeds::show_feed_form))
+        .route("/feeds/diagnostics", get(api::feeds::show_feed_diagnostics))
+        .route("/feeds/export.opml", get(api::feeds::export_opml))
         .route("/feeds/import/form", get(api::feeds::show_import_form))
         .route("/feeds/import", post(api::feeds::import_feeds))
         .route(
@@ -88,7 +198,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .post(api::feeds::update_feed)
                 .delete(api::feeds::delete_feed),
         )
+        .route("/feeds/:id/row", get(api::feeds::show_feed_row))
+        .route("/feeds/:id/opml", get(api::feeds::export_feed_opml))
         .route("/feeds/:id/fetch", post(api::feeds::fetch_feed))
+        .route(
+            "/feeds/:id/toggle-muted",
+            post(api::feeds::toggle_feed_muted),
+        )
+        .route(
+            "/feeds/:id/toggle-pinned",
+            post(api::feeds::toggle_feed_pinned),
+        )
+        .route(
+            "/feeds/:id/refresh-opengraph",
+            post(api::feeds::refresh_opengraph),
+        )
         .route("/feeds/:id/edit", get(api::feeds::show_edit_feed_form))
         .route("/feeds/:id/group", put(api::groups::assign_feed_to_group))
         // Group routes
@@ -97,6 +221,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             get(api::groups::list_groups).post(api::groups::create_group),
         )
         .route("/groups/new", get(api::groups::show_new_group_form))
+        .route(
+            "/groups/unread-nav",
+            get(api::groups::show_group_unread_nav),
+        )
         .route(
             "/groups/assign-feed/:feed_id",
             get(api::groups::show_assign_feed_form),
@@ -106,7 +234,34 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             delete(api::groups::delete_group).put(api::groups::update_group),
         )
         .route("/groups/:id/edit", get(api::groups::show_edit_group_form))
+        .route("/groups/:id/feeds", get(api::groups::show_group_feeds))
+        .route(
+            "/groups/ungrouped/assign-feeds",
+            put(api::groups::unassign_feeds),
+        )
+        .route(
+            "/groups/:id/assign-feeds",
+            put(api::groups::assign_feeds_to_group),
+        )
         .route("/groups/:id/parent", put(api::groups::move_group))
+        .route(
+            "/groups/:id/collapse",
+            put(api::groups::toggle_group_collapse),
+        )
+        .route(
+            "/unread-feed/links",
+            get(api::unread_feed::show_unread_feed_links)
+                .post(api::unread_feed::create_unread_feed_link),
+        )
+        .route(
+            "/unread-feed/links/:id",
+            delete(api::unread_feed::revoke_unread_feed_link),
+        )
+        .route("/groups/:id/shares", post(api::groups::create_share_link))
+        .route(
+            "/groups/:id/shares/:share_id",
+            delete(api::groups::revoke_share_link),
+        )
         // Tag routes
         .route(
             "/tags",
@@ -120,6 +275,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .route("/tags/:id/edit", get(api::tags::show_edit_tag_form))
         .route("/articles", get(api::articles::list_articles))
         .route("/articles/search", get(api::articles::search_articles))
+        .route(
+            "/articles/search/mark-read",
+            post(api::articles::mark_search_read),
+        )
+        .route("/articles/digest", get(api::articles::show_digest))
+        .route("/articles/read-later", get(api::articles::list_read_later))
+        .route(
+            "/articles/import-state/form",
+            get(api::articles::show_state_import_form),
+        )
+        .route(
+            "/articles/import-state",
+            post(api::articles::import_article_states),
+        )
         .route(
             "/articles/filter-modal",
             get(api::groups::show_feed_filter_modal),
@@ -128,6 +297,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "/articles/tag-filter-modal",
             get(api::tags::show_tag_filter_modal),
         )
+        .route("/articles/:id", get(api::articles::show_article))
+        .route(
+            "/articles/:id/content",
+            get(api::articles::show_article_content),
+        )
         .route(
             "/articles/:id/toggle-read",
             post(api::articles::toggle_read_status),
@@ -164,21 +338,86 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "/articles/:id/mark-read-fullscreen",
             post(api::articles::mark_read_status_fullscreen),
         )
+        .route(
+            "/articles/:id/toggle-read-detail",
+            post(api::articles::toggle_read_status_detail),
+        )
+        .route(
+            "/articles/:id/toggle-starred-detail",
+            post(api::articles::toggle_starred_status_detail),
+        )
+        .route(
+            "/articles/:id/toggle-read-later",
+            post(api::articles::toggle_read_later_status),
+        )
+        .route(
+            "/articles/:id/toggle-read-later-compact",
+            post(api::articles::toggle_read_later_status_compact),
+        )
+        .route(
+            "/articles/:id/toggle-read-later-fullscreen",
+            post(api::articles::toggle_read_later_status_fullscreen),
+        )
+        .route(
+            "/articles/:id/toggle-read-later-detail",
+            post(api::articles::toggle_read_later_status_detail),
+        )
+        .route(
+            "/articles/:id/toggle-feed-muted",
+            post(api::articles::toggle_feed_muted),
+        )
+        .route(
+            "/articles/:id/toggle-feed-muted-compact",
+            post(api::articles::toggle_feed_muted_compact),
+        )
         .route(
             "/articles/mark-all-read",
             post(api::articles::mark_all_read),
         )
+        .route("/articles/bulk", post(api::articles::bulk_update))
+        .route(
+            "/articles/:id/share",
+            get(api::article_share::share_article),
+        )
+        .route(
+            "/articles/:id/share.txt",
+            get(api::article_share::share_article_text),
+        )
+        .route("/articles/manual", post(api::reader::save_page))
         .route("/articles/:id/reader", get(api::reader::show_reader_mode))
         .route(
             "/articles/:id/reader-content",
             get(api::reader::get_reader_content),
         )
+        .route("/img", get(api::image_proxy::proxy_image))
+        .route("/preferences/theme", post(api::preferences::set_theme))
+        .route(
+            "/preferences/view-mode",
+            post(api::preferences::set_view_mode),
+        )
         .route("/logs", get(api::logs::list_logs))
-        .route("/api/fetch", post(api::manual_fetch::trigger_fetch))
+        .route("/logs/export.csv", get(api::logs::export_logs_csv))
+        .merge(api_routes)
+        .fallback(error_pages::not_found)
+        .layer(middleware::from_fn_with_state(
+            state.group_tree_cache.clone(),
+            invalidate_group_tree_cache,
+        ))
         .nest_service("/static", ServeDir::new("static"))
-        .layer(middleware::from_fn(security_headers_middleware))
-        .layer(middleware::from_fn(csrf_middleware))
+        .layer(middleware::from_fn_with_state(
+            csp,
+            security_headers_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            config.csrf_same_site,
+            csrf_middleware,
+        ))
         .layer(middleware::from_fn(error_page_middleware))
+        .merge(share_routes)
+        .layer(middleware::from_fn_with_state(
+            std::sync::Arc::from(config.trusted_proxies.clone()),
+            client_info_middleware,
+        ))
         .layer(CompressionLayer::new())
         .layer(TraceLayer::new_for_http())
         .with_state(state);
@@ -188,7 +427,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("FluxFeed server listening on http://{}", bind_addr);
 
     let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
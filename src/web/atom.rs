@@ -0,0 +1,281 @@
+use crate::domain::models::{Article, Feed};
+use crate::web::templates::ArticleWithFeed;
+use chrono::{DateTime, SecondsFormat, Utc};
+use quick_xml::events::{BytesDecl, BytesText, Event};
+use quick_xml::Writer;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io;
+
+const ATOM_NS: &str = "http://www.w3.org/2005/Atom";
+
+/// Serialize a feed and its stored articles as an Atom 1.0 document.
+///
+/// Article content is written as escaped HTML; it was sanitized when stored.
+pub fn render_feed(feed: &Feed, articles: &[Article]) -> io::Result<String> {
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("utf-8"), None)))?;
+
+    let mut root = writer
+        .create_element("feed")
+        .with_attribute(("xmlns", ATOM_NS));
+    if let Some(language) = &feed.language {
+        root = root.with_attribute(("xml:lang", language.as_str()));
+    }
+
+    root.write_inner_content(|w| {
+        text_element(w, "id", &format!("urn:fluxfeed:feed:{}", feed.id))?;
+        text_element(w, "title", &feed.title)?;
+        if let Some(description) = &feed.description {
+            text_element(w, "subtitle", description)?;
+        }
+        text_element(w, "updated", &timestamp(feed_updated(feed, articles)))?;
+        if let Some(site_url) = &feed.site_url {
+            link_element(w, "alternate", site_url)?;
+        }
+        link_element(w, "via", &feed.url)?;
+        text_element(w, "generator", "FluxFeed")?;
+
+        for article in articles {
+            write_entry(w, article, None)?;
+        }
+        Ok::<(), io::Error>(())
+    })?;
+
+    Ok(String::from_utf8_lossy(&writer.into_inner()).into_owned())
+}
+
+/// Serialize unread articles of all feeds as one Atom 1.0 document, each
+/// entry naming the feed it came from in its `<source>`
+pub fn render_unread_feed(articles: &[ArticleWithFeed]) -> io::Result<String> {
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("utf-8"), None)))?;
+
+    writer
+        .create_element("feed")
+        .with_attribute(("xmlns", ATOM_NS))
+        .write_inner_content(|w| {
+            text_element(w, "id", "urn:fluxfeed:unread")?;
+            text_element(w, "title", "FluxFeed: Unread Articles")?;
+            text_element(w, "updated", &timestamp(unread_updated(articles)))?;
+            text_element(w, "generator", "FluxFeed")?;
+
+            for article in articles {
+                write_entry(w, &article.article, Some(&article.feed_title))?;
+            }
+            Ok::<(), io::Error>(())
+        })?;
+
+    Ok(String::from_utf8_lossy(&writer.into_inner()).into_owned())
+}
+
+fn write_entry(
+    w: &mut Writer<Vec<u8>>,
+    article: &Article,
+    source_title: Option<&str>,
+) -> io::Result<()> {
+    w.create_element("entry").write_inner_content(|w| {
+        text_element(w, "id", &format!("urn:fluxfeed:article:{}", article.id))?;
+        text_element(w, "title", &article.title)?;
+        if let Some(url) = &article.url {
+            link_element(w, "alternate", url)?;
+        }
+        text_element(w, "updated", &timestamp(article.updated_at))?;
+        if let Some(published_at) = article.published_at {
+            text_element(w, "published", &timestamp(published_at))?;
+        }
+        if let Some(author) = &article.author {
+            w.create_element("author")
+                .write_inner_content(|w| text_element(w, "name", author))?;
+        }
+        if let Some(summary) = &article.summary {
+            html_element(w, "summary", summary)?;
+        }
+        if let Some(content) = &article.content {
+            html_element(w, "content", content)?;
+        }
+        if let Some(source_title) = source_title {
+            w.create_element("source").write_inner_content(|w| {
+                text_element(w, "id", &format!("urn:fluxfeed:feed:{}", article.feed_id))?;
+                text_element(w, "title", source_title)
+            })?;
+        }
+        Ok::<(), io::Error>(())
+    })?;
+    Ok(())
+}
+
+/// Entity tag of an Atom export, derived from the feed title, the number of
+/// entries and the newest entry, so it changes whenever the document would
+pub fn entity_tag(feed: &Feed, articles: &[Article]) -> String {
+    let mut hasher = DefaultHasher::new();
+    feed.id.hash(&mut hasher);
+    feed.title.hash(&mut hasher);
+    articles.len().hash(&mut hasher);
+    articles.iter().map(|a| a.id).max().hash(&mut hasher);
+    feed_updated(feed, articles).hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+/// The feed's `<updated>` is its newest article, falling back to the feed row itself
+pub fn feed_updated(feed: &Feed, articles: &[Article]) -> DateTime<Utc> {
+    articles
+        .iter()
+        .map(|a| a.updated_at)
+        .max()
+        .unwrap_or(feed.updated_at)
+}
+
+/// Entity tag of the unread feed, changing whenever an article is read or arrives
+pub fn unread_entity_tag(articles: &[ArticleWithFeed]) -> String {
+    let mut hasher = DefaultHasher::new();
+    for article in articles {
+        article.article.id.hash(&mut hasher);
+        article.article.updated_at.hash(&mut hasher);
+    }
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+/// The unread feed's `<updated>` is its newest article, or the epoch if there is none
+pub fn unread_updated(articles: &[ArticleWithFeed]) -> DateTime<Utc> {
+    articles
+        .iter()
+        .map(|a| a.article.updated_at)
+        .max()
+        .unwrap_or(DateTime::UNIX_EPOCH)
+}
+
+fn timestamp(dt: DateTime<Utc>) -> String {
+    dt.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn text_element(w: &mut Writer<Vec<u8>>, name: &str, text: &str) -> io::Result<()> {
+    w.create_element(name)
+        .write_text_content(BytesText::new(text))?;
+    Ok(())
+}
+
+fn html_element(w: &mut Writer<Vec<u8>>, name: &str, html: &str) -> io::Result<()> {
+    w.create_element(name)
+        .with_attribute(("type", "html"))
+        .write_text_content(BytesText::new(html))?;
+    Ok(())
+}
+
+fn link_element(w: &mut Writer<Vec<u8>>, rel: &str, href: &str) -> io::Result<()> {
+    w.create_element("link")
+        .with_attribute(("rel", rel))
+        .with_attribute(("href", href))
+        .write_empty()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn sample_feed() -> Feed {
+        let created = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        Feed {
+            id: 7,
+            url: "https://example.com/feed.xml".to_string(),
+            title: "Example & Co".to_string(),
+            description: None,
+            site_url: Some("https://example.com/".to_string()),
+            group_id: None,
+            last_fetched_at: None,
+            last_modified: None,
+            etag: None,
+            fetch_interval_minutes: 60,
+            color: "#000000".to_string(),
+            fetch_frequency: "adaptive".to_string(),
+            ttl_minutes: None,
+            ttl_from_headers: false,
+            consecutive_new_articles: 0,
+            ignore_pattern: None,
+            created_at: created,
+            updated_at: created,
+            consecutive_failures: 0,
+            next_retry_at: None,
+            never_trim: false,
+            auto_fetch_full_content: false,
+            title_is_custom: false,
+            subscribed_at: created,
+            muted: false,
+            language: None,
+            guid_strategy: "auto".to_string(),
+            previous_guid_strategy: None,
+            prefer_summary: false,
+            mark_new_as_read: false,
+            pinned: false,
+            quiet_hours: None,
+        }
+    }
+
+    fn sample_article() -> Article {
+        let published = Utc.with_ymd_and_hms(2026, 2, 3, 4, 5, 6).unwrap();
+        Article {
+            id: 42,
+            feed_id: 7,
+            guid: "guid-42".to_string(),
+            title: "Hello <world>".to_string(),
+            url: Some("https://example.com/hello".to_string()),
+            content: Some("<p>Body</p>".to_string()),
+            summary: None,
+            content_preview: None,
+            word_count: None,
+            author: Some("Jane".to_string()),
+            published_at: Some(published),
+            is_read: false,
+            is_starred: false,
+            is_read_later: false,
+            og_image: None,
+            og_description: None,
+            og_site_name: None,
+            language: None,
+            created_at: published,
+            updated_at: published,
+        }
+    }
+
+    #[test]
+    fn test_render_feed_escapes_and_includes_entries() {
+        let xml = render_feed(&sample_feed(), &[sample_article()]).unwrap();
+
+        assert!(xml.contains(r#"<feed xmlns="http://www.w3.org/2005/Atom">"#));
+        assert!(xml.contains("<title>Example &amp; Co</title>"));
+        assert!(xml.contains("<updated>2026-02-03T04:05:06Z</updated>"));
+        assert!(xml.contains("<id>urn:fluxfeed:article:42</id>"));
+        assert!(xml.contains("<title>Hello &lt;world&gt;</title>"));
+        assert!(xml.contains(r#"<link rel="alternate" href="https://example.com/hello"/>"#));
+        assert!(xml.contains(r#"<content type="html">&lt;p&gt;Body&lt;/p&gt;</content>"#));
+        assert!(xml.contains("<name>Jane</name>"));
+    }
+
+    #[test]
+    fn test_render_feed_without_articles_uses_feed_timestamp() {
+        let xml = render_feed(&sample_feed(), &[]).unwrap();
+
+        assert!(xml.contains("<updated>2026-01-01T00:00:00Z</updated>"));
+        assert!(!xml.contains("<entry>"));
+    }
+
+    #[test]
+    fn test_render_unread_feed_names_source_feed() {
+        let article = ArticleWithFeed {
+            article: sample_article(),
+            feed_title: "Example & Co".to_string(),
+            feed_color: "#000000".to_string(),
+            feed_muted: false,
+            feed_prefer_summary: false,
+            tags: vec![],
+        };
+        let xml = render_unread_feed(&[article]).unwrap();
+
+        assert!(xml.contains("<id>urn:fluxfeed:unread</id>"));
+        assert!(xml.contains("<updated>2026-02-03T04:05:06Z</updated>"));
+        assert!(xml.contains("<source>"));
+        assert!(xml.contains("<id>urn:fluxfeed:feed:7</id>"));
+        assert!(xml.contains("<title>Example &amp; Co</title>"));
+    }
+}
@@ -0,0 +1,52 @@
+/// Format one CSV record (RFC 4180), terminated by CRLF.
+///
+/// Fields containing separators, quotes or line breaks are quoted. Fields that a
+/// spreadsheet would evaluate as a formula get a leading `'`, since feed titles
+/// and error messages come from remote servers.
+pub fn record(fields: &[&str]) -> String {
+    let mut line = fields
+        .iter()
+        .map(|field| escape_field(field))
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push_str("\r\n");
+    line
+}
+
+fn escape_field(field: &str) -> String {
+    let field = if field.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", field)
+    } else {
+        field.to_string()
+    };
+
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_quotes_separators_and_newlines() {
+        assert_eq!(
+            record(&["plain", "a,b", "say \"hi\"", "line\nbreak", ""]),
+            "plain,\"a,b\",\"say \"\"hi\"\"\",\"line\nbreak\",\r\n"
+        );
+    }
+
+    #[test]
+    fn test_record_neutralizes_formulas() {
+        assert_eq!(
+            record(&["=HYPERLINK(\"x\")"]),
+            "\"'=HYPERLINK(\"\"x\"\")\"\r\n"
+        );
+        assert_eq!(record(&["@SUM(A1)"]), "'@SUM(A1)\r\n");
+        assert_eq!(record(&["\t=1+1"]), "'\t=1+1\r\n");
+        assert_eq!(record(&["\r=1+1"]), "\"'\r=1+1\"\r\n");
+    }
+}
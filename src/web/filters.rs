@@ -1,4 +1,6 @@
+use crate::infrastructure::image_proxy::ImageSigner;
 use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use std::collections::HashMap;
 use std::sync::LazyLock;
 
@@ -30,6 +32,34 @@ pub fn friendly_date(dt: &DateTime<Utc>) -> askama::Result<String> {
     Ok(result)
 }
 
+/// Format a DateTime as an absolute time in the display time zone
+/// Usage: {{ dt|local_date("%b %d, %Y %H:%M", timezone) }}
+pub fn local_date(dt: &DateTime<Utc>, format: &str, tz: &Tz) -> askama::Result<String> {
+    Ok(format_in_zone(dt, *tz, format))
+}
+
+fn format_in_zone(dt: &DateTime<Utc>, tz: Tz, format: &str) -> String {
+    dt.with_timezone(&tz).format(format).to_string()
+}
+
+/// Route the images of sanitized HTML through the image proxy, if enabled
+/// Usage: {{ html|proxy_images(image_proxy)|safe }}
+pub fn proxy_images(html: &str, signer: &Option<ImageSigner>) -> askama::Result<String> {
+    Ok(match signer {
+        Some(signer) => signer.proxy_images(html),
+        None => html.to_string(),
+    })
+}
+
+/// Route a single image URL through the image proxy, if enabled
+/// Usage: {{ url|proxied_src(image_proxy) }}
+pub fn proxied_src(src: &str, signer: &Option<ImageSigner>) -> askama::Result<String> {
+    Ok(signer
+        .as_ref()
+        .and_then(|signer| signer.proxied_src(src))
+        .unwrap_or_else(|| src.to_string()))
+}
+
 /// Check if an i64 value is in a slice
 pub fn in_list(value: &i64, list: &[i64]) -> askama::Result<bool> {
     Ok(list.contains(value))
@@ -50,3 +80,42 @@ pub fn js_path(name: &str) -> askama::Result<String> {
         .unwrap_or_else(|| name.to_string());
     Ok(format!("/static/js/dist/{}", hashed))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_format_in_zone_converts_from_utc() {
+        let winter = Utc.with_ymd_and_hms(2026, 1, 15, 12, 0, 0).unwrap();
+        let format = "%b %d, %Y %H:%M";
+
+        assert_eq!(
+            format_in_zone(&winter, Tz::UTC, format),
+            "Jan 15, 2026 12:00"
+        );
+        assert_eq!(
+            format_in_zone(&winter, chrono_tz::Europe::Berlin, format),
+            "Jan 15, 2026 13:00"
+        );
+        assert_eq!(
+            format_in_zone(&winter, chrono_tz::America::New_York, format),
+            "Jan 15, 2026 07:00"
+        );
+    }
+
+    #[test]
+    fn test_format_in_zone_follows_daylight_saving() {
+        let summer = Utc.with_ymd_and_hms(2026, 7, 15, 23, 30, 0).unwrap();
+
+        assert_eq!(
+            format_in_zone(&summer, chrono_tz::Europe::Berlin, "%Y-%m-%d %H:%M"),
+            "2026-07-16 01:30"
+        );
+        assert_eq!(
+            format_in_zone(&summer, chrono_tz::Asia::Kolkata, "%Y-%m-%d %H:%M"),
+            "2026-07-16 05:00"
+        );
+    }
+}
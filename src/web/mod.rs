@@ -1,3 +1,7 @@
+pub mod atom;
+pub mod csv;
 pub mod filters;
+pub mod opml;
+pub mod redirect;
 pub mod templates;
 pub mod url_builders;
@@ -0,0 +1,214 @@
+//! OPML 2.0 subscription lists.
+//!
+//! Full exports carry FluxFeed's own feed settings as attributes in the
+//! `fluxfeed` namespace. Other readers ignore them; importing the list into
+//! FluxFeed restores the settings. Single-feed exports are meant for sharing
+//! and leave them out.
+
+use crate::domain::models::Feed;
+use quick_xml::events::attributes::Attribute;
+use quick_xml::events::{BytesDecl, BytesStart, BytesText, Event};
+use quick_xml::name::{Namespace, ResolveResult};
+use quick_xml::{NsReader, Writer};
+use std::io;
+
+const FLUXFEED_NS: &str = "urn:fluxfeed:opml";
+
+/// A feed outline read from an OPML document
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct OpmlFeed {
+    pub url: String,
+    pub title: Option<String>,
+    /// `adaptive` or a number of hours, as stored in the feed
+    pub fetch_frequency: Option<String>,
+    pub color: Option<String>,
+    pub ignore_pattern: Option<String>,
+}
+
+/// Serialize feeds as an OPML 2.0 document, one outline per feed
+pub fn render_opml(feeds: &[Feed]) -> io::Result<String> {
+    render_document("FluxFeed Subscriptions", feeds, true)
+}
+
+/// Serialize a single feed for sharing. Its outline has only the standard
+/// attributes, none of the FluxFeed settings.
+pub fn render_feed_opml(feed: &Feed) -> io::Result<String> {
+    render_document(&feed.title, std::slice::from_ref(feed), false)
+}
+
+fn render_document(title: &str, feeds: &[Feed], with_settings: bool) -> io::Result<String> {
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("utf-8"), None)))?;
+
+    let mut root = writer
+        .create_element("opml")
+        .with_attribute(("version", "2.0"));
+    if with_settings {
+        root = root.with_attribute(("xmlns:fluxfeed", FLUXFEED_NS));
+    }
+    root.write_inner_content(|w| {
+        w.create_element("head").write_inner_content(|w| {
+            w.create_element("title")
+                .write_text_content(BytesText::new(title))?;
+            Ok::<(), io::Error>(())
+        })?;
+        w.create_element("body").write_inner_content(|w| {
+            for feed in feeds {
+                write_outline(w, feed, with_settings)?;
+            }
+            Ok::<(), io::Error>(())
+        })?;
+        Ok::<(), io::Error>(())
+    })?;
+
+    Ok(String::from_utf8_lossy(&writer.into_inner()).into_owned())
+}
+
+fn write_outline(w: &mut Writer<Vec<u8>>, feed: &Feed, with_settings: bool) -> io::Result<()> {
+    let mut outline = w
+        .create_element("outline")
+        .with_attribute(("type", "rss"))
+        .with_attribute(("text", feed.title.as_str()))
+        .with_attribute(("title", feed.title.as_str()))
+        .with_attribute(("xmlUrl", feed.url.as_str()));
+    if let Some(site_url) = &feed.site_url {
+        outline = outline.with_attribute(("htmlUrl", site_url.as_str()));
+    }
+    if with_settings {
+        outline = outline
+            .with_attribute(("fluxfeed:fetchFrequency", feed.fetch_frequency.as_str()))
+            .with_attribute(("fluxfeed:color", feed.color.as_str()));
+        if let Some(pattern) = &feed.ignore_pattern {
+            outline = outline.with_attribute(("fluxfeed:ignorePattern", pattern.as_str()));
+        }
+    }
+    outline.write_empty()?;
+    Ok(())
+}
+
+/// Feed outlines of an OPML document, at any depth; outlines without an
+/// `xmlUrl` are categories and only contribute their children
+pub fn parse_opml(input: &str) -> Result<Vec<OpmlFeed>, quick_xml::Error> {
+    let mut reader = NsReader::from_str(input);
+    let mut feeds = Vec::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"outline" => {
+                if let Some(feed) = outline_feed(&reader, &e)? {
+                    feeds.push(feed);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(feeds)
+}
+
+fn outline_feed(
+    reader: &NsReader<&[u8]>,
+    outline: &BytesStart,
+) -> Result<Option<OpmlFeed>, quick_xml::Error> {
+    let mut feed = OpmlFeed::default();
+    let mut text = None;
+
+    for attribute in outline.attributes() {
+        let attribute = attribute?;
+        let value = attribute_value(reader, &attribute)?;
+        match reader.resolve_attribute(attribute.key) {
+            (ResolveResult::Unbound, name) => match name.as_ref() {
+                b"xmlUrl" => feed.url = value.unwrap_or_default(),
+                b"title" => feed.title = value,
+                b"text" => text = value,
+                _ => {}
+            },
+            (ResolveResult::Bound(Namespace(ns)), name) if ns == FLUXFEED_NS.as_bytes() => {
+                match name.as_ref() {
+                    b"fetchFrequency" => feed.fetch_frequency = value,
+                    b"color" => feed.color = value,
+                    b"ignorePattern" => feed.ignore_pattern = value,
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if feed.url.is_empty() {
+        return Ok(None);
+    }
+    feed.title = feed.title.or(text);
+    Ok(Some(feed))
+}
+
+/// Unescaped, trimmed attribute value; `None` when blank
+fn attribute_value(
+    reader: &NsReader<&[u8]>,
+    attribute: &Attribute,
+) -> Result<Option<String>, quick_xml::Error> {
+    let value = attribute.decode_and_unescape_value(reader.decoder())?;
+    let value = value.trim();
+    Ok((!value.is_empty()).then(|| value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_standard_opml_from_other_readers() {
+        let opml = r#"<?xml version="1.0"?>
+            <opml version="1.0">
+              <head><title>Subscriptions</title></head>
+              <body>
+                <outline text="Tech">
+                  <outline type="rss" text="Example &amp; Co" xmlUrl="https://example.com/feed.xml"/>
+                </outline>
+                <outline type="rss" text="No title" title="Blog" xmlUrl=" https://blog.example/rss "/>
+                <outline type="rss" text="Broken" xmlUrl=""/>
+              </body>
+            </opml>"#;
+
+        let feeds = parse_opml(opml).unwrap();
+
+        assert_eq!(
+            feeds,
+            vec![
+                OpmlFeed {
+                    url: "https://example.com/feed.xml".to_string(),
+                    title: Some("Example & Co".to_string()),
+                    ..Default::default()
+                },
+                OpmlFeed {
+                    url: "https://blog.example/rss".to_string(),
+                    title: Some("Blog".to_string()),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_reads_settings_by_namespace() {
+        // The prefix is whatever the document binds to the namespace
+        let opml = r##"<opml version="2.0" xmlns:ff="urn:fluxfeed:opml" xmlns:other="urn:other">
+              <body>
+                <outline text="A" xmlUrl="https://a.example/feed" ff:fetchFrequency="6"
+                         ff:color="#FF0000" ff:ignorePattern="^Sponsored" other:color="#00FF00"/>
+              </body>
+            </opml>"##;
+
+        let feeds = parse_opml(opml).unwrap();
+
+        assert_eq!(feeds[0].fetch_frequency.as_deref(), Some("6"));
+        assert_eq!(feeds[0].color.as_deref(), Some("#FF0000"));
+        assert_eq!(feeds[0].ignore_pattern.as_deref(), Some("^Sponsored"));
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_xml() {
+        assert!(parse_opml("<opml><body><outline xmlUrl=\"x></body>").is_err());
+    }
+}
@@ -0,0 +1,118 @@
+use axum::{
+    http::{HeaderValue, StatusCode},
+    response::{IntoResponse, IntoResponseParts, Redirect, Response, ResponseParts},
+};
+use url::Url;
+
+/// Redirect to `target` only if it stays on this site or points at one of the
+/// `allowed_origins` (e.g. `https://example.com`).
+///
+/// Anything else is rejected with a 400, so a crafted article or feed URL
+/// can't turn FluxFeed into an open redirector.
+/// Every redirect goes through this or [`HxRedirect`].
+pub fn safe_redirect(target: &str, allowed_origins: &[&str]) -> Response {
+    if is_safe_redirect(target, allowed_origins) {
+        Redirect::to(target).into_response()
+    } else {
+        tracing::warn!("Rejected redirect to {}", target);
+        (StatusCode::BAD_REQUEST, "Invalid redirect target").into_response()
+    }
+}
+
+/// `HX-Redirect` header sending an HTMX request on to a path of this site,
+/// checked like [`safe_redirect`]: any other target answers with a 400
+pub struct HxRedirect(pub String);
+
+impl IntoResponseParts for HxRedirect {
+    type Error = Response;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Response> {
+        let value = HeaderValue::from_str(&self.0)
+            .ok()
+            .filter(|_| is_safe_redirect(&self.0, &[]));
+        let Some(value) = value else {
+            tracing::warn!("Rejected redirect to {}", self.0);
+            return Err((StatusCode::BAD_REQUEST, "Invalid redirect target").into_response());
+        };
+        res.headers_mut().insert("HX-Redirect", value);
+        Ok(res)
+    }
+}
+
+impl IntoResponse for HxRedirect {
+    fn into_response(self) -> Response {
+        (self, ()).into_response()
+    }
+}
+
+pub fn is_safe_redirect(target: &str, allowed_origins: &[&str]) -> bool {
+    if is_relative_path(target) {
+        return true;
+    }
+
+    let Ok(url) = Url::parse(target) else {
+        return false;
+    };
+    if !matches!(url.scheme(), "http" | "https") {
+        return false;
+    }
+    let origin = url.origin().ascii_serialization();
+    allowed_origins
+        .iter()
+        .any(|allowed| allowed.trim_end_matches('/') == origin)
+}
+
+/// A path on this origin. Browsers treat `//host` and `/\host` as
+/// protocol-relative URLs, so those don't count.
+fn is_relative_path(target: &str) -> bool {
+    target.starts_with('/')
+        && !target.starts_with("//")
+        && !target.contains('\\')
+        && !target.chars().any(char::is_control)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_path_is_allowed() {
+        assert!(is_safe_redirect("/articles?is_read=false", &[]));
+
+        let response = safe_redirect("/articles", &[]);
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(response.headers()["location"], "/articles");
+    }
+
+    #[test]
+    fn test_protocol_relative_url_is_rejected() {
+        assert!(!is_safe_redirect("//evil.com", &[]));
+        assert!(!is_safe_redirect("/\\evil.com", &[]));
+        assert!(!is_safe_redirect("/\t/evil.com", &[]));
+
+        let response = safe_redirect("//evil.com", &[]);
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_hx_redirect_checks_target() {
+        let response = HxRedirect("/feeds/1".to_string()).into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["hx-redirect"], "/feeds/1");
+
+        let response = HxRedirect("//evil.com".to_string()).into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(response.headers().get("hx-redirect").is_none());
+    }
+
+    #[test]
+    fn test_absolute_url_needs_allowlisted_origin() {
+        let allowed = ["https://example.com/"];
+        assert!(is_safe_redirect("https://example.com/post/1", &allowed));
+        assert!(!is_safe_redirect("https://example.com.evil.com/", &allowed));
+        assert!(!is_safe_redirect("http://example.com/post/1", &allowed));
+        assert!(!is_safe_redirect("https://evil.com/", &allowed));
+        assert!(!is_safe_redirect("javascript:alert(1)", &allowed));
+        assert!(!is_safe_redirect("articles", &allowed));
+    }
+}
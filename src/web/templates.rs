@@ -1,10 +1,14 @@
 use crate::domain::models::{
-    Article, Feed, FeedWithUnread, GroupNode, GroupNodeWithUnread, LogWithFeed, Tag,
+    Article, DigestFeed, Feed, FeedDiagnostic, FeedListFilter, FeedWithUnread, GroupNode,
+    GroupNodeWithUnread, GuidStrategy, LogWithFeed, Tag, Theme,
 };
+use crate::infrastructure::image_proxy::ImageSigner;
+use crate::infrastructure::insert_progress::InsertProgress;
 use crate::infrastructure::repository::ArticleCounts;
 use crate::web::filters;
-use crate::web::url_builders::{ArticleFilters, LogFilters};
+use crate::web::url_builders::{self, ArticleFilters, LogFilters};
 use askama::Template;
+use chrono_tz::Tz;
 
 #[derive(Template)]
 #[template(path = "error.html")]
@@ -12,12 +16,51 @@ pub struct ErrorTemplate {
     pub status_code: u16,
     pub status_text: String,
     pub message: String,
+    /// Where to go instead, e.g. the existing feed for a duplicate
+    pub link: Option<ErrorLink>,
+    pub theme: Theme,
+}
+
+#[derive(Debug, Clone)]
+pub struct ErrorLink {
+    pub href: String,
+    pub label: String,
 }
 
 #[derive(Template)]
 #[template(path = "feeds/list.html")]
 pub struct FeedsListTemplate {
     pub feeds: Vec<Feed>,
+    pub groups: Vec<Group>,
+    pub offset: i64,
+    pub limit: i64,
+    pub has_more: bool,
+    pub filter: FeedListFilter,
+    pub theme: Theme,
+}
+
+#[derive(Template)]
+#[template(path = "feeds/diagnostics.html")]
+pub struct FeedDiagnosticsTemplate {
+    pub failing: Vec<FeedDiagnostic>,
+    pub pending: Vec<FeedDiagnostic>,
+    pub healthy: Vec<FeedDiagnostic>,
+    pub theme: Theme,
+    /// Zone of absolute timestamps
+    pub timezone: Tz,
+}
+
+#[derive(Template)]
+#[template(path = "feeds/_feed_rows.html")]
+pub struct FeedRowsTemplate {
+    pub feeds: Vec<Feed>,
+}
+
+#[derive(Template)]
+#[template(path = "feeds/_load_more_button.html")]
+pub struct LoadMoreButtonFeedsTemplate {
+    pub next_offset: i64,
+    pub filter: FeedListFilter,
 }
 
 #[derive(Template)]
@@ -26,6 +69,16 @@ pub struct FeedRowTemplate {
     pub feed: Feed,
 }
 
+/// Placeholder row of a feed whose first fetch is still running, polling
+/// until it can be replaced by the full row
+#[derive(Template)]
+#[template(path = "feeds/feed_row_fetching.html")]
+pub struct FeedRowFetchingTemplate {
+    pub feed: Feed,
+    /// Articles stored so far, while a large first fetch is being stored
+    pub progress: Option<InsertProgress>,
+}
+
 #[derive(Template)]
 #[template(path = "feeds/form.html")]
 pub struct FeedFormTemplate;
@@ -35,6 +88,9 @@ pub struct FeedFormTemplate;
 pub struct FeedDetailTemplate {
     pub feed: Feed,
     pub tags: Vec<Tag>,
+    pub theme: Theme,
+    /// Zone of absolute timestamps
+    pub timezone: Tz,
 }
 
 #[derive(Template)]
@@ -43,6 +99,17 @@ pub struct FeedEditFormTemplate {
     pub feed: Feed,
     pub all_tags: Vec<Tag>,
     pub feed_tag_ids: Vec<i64>,
+    pub custom_headers: String,
+    /// Whether a bearer token is stored; the token itself is never rendered
+    pub has_bearer_token: bool,
+    pub groups: Vec<Group>,
+    pub theme: Theme,
+}
+
+impl FeedEditFormTemplate {
+    pub fn guid_strategies(&self) -> [GuidStrategy; 4] {
+        GuidStrategy::ALL
+    }
 }
 
 #[derive(Template)]
@@ -87,18 +154,67 @@ pub struct ArticlesListTemplate {
     pub active_filter: String,
     pub all_tags: Vec<Tag>,
     pub view_mode: String,
+    pub theme: Theme,
+    pub image_proxy: Option<ImageSigner>,
+    /// Zone of absolute timestamps
+    pub timezone: Tz,
 }
 
 #[derive(Template)]
 #[template(path = "articles/article_row.html")]
 pub struct ArticleRowTemplate {
     pub item: ArticleWithFeed,
+    pub image_proxy: Option<ImageSigner>,
+    /// Zone of absolute timestamps
+    pub timezone: Tz,
 }
 
 #[derive(Template)]
 #[template(path = "articles/article_compact_row.html")]
 pub struct ArticleCompactRowTemplate {
     pub item: ArticleWithFeed,
+    pub image_proxy: Option<ImageSigner>,
+    /// Zone of absolute timestamps
+    pub timezone: Tz,
+}
+
+#[derive(Template)]
+#[template(path = "articles/detail.html")]
+pub struct ArticleDetailTemplate {
+    pub item: ArticleWithFeed,
+    pub theme: Theme,
+    pub image_proxy: Option<ImageSigner>,
+    /// Zone of absolute timestamps
+    pub timezone: Tz,
+}
+
+#[derive(Template)]
+#[template(path = "articles/_detail_card.html")]
+pub struct ArticleDetailCardTemplate {
+    pub item: ArticleWithFeed,
+    pub image_proxy: Option<ImageSigner>,
+    /// Zone of absolute timestamps
+    pub timezone: Tz,
+}
+
+#[derive(Template)]
+#[template(path = "articles/state_import_form.html")]
+pub struct ArticleStateImportFormTemplate;
+
+#[derive(Template)]
+#[template(path = "articles/state_import_results.html")]
+pub struct ArticleStateImportResultsTemplate {
+    pub applied: usize,
+    pub unmatched: Vec<String>,
+}
+
+/// Full summary or content of an article, fetched when its preview in a
+/// list view is expanded
+#[derive(Template)]
+#[template(path = "articles/_article_content.html")]
+pub struct ArticleContentTemplate {
+    pub item: ArticleWithFeed,
+    pub image_proxy: Option<ImageSigner>,
 }
 
 // Combined article + feed data for display
@@ -107,25 +223,82 @@ pub struct ArticleWithFeed {
     pub article: Article,
     pub feed_title: String,
     pub feed_color: String,
+    pub feed_muted: bool,
+    /// The feed prefers its summaries over the content, see
+    /// [`Self::detail_body`]
+    pub feed_prefer_summary: bool,
     pub tags: Vec<Tag>,
 }
 
+/// Reading speed assumed for the estimated reading time
+const WORDS_PER_MINUTE: i64 = 200;
+
+impl ArticleWithFeed {
+    /// HTML shown as the article's text in the list views: the summary, or
+    /// the content if there is none
+    pub fn body(&self) -> Option<&str> {
+        self.article
+            .summary
+            .as_deref()
+            .or(self.article.content.as_deref())
+    }
+
+    /// HTML shown as the article's body in the detail view: the content, or
+    /// the summary if there is none. A feed preferring summaries gets the
+    /// summary instead, unless it is empty.
+    pub fn detail_body(&self) -> Option<&str> {
+        let content = self.article.content.as_deref();
+        let summary = self.article.summary.as_deref();
+        if self.feed_prefer_summary {
+            summary.filter(|html| !html.trim().is_empty()).or(content)
+        } else {
+            content.or(summary)
+        }
+    }
+
+    /// Estimated minutes to read the article, at least one; `None` if its
+    /// words were never counted or it has no text
+    pub fn reading_time_minutes(&self) -> Option<i64> {
+        self.article
+            .word_count
+            .filter(|&words| words > 0)
+            .map(|words| ((words + WORDS_PER_MINUTE - 1) / WORDS_PER_MINUTE).max(1))
+    }
+
+    /// The language without region or script, e.g. `en` for `en-US`
+    pub fn primary_language(&self) -> Option<&str> {
+        self.article
+            .language
+            .as_deref()
+            .and_then(|language| language.split('-').next())
+    }
+}
+
 #[derive(Template)]
 #[template(path = "articles/_article_rows.html")]
 pub struct ArticleRowsTemplate {
     pub articles: Vec<ArticleWithFeed>,
+    pub image_proxy: Option<ImageSigner>,
+    /// Zone of absolute timestamps
+    pub timezone: Tz,
 }
 
 #[derive(Template)]
 #[template(path = "articles/_article_compact_rows.html")]
 pub struct ArticleCompactRowsTemplate {
     pub articles: Vec<ArticleWithFeed>,
+    pub image_proxy: Option<ImageSigner>,
+    /// Zone of absolute timestamps
+    pub timezone: Tz,
 }
 
 #[derive(Template)]
 #[template(path = "articles/article_fullscreen_row.html")]
 pub struct ArticleFullscreenRowTemplate {
     pub item: ArticleWithFeed,
+    pub image_proxy: Option<ImageSigner>,
+    /// Zone of absolute timestamps
+    pub timezone: Tz,
 }
 
 #[derive(Template)]
@@ -133,6 +306,9 @@ pub struct ArticleFullscreenRowTemplate {
 #[allow(dead_code)]
 pub struct ArticleFullscreenRowsTemplate {
     pub articles: Vec<ArticleWithFeed>,
+    pub image_proxy: Option<ImageSigner>,
+    /// Zone of absolute timestamps
+    pub timezone: Tz,
 }
 
 #[derive(Template)]
@@ -154,6 +330,20 @@ pub struct ArticleSearchTemplate {
     pub search_query: Option<String>,
     pub date_from: Option<String>,
     pub date_to: Option<String>,
+    pub theme: Theme,
+    pub image_proxy: Option<ImageSigner>,
+    /// Zone of absolute timestamps
+    pub timezone: Tz,
+}
+
+impl ArticleSearchTemplate {
+    pub fn mark_read_url(&self) -> String {
+        url_builders::mark_search_read_url(
+            self.search_query.as_deref(),
+            self.date_from.as_deref(),
+            self.date_to.as_deref(),
+        )
+    }
 }
 
 #[derive(Template)]
@@ -165,12 +355,17 @@ pub struct LogsListTemplate {
     pub limit: i64,
     pub has_more: bool,
     pub filters: LogFilters,
+    pub theme: Theme,
+    /// Zone of absolute timestamps
+    pub timezone: Tz,
 }
 
 #[derive(Template)]
 #[template(path = "logs/_log_rows.html")]
 pub struct LogRowsTemplate {
     pub logs: Vec<LogWithFeed>,
+    /// Zone of absolute timestamps
+    pub timezone: Tz,
 }
 
 #[derive(Template)]
@@ -188,6 +383,10 @@ pub struct ReaderModeTemplate {
     pub content: String,
     pub byline: Option<String>,
     pub excerpt: Option<String>,
+    pub degraded: bool,
+    pub language: Option<String>,
+    pub theme: Theme,
+    pub image_proxy: Option<ImageSigner>,
 }
 
 #[derive(Template)]
@@ -198,6 +397,9 @@ pub struct ReaderContentTemplate {
     pub content: String,
     pub byline: Option<String>,
     pub excerpt: Option<String>,
+    pub degraded: bool,
+    pub language: Option<String>,
+    pub image_proxy: Option<ImageSigner>,
 }
 
 #[derive(Template)]
@@ -218,13 +420,14 @@ pub struct TagFilterModalTemplate {
 }
 
 // Group templates
-use crate::domain::models::{FlatTreeItem, Group};
+use crate::domain::models::{FlatTreeItem, Group, ShareToken, UnreadFeedToken};
 
 #[derive(Template)]
 #[template(path = "groups/list.html")]
 pub struct GroupsListTemplate {
     pub tree_items: Vec<FlatTreeItem>,
     pub ungrouped_feeds: Vec<Feed>,
+    pub theme: Theme,
 }
 
 #[derive(Template)]
@@ -239,6 +442,38 @@ pub struct GroupListContentTemplate {
 pub struct GroupFormTemplate {
     pub group: Option<Group>,
     pub available_groups: Vec<Group>,
+    pub share_tokens: Vec<ShareToken>,
+}
+
+#[derive(Template)]
+#[template(path = "groups/_unread_nav.html")]
+pub struct GroupUnreadNavTemplate {
+    pub groups: Vec<GroupNodeWithUnread>,
+}
+
+#[derive(Template)]
+#[template(path = "feeds/unread_feed_links.html")]
+pub struct UnreadFeedLinksModalTemplate {
+    pub tokens: Vec<UnreadFeedToken>,
+}
+
+#[derive(Template)]
+#[template(path = "feeds/_unread_feed_links.html")]
+pub struct UnreadFeedLinksTemplate {
+    pub tokens: Vec<UnreadFeedToken>,
+}
+
+#[derive(Template)]
+#[template(path = "groups/_member_feeds.html")]
+pub struct GroupMemberFeedsTemplate {
+    pub feeds: Vec<Feed>,
+}
+
+#[derive(Template)]
+#[template(path = "groups/_share_links.html")]
+pub struct GroupShareLinksTemplate {
+    pub group_id: i64,
+    pub share_tokens: Vec<ShareToken>,
 }
 
 #[derive(Template)]
@@ -248,12 +483,46 @@ pub struct AssignFeedTemplate {
     pub groups: Vec<Group>,
 }
 
+// Shared (read-only) templates
+
+#[derive(Template)]
+#[template(path = "share/group.html")]
+pub struct SharedGroupTemplate {
+    pub group_name: String,
+    pub articles: Vec<ArticleWithFeed>,
+    pub has_more: bool,
+    pub next_offset: i64,
+    pub image_proxy: Option<ImageSigner>,
+    /// Zone of absolute timestamps
+    pub timezone: Tz,
+}
+
+#[derive(Template)]
+#[template(path = "articles/digest.html")]
+pub struct ArticleDigestTemplate {
+    pub date: chrono::NaiveDate,
+    pub unread_only: bool,
+    pub article_count: usize,
+    pub feeds: Vec<DigestFeed>,
+    /// Zone of absolute timestamps
+    pub timezone: Tz,
+}
+
+#[derive(Template)]
+#[template(path = "share/error.html")]
+pub struct SharedErrorTemplate {
+    pub status_code: u16,
+    pub status_text: String,
+    pub message: String,
+}
+
 // Tag templates
 
 #[derive(Template)]
 #[template(path = "tags/list.html")]
 pub struct TagsListTemplate {
     pub tags: Vec<Tag>,
+    pub theme: Theme,
 }
 
 #[derive(Template)]
@@ -267,3 +536,69 @@ pub struct TagListContentTemplate {
 pub struct TagFormTemplate {
     pub tag: Option<Tag>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item() -> ArticleWithFeed {
+        let now = chrono::Utc::now();
+        ArticleWithFeed {
+            article: Article {
+                id: 1,
+                feed_id: 1,
+                guid: "guid".to_string(),
+                title: "A title".to_string(),
+                url: None,
+                content: None,
+                summary: None,
+                content_preview: None,
+                word_count: None,
+                author: None,
+                published_at: None,
+                is_read: false,
+                is_starred: false,
+                is_read_later: false,
+                og_image: None,
+                og_description: None,
+                og_site_name: None,
+                language: None,
+                created_at: now,
+                updated_at: now,
+            },
+            feed_title: "Feed".to_string(),
+            feed_color: "#000000".to_string(),
+            feed_muted: false,
+            feed_prefer_summary: false,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_reading_time() {
+        let mut item = item();
+        assert_eq!(item.reading_time_minutes(), None);
+        item.article.word_count = Some(6);
+        assert_eq!(item.reading_time_minutes(), Some(1));
+        item.article.word_count = Some(401);
+        assert_eq!(item.reading_time_minutes(), Some(3));
+    }
+
+    #[test]
+    fn test_prefer_summary_flips_detail_body() {
+        let mut item = item();
+        item.article.content = Some("<p>Content</p>".to_string());
+        item.article.summary = Some("<p>Summary</p>".to_string());
+        // List views show the summary first, the detail view the content
+        assert_eq!(item.body(), Some("<p>Summary</p>"));
+        assert_eq!(item.detail_body(), Some("<p>Content</p>"));
+
+        item.feed_prefer_summary = true;
+        assert_eq!(item.body(), Some("<p>Summary</p>"));
+        assert_eq!(item.detail_body(), Some("<p>Summary</p>"));
+
+        // An empty summary falls back to the content
+        item.article.summary = Some("  ".to_string());
+        assert_eq!(item.detail_body(), Some("<p>Content</p>"));
+    }
+}
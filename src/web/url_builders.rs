@@ -1,3 +1,4 @@
+use crate::domain::models::FeedListFilter;
 use url::form_urlencoded;
 
 #[derive(Clone)]
@@ -7,9 +8,13 @@ pub struct ArticleFilters {
     pub tag_ids: Vec<i64>,
     pub is_read: Option<bool>,
     pub is_starred: Option<bool>,
+    pub is_read_later: Option<bool>,
     pub search_query: Option<String>,
     pub date_from: Option<String>,
     pub date_to: Option<String>,
+    pub new_since_last_visit: bool,
+    /// Language tag the articles are limited to (`lang`)
+    pub language: Option<String>,
 }
 
 #[derive(Clone)]
@@ -47,6 +52,9 @@ impl ArticleFilters {
         if let Some(v) = self.is_starred {
             append_param(p, &format!("is_starred={}", v));
         }
+        if let Some(v) = self.is_read_later {
+            append_param(p, &format!("is_read_later={}", v));
+        }
     }
 
     fn filter_params(&self) -> String {
@@ -70,6 +78,12 @@ impl ArticleFilters {
         if let Some(ref v) = self.date_to {
             append_param(&mut p, &format!("date_to={}", url_encode(v)));
         }
+        if self.new_since_last_visit {
+            append_param(&mut p, "new_since_last_visit=true");
+        }
+        if let Some(ref v) = self.language {
+            append_param(&mut p, &format!("lang={}", url_encode(v)));
+        }
         p
     }
 
@@ -91,11 +105,23 @@ impl ArticleFilters {
         }
     }
 
+    /// The article list URL with the current filters (first page)
+    pub fn list_url(&self) -> String {
+        let p = self.filter_params();
+        if p.is_empty() {
+            "/articles".to_string()
+        } else {
+            format!("/articles?{}", p)
+        }
+    }
+
+    /// Carries all filters so the handler can redirect back to the same view
     pub fn mark_all_read_url(&self) -> String {
-        if self.feed_ids.is_empty() {
+        let p = self.filter_params();
+        if p.is_empty() {
             "/articles/mark-all-read".to_string()
         } else {
-            format!("/articles/mark-all-read?feed_ids={}", ids_to_csv(&self.feed_ids))
+            format!("/articles/mark-all-read?{}", p)
         }
     }
 
@@ -160,6 +186,51 @@ impl ArticleFilters {
     }
 }
 
+fn search_params(q: Option<&str>, date_from: Option<&str>, date_to: Option<&str>) -> String {
+    let mut p = String::new();
+    for (name, value) in [("q", q), ("date_from", date_from), ("date_to", date_to)] {
+        if let Some(value) = value {
+            append_param(&mut p, &format!("{}={}", name, url_encode(value)));
+        }
+    }
+    p
+}
+
+/// The search page for a query and date range
+pub fn search_url(q: Option<&str>, date_from: Option<&str>, date_to: Option<&str>) -> String {
+    let p = search_params(q, date_from, date_to);
+    if p.is_empty() {
+        "/articles/search".to_string()
+    } else {
+        format!("/articles/search?{}", p)
+    }
+}
+
+/// Marks read the results of the search for a query and date range
+pub fn mark_search_read_url(
+    q: Option<&str>,
+    date_from: Option<&str>,
+    date_to: Option<&str>,
+) -> String {
+    format!(
+        "/articles/search/mark-read?{}",
+        search_params(q, date_from, date_to)
+    )
+}
+
+impl FeedListFilter {
+    pub fn feeds_url(&self, offset: &i64) -> String {
+        let mut url = format!("/feeds?offset={}", offset);
+        if let Some(ref q) = self.search {
+            url.push_str(&format!("&q={}", url_encode(q)));
+        }
+        if let Some(id) = self.group_id {
+            url.push_str(&format!("&group_id={}", id));
+        }
+        url
+    }
+}
+
 impl LogFilters {
     pub fn logs_url(&self, offset: &i64) -> String {
         let mut url = format!("/logs?offset={}", offset);
@@ -174,6 +245,24 @@ impl LogFilters {
         }
         url
     }
+
+    pub fn export_csv_url(&self) -> String {
+        let mut p = String::new();
+        if let Some(id) = self.feed_id {
+            append_param(&mut p, &format!("feed_id={}", id));
+        }
+        if let Some(ref name) = self.feed_name {
+            append_param(&mut p, &format!("feed_name={}", url_encode(name)));
+        }
+        if let Some(ref t) = self.log_type {
+            append_param(&mut p, &format!("log_type={}", url_encode(t)));
+        }
+        if p.is_empty() {
+            "/logs/export.csv".to_string()
+        } else {
+            format!("/logs/export.csv?{}", p)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -187,9 +276,12 @@ mod tests {
             tag_ids: vec![],
             is_read: None,
             is_starred: None,
+            is_read_later: None,
             search_query: None,
             date_from: None,
             date_to: None,
+            new_since_last_visit: false,
+            language: None,
         }
     }
 
@@ -213,9 +305,12 @@ mod tests {
             tag_ids: vec![5],
             is_read: Some(false),
             is_starred: Some(true),
+            is_read_later: None,
             search_query: Some("hello world".to_string()),
             date_from: Some("2024-01-01".to_string()),
             date_to: Some("2024-12-31".to_string()),
+            new_since_last_visit: true,
+            language: Some("en".to_string()),
         };
         let url = f.articles_url(&0);
         assert!(url.contains("feed_ids=3"));
@@ -223,9 +318,11 @@ mod tests {
         assert!(url.contains("tag_ids=5"));
         assert!(url.contains("is_read=false"));
         assert!(url.contains("is_starred=true"));
+        assert!(url.contains("new_since_last_visit=true"));
         assert!(url.contains("q=hello+world") || url.contains("q=hello%20world"));
         assert!(url.contains("date_from=2024-01-01"));
         assert!(url.contains("date_to=2024-12-31"));
+        assert!(url.contains("lang=en"));
     }
 
     #[test]
@@ -249,6 +346,27 @@ mod tests {
         assert_eq!(f.mark_all_read_url(), "/articles/mark-all-read?feed_ids=7,8");
     }
 
+    #[test]
+    fn mark_all_read_url_keeps_all_filters() {
+        let f = ArticleFilters {
+            group_ids: vec![2],
+            is_read: Some(false),
+            search_query: Some("rust".to_string()),
+            ..empty_filters()
+        };
+        assert_eq!(
+            f.mark_all_read_url(),
+            "/articles/mark-all-read?group_ids=2&is_read=false&q=rust"
+        );
+    }
+
+    #[test]
+    fn list_url_with_and_without_filters() {
+        assert_eq!(empty_filters().list_url(), "/articles");
+        let f = ArticleFilters { feed_ids: vec![1], is_starred: Some(true), ..empty_filters() };
+        assert_eq!(f.list_url(), "/articles?feed_ids=1&is_starred=true");
+    }
+
     #[test]
     fn clear_feed_filter_url_keeps_read_and_tags() {
         let f = ArticleFilters {
@@ -257,6 +375,7 @@ mod tests {
             tag_ids: vec![3],
             is_read: Some(false),
             is_starred: None,
+            is_read_later: None,
             ..empty_filters()
         };
         let url = f.clear_feed_filter_url();
@@ -280,6 +399,7 @@ mod tests {
             tag_ids: vec![3],
             is_read: Some(true),
             is_starred: None,
+            is_read_later: None,
             ..empty_filters()
         };
         let url = f.clear_tag_filter_url();
@@ -311,6 +431,7 @@ mod tests {
             group_ids: vec![2],
             tag_ids: vec![3],
             is_starred: Some(true),
+            is_read_later: None,
             ..empty_filters()
         };
         let url = f.tag_filter_modal_url();
@@ -333,6 +454,7 @@ mod tests {
             feed_ids: vec![1],
             is_read: Some(false),
             is_starred: Some(true),
+            is_read_later: None,
             ..empty_filters()
         };
         let url = f.clear_feed_filter_url();
@@ -360,4 +482,17 @@ mod tests {
         assert!(url.contains("feed_name=My+Feed") || url.contains("feed_name=My%20Feed"));
         assert!(url.contains("log_type=error"));
     }
+
+    #[test]
+    fn log_filters_export_csv_url() {
+        let f = LogFilters { feed_id: None, feed_name: None, log_type: None };
+        assert_eq!(f.export_csv_url(), "/logs/export.csv");
+
+        let f = LogFilters {
+            feed_id: Some(42),
+            feed_name: None,
+            log_type: Some("error".to_string()),
+        };
+        assert_eq!(f.export_csv_url(), "/logs/export.csv?feed_id=42&log_type=error");
+    }
 }
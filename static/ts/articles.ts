@@ -98,15 +98,9 @@ function loadMoreFullscreenArticles(url: string): void {
   }
 }
 
-// Cookie helper functions
-function getCookie(name: string): string | null {
-  const match = document.cookie.match(new RegExp('(^| )' + name + '=([^;]+)'));
-  return match ? match[2] : null;
-}
-
-function setCookie(name: string, value: string, days: number = 365): void {
-  const expires = new Date(Date.now() + days * 864e5).toUTCString();
-  document.cookie = `${name}=${value}; expires=${expires}; path=/; SameSite=Lax`;
+// The view the page shows; the server renders the requested or stored one
+function currentView(): string {
+  return document.getElementById('articles-container')?.dataset.viewMode || 'cards';
 }
 
 interface LoadMoreButton extends HTMLButtonElement {
@@ -119,7 +113,7 @@ function updateLoadMoreButton(): void {
   const loadMoreBtn = document.getElementById('load-more-btn') as LoadMoreButton | null;
   if (!loadMoreBtn) return;
 
-  const savedView = getCookie('articleView') || 'cards';
+  const savedView = currentView();
   const currentUrl = loadMoreBtn.getAttribute('hx-get');
   if (!currentUrl) return;
 
@@ -154,10 +148,14 @@ function updateViewButtonState(buttons: NodeListOf<Element>, isActive: boolean):
   });
 }
 
-// View toggle with cookie persistence
-function setView(view: string): void {
-  // Save preference to cookie (server can read this)
-  setCookie('articleView', view);
+// View toggle, remembered server-side as the default view
+function setView(view: string, persist: boolean = true): void {
+  if (persist && typeof htmx !== 'undefined') {
+    htmx.ajax('POST', '/preferences/view-mode', {
+      swap: 'none',
+      values: { view_mode: view }
+    });
+  }
 
   // Update UI elements
   const cardsView = document.getElementById('articles-cards');
@@ -168,6 +166,10 @@ function setView(view: string): void {
   const normalLayout = document.getElementById('normal-layout');
   const fullscreenLayout = document.getElementById('fullscreen-layout');
 
+  if (mainContainer) {
+    mainContainer.dataset.viewMode = view;
+  }
+
   // All view toggle buttons (desktop sidebar, mobile nav)
   const allCardsBtns = document.querySelectorAll('[id^="view-cards"]');
   const allCompactBtns = document.querySelectorAll('[id^="view-compact"]');
@@ -290,8 +292,8 @@ function checkCollapsibleContent(): void {
 
 // Initialize on page load
 document.addEventListener('DOMContentLoaded', () => {
-  const savedView = getCookie('articleView') || 'cards';
-  setView(savedView);
+  const savedView = currentView();
+  setView(savedView, false);
   updateLoadMoreButton();
   checkCollapsibleContent();
   // Setup infinite scroll if in fullscreen mode
@@ -4,6 +4,7 @@
  * - Select all/none feeds
  * - Apply filter
  * - Group checkbox cascade
+ * - Expand/collapse groups
  */
 
 function closeFeedFilterModal(): void {
@@ -48,6 +49,16 @@ function applyFeedFilter(): void {
   window.location.href = '/articles' + (queryString ? '?' + queryString : '');
 }
 
+// Show or hide the feeds and subgroups of a group; the toggle is the
+// button in the group's row
+function toggleFilterGroup(button: HTMLElement): void {
+  const children = button.closest('.group-container')?.querySelector('.group-children');
+  if (children) {
+    children.classList.toggle('hidden');
+    button.classList.toggle('-rotate-90');
+  }
+}
+
 function initFeedFilterModal(): void {
   // Toggle group checkbox cascades to all child checkboxes
   document.querySelectorAll<HTMLInputElement>('.group-checkbox').forEach(groupCb => {
@@ -84,3 +95,4 @@ document.body.addEventListener('htmx:afterSwap', (evt: Event) => {
 (window as unknown as Record<string, unknown>).selectAllFeeds = selectAllFeeds;
 (window as unknown as Record<string, unknown>).selectNoneFeeds = selectNoneFeeds;
 (window as unknown as Record<string, unknown>).applyFeedFilter = applyFeedFilter;
+(window as unknown as Record<string, unknown>).toggleFilterGroup = toggleFilterGroup;
@@ -5,7 +5,7 @@
 declare const htmx: {
   process(element: Element): void;
   ajax(method: string, url: string, options: {
-    target: string;
+    target?: string;
     swap: string;
     values?: Record<string, string>;
   }): Promise<void>;
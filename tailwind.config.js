@@ -9,5 +9,6 @@ module.exports = {
   plugins: [
     require('@tailwindcss/typography'),
   ],
-  darkMode: 'media',
+  // The `dark` class is set from the theme preference, see base.html
+  darkMode: 'selector',
 }
@@ -1,12 +1,60 @@
-use axum::{http::StatusCode, Router};
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    Router,
+};
 use axum_test::TestServer;
-use fluxfeed::api::{articles, feeds, health};
+use chrono::{TimeZone, Utc};
+use fluxfeed::api::{
+    article_share, articles, feed_articles, feed_stats, feed_validation, feeds, groups, health,
+    image_proxy, logs, maintenance, preferences, reader, share, status, unread_feed,
+};
+use fluxfeed::config::{
+    AdaptiveInterval, CookieSameSite, FeedSort, FetchDelays, HostPolicy, PageSize, StorageLimits,
+};
+use fluxfeed::domain::models::{CreateFeed, NewArticle, Theme};
+use fluxfeed::infrastructure::image_proxy::{ImageProxy, ImageSigner};
+use fluxfeed::infrastructure::reader_limiter::ReaderLimiter;
+use fluxfeed::infrastructure::{api_auth, csrf, error_pages, repository};
 use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 async fn setup_test_app() -> (TestServer, SqlitePool) {
+    setup_test_app_with_token(None).await
+}
+
+async fn setup_test_app_with_token(api_token: Option<&str>) -> (TestServer, SqlitePool) {
+    let pool = test_pool().await;
+    let state = test_state(&pool, api_token);
+
+    let server = TestServer::new(test_router(state)).unwrap();
+    (server, pool)
+}
+
+/// Test app behind the CSRF middleware, as the real server runs it
+async fn setup_csrf_app_with_token(api_token: Option<&str>) -> (TestServer, SqlitePool) {
+    let pool = test_pool().await;
+    let router = test_router(test_state(&pool, api_token)).layer(
+        axum::middleware::from_fn_with_state(CookieSameSite::default(), csrf::csrf_middleware),
+    );
+
+    let server = TestServer::new(router).unwrap();
+    (server, pool)
+}
+
+/// Test app with the image proxy turned on, and what its pages sign image
+/// URLs with
+async fn setup_image_proxy_app() -> (TestServer, ImageSigner) {
+    let pool = test_pool().await;
+    let mut state = test_state(&pool, None);
+    state.image_proxy = ImageProxy::new(true, &state.secret_key);
+    let signer = state.image_proxy.signer().unwrap();
+
+    (TestServer::new(test_router(state)).unwrap(), signer)
+}
+
+async fn test_pool() -> SqlitePool {
     let pool = SqlitePoolOptions::new()
         .max_connections(1)
         .connect("sqlite::memory:")
@@ -18,17 +66,78 @@ async fn setup_test_app() -> (TestServer, SqlitePool) {
         .run(&pool)
         .await
         .expect("Failed to run migrations");
+    pool
+}
 
-    let state = feeds::AppState {
+fn test_state(pool: &SqlitePool, api_token: Option<&str>) -> feeds::AppState {
+    feeds::AppState {
         db_pool: pool.clone(),
         import_jobs: Arc::new(RwLock::new(HashMap::new())),
-    };
+        fetch_delays: FetchDelays::default(),
+        adaptive_interval: AdaptiveInterval::default(),
+        archive_backfill_pages: 0,
+        max_feeds_per_cycle: 0,
+        quiet_hours: None,
+        storage_limits: StorageLimits::default(),
+        api_token: api_token.map(str::to_string),
+        page_size: PageSize::default(),
+        feed_sort: FeedSort::default(),
+        group_tree_cache: Default::default(),
+        reader_limiter: Default::default(),
+        scheduler_status: Default::default(),
+        insert_progress: Default::default(),
+        image_proxy: Default::default(),
+        display_timezone: chrono_tz::Tz::UTC,
+        reader_sanitization: Default::default(),
+        fetch_host_policy: Default::default(),
+        secret_key: Default::default(),
+    }
+}
+
+fn test_router(state: feeds::AppState) -> Router {
+    let api_routes = Router::new()
+        .route(
+            "/api/feeds",
+            axum::routing::get(feed_stats::list_feed_stats),
+        )
+        .route(
+            "/api/feeds/:id/articles",
+            axum::routing::get(feed_articles::list_feed_articles),
+        )
+        .route(
+            "/api/feeds/validate",
+            axum::routing::post(feed_validation::validate_feed),
+        )
+        .route(
+            "/api/maintenance/vacuum",
+            axum::routing::post(maintenance::vacuum_database).route_layer(
+                axum::middleware::from_fn_with_state(
+                    state.clone(),
+                    api_auth::require_api_token_middleware,
+                ),
+            ),
+        )
+        .route("/api/status", axum::routing::get(status::show_status))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            api_auth::api_token_middleware,
+        ));
 
-    let app = Router::new()
+    Router::new()
         .route("/health", axum::routing::get(health::check))
         .route("/feeds", axum::routing::get(feeds::list_feeds))
         .route("/feeds", axum::routing::post(feeds::create_feed))
         .route("/feeds/new", axum::routing::get(feeds::show_feed_form))
+        .route("/feeds/export.opml", axum::routing::get(feeds::export_opml))
+        .route("/feeds/import", axum::routing::post(feeds::import_feeds))
+        .route(
+            "/feeds/:id/opml",
+            axum::routing::get(feeds::export_feed_opml),
+        )
+        .route(
+            "/feeds/diagnostics",
+            axum::routing::get(feeds::show_feed_diagnostics),
+        )
         .route(
             "/feeds/:id",
             axum::routing::get(feeds::show_feed)
@@ -36,18 +145,106 @@ async fn setup_test_app() -> (TestServer, SqlitePool) {
                 .delete(feeds::delete_feed),
         )
         .route("/articles", axum::routing::get(articles::list_articles))
+        .route(
+            "/articles/digest",
+            axum::routing::get(articles::show_digest),
+        )
+        .route(
+            "/articles/read-later",
+            axum::routing::get(articles::list_read_later),
+        )
+        .route("/articles/:id", axum::routing::get(articles::show_article))
+        .route(
+            "/articles/:id/reader",
+            axum::routing::get(reader::show_reader_mode),
+        )
+        .route(
+            "/articles/:id/reader-content",
+            axum::routing::get(reader::get_reader_content),
+        )
+        .route("/img", axum::routing::get(image_proxy::proxy_image))
+        .route(
+            "/preferences/theme",
+            axum::routing::post(preferences::set_theme),
+        )
+        .route(
+            "/preferences/view-mode",
+            axum::routing::post(preferences::set_view_mode),
+        )
         .route(
             "/articles/:id/toggle-read",
             axum::routing::post(articles::toggle_read_status),
         )
+        .route(
+            "/articles/:id/toggle-starred-detail",
+            axum::routing::post(articles::toggle_starred_status_detail),
+        )
+        .route(
+            "/articles/:id/toggle-read-later",
+            axum::routing::post(articles::toggle_read_later_status),
+        )
         .route(
             "/articles/mark-all-read",
             axum::routing::post(articles::mark_all_read),
         )
-        .with_state(state);
-
-    let server = TestServer::new(app).unwrap();
-    (server, pool)
+        .route(
+            "/articles/search/mark-read",
+            axum::routing::post(articles::mark_search_read),
+        )
+        .route("/articles/bulk", axum::routing::post(articles::bulk_update))
+        .route(
+            "/articles/:id/share",
+            axum::routing::get(article_share::share_article),
+        )
+        .route(
+            "/articles/:id/share.txt",
+            axum::routing::get(article_share::share_article_text),
+        )
+        .route(
+            "/feeds/:id/refresh-opengraph",
+            axum::routing::post(feeds::refresh_opengraph),
+        )
+        .route(
+            "/articles/filter-modal",
+            axum::routing::get(groups::show_feed_filter_modal),
+        )
+        .route("/groups", axum::routing::post(groups::create_group))
+        .route("/groups/:id", axum::routing::put(groups::update_group))
+        .route(
+            "/groups/:id/feeds",
+            axum::routing::get(groups::show_group_feeds),
+        )
+        .route(
+            "/groups/unread-nav",
+            axum::routing::get(groups::show_group_unread_nav),
+        )
+        .route(
+            "/groups/ungrouped/assign-feeds",
+            axum::routing::put(groups::unassign_feeds),
+        )
+        .route(
+            "/groups/:id/assign-feeds",
+            axum::routing::put(groups::assign_feeds_to_group),
+        )
+        .route("/logs", axum::routing::get(logs::list_logs))
+        .route(
+            "/logs/export.csv",
+            axum::routing::get(logs::export_logs_csv),
+        )
+        .merge(api_routes)
+        .fallback(error_pages::not_found)
+        .layer(axum::middleware::from_fn(
+            error_pages::error_page_middleware,
+        ))
+        .route(
+            "/share/:token",
+            axum::routing::get(share::show_shared_group),
+        )
+        .route(
+            "/feed/:token/unread.atom",
+            axum::routing::get(unread_feed::show_unread_feed),
+        )
+        .with_state(state)
 }
 
 #[tokio::test]
@@ -61,6 +258,157 @@ async fn test_health_check() {
     assert!(body.contains("\"status\":\"ok\""));
 }
 
+#[tokio::test]
+async fn test_unknown_path_renders_error_page() {
+    let (server, _pool) = setup_test_app().await;
+
+    let response = server.get("/nonexistent").await;
+
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+    let content_type = response.header(header::CONTENT_TYPE);
+    assert!(content_type.to_str().unwrap().contains("text/html"));
+    let body = response.text();
+    assert!(body.contains("Not Found"));
+    assert!(body.contains("looking for"));
+
+    let response = server.get("/api/nonexistent").await;
+
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["status"], 404);
+}
+
+#[tokio::test]
+async fn test_image_proxy_refuses_internal_urls() {
+    let (server, signer) = setup_image_proxy_app().await;
+
+    // Signed like the pages of this instance, so the SSRF checks apply
+    for url in [
+        "http://127.0.0.1/pixel.png",
+        "http://169.254.169.254/latest/meta-data",
+    ] {
+        let proxied = signer.proxied_src(url).unwrap();
+        let response = server.get(&proxied).await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+        assert!(response.text().contains("internal or private networks"));
+    }
+    let response = server.get("/img?url=file%3A%2F%2F%2Fetc%2Fpasswd").await;
+    assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+
+    let response = server.get("/img").await;
+    assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_image_proxy_refuses_unsigned_urls() {
+    let (server, signer) = setup_image_proxy_app().await;
+
+    let proxied = signer.proxied_src("https://cdn.example/a.png").unwrap();
+    let (unsigned, _) = proxied.split_once("&sig=").unwrap();
+    let other = signer.proxied_src("https://cdn.example/b.png").unwrap();
+    let (_, other_sig) = other.split_once("&sig=").unwrap();
+
+    for url in [
+        unsigned.to_string(),
+        format!("{}&sig=", unsigned),
+        format!("{}&sig={}", unsigned, other_sig),
+    ] {
+        let response = server.get(&url).await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN, "{}", url);
+        assert!(response.text().contains("Only images of pages shown"));
+    }
+
+    // Turned off, the proxy serves nothing, signed or not
+    let (server, _pool) = setup_test_app().await;
+    let response = server.get(&proxied).await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_theme_preference_round_trips() {
+    let (server, pool) = setup_test_app().await;
+    assert_eq!(
+        repository::get_preferences(&pool).await.unwrap().theme,
+        Theme::Auto
+    );
+
+    let response = server
+        .post("/preferences/theme")
+        .form(&[("theme", "dark")])
+        .await;
+    assert_eq!(response.status_code(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        repository::get_preferences(&pool).await.unwrap().theme,
+        Theme::Dark
+    );
+
+    // The first render already uses the stored theme
+    let body = server.get("/articles").await.text();
+    assert!(body.contains(r#"data-theme="dark""#));
+    assert!(body.contains(r#"<option value="dark" selected>"#));
+
+    // Another database keeps its own preference
+    let (other_server, _other_pool) = setup_test_app().await;
+    let body = other_server.get("/articles").await.text();
+    assert!(body.contains(r#"data-theme="auto""#));
+
+    let response = server
+        .post("/preferences/theme")
+        .form(&[("theme", "sepia")])
+        .await;
+    assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        repository::get_preferences(&pool).await.unwrap().theme,
+        Theme::Dark
+    );
+}
+
+#[tokio::test]
+async fn test_stored_view_mode_applies_without_view_param() {
+    let (server, _pool) = setup_test_app().await;
+
+    let body = server.get("/articles").await.text();
+    assert!(body.contains(r#"data-view-mode="cards""#));
+
+    let response = server
+        .post("/preferences/view-mode")
+        .form(&[("view_mode", "compact")])
+        .await;
+    assert_eq!(response.status_code(), StatusCode::NO_CONTENT);
+
+    let body = server.get("/articles").await.text();
+    assert!(body.contains(r#"data-view-mode="compact""#));
+    assert!(body.contains(r#"id="articles-cards" class="hidden "#));
+
+    // An explicit view still wins
+    let body = server.get("/articles?view=cards").await.text();
+    assert!(body.contains(r#"data-view-mode="cards""#));
+}
+
+#[tokio::test]
+async fn test_reader_mode_throttles_beyond_concurrency_limit() {
+    let pool = test_pool().await;
+    let state = feeds::AppState {
+        reader_limiter: ReaderLimiter::new(1),
+        ..test_state(&pool, None)
+    };
+    let limiter = state.reader_limiter.clone();
+    let server = TestServer::new(test_router(state)).unwrap();
+
+    // The only slot is held by an extraction still in progress
+    let in_flight = limiter.try_acquire().unwrap();
+
+    for path in ["/articles/1/reader", "/articles/1/reader-content"] {
+        let response = server.get(path).await;
+        assert_eq!(response.status_code(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.header(header::RETRY_AFTER), "5");
+    }
+
+    drop(in_flight);
+    let response = server.get("/articles/1/reader").await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+}
+
 #[tokio::test]
 async fn test_list_feeds_empty() {
     let (server, _pool) = setup_test_app().await;
@@ -72,6 +420,20 @@ async fn test_list_feeds_empty() {
     assert!(body.contains("FluxFeed"));
 }
 
+#[tokio::test]
+async fn test_list_feeds_search() {
+    let (server, pool) = setup_test_app().await;
+    create_grouped_article(&pool, "Rust", "Release").await;
+    create_grouped_article(&pool, "Gardening", "Tomatoes").await;
+
+    let response = server.get("/feeds").add_query_param("q", "rust").await;
+
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body = response.text();
+    assert!(body.contains("Rust Feed"));
+    assert!(!body.contains("Gardening Feed"));
+}
+
 #[tokio::test]
 async fn test_create_feed_invalid_url() {
     let (server, _pool) = setup_test_app().await;
@@ -85,6 +447,34 @@ async fn test_create_feed_invalid_url() {
     assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
 }
 
+#[tokio::test]
+async fn test_duplicate_feed_links_to_existing_feed() {
+    let (server, pool) = setup_test_app().await;
+    let existing = repository::create_feed(
+        &pool,
+        CreateFeed {
+            url: "https://93.184.215.14/feed.xml".to_string(),
+            title: "Existing".to_string(),
+            description: None,
+            color: None,
+            fetch_frequency: None,
+            ignore_pattern: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    let response = server
+        .post("/feeds?defer=true")
+        .form(&[("url", "https://93.184.215.14/feed.xml"), ("title", "")])
+        .await;
+
+    assert_eq!(response.status_code(), StatusCode::CONFLICT);
+    let body = response.text();
+    assert!(body.contains("already in your collection"));
+    assert!(body.contains(&format!(r#"href="/feeds/{}""#, existing.id)));
+}
+
 #[tokio::test]
 async fn test_show_feed_form() {
     let (server, _pool) = setup_test_app().await;
@@ -117,6 +507,76 @@ async fn test_list_articles_empty() {
     assert!(body.contains("Articles") || body.contains("article"));
 }
 
+#[tokio::test]
+async fn test_read_later_toggle_and_view() {
+    let (server, pool) = setup_test_app().await;
+    create_grouped_article(&pool, "Later", "Saved Article").await;
+    create_grouped_article(&pool, "Now", "Other Article").await;
+
+    let response = server.post("/articles/1/toggle-read-later").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    assert!(response.text().contains("Remove from read later"));
+
+    let response = server.get("/articles/read-later").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body = response.text();
+    assert!(body.contains("Saved Article"));
+    assert!(!body.contains("Other Article"));
+
+    // Starring is a separate flag
+    let article = repository::get_article_by_id(&pool, 1)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(article.is_read_later);
+    assert!(!article.is_starred);
+
+    let response = server.post("/articles/1/toggle-read-later").await;
+    assert!(response.text().contains("title=\"Read later\""));
+    let body = server.get("/articles/read-later").await.text();
+    assert!(body.contains("Nothing saved to read later"));
+
+    let response = server.post("/articles/9999/toggle-read-later").await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_bulk_article_actions() {
+    let (server, pool) = setup_test_app().await;
+    create_grouped_article(&pool, "One", "First Article").await;
+    create_grouped_article(&pool, "Two", "Second Article").await;
+
+    let bulk = |ids: &'static str, action: &'static str| {
+        server
+            .post("/articles/bulk")
+            .form(&[("article_ids", ids), ("action", action)])
+    };
+    let affected = |response: axum_test::TestResponse| {
+        assert_eq!(response.status_code(), StatusCode::OK);
+        response.json::<serde_json::Value>()["affected"]
+            .as_u64()
+            .unwrap()
+    };
+
+    assert_eq!(affected(bulk("1,2", "read").await), 2);
+    assert_eq!(repository::get_total_unread_count(&pool).await.unwrap(), 0);
+    assert_eq!(affected(bulk("1,2", "read").await), 0);
+
+    assert_eq!(affected(bulk("2", "unread").await), 1);
+    assert_eq!(repository::get_total_unread_count(&pool).await.unwrap(), 1);
+
+    assert_eq!(affected(bulk("1,2", "star").await), 2);
+    assert_eq!(affected(bulk("1", "unstar").await), 1);
+    let counts = repository::get_article_counts(&pool).await.unwrap();
+    assert_eq!(counts.starred, 1);
+
+    // An empty selection changes nothing
+    assert_eq!(affected(bulk("", "read").await), 0);
+
+    let response = bulk("1", "archive").await;
+    assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn test_toggle_read_nonexistent_article() {
     let (server, _pool) = setup_test_app().await;
@@ -176,3 +636,1188 @@ async fn test_update_feed_form_deserialization() {
         "Form deserialization failed with 422"
     );
 }
+
+#[tokio::test]
+async fn test_update_feed_reassigns_group() {
+    let (server, pool) = setup_test_app().await;
+    let old_group = create_grouped_article(&pool, "Old", "Article").await;
+    let new_group = repository::create_group(&pool, "New", None).await.unwrap();
+
+    let update = |group_id: String| {
+        let server = &server;
+        async move {
+            server
+                .post("/feeds/1")
+                .form(&[
+                    ("title", "Grouped Feed"),
+                    ("url", "https://example.com/grouped.xml"),
+                    ("color", "#3b82f6"),
+                    ("fetch_frequency", "adaptive"),
+                    ("group_id", &group_id),
+                ])
+                .await
+        }
+    };
+
+    let response = update(new_group.id.to_string()).await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let feed = repository::get_feed_by_id(&pool, 1).await.unwrap().unwrap();
+    assert_eq!(feed.group_id, Some(new_group.id));
+    assert_ne!(feed.group_id, Some(old_group));
+
+    let response = update("999".to_string()).await;
+    assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+    let response = update(String::new()).await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let feed = repository::get_feed_by_id(&pool, 1).await.unwrap().unwrap();
+    assert_eq!(feed.group_id, None);
+}
+
+#[tokio::test]
+async fn test_update_feed_saves_all_settings_or_none() {
+    let (server, pool) = setup_test_app().await;
+    create_grouped_article(&pool, "Group", "Article").await;
+
+    // The unknown tag fails after the properties are written
+    let response = server
+        .post("/feeds/1")
+        .form(&[
+            ("title", "Renamed Feed"),
+            ("url", "https://example.com/renamed.xml"),
+            ("color", "#3b82f6"),
+            ("fetch_frequency", "adaptive"),
+            ("tag_ids", "999"),
+        ])
+        .await;
+
+    assert_eq!(response.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    let feed = repository::get_feed_by_id(&pool, 1).await.unwrap().unwrap();
+    assert_eq!(feed.title, "Group Feed");
+    assert_eq!(feed.url, "https://example.com/1.xml");
+}
+
+/// Create a feed in a new group with a single article
+async fn create_grouped_article(pool: &SqlitePool, group: &str, title: &str) -> i64 {
+    let group = repository::create_group(pool, group, None).await.unwrap();
+    let feed = repository::create_feed(
+        pool,
+        CreateFeed {
+            url: format!("https://example.com/{}.xml", group.id),
+            title: format!("{} Feed", group.name),
+            description: None,
+            color: None,
+            fetch_frequency: None,
+            ignore_pattern: None,
+        },
+    )
+    .await
+    .unwrap();
+    repository::update_feed_group(pool, feed.id, Some(group.id))
+        .await
+        .unwrap();
+    repository::insert_article_if_new(
+        pool,
+        NewArticle {
+            feed_id: feed.id,
+            guid: title.to_string(),
+            title: title.to_string(),
+            url: None,
+            content: None,
+            summary: None,
+            author: None,
+            published_at: None,
+            updated_at_source: None,
+            og_image: None,
+            og_description: None,
+            og_site_name: None,
+            language: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    group.id
+}
+
+#[tokio::test]
+async fn test_filter_modal_keeps_collapsed_groups_collapsed() {
+    let (server, pool) = setup_test_app().await;
+    let rust = create_grouped_article(&pool, "Rust", "Release").await;
+    create_grouped_article(&pool, "Gardening", "Tomatoes").await;
+    repository::toggle_group_collapsed(&pool, rust)
+        .await
+        .unwrap();
+
+    let response = server.get("/articles/filter-modal").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let html = response.text();
+    // Only the collapsed group's feeds start out hidden
+    let group_of = |feed: &str| {
+        html.split(r#"<div class="group-container">"#)
+            .find(|group| group.contains(feed))
+            .unwrap()
+    };
+    assert!(group_of("Rust Feed").contains(r#"class="group-children hidden""#));
+    assert!(!group_of("Gardening Feed").contains(r#"class="group-children hidden""#));
+}
+
+#[tokio::test]
+async fn test_shared_group_is_read_only_and_scoped() {
+    let (server, pool) = setup_test_app().await;
+    let group_id = create_grouped_article(&pool, "Shared", "Shared Article").await;
+    create_grouped_article(&pool, "Private", "Private Article").await;
+    repository::create_share_token(&pool, group_id, "secret-token")
+        .await
+        .unwrap();
+
+    let response = server.get("/share/secret-token").await;
+
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body = response.text();
+    assert!(body.contains("Shared Article"));
+    assert!(!body.contains("Private Article"));
+    assert!(!body.contains("hx-post"));
+    assert!(!body.contains("href=\"/articles"));
+}
+
+#[tokio::test]
+async fn test_shared_group_unknown_or_revoked_token() {
+    let (server, pool) = setup_test_app().await;
+    let group_id = create_grouped_article(&pool, "Shared", "Shared Article").await;
+    repository::create_share_token(&pool, group_id, "secret-token")
+        .await
+        .unwrap();
+
+    let response = server.get("/share/not-a-token").await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+
+    let share = repository::get_share_token(&pool, "secret-token")
+        .await
+        .unwrap()
+        .unwrap();
+    repository::delete_share_token(&pool, group_id, share.id)
+        .await
+        .unwrap();
+
+    let response = server.get("/share/secret-token").await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_unread_feed_lists_only_unread_articles() {
+    let (server, pool) = setup_test_app().await;
+    create_grouped_article(&pool, "News", "Unread Article").await;
+    create_grouped_article(&pool, "Blog", "Read Article").await;
+    sqlx::query("UPDATE articles SET is_read = 1 WHERE title = 'Read Article'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    repository::create_unread_feed_token(&pool, "unread-token")
+        .await
+        .unwrap();
+
+    let response = server.get("/feed/unread-token/unread.atom").await;
+
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let content_type = response.header(header::CONTENT_TYPE);
+    assert!(content_type
+        .to_str()
+        .unwrap()
+        .starts_with("application/atom+xml"));
+    let body = response.text();
+    assert!(body.contains("<title>Unread Article</title>"));
+    assert!(!body.contains("Read Article</title>"));
+
+    let response = server.get("/feed/not-a-token/unread.atom").await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_feed_stats_json() {
+    let (server, pool) = setup_test_app().await;
+    let group_id = create_grouped_article(&pool, "Stats", "Stats Article").await;
+    let feed_id = repository::get_feed_ids_in_group_recursive(&pool, group_id)
+        .await
+        .unwrap()[0];
+    repository::insert_log(
+        &pool,
+        feed_id,
+        "success",
+        None,
+        None,
+        None,
+        Some(10),
+        Some(1),
+    )
+    .await
+    .unwrap();
+
+    let response = server.get("/api/feeds").await;
+
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body: serde_json::Value = response.json();
+    let feed = &body.as_array().unwrap()[0];
+    assert_eq!(feed["id"], feed_id);
+    assert_eq!(feed["total_articles"], 1);
+    assert_eq!(feed["unread"], 1);
+    assert_eq!(feed["last_status"], "success");
+    assert!(feed["last_fetched_at"].is_null());
+}
+
+#[tokio::test]
+async fn test_feed_stats_requires_configured_token() {
+    let (server, _pool) = setup_test_app_with_token(Some("secret")).await;
+
+    let response = server.get("/api/feeds").await;
+    assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+    assert_eq!(response.header(header::WWW_AUTHENTICATE), "Bearer");
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["error"], "Unauthorized");
+    assert_eq!(body["status"], 401);
+
+    let response = server.get("/api/feeds").authorization_bearer("wrong").await;
+    assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+
+    let response = server
+        .get("/api/feeds")
+        .authorization_bearer("secret")
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_csrf_cookie_uses_configured_same_site() {
+    let pool = test_pool().await;
+
+    for (same_site, attribute) in [
+        (CookieSameSite::Strict, "SameSite=Strict"),
+        (CookieSameSite::Lax, "SameSite=Lax"),
+    ] {
+        let router = test_router(test_state(&pool, None)).layer(
+            axum::middleware::from_fn_with_state(same_site, csrf::csrf_middleware),
+        );
+        let server = TestServer::new(router).unwrap();
+
+        let response = server.get("/logs").await;
+        let cookie = response.header(header::SET_COOKIE);
+        assert!(cookie.to_str().unwrap().contains(attribute));
+    }
+}
+
+#[tokio::test]
+async fn test_api_post_with_bearer_token_skips_csrf() {
+    let (server, _pool) = setup_csrf_app_with_token(Some("secret")).await;
+
+    // Forms and HTMX requests still need the CSRF token
+    let response = server.post("/feeds").await;
+    assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+    let response = server.post("/api/feeds/validate").await;
+    assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+
+    // A wrong token gets past CSRF, but not past the token check
+    let response = server
+        .post("/api/feeds/validate")
+        .authorization_bearer("wrong")
+        .json(&serde_json::json!({ "url": "ftp://example.com/feed.xml" }))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_status_reports_scheduler_behind_token() {
+    let (server, _pool) = setup_test_app_with_token(Some("secret")).await;
+
+    let response = server.get("/api/status").await;
+    assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+
+    let response = server
+        .get("/api/status")
+        .authorization_bearer("secret")
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body: serde_json::Value = response.json();
+    let scheduler = &body["scheduler"];
+    // The test app never starts the scheduler
+    assert_eq!(scheduler["enabled"], false);
+    assert_eq!(scheduler["running"], false);
+    assert_eq!(scheduler["interval_minutes"], 5);
+    assert_eq!(scheduler["in_flight_fetches"], 0);
+    assert!(scheduler["last_cycle"].is_null());
+}
+
+#[tokio::test]
+async fn test_opml_export_round_trips_settings() {
+    let (server, pool) = setup_test_app().await;
+    // A literal public address passes SSRF validation without DNS
+    let feed = repository::create_feed(
+        &pool,
+        CreateFeed {
+            url: "https://93.184.215.14/feed.xml".to_string(),
+            title: "Example".to_string(),
+            description: None,
+            color: Some("#FF0000".to_string()),
+            fetch_frequency: Some(("6".to_string(), 360)),
+            ignore_pattern: Some("^Sponsored".to_string()),
+        },
+    )
+    .await
+    .unwrap();
+    // The feed of saved pages is not a subscription to export
+    repository::get_or_create_saved_feed(&pool).await.unwrap();
+
+    let response = server.get("/feeds/export.opml").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    assert_eq!(
+        response.header(header::CONTENT_TYPE),
+        "text/x-opml; charset=utf-8"
+    );
+    let opml = response.text();
+    assert!(opml.contains(r#"xmlUrl="https://93.184.215.14/feed.xml""#));
+    assert!(!opml.contains(repository::SAVED_FEED_URL));
+
+    repository::delete_feed(&pool, feed.id).await.unwrap();
+    let response = server
+        .post("/feeds/import")
+        .form(&[("feeds", opml.as_str())])
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+
+    // The import runs in the background; the feed is stored together with
+    // its settings, so it never shows up with the defaults
+    let mut imported = None;
+    for _ in 0..50 {
+        imported = repository::list_feeds(&pool).await.unwrap().pop();
+        if imported.is_some() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    let imported = imported.expect("feed was not imported");
+    assert_eq!(imported.url, "https://93.184.215.14/feed.xml");
+    assert_eq!(imported.fetch_frequency, "6");
+    assert_eq!(imported.fetch_interval_minutes, 360);
+    assert_eq!(imported.color, "#FF0000");
+    assert_eq!(imported.ignore_pattern.as_deref(), Some("^Sponsored"));
+}
+
+#[tokio::test]
+async fn test_single_feed_opml_has_one_outline() {
+    let (server, pool) = setup_test_app().await;
+    let group_id = create_grouped_article(&pool, "Shared", "Article").await;
+    create_grouped_article(&pool, "Private", "Other").await;
+    let feed_id = repository::get_feed_ids_in_group_recursive(&pool, group_id)
+        .await
+        .unwrap()[0];
+
+    let response = server.get(&format!("/feeds/{}/opml", feed_id)).await;
+
+    assert_eq!(response.status_code(), StatusCode::OK);
+    assert_eq!(
+        response.header(header::CONTENT_DISPOSITION),
+        format!("attachment; filename=\"fluxfeed-feed-{}.opml\"", feed_id).as_str()
+    );
+    let opml = response.text();
+    assert_eq!(opml.matches("<outline").count(), 1);
+    assert!(opml.contains(&format!(r#"xmlUrl="https://example.com/{}.xml""#, group_id)));
+    assert!(!opml.contains("fluxfeed:"));
+
+    let response = server.get("/feeds/999/opml").await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_feed_articles_json() {
+    let (server, pool) = setup_test_app_with_token(Some("secret")).await;
+    let group_id = create_grouped_article(&pool, "Json", "First").await;
+    let feed_id = repository::get_feed_ids_in_group_recursive(&pool, group_id)
+        .await
+        .unwrap()[0];
+    create_grouped_article(&pool, "Other", "Elsewhere").await;
+    let second = repository::insert_article_if_new(
+        &pool,
+        NewArticle {
+            feed_id,
+            guid: "second".to_string(),
+            title: "Second".to_string(),
+            url: Some("https://example.com/second".to_string()),
+            content: None,
+            summary: None,
+            author: None,
+            published_at: Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).single(),
+            updated_at_source: None,
+            og_image: None,
+            og_description: None,
+            og_site_name: None,
+            language: None,
+        },
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    repository::update_article_read_status(&pool, second.id, true)
+        .await
+        .unwrap();
+
+    let url = format!("/api/feeds/{}/articles", feed_id);
+    let response = server.get(&url).await;
+    assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+
+    let response = server.get(&url).authorization_bearer("secret").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body: serde_json::Value = response.json();
+    let titles: Vec<_> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| a["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(titles, vec!["Second", "First"]);
+    assert_eq!(body[0]["feed_title"], "Json Feed");
+    assert_eq!(body[0]["url"], "https://example.com/second");
+
+    let response = server
+        .get(&format!("{}?unread=true", url))
+        .authorization_bearer("secret")
+        .await;
+    let body: serde_json::Value = response.json();
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["title"], "First");
+
+    let response = server
+        .get(&format!("{}?limit=1&offset=1", url))
+        .authorization_bearer("secret")
+        .await;
+    let body: serde_json::Value = response.json();
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["title"], "First");
+
+    let response = server
+        .get("/api/feeds/999/articles")
+        .authorization_bearer("secret")
+        .await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["error"], "Not Found");
+    assert_eq!(body["status"], 404);
+    assert_eq!(
+        body["message"],
+        "The feed you're looking for doesn't exist."
+    );
+}
+
+#[tokio::test]
+async fn test_feed_diagnostics_buckets() {
+    let (server, pool) = setup_test_app().await;
+    let mut feed_ids = Vec::new();
+    for title in ["Broken", "Quiet", "Working"] {
+        let feed = repository::create_feed(
+            &pool,
+            CreateFeed {
+                url: format!("https://example.com/{}.xml", title),
+                title: title.to_string(),
+                description: None,
+                color: None,
+                fetch_frequency: None,
+                ignore_pattern: None,
+            },
+        )
+        .await
+        .unwrap();
+        feed_ids.push(feed.id);
+    }
+    repository::insert_log(
+        &pool,
+        feed_ids[0],
+        "error",
+        Some(500),
+        Some("upstream exploded"),
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    sqlx::query("UPDATE feeds SET last_fetched_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(feed_ids[2])
+        .execute(&pool)
+        .await
+        .unwrap();
+    repository::insert_log(&pool, feed_ids[2], "success", None, None, None, None, None)
+        .await
+        .unwrap();
+
+    let response = server.get("/feeds/diagnostics").await;
+
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body = response.text();
+    assert!(body.contains("upstream exploded"));
+    let position = |text: &str| body.find(text).unwrap();
+    let (failing, pending, healthy) = (
+        position("id=\"failing\""),
+        position("id=\"pending\""),
+        position("id=\"healthy\""),
+    );
+    assert!((failing..pending).contains(&position(">Broken<")));
+    assert!((pending..healthy).contains(&position(">Quiet<")));
+    assert!(position(">Working<") > healthy);
+}
+
+#[tokio::test]
+async fn test_api_errors_are_json() {
+    let (server, _pool) = setup_test_app().await;
+
+    let response = server.get("/api/does-not-exist").await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["error"], "Not Found");
+    assert_eq!(body["status"], 404);
+    assert!(body["message"].is_string());
+
+    // Handler errors keep their specific message when JSON is accepted
+    let response = server
+        .get("/feeds/999")
+        .add_header(header::ACCEPT, HeaderValue::from_static("application/json"))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["error"], "Not Found");
+    assert_eq!(
+        body["message"],
+        "The feed you're looking for doesn't exist."
+    );
+    assert_eq!(body["status"], 404);
+
+    // Browsers still get the HTML error page
+    let response = server.get("/feeds/999").await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+    assert!(response.text().contains("<html"));
+}
+
+#[tokio::test]
+async fn test_mark_all_read_redirects_with_filters() {
+    let (server, _pool) = setup_test_app().await;
+
+    let response = server
+        .post("/articles/mark-all-read?feed_ids=1,2&is_read=false&q=rust+news")
+        .await;
+
+    assert_eq!(response.status_code(), StatusCode::OK);
+    assert_eq!(
+        response.header("HX-Redirect"),
+        "/articles?feed_ids=1,2&is_read=false&q=rust+news"
+    );
+}
+
+#[tokio::test]
+async fn test_mark_all_read_in_group_view_leaves_other_groups_unread() {
+    let (server, pool) = setup_test_app().await;
+    let first = create_grouped_article(&pool, "One", "In group one").await;
+    create_grouped_article(&pool, "Two", "In group two").await;
+
+    let response = server
+        .post(&format!("/articles/mark-all-read?group_ids={}", first))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    assert_eq!(response.text(), "Marked 1 articles as read");
+
+    assert_eq!(
+        response.header("HX-Redirect"),
+        format!("/articles?group_ids={}", first)
+    );
+
+    let read: Vec<(String, bool)> =
+        sqlx::query_as("SELECT title, is_read FROM articles ORDER BY title")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        read,
+        vec![
+            ("In group one".to_string(), true),
+            ("In group two".to_string(), false),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_mark_search_read_marks_only_matches() {
+    let (server, pool) = setup_test_app().await;
+    create_grouped_article(&pool, "One", "Rust Release").await;
+    create_grouped_article(&pool, "Two", "Rust Tips").await;
+    create_grouped_article(&pool, "Three", "Tomatoes").await;
+
+    // A query without terms, feed or dates would match every article
+    for query in ["", "?q=", "?q=is:unread"] {
+        let response = server
+            .post(&format!("/articles/search/mark-read{}", query))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+    }
+
+    let response = server.post("/articles/search/mark-read?q=rust").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    assert_eq!(response.header("HX-Redirect"), "/articles/search?q=rust");
+
+    let read: Vec<(String, bool)> =
+        sqlx::query_as("SELECT title, is_read FROM articles ORDER BY title")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        read,
+        vec![
+            ("Rust Release".to_string(), true),
+            ("Rust Tips".to_string(), true),
+            ("Tomatoes".to_string(), false),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_show_feed_negotiates_atom() {
+    let (server, pool) = setup_test_app().await;
+    let group_id = create_grouped_article(&pool, "Atom", "Atom Article").await;
+    let feed_id = repository::get_feed_ids_in_group_recursive(&pool, group_id)
+        .await
+        .unwrap()[0];
+
+    let response = server
+        .get(&format!("/feeds/{}", feed_id))
+        .add_header(
+            header::ACCEPT,
+            HeaderValue::from_static("application/atom+xml"),
+        )
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    assert_eq!(
+        response.header(header::CONTENT_TYPE),
+        "application/atom+xml; charset=utf-8"
+    );
+    assert_eq!(response.header(header::VARY), "Accept");
+    assert!(response.text().contains("<title>Atom Article</title>"));
+
+    let response = server.get(&format!("/feeds/{}.atom", feed_id)).await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    assert!(response.maybe_header(header::VARY).is_none());
+    assert!(response.text().contains("<entry>"));
+    let etag = response.header(header::ETAG);
+    assert!(response.maybe_header(header::LAST_MODIFIED).is_some());
+
+    // An unchanged export is answered with 304 and no body
+    let response = server
+        .get(&format!("/feeds/{}.atom", feed_id))
+        .add_header(header::IF_NONE_MATCH, etag.clone())
+        .await;
+    assert_eq!(response.status_code(), StatusCode::NOT_MODIFIED);
+    assert!(response.text().is_empty());
+
+    // A new article changes the tag
+    repository::insert_article_if_new(
+        &pool,
+        NewArticle {
+            feed_id,
+            guid: "second".to_string(),
+            title: "Second Article".to_string(),
+            url: None,
+            content: None,
+            summary: None,
+            author: None,
+            published_at: None,
+            updated_at_source: None,
+            og_image: None,
+            og_description: None,
+            og_site_name: None,
+            language: None,
+        },
+    )
+    .await
+    .unwrap();
+    let response = server
+        .get(&format!("/feeds/{}.atom", feed_id))
+        .add_header(header::IF_NONE_MATCH, etag)
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    assert!(response.text().contains("Second Article"));
+
+    let response = server
+        .get(&format!("/feeds/{}", feed_id))
+        .add_header(header::ACCEPT, HeaderValue::from_static("text/html"))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    assert_eq!(response.header(header::VARY), "Accept");
+    assert!(response.text().contains("<!DOCTYPE html>"));
+}
+
+#[tokio::test]
+async fn test_share_article_payload() {
+    let (server, pool) = setup_test_app().await;
+    let feed = repository::create_feed(
+        &pool,
+        CreateFeed {
+            url: "https://example.com/feed.xml".to_string(),
+            title: "Example Feed".to_string(),
+            description: None,
+            color: None,
+            fetch_frequency: None,
+            ignore_pattern: None,
+        },
+    )
+    .await
+    .unwrap();
+    let article = repository::insert_article_if_new(
+        &pool,
+        NewArticle {
+            feed_id: feed.id,
+            guid: "shared".to_string(),
+            title: "Shared Post".to_string(),
+            url: Some("https://example.com/shared".to_string()),
+            content: None,
+            summary: Some("<p>A short summary</p>".to_string()),
+            author: None,
+            published_at: None,
+            updated_at_source: None,
+            og_image: None,
+            og_description: None,
+            og_site_name: None,
+            language: None,
+        },
+    )
+    .await
+    .unwrap()
+    .unwrap();
+
+    let response = server.get(&format!("/articles/{}/share", article.id)).await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["title"], "Shared Post");
+    assert_eq!(body["url"], "https://example.com/shared");
+    assert_eq!(body["excerpt"], "A short summary");
+    assert_eq!(body["site_name"], "Example Feed");
+
+    let response = server
+        .get(&format!("/articles/{}/share.txt", article.id))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    assert_eq!(response.text(), "Shared Post — https://example.com/shared");
+
+    let response = server.get("/articles/9999/share").await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_share_article_without_url() {
+    let (server, pool) = setup_test_app().await;
+    create_grouped_article(&pool, "Group", "No Link").await;
+
+    let response = server.get("/articles/1/share").await;
+
+    assert_eq!(response.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_article_permalink_page() {
+    let (server, pool) = setup_test_app().await;
+    create_grouped_article(&pool, "Permalinks", "Linked Article").await;
+
+    let response = server.get("/articles/1").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body = response.text();
+    assert!(body.contains("Linked Article"));
+    assert!(body.contains("Permalinks Feed"));
+    assert!(body.contains("/articles/1/reader"));
+
+    let response = server.post("/articles/1/toggle-starred-detail").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    assert!(response.text().contains("Unstar article"));
+
+    let response = server.get("/articles/9999").await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_group_unread_nav_refreshes_after_read() {
+    let (server, pool) = setup_test_app().await;
+    create_grouped_article(&pool, "Unread Group", "Unread Article").await;
+    create_grouped_article(&pool, "Empty Group", "Read Article").await;
+    repository::update_article_read_status(&pool, 2, true)
+        .await
+        .unwrap();
+
+    let response = server.get("/groups/unread-nav").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body = response.text();
+    assert!(body.contains("Unread Group"));
+    assert!(!body.contains("Empty Group"));
+
+    let response = server.post("/articles/1/toggle-read").await;
+    assert_eq!(response.header("HX-Trigger"), "articlesRead");
+
+    let response = server.get("/groups/unread-nav").await;
+    assert!(!response.text().contains("Unread Group"));
+}
+
+#[tokio::test]
+async fn test_log_times_use_the_display_timezone() {
+    let pool = test_pool().await;
+    let group_id = create_grouped_article(&pool, "Logs", "Logged Article").await;
+    let feed_id = repository::get_feed_ids_in_group_recursive(&pool, group_id)
+        .await
+        .unwrap()[0];
+    repository::insert_log(&pool, feed_id, "success", Some(200), None, None, None, None)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE logs SET fetched_at = '2026-01-15T12:00:00Z'")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    for (timezone, shown) in [
+        (chrono_tz::Tz::UTC, "Jan 15, 2026 12:00:00"),
+        (chrono_tz::Asia::Tokyo, "Jan 15, 2026 21:00:00"),
+    ] {
+        let state = feeds::AppState {
+            display_timezone: timezone,
+            ..test_state(&pool, None)
+        };
+        let server = TestServer::new(test_router(state)).unwrap();
+
+        let response = server.get("/logs").await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.text().contains(shown), "{}", timezone);
+    }
+}
+
+#[tokio::test]
+async fn test_export_logs_csv_filters_and_escapes() {
+    let (server, pool) = setup_test_app().await;
+    let group_id = create_grouped_article(&pool, "Logs", "Logged Article").await;
+    let feed_id = repository::get_feed_ids_in_group_recursive(&pool, group_id)
+        .await
+        .unwrap()[0];
+    repository::insert_log(&pool, feed_id, "success", Some(200), None, None, None, None)
+        .await
+        .unwrap();
+    repository::insert_log(
+        &pool,
+        feed_id,
+        "error",
+        Some(500),
+        Some("Upstream failed, \"badly\"\nretry later"),
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let response = server.get("/logs/export.csv?log_type=error").await;
+
+    assert_eq!(response.status_code(), StatusCode::OK);
+    assert_eq!(
+        response.header(header::CONTENT_TYPE),
+        "text/csv; charset=utf-8"
+    );
+    let body = response.text();
+    let mut lines = body.split("\r\n");
+    assert_eq!(
+        lines.next().unwrap(),
+        "fetched_at,feed_title,feed_url,log_type,status_code,error_message,retry_after"
+    );
+    let row = lines.next().unwrap();
+    assert!(row.contains(",Logs Feed,https://example.com/"));
+    assert!(row.ends_with(",error,500,\"Upstream failed, \"\"badly\"\"\nretry later\","));
+    assert_eq!(lines.next(), Some(""));
+}
+
+#[tokio::test]
+async fn test_group_feeds_include_nested_groups() {
+    let (server, pool) = setup_test_app().await;
+    let parent = create_grouped_article(&pool, "Parent", "Parent Article").await;
+    create_grouped_article(&pool, "Other", "Other Article").await;
+    let child = repository::create_group(&pool, "Child", Some(parent))
+        .await
+        .unwrap();
+    let feed = repository::create_feed(
+        &pool,
+        CreateFeed {
+            url: "https://example.com/child.xml".to_string(),
+            title: "Child Feed".to_string(),
+            description: None,
+            color: None,
+            fetch_frequency: None,
+            ignore_pattern: None,
+        },
+    )
+    .await
+    .unwrap();
+    repository::update_feed_group(&pool, feed.id, Some(child.id))
+        .await
+        .unwrap();
+
+    let response = server.get(&format!("/groups/{}/feeds", parent)).await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body = response.text();
+    assert!(body.contains("Parent Feed"));
+    assert!(body.contains("Child Feed"));
+    assert!(!body.contains("Other Feed"));
+
+    let response = server.get(&format!("/groups/{}/feeds", child.id)).await;
+    let body = response.text();
+    assert!(body.contains("Child Feed"));
+    assert!(!body.contains("Parent Feed"));
+
+    let response = server.get("/groups/999/feeds").await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_assign_and_unassign_multiple_feeds() {
+    let (server, pool) = setup_test_app().await;
+    let first_group = create_grouped_article(&pool, "First", "First Article").await;
+    let second_group = create_grouped_article(&pool, "Second", "Second Article").await;
+    let target = repository::create_group(&pool, "Target", None)
+        .await
+        .unwrap();
+
+    let response = server
+        .put(&format!("/groups/{}/assign-feeds", target.id))
+        .form(&[("feed_ids", "1,2")])
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let moved = repository::get_feed_ids_in_group_recursive(&pool, target.id)
+        .await
+        .unwrap();
+    assert_eq!(moved.len(), 2);
+    for group_id in [first_group, second_group] {
+        assert!(repository::get_feed_ids_in_group_recursive(&pool, group_id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    let response = server
+        .put("/groups/ungrouped/assign-feeds")
+        .form(&[("feed_ids", "1")])
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let remaining = repository::get_feed_ids_in_group_recursive(&pool, target.id)
+        .await
+        .unwrap();
+    assert_eq!(remaining, vec![2]);
+
+    let response = server
+        .put("/groups/999/assign-feeds")
+        .form(&[("feed_ids", "2")])
+        .await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_create_group_with_nonexistent_parent_is_rejected() {
+    let (server, pool) = setup_test_app().await;
+
+    let response = server
+        .post("/groups")
+        .form(&[("name", "Orphan"), ("parent_id", "999")])
+        .await;
+    assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+    assert!(repository::list_groups(&pool).await.unwrap().is_empty());
+
+    let parent = repository::create_group(&pool, "Parent", None)
+        .await
+        .unwrap();
+    let response = server
+        .post("/groups")
+        .form(&[("name", "Child"), ("parent_id", &parent.id.to_string())])
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_update_group_rejects_cycles() {
+    let (server, pool) = setup_test_app().await;
+    let parent = repository::create_group(&pool, "Parent", None)
+        .await
+        .unwrap();
+    let child = repository::create_group(&pool, "Child", Some(parent.id))
+        .await
+        .unwrap();
+
+    for parent_id in [parent.id, child.id, 999] {
+        let response = server
+            .put(&format!("/groups/{}", parent.id))
+            .form(&[("name", "Parent"), ("parent_id", &parent_id.to_string())])
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+    }
+    let stored = repository::get_group(&pool, parent.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.parent_id, None);
+}
+
+#[tokio::test]
+async fn test_refresh_opengraph_reports_queued_articles() {
+    let (server, pool) = setup_test_app().await;
+    let group_id = create_grouped_article(&pool, "Previews", "No Link").await;
+    let feed_id = repository::get_feed_ids_in_group_recursive(&pool, group_id)
+        .await
+        .unwrap()[0];
+
+    // Articles without a URL have nothing to enrich
+    let response = server
+        .post(&format!("/feeds/{}/refresh-opengraph", feed_id))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["queued"], 0);
+
+    let response = server.post("/feeds/999/refresh-opengraph").await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_digest_lists_unread_articles_of_the_day() {
+    let (server, pool) = setup_test_app().await;
+    create_grouped_article(&pool, "Digest", "Undated").await;
+    for (guid, hour, day) in [("Morning", 8, 10), ("Evening", 20, 10), ("Next Day", 8, 11)] {
+        repository::insert_article_if_new(
+            &pool,
+            NewArticle {
+                feed_id: 1,
+                guid: guid.to_string(),
+                title: guid.to_string(),
+                url: Some(format!("https://example.com/{}", day)),
+                content: None,
+                summary: Some("<p>An <em>excerpt</em></p>".to_string()),
+                author: None,
+                published_at: Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).single(),
+                updated_at_source: None,
+                og_image: None,
+                og_description: None,
+                og_site_name: None,
+                language: None,
+            },
+        )
+        .await
+        .unwrap();
+    }
+    // "Evening" has been read already
+    repository::update_article_read_status(&pool, 3, true)
+        .await
+        .unwrap();
+
+    let response = server.get("/articles/digest?date=2026-03-10").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body = response.text();
+    assert!(body.contains("Digest Feed"));
+    assert!(body.contains("Morning"));
+    assert!(body.contains("An excerpt"));
+    assert!(!body.contains("Evening"));
+    assert!(!body.contains("Next Day"));
+    assert!(!body.contains("hx-"));
+
+    let response = server
+        .get("/articles/digest?date=2026-03-10&unread=false")
+        .await;
+    assert!(response.text().contains("Evening"));
+
+    let response = server.get("/articles/digest?date=yesterday").await;
+    assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_validate_feed_rejects_without_storing() {
+    let (server, pool) = setup_test_app().await;
+
+    let response = server
+        .post("/api/feeds/validate")
+        .json(&serde_json::json!({ "url": "ftp://example.com/feed.xml" }))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["valid"], false);
+    assert!(body["error"].as_str().unwrap().contains("http://"));
+
+    let response = server
+        .post("/api/feeds/validate")
+        .json(&serde_json::json!({ "url": "http://127.0.0.1/feed.xml" }))
+        .await;
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["valid"], false);
+    assert_eq!(body["error"], "URL points to internal/private network");
+
+    assert!(repository::list_feeds(&pool).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_validate_feed_applies_host_policy() {
+    let pool = test_pool().await;
+    let state = feeds::AppState {
+        fetch_host_policy: Arc::new(HostPolicy {
+            allowlist: Vec::new(),
+            blocklist: vec!["*.example.com".to_string()],
+        }),
+        ..test_state(&pool, None)
+    };
+    let server = TestServer::new(test_router(state)).unwrap();
+
+    let response = server
+        .post("/api/feeds/validate")
+        .json(&serde_json::json!({ "url": "https://blog.example.com/feed.xml" }))
+        .await;
+
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["valid"], false);
+    assert_eq!(
+        body["error"],
+        "Feeds from blog.example.com are not allowed on this server"
+    );
+}
+
+#[tokio::test]
+async fn test_validate_feed_with_only_bearer_token() {
+    let (server, _pool) = setup_csrf_app_with_token(Some("secret")).await;
+
+    let response = server
+        .post("/api/feeds/validate")
+        .authorization_bearer("secret")
+        .json(&serde_json::json!({ "url": "ftp://example.com/feed.xml" }))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["valid"], false);
+}
+
+#[tokio::test]
+async fn test_vacuum_with_only_bearer_token() {
+    let (server, _pool) = setup_csrf_app_with_token(Some("secret")).await;
+
+    let response = server.post("/api/maintenance/vacuum").await;
+    assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+
+    let response = server
+        .post("/api/maintenance/vacuum")
+        .authorization_bearer("secret")
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["message"], "Database vacuumed");
+}
+
+#[tokio::test]
+async fn test_vacuum_is_hidden_without_api_token() {
+    let (server, _pool) = setup_test_app_with_token(None).await;
+
+    let response = server.post("/api/maintenance/vacuum").await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["status"], 404);
+
+    // The rest of the API stays open
+    let response = server.get("/api/status").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+}